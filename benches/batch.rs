@@ -7,6 +7,7 @@ use vector::{
     buffers::Acker,
     sinks::util::{
         batch::{Batch, BatchConfig, BatchError, BatchSettings, BatchSize, PushResult},
+        flush::FlushSignal,
         BatchSink, Buffer, Compression, Partition, PartitionBatchSink,
     },
     test_util::{random_lines, runtime},
@@ -39,6 +40,7 @@ fn batching(
                     Buffer::new(batch, compression),
                     Duration::from_secs(1),
                     acker,
+                    FlushSignal::default(),
                 )
                 .sink_map_err(|error| panic!(error));
 
@@ -82,6 +84,7 @@ fn partitioned_batching(
                     PartitionedBuffer::new(batch, compression),
                     Duration::from_secs(1),
                     acker,
+                    FlushSignal::default(),
                 )
                 .sink_map_err(|error| panic!(error));
 
@@ -50,8 +50,10 @@ fn benchmark_files_without_partitions(c: &mut Criterion) {
                     sinks::file::FileSinkConfig {
                         path: output.try_into().unwrap(),
                         idle_timeout_secs: None,
-                        encoding: sinks::file::Encoding::Text.into(),
-                        compression: sinks::file::Compression::None,
+                        encoding: sinks::util::encoding::StandardEncodings::Text.into(),
+                        compression: sinks::util::Compression::None,
+                        rotate: Default::default(),
+                        retain: Default::default(),
                     },
                 );
 
@@ -40,7 +40,7 @@ fn benchmark_http_no_compression(c: &mut Criterion) {
                         auth: Default::default(),
                         headers: Default::default(),
                         batch: Default::default(),
-                        encoding: sinks::http::Encoding::Text.into(),
+                        encoding: sinks::util::encoding::StandardEncodings::Text.into(),
                         request: Default::default(),
                         tls: Default::default(),
                     },
@@ -98,7 +98,7 @@ fn benchmark_http_gzip(c: &mut Criterion) {
                         auth: Default::default(),
                         headers: Default::default(),
                         batch: Default::default(),
-                        encoding: sinks::http::Encoding::Text.into(),
+                        encoding: sinks::util::encoding::StandardEncodings::Text.into(),
                         request: Default::default(),
                         tls: Default::default(),
                     },
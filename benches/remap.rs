@@ -0,0 +1,97 @@
+use criterion::{criterion_group, criterion_main, BatchSize, BenchmarkId, Criterion};
+use remap::{Function, Program, Runtime};
+use vector::{
+    event::Event,
+    remap::{Contains, Match, ParseDuration, ParseGrok, Upcase},
+};
+
+// Each program is run against the same event by `Runtime::execute`. Changes to
+// the interpreter are measured against a saved criterion baseline.
+const PROGRAMS: &[(&str, &str)] = &[
+    (
+        "literals",
+        r#"
+        .duration_ms = 60 * 60 * 1000
+        .greeting = "hello" + " " + "world"
+        if !false && 1 < 2 { .enabled = true } else { .enabled = false }
+        "#,
+    ),
+    (
+        "paths",
+        r#"
+        .copy = .message
+        .nested.copy = .nested.field
+        .host_copy = .host
+        "#,
+    ),
+    (
+        "functions",
+        r#"
+        .upper = upcase(.message)
+        .has_get = contains(.message, "get", case_sensitive: false)
+        .timeout = parse_duration("1500ms", output: "s")
+        .is_ok = match(.message, /\s200\s/)
+        "#,
+    ),
+    (
+        "grok",
+        r#"
+        .parsed = parse_grok(.message, "%{WORD:method} %{URIPATH:path} %{NUMBER:status}")
+        "#,
+    ),
+];
+
+fn event() -> Event {
+    let mut event = Event::from("GET /index.html 200 OK");
+    event.as_mut_log().insert("host", "localhost");
+    event.as_mut_log().insert("nested.field", "value");
+    event
+}
+
+fn functions() -> Vec<Box<dyn Function>> {
+    vec![
+        Box::new(Upcase),
+        Box::new(Contains),
+        Box::new(ParseDuration),
+        Box::new(Match),
+        Box::new(ParseGrok),
+    ]
+}
+
+fn benchmark_remap(c: &mut Criterion) {
+    let mut group = c.benchmark_group("remap");
+    let functions = functions();
+
+    for (name, source) in PROGRAMS {
+        let program = Program::new(source, &functions).unwrap();
+
+        group.bench_with_input(BenchmarkId::from_parameter(name), &program, |b, program| {
+            b.iter_batched(
+                event,
+                |mut event| {
+                    Runtime::default().execute(&mut event, program).unwrap();
+                    event
+                },
+                BatchSize::SmallInput,
+            )
+        });
+    }
+
+    group.finish();
+}
+
+fn benchmark_compile(c: &mut Criterion) {
+    let mut group = c.benchmark_group("remap_compile");
+    let functions = functions();
+
+    for (name, source) in PROGRAMS {
+        group.bench_with_input(BenchmarkId::from_parameter(name), source, |b, source| {
+            b.iter(|| Program::new(source, &functions).unwrap())
+        });
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, benchmark_remap, benchmark_compile);
+criterion_main!(benches);
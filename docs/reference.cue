@@ -164,9 +164,12 @@ _values: {
 //
 // * `none` - compression is not applied
 // * `gzip` - gzip compression applied
+// * `lz4` - lz4 compression applied
+// * `snappy` - snappy compression applied
+// * `zstd` - zstd compression applied
 #CompressionAlgorithm: "none" | "gzip" | "lz4" | "snappy" | "zstd"
 
-#CompressionLevel: "none" | "fast" | "default" | "best" | >=0 & <=9
+#CompressionLevel: "none" | "fast" | "default" | "best" | >=0 & <=21
 
 #Date: =~"^\\d{4}-\\d{2}-\\d{2}"
 
@@ -898,7 +901,7 @@ remap: {
 			},
 		]
 		return: [#RemapReturnTypes, ...]
-		category:    "coerce" | "parse" | "text" | "hash" | "event"
+		category:    "coerce" | "encode" | "enrichment" | "event" | "hash" | "networking" | "parse" | "text"
 		description: string
 		examples: [
 			...{
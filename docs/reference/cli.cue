@@ -123,6 +123,54 @@ cli: #CommandLineTool & {
 	}
 
 	commands: {
+		"diagnose": {
+			description: """
+				Gather the version, host, redacted config, validation results and
+				internal metrics of Vector, and the state of a running instance if its
+				API is reachable, into a tarball for support, then exit
+				"""
+
+			flags: _default_flags & {
+				"no-environment": {
+					description: """
+						Disables environment checks. That includes component
+						checks and health checks
+						"""
+				}
+			}
+
+			options: {
+				"output": {
+					_short:      "o"
+					description: "File to write the bundle to. Defaults to `vector-diagnose-<timestamp>.tar.gz` in the current directory"
+				}
+				"url": {
+					_short:      "u"
+					description: "Vector GraphQL API server endpoint of a running instance, over `http` or `https`. Defaults to the local API address"
+				}
+				"token": {
+					description: "Bearer token for an API server requiring authentication. Can also be set with `VECTOR_API_TOKEN`"
+				}
+				"user": {
+					description: "User for an API server requiring basic authentication"
+				}
+				"password": {
+					description: "Password of `--user`. Can also be set with `VECTOR_API_PASSWORD`"
+				}
+			}
+
+			args: {
+				paths: {
+					description: """
+						Any number of Vector config files to include. If none are specified
+						the default config path `/etc/vector/vector.toml` will be targeted
+						"""
+					type:    "list"
+					default: "/etc/vector/vector.toml"
+				}
+			}
+		}
+
 		"generate": {
 			description: "Generate a Vector configuration containing a list of components"
 
@@ -171,6 +219,34 @@ cli: #CommandLineTool & {
 			}
 		}
 
+		"graph": {
+			description: """
+				Print the topology of a config as a Graphviz DOT or Mermaid diagram,
+				then exit. The components are neither built nor started
+				"""
+
+			flags: _default_flags
+
+			options: {
+				"format": {
+					description: "Format the graph in a diagram language"
+					default:     "dot"
+					enum: ["dot", "mermaid"]
+				}
+			}
+
+			args: {
+				paths: {
+					description: """
+						Any number of Vector config files to render. If none are specified
+						the default config path `/etc/vector/vector.toml` will be targeted
+						"""
+					type:    "list"
+					default: "/etc/vector/vector.toml"
+				}
+			}
+		}
+
 		"help": {
 			description: "Prints this message or the help of the given subcommand(s)"
 		}
@@ -189,6 +265,61 @@ cli: #CommandLineTool & {
 			}
 		}
 
+		"tap": {
+			description: """
+				Print a sample of the events leaving sources and transforms, for a
+				local or remote Vector instance with the API enabled. Events are
+				copied from the running topology without slowing it down, so not
+				every event is printed
+				"""
+
+			flags: _default_flags & {
+				"meta": {
+					_short:      "m"
+					description: "Prefix each event with the name of the component it left"
+				}
+			}
+
+			options: {
+				"interval": {
+					_short:      "i"
+					description: "How often events are sampled, in milliseconds"
+					default:     "500"
+				}
+				"limit": {
+					_short:      "l"
+					description: "Most events sampled per interval"
+					default:     "100"
+				}
+				"format": {
+					_short:      "f"
+					description: "Encoding of the printed events"
+					default:     "json"
+					enum: ["json", "logfmt"]
+				}
+				"url": {
+					_short:      "u"
+					description: "Vector GraphQL API server endpoint, over `http` or `https`. Defaults to the local API address"
+				}
+				"token": {
+					description: "Bearer token for an API server requiring authentication. Can also be set with `VECTOR_API_TOKEN`"
+				}
+				"user": {
+					description: "User for an API server requiring basic authentication"
+				}
+				"password": {
+					description: "Password of `--user`. Can also be set with `VECTOR_API_PASSWORD`"
+				}
+			}
+
+			args: {
+				component_names: {
+					description: "Names of the sources and transforms to tap, which can be globs, e.g. `parse_*`"
+					type:        "list"
+				}
+			}
+		}
+
 		"test": {
 			description: """
 				Run Vector config unit tests, then exit. This command is experimental and
@@ -239,5 +370,27 @@ cli: #CommandLineTool & {
 				}
 			}
 		}
+
+		"vrl": {
+			description: """
+				Run remap programs. Without `--program`, starts an interactive
+				session against a single event where each line is executed in
+				turn and variables are kept between lines
+				"""
+
+			options: {
+				"program": {
+					_short:      "p"
+					description: "Remap program to run against every input event"
+				}
+				"input": {
+					_short: "i"
+					description: """
+						File containing newline delimited JSON events. Defaults to
+						`stdin` when running a program
+						"""
+				}
+			}
+		}
 	}
 }
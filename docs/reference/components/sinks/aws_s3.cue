@@ -26,7 +26,7 @@ components: sinks: aws_s3: {
 			compression: {
 				enabled: true
 				default: "gzip"
-				algorithms: ["none", "gzip"]
+				algorithms: ["none", "gzip", "zstd", "snappy", "lz4"]
 				levels: ["none", "fast", "default", "best", 0, 1, 2, 3, 4, 5, 6, 7, 8, 9]
 			}
 			encoding: {
@@ -34,7 +34,7 @@ components: sinks: aws_s3: {
 				codec: {
 					enabled: true
 					default: "text"
-					enum: ["ndjson", "text"]
+					enum: ["avro", "csv", "json", "logfmt", "ndjson", "protobuf", "raw", "text"]
 				}
 			}
 			request: {
@@ -215,6 +215,52 @@ components: sinks: aws_s3: {
 				templateable: true
 			}
 		}
+		parquet: {
+			category:    "Encoding"
+			common:      false
+			description: "Writes each batch as a Parquet file instead of encoding each event. The `compression` option does not apply to Parquet files, which compress their columns themselves, and the file extension defaults to `parquet`. Requires Vector to be built with the `parquet` feature."
+			required:    false
+			warnings: []
+			type: object: {
+				examples: []
+				options: {
+					compression: {
+						common:      false
+						description: "The compression applied to each column."
+						required:    false
+						warnings: []
+						type: string: {
+							default: "snappy"
+							enum: {
+								none:   "Columns are not compressed."
+								snappy: "Columns are compressed with Snappy."
+								zstd:   "Columns are compressed with Zstandard."
+							}
+						}
+					}
+					row_group_size: {
+						common:      false
+						description: "The most rows written to a single row group. Batches with more events are split into several row groups."
+						required:    false
+						warnings: []
+						type: uint: {
+							default: 10000
+							unit:    "events"
+						}
+					}
+					schema: {
+						common:      true
+						description: "The columns of the file, in order. Each key is the event field a column is read from, and each value the column's type. Missing fields, and values that don't fit the column type, are written as nulls."
+						required:    true
+						warnings: []
+						type: object: {
+							examples: [{"message": "string", "status": "int64", "duration": "double", "cached": "boolean", "timestamp": "timestamp"}]
+							options: {}
+						}
+					}
+				}
+			}
+		}
 		server_side_encryption: {
 			category:    "Encryption"
 			common:      false
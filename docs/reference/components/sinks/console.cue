@@ -21,7 +21,7 @@ components: sinks: console: {
 				codec: {
 					enabled: true
 					default: null
-					enum: ["json", "text"]
+					enum: ["avro", "csv", "json", "logfmt", "ndjson", "protobuf", "raw", "text"]
 				}
 			}
 			request: enabled: false
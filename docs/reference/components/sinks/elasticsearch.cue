@@ -137,6 +137,61 @@ components: sinks: elasticsearch: {
 				}
 			}
 		}
+		bulk_action: {
+			common:      false
+			description: "The [bulk action][urls.elasticsearch_bulk] used to write each event. `update` writes the event as a partial document, and creates the document if it doesn't exist yet. `update` and `delete` require `id_key`: a static `update` or `delete` without it fails to load, and events without an id, or rendering any other action, are dropped. Data streams only accept `create`."
+			required:    false
+			warnings: []
+			type: string: {
+				default: "index"
+				examples: ["index", "create", "update", "delete", "{{ action }}"]
+				templateable: true
+			}
+		}
+		data_stream: {
+			common:      false
+			description: "The name of the data stream events are appended to when `mode` is `data_stream`, as `<type>-<dataset>-<namespace>`. These parts are also added to each event, as the `data_stream.type`, `data_stream.dataset` and `data_stream.namespace` fields."
+			required:    false
+			warnings: []
+			type: object: {
+				examples: []
+				options: {
+					dataset: {
+						common:      true
+						description: "The data stream dataset."
+						required:    false
+						warnings: []
+						type: string: {
+							default: "generic"
+							examples: ["nginx", "{{ service }}"]
+							templateable: true
+						}
+					}
+					namespace: {
+						common:      true
+						description: "The data stream namespace."
+						required:    false
+						warnings: []
+						type: string: {
+							default: "default"
+							examples: ["production", "{{ environment }}"]
+							templateable: true
+						}
+					}
+					type: {
+						common:      false
+						description: "The data stream type."
+						required:    false
+						warnings: []
+						type: string: {
+							default: "logs"
+							examples: ["logs", "metrics"]
+							templateable: true
+						}
+					}
+				}
+			}
+		}
 		doc_type: {
 			common:      false
 			description: "The `doc_type` for your index data. This is only relevant for Elasticsearch <= 6.X. If you are using >= 7.0 you do not need to set this option since Elasticsearch has removed it."
@@ -190,6 +245,19 @@ components: sinks: elasticsearch: {
 				templateable: true
 			}
 		}
+		mode: {
+			common:      false
+			description: "How events are written."
+			required:    false
+			warnings: []
+			type: string: {
+				default: "normal"
+				enum: {
+					normal:      "Events are written to `index` with `bulk_action`."
+					data_stream: "Events are appended to the [data stream][urls.elasticsearch_data_streams] named by `data_stream`, with the `create` action. The event's timestamp is written to the `@timestamp` field data streams require, and `index` can't be set."
+				}
+			}
+		}
 		pipeline: {
 			common:      true
 			description: "Name of the pipeline to apply."
@@ -222,21 +290,36 @@ components: sinks: elasticsearch: {
 			title: "Conflicts"
 			body: """
 				Vector [batches](#buffers--batches) data flushes it to Elasticsearch's
-				[`_bulk` API endpoint][urls.elasticsearch_bulk]. All events are inserted
-				via the `index` action. In the case of an conflict, such as a document with the
-				same `id`, Vector will add or _replace_ the document as necessary.
+				[`_bulk` API endpoint][urls.elasticsearch_bulk]. By default, all events
+				are inserted via the `index` action. In the case of an conflict, such as
+				a document with the same `id`, Vector will add or _replace_ the document
+				as necessary. Set `bulk_action` to `create` to keep the existing document
+				instead.
 				"""
 		}
 
 		partial_failures: {
 			title: "Partial Failures"
 			body:  """
-					By default, Elasticsearch will allow partial bulk ingestion
-					failures. This is typically due to type Elasticsearch index
-					mapping errors, where data keys are not consistently typed.
-					To change this behavior please refer to the Elasticsearch
-					[`ignore_malformed` setting](\(urls.elasticsearch_ignore_malformed)).
+					Elasticsearch reports the outcome of each document in a bulk
+					request separately. Documents rejected because the cluster is
+					overloaded (status 429) or failing (status 5xx) are retried,
+					without resending the documents that succeeded. Documents
+					rejected for any other reason, typically index mapping errors
+					where data keys are not consistently typed, are logged and
+					dropped. To change this behavior please refer to the
+					Elasticsearch [`ignore_malformed`
+					setting](\(urls.elasticsearch_ignore_malformed)).
 					"""
 		}
+
+		compression: {
+			title: "Compression"
+			body: """
+				Elasticsearch only decodes gzip and deflate compressed
+				requests. Vector sends gzip, and rejects the `zstd`, `snappy`
+				and `lz4` algorithms when the sink is built.
+				"""
+		}
 	}
 }
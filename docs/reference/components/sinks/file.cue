@@ -19,7 +19,7 @@ components: sinks: file: {
 			compression: {
 				enabled: true
 				default: "none"
-				algorithms: ["none", "gzip"]
+				algorithms: ["none", "gzip", "zstd", "snappy", "lz4"]
 				levels: ["none", "fast", "default", "best", 0, 1, 2, 3, 4, 5, 6, 7, 8, 9]
 			}
 			encoding: {
@@ -27,7 +27,7 @@ components: sinks: file: {
 				codec: {
 					enabled: true
 					default: null
-					enum: ["ndjson", "text"]
+					enum: ["avro", "csv", "json", "logfmt", "ndjson", "protobuf", "raw", "text"]
 				}
 			}
 			request: enabled: false
@@ -70,6 +70,84 @@ components: sinks: file: {
 				templateable: true
 			}
 		}
+		retain: {
+			common:      false
+			description: "Which rotated files to keep. Files are removed each time their file rotates. Requires `rotate.max_bytes` or `rotate.interval_secs`."
+			required:    false
+			warnings: []
+			type: object: {
+				examples: []
+				options: {
+					max_age_secs: {
+						common:      false
+						description: "Removes rotated files once this long has passed since their rotation."
+						required:    false
+						warnings: []
+						type: uint: {
+							default: null
+							unit:    "seconds"
+						}
+					}
+					max_files: {
+						common:      true
+						description: "The most rotated files kept for each path. The oldest are removed first."
+						required:    false
+						warnings: []
+						type: uint: {
+							default: null
+							unit:    null
+						}
+					}
+				}
+			}
+		}
+		rotate: {
+			common:      false
+			description: "Rotates files by size or age. A file is rotated by renaming it to `<path>.<timestamp>`, and the next event opens a new file at `path`."
+			required:    false
+			warnings: []
+			type: object: {
+				examples: []
+				options: {
+					compression: {
+						common:      false
+						description: "The compression applied to rotated files, which adds the usual suffix of the algorithm to their names. Can't be combined with the sink's `compression` option."
+						required:    false
+						warnings: []
+						type: string: {
+							default: "none"
+							enum: {
+								none: "Rotated files are not compressed."
+								gzip: "Rotated files are compressed with [Gzip](\(urls.gzip))."
+								zstd: "Rotated files are compressed with [Zstandard](\(urls.zstd))."
+								snappy: "Rotated files are compressed with [Snappy](\(urls.snappy))."
+								lz4: "Rotated files are compressed with [LZ4](\(urls.lz4))."
+							}
+						}
+					}
+					interval_secs: {
+						common:      true
+						description: "Rotates a file once this long has passed since it was created."
+						required:    false
+						warnings: []
+						type: uint: {
+							default: null
+							unit:    "seconds"
+						}
+					}
+					max_bytes: {
+						common:      true
+						description: "Rotates a file once it holds at least this many bytes."
+						required:    false
+						warnings: []
+						type: uint: {
+							default: null
+							unit:    "bytes"
+						}
+					}
+				}
+			}
+		}
 	}
 
 	input: {
@@ -78,6 +156,23 @@ components: sinks: file: {
 	}
 
 	how_it_works: {
+		rotation: {
+			title: "File Rotation"
+			body: """
+				When `rotate.max_bytes` or `rotate.interval_secs` is set, Vector
+				checks a file before each write. A file due for rotation is
+				closed and atomically renamed to `<path>.<timestamp>`, where the
+				timestamp is the UTC time of the rotation, such as
+				`app.log.20201018T120000.000Z`, and the event is written to a new
+				file at `path`. Files appended to after a restart keep their size
+				and creation time.
+
+				Rotated files are then compressed if `rotate.compression` is
+				set, and the `retain` options applied to all the files rotated
+				from the same path.
+				"""
+		}
+
 		dir_and_file_creation: {
 			title: "File & Directory Creation"
 			body: """
@@ -26,7 +26,11 @@ components: sinks: gcp_pubsub: {
 			compression: enabled: false
 			encoding: {
 				enabled: true
-				codec: enabled: false
+				codec: {
+					enabled: true
+					default: "json"
+					enum: ["avro", "csv", "json", "logfmt", "ndjson", "protobuf", "raw", "text"]
+				}
 			}
 			request: {
 				enabled:                    true
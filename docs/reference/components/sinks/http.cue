@@ -26,7 +26,7 @@ components: sinks: http: {
 			compression: {
 				enabled: true
 				default: "none"
-				algorithms: ["none", "gzip"]
+				algorithms: ["none", "gzip", "zstd", "snappy", "lz4"]
 				levels: ["none", "fast", "default", "best", 0, 1, 2, 3, 4, 5, 6, 7, 8, 9]
 			}
 			encoding: {
@@ -34,7 +34,7 @@ components: sinks: http: {
 				codec: {
 					enabled: true
 					default: null
-					enum: ["json", "ndjson", "text"]
+					enum: ["avro", "csv", "json", "logfmt", "ndjson", "protobuf", "raw", "text"]
 				}
 			}
 			request: {
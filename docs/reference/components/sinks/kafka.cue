@@ -27,7 +27,7 @@ components: sinks: kafka: {
 				codec: {
 					enabled: true
 					default: null
-					enum: ["json", "text"]
+					enum: ["avro", "csv", "json", "logfmt", "ndjson", "protobuf", "raw", "text"]
 				}
 			}
 			request: enabled: false
@@ -46,11 +46,28 @@ components: sinks: kafka: {
 
 	configuration: {
 		bootstrap_servers: components._kafka.configuration.bootstrap_servers
+		headers: {
+			common:      false
+			description: "A map of Kafka record header names to the log fields holding their values. Fields that do not exist on the log are left out."
+			required:    false
+			warnings: []
+			type: object: {
+				examples: [
+					{
+						"trace-id": "trace_id"
+						"tenant":   "kubernetes.pod_namespace"
+					},
+				]
+				options: {}
+			}
+		}
 		key_field: {
-			description: "The log field name to use for the topic key. If unspecified, the key will be randomly generated. If the field does not exist on the log, a blank value will be used."
-			required:    true
+			common:      true
+			description: "The log field name to use for the topic key. If unspecified, the key will be randomly generated. If the field does not exist on the log, a blank value will be used. Cannot be used with `partition_key`."
+			required:    false
 			warnings: []
 			type: string: {
+				default: null
 				examples: ["user_id"]
 			}
 		}
@@ -66,6 +83,17 @@ components: sinks: kafka: {
 				unit: null
 			}
 		}
+		partition_key: {
+			common:      false
+			description: "A template rendered into the record key, which decides the partition the record is written to. Events missing any of its fields are dropped. Cannot be used with `key_field`."
+			required:    false
+			warnings: []
+			type: string: {
+				default: null
+				examples: ["{{ tenant }}-{{ user_id }}"]
+				templateable: true
+			}
+		}
 		sasl: {
 			common:      false
 			description: "Options for SASL/SCRAM authentication support."
@@ -123,6 +151,26 @@ components: sinks: kafka: {
 				examples: ["topic-1234", "logs-{{unit}}-%Y-%m-%d"]
 			}
 		}
+		transaction_timeout_ms: {
+			common:      false
+			description: "The longest a transaction can stay open before the broker aborts it. Only used with `transactional_id`."
+			required:    false
+			warnings: []
+			type: uint: {
+				default: 60000
+				unit:    "milliseconds"
+			}
+		}
+		transactional_id: {
+			common:      false
+			description: "Enables the idempotent, transactional producer with this `transactional.id`. Events are written in transactions that are only committed once all their events are delivered, and only acknowledged after that. Each Vector instance must use its own id."
+			required:    false
+			warnings: []
+			type: string: {
+				default: null
+				examples: ["vector-1"]
+			}
+		}
 	}
 
 	input: {
@@ -130,5 +178,23 @@ components: sinks: kafka: {
 		metrics: null
 	}
 
-	how_it_works: components._kafka.how_it_works
+	how_it_works: components._kafka.how_it_works & {
+		transactions: {
+			title: "Exactly-Once Delivery"
+			body: """
+				With `transactional_id` set, the sink writes events in Kafka
+				transactions. A transaction is committed once every event
+				written in it is delivered, whenever the sink has caught up
+				with its input, and its events are only acknowledged after the
+				commit. A transaction in which an event fails delivery, or
+				whose commit still fails after a few retries, is aborted and
+				the sink stops. If Vector stops before a commit, the
+				transaction is aborted when the sink next starts with the
+				same `transactional_id`, and a disk buffer sends the
+				unacknowledged events again. Consumers using
+				`isolation.level = "read_committed"` therefore see each event
+				once.
+				"""
+		}
+	}
 }
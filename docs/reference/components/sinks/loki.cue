@@ -23,7 +23,12 @@ components: sinks: loki: {
 				max_bytes:    null
 				timeout_secs: 1
 			}
-			compression: enabled: false
+			compression: {
+				enabled: true
+				default: "none"
+				algorithms: ["none", "gzip"]
+				levels: ["none", "fast", "default", "best", 0, 1, 2, 3, 4, 5, 6, 7, 8, 9]
+			}
 			encoding: {
 				enabled: true
 				codec: {
@@ -34,7 +39,7 @@ components: sinks: loki: {
 			}
 			request: {
 				enabled:                    true
-				in_flight_limit:            5
+				in_flight_limit:            1
 				rate_limit_duration_secs:   1
 				rate_limit_num:             5
 				retry_initial_backoff_secs: 1
@@ -139,6 +144,20 @@ components: sinks: loki: {
 				}
 			}
 		}
+		out_of_order_action: {
+			common:      false
+			description: "What to do with an event older than the latest event Loki accepted for its stream, which Loki would otherwise reject."
+			required:    false
+			warnings: []
+			type: string: {
+				default: "drop"
+				enum: {
+					drop:              "Drop the event, with a warning."
+					rewrite_timestamp: "Send the event with the timestamp of the latest event Loki accepted for its stream."
+					accept:            "Send the event as is. Use this with Loki versions that accept out-of-order events."
+				}
+			}
+		}
 		remove_label_fields: {
 			common:      false
 			description: "If this is set to `true` then when labels are collected from events those fields will also get removed from the event."
@@ -192,6 +211,35 @@ components: sinks: loki: {
 				accepted by Loki. If no timestamp is supplied with events
 				then the Loki sink will supply its own monotonically
 				increasing timestamp.
+
+				Sorting only applies within a batch. An event older than the
+				latest event Loki accepted for its stream is handled according
+				to the `out_of_order_action` option when its batch is sent, so
+				a failed request doesn't affect the events after it.
+
+				Only one request is in flight by default, so that every batch
+				is checked against all the batches Loki accepted before it.
+				Raising `request.in_flight_limit` sends batches concurrently,
+				and Loki may then reject some of their events as out of order.
+				"""
+		}
+
+		stream_partitioning: {
+			title: "Stream Partitioning"
+			body: """
+				Each stream, that is each set of rendered `labels`, is
+				batched and sent in its own request. If Loki rejects the
+				events of one stream, only the request for that stream is
+				retried.
+				"""
+		}
+
+		compression: {
+			title: "Compression"
+			body: """
+				Loki only decodes gzip compressed push requests, so the
+				`zstd`, `snappy` and `lz4` algorithms are rejected when the
+				sink is built.
 				"""
 		}
 	}
@@ -22,7 +22,7 @@ components: sinks: pulsar: {
 				codec: {
 					enabled: true
 					default: "text"
-					enum: ["text", "json"]
+					enum: ["avro", "csv", "json", "logfmt", "ndjson", "protobuf", "raw", "text"]
 				}
 			}
 			request: enabled: false
@@ -165,4 +165,15 @@ components: sinks: splunk_hec: {
 		logs:    true
 		metrics: null
 	}
+
+	how_it_works: {
+		compression: {
+			title: "Compression"
+			body: """
+				Splunk HEC only decodes gzip compressed requests, so the
+				`zstd`, `snappy` and `lz4` algorithms are rejected when the
+				sink is built.
+				"""
+		}
+	}
 }
@@ -0,0 +1,102 @@
+package metadata
+
+components: transforms: enrichment_table: {
+	title: "Enrichment Table"
+
+	classes: {
+		commonly_used: false
+		development:   "beta"
+		egress_method: "stream"
+	}
+
+	features: {
+		shape: {}
+	}
+
+	support: {
+		platforms: {
+			"aarch64-unknown-linux-gnu":  true
+			"aarch64-unknown-linux-musl": true
+			"x86_64-apple-darwin":        true
+			"x86_64-pc-windows-msv":      true
+			"x86_64-unknown-linux-gnu":   true
+			"x86_64-unknown-linux-musl":  true
+		}
+
+		requirements: []
+		warnings: []
+		notices: []
+	}
+
+	configuration: {
+		keys: {
+			description: "The table fields to match, mapped to the event fields holding the values they must equal. Values are compared as strings."
+			required:    true
+			warnings: []
+			type: object: {
+				examples: [{"host": "hostname", "region": "dc"}]
+				options: {}
+			}
+		}
+		table: {
+			description: "The name of the table to look records up in, as configured in the top-level `enrichment_tables` section."
+			required:    true
+			warnings: []
+			type: string: {
+				examples: ["owners"]
+			}
+		}
+		target: {
+			common:      true
+			description: "The field to insert the fields of the matching record under. Defaults to the table name."
+			required:    false
+			warnings: []
+			type: string: {
+				default: null
+				examples: ["owner", "parent.child"]
+			}
+		}
+	}
+
+	input: {
+		logs:    true
+		metrics: null
+	}
+
+	how_it_works: {
+		tables: {
+			title: "Enrichment Tables"
+			body: """
+				Tables are configured in the top-level `enrichment_tables` section
+				and loaded when Vector starts or reloads its configuration:
+
+				```toml
+				[enrichment_tables.owners]
+				path = "/etc/vector/owners.csv"
+				format = "csv"             # or "json", an array of objects
+				delimiter = ","            # CSV only
+				case_sensitive = true
+				indexes = [["host"], ["host", "region"]]
+				reload_interval_secs = 10
+				```
+
+				The file is checked for changes in the background every
+				`reload_interval_secs`, and reloaded when it was modified. If the
+				reload fails the previously loaded records are kept. Tables whose
+				configuration changes are only replaced once the reloaded
+				configuration has started.
+
+				Lookups on a set of fields listed in `indexes` are constant time,
+				other lookups scan the whole table.
+				"""
+		}
+
+		unmatched_events: {
+			title: "Unmatched Events"
+			body: """
+				Events are passed through unchanged when a key field is missing,
+				or when no record, or more than one record, matches.
+				"""
+		}
+	}
+}
@@ -6,6 +6,31 @@ _vector_events_processed_total: {
 	description: "The total number of events processed by this component."
 	tags:        _component_tags
 }
+_vector_events_in_total: {
+	type:        "counter"
+	description: "The total number of events received by this transform or sink from its inputs."
+	tags:        _component_tags
+}
+_vector_events_out_total: {
+	type:        "counter"
+	description: "The total number of events sent by this source or transform to its outputs."
+	tags:        _component_tags
+}
+_vector_end_to_end_latency_nanoseconds: {
+	type: "histogram"
+	description: """
+		The time logs spent in Vector, from their source until their sink
+		acknowledged them. Only recorded with `telemetry.end_to_end_latency`
+		enabled, and only by the `aws_cloudwatch_logs`,
+		`aws_kinesis_firehose`, `aws_kinesis_streams`, `aws_s3`,
+		`azure_monitor_logs`, `blackhole`, `clickhouse`, `console`,
+		`datadog_logs`, `elasticsearch`, `file`, `gcp_cloud_storage`,
+		`gcp_pubsub`, `gcp_stackdriver_logs`, `honeycomb`, `http`,
+		`humio_logs`, `influxdb_logs`, `logdna`, `loki`, `new_relic_logs`,
+		`sematext_logs` and `splunk_hec` sinks. Metric events are never timed.
+		"""
+	tags: _component_tags
+}
 _vector_processed_bytes_total: {
 	description: "The total number of bytes processed by the component."
 	type:        "counter"
@@ -8,6 +8,9 @@ remap: {
 		ParseError: {
 			description: "Raised when the provided input cannot be parsed."
 		}
+		LookupError: {
+			description: "Raised when no single matching record can be found."
+		}
 	}
 
 	functions: {
@@ -0,0 +1,50 @@
+package metadata
+
+remap: functions: decode_base64: {
+	arguments: [
+		{
+			name:        "value"
+			description: "The base64 encoded string."
+			required:    true
+			type: ["string"]
+		},
+		{
+			name:        "charset"
+			description: "The character set to use, either `standard` or `url_safe`."
+			required:    false
+			type: ["string"]
+			default: "standard"
+		},
+	]
+	return: ["string"]
+	category: "encode"
+	description: #"""
+		Decodes a base64 encoded string. Padding is optional.
+		"""#
+	examples: [
+		{
+			title: "Success"
+			input: {
+				encoded: "cGxlYXNlIGVuY29kZSBtZQ=="
+			}
+			source: #"""
+				.message = decode_base64(.encoded)
+				"""#
+			output: {
+				message: "please encode me"
+			}
+		},
+		{
+			title: "Error"
+			input: {
+				encoded: "not base64!"
+			}
+			source: #"""
+				.message = decode_base64(.encoded)
+				"""#
+			output: {
+				error: remap.errors.ParseError
+			}
+		},
+	]
+}
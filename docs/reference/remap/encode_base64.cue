@@ -0,0 +1,57 @@
+package metadata
+
+remap: functions: encode_base64: {
+	arguments: [
+		{
+			name:        "value"
+			description: "The string to encode."
+			required:    true
+			type: ["string"]
+		},
+		{
+			name:        "padding"
+			description: "Whether the output is padded with `=` characters."
+			required:    false
+			type: ["boolean"]
+			default: true
+		},
+		{
+			name:        "charset"
+			description: "The character set to use, either `standard` or `url_safe`."
+			required:    false
+			type: ["string"]
+			default: "standard"
+		},
+	]
+	return: ["string"]
+	category: "encode"
+	description: #"""
+		Encodes a string to base64.
+		"""#
+	examples: [
+		{
+			title: "Success"
+			input: {
+				message: "please encode me"
+			}
+			source: #"""
+				.encoded = encode_base64(.message)
+				"""#
+			output: {
+				encoded: "cGxlYXNlIGVuY29kZSBtZQ=="
+			}
+		},
+		{
+			title: "URL safe without padding"
+			input: {
+				message: "<<??>>"
+			}
+			source: #"""
+				.encoded = encode_base64(.message, padding = false, charset = "url_safe")
+				"""#
+			output: {
+				encoded: "PDw_Pz4-"
+			}
+		},
+	]
+}
@@ -0,0 +1,31 @@
+package metadata
+
+remap: functions: encode_json: {
+	arguments: [
+		{
+			name:        "value"
+			description: "The value to encode."
+			required:    true
+			type: ["any"]
+		},
+	]
+	return: ["string"]
+	category: "encode"
+	description: #"""
+		Encodes any value as a JSON string.
+		"""#
+	examples: [
+		{
+			title: "Success"
+			input: {
+				fields: {"key": "val"}
+			}
+			source: #"""
+				.message = encode_json(.fields)
+				"""#
+			output: {
+				message: #"{"key":"val"}"#
+			}
+		},
+	]
+}
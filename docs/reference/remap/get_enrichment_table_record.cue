@@ -0,0 +1,59 @@
+package metadata
+
+remap: functions: get_enrichment_table_record: {
+	arguments: [
+		{
+			name:        "table"
+			description: "The name of the enrichment table, as configured in the `enrichment_tables` section."
+			required:    true
+			type: ["string"]
+		},
+		{
+			name:        "condition"
+			description: "The fields the record must match. Values are compared as strings, and case insensitively if the table isn't `case_sensitive`."
+			required:    true
+			type: ["map"]
+		},
+	]
+	return: ["map"]
+	category: "enrichment"
+	description: #"""
+		Looks up the single record in an enrichment table whose fields match
+		all of the given values. Fails if no record, or more than one record,
+		matches. Only available in the `remap` transform.
+		"""#
+	examples: [
+		{
+			title: "Success"
+			configuration: {
+				"enrichment_tables.owners.path":   "/etc/vector/owners.csv"
+				"enrichment_tables.owners.format": "csv"
+			}
+			input: {
+				host: "web-1"
+			}
+			source: #"""
+				.owner = get_enrichment_table_record("owners", { "host": .host })
+				"""#
+			output: {
+				host: "web-1"
+				owner: {
+					host: "web-1"
+					team: "frontend"
+				}
+			}
+		},
+		{
+			title: "Error"
+			input: {
+				host: "unknown"
+			}
+			source: #"""
+				.owner = get_enrichment_table_record("owners", { "host": .host })
+				"""#
+			output: {
+				error: remap.errors.LookupError
+			}
+		},
+	]
+}
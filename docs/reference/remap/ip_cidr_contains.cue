@@ -0,0 +1,50 @@
+package metadata
+
+remap: functions: ip_cidr_contains: {
+	arguments: [
+		{
+			name:        "cidr"
+			description: "The CIDR block, or list of CIDR blocks, to check against."
+			required:    true
+			type: ["string", "array"]
+		},
+		{
+			name:        "value"
+			description: "The IP address to check."
+			required:    true
+			type: ["string"]
+		},
+	]
+	return: ["boolean"]
+	category: "networking"
+	description: #"""
+		Determines whether an IP address is contained in a CIDR block, or in any
+		of a list of CIDR blocks.
+		"""#
+	examples: [
+		{
+			title: "IPv4"
+			input: {
+				address: "192.168.10.32"
+			}
+			source: #"""
+				.private = ip_cidr_contains("192.168.0.0/16", .address)
+				"""#
+			output: {
+				private: true
+			}
+		},
+		{
+			title: "Multiple blocks"
+			input: {
+				address: "2001:4f8:3:ba:2e0:81ff:fe22:d1f1"
+			}
+			source: #"""
+				.matched = ip_cidr_contains(["10.0.0.0/8", "2001:4f8:3:ba::/64"], .address)
+				"""#
+			output: {
+				matched: true
+			}
+		},
+	]
+}
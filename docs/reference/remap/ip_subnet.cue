@@ -0,0 +1,51 @@
+package metadata
+
+remap: functions: ip_subnet: {
+	arguments: [
+		{
+			name:        "value"
+			description: "The IP address."
+			required:    true
+			type: ["string"]
+		},
+		{
+			name:        "subnet"
+			description: "The subnet prefix length or mask."
+			required:    true
+			type: ["string"]
+		},
+	]
+	return: ["string"]
+	category: "networking"
+	description: #"""
+		Returns the network address of an IP address for the given subnet. The
+		subnet can be given as a prefix length, such as `/24`, or as a mask,
+		such as `255.255.255.0`.
+		"""#
+	examples: [
+		{
+			title: "Mask"
+			input: {
+				address: "192.168.10.32"
+			}
+			source: #"""
+				.subnet = ip_subnet(.address, "255.255.255.0")
+				"""#
+			output: {
+				subnet: "192.168.10.0"
+			}
+		},
+		{
+			title: "Prefix"
+			input: {
+				address: "2404:6800:4003:c02::64"
+			}
+			source: #"""
+				.subnet = ip_subnet(.address, "/32")
+				"""#
+			output: {
+				subnet: "2404:6800::"
+			}
+		},
+	]
+}
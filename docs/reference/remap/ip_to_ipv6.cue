@@ -0,0 +1,32 @@
+package metadata
+
+remap: functions: ip_to_ipv6: {
+	arguments: [
+		{
+			name:        "value"
+			description: "The IP address."
+			required:    true
+			type: ["string"]
+		},
+	]
+	return: ["string"]
+	category: "networking"
+	description: #"""
+		Converts an IPv4 address to its IPv4-mapped IPv6 form. IPv6 addresses
+		are returned unchanged.
+		"""#
+	examples: [
+		{
+			title: "Success"
+			input: {
+				address: "192.168.0.1"
+			}
+			source: #"""
+				.address = ip_to_ipv6(.address)
+				"""#
+			output: {
+				address: "::ffff:192.168.0.1"
+			}
+		},
+	]
+}
@@ -0,0 +1,45 @@
+package metadata
+
+remap: functions: is_private_ip: {
+	arguments: [
+		{
+			name:        "value"
+			description: "The IP address."
+			required:    true
+			type: ["string"]
+		},
+	]
+	return: ["boolean"]
+	category: "networking"
+	description: #"""
+		Determines whether an IP address is not globally routable. This covers
+		private (RFC 1918), shared (RFC 6598), loopback, link-local and unique
+		local (RFC 4193) addresses.
+		"""#
+	examples: [
+		{
+			title: "Private"
+			input: {
+				address: "10.0.0.1"
+			}
+			source: #"""
+				.private = is_private_ip(.address)
+				"""#
+			output: {
+				private: true
+			}
+		},
+		{
+			title: "Public"
+			input: {
+				address: "8.8.8.8"
+			}
+			source: #"""
+				.private = is_private_ip(.address)
+				"""#
+			output: {
+				private: false
+			}
+		},
+	]
+}
@@ -0,0 +1,73 @@
+package metadata
+
+remap: functions: parse_apache_log: {
+	arguments: [
+		{
+			name:        "value"
+			description: "The string to parse."
+			required:    true
+			type: ["string"]
+		},
+		{
+			name:        "format"
+			description: "The log format, one of `common`, `combined` or `error`."
+			required:    true
+			type: ["string"]
+		},
+		{
+			name:        "timestamp_format"
+			description: "The [strftime](https://docs.rs/chrono/0.4.19/chrono/format/strftime/index.html) format of the timestamp. Defaults to the format used by the given log format."
+			required:    false
+			type: ["string"]
+		},
+	]
+	return: ["map"]
+	category: "parse"
+	description: #"""
+		Parses an Apache HTTP Server access or error log line. Fields set to
+		`-` are omitted from the result.
+		"""#
+	examples: [
+		{
+			title: "Combined"
+			input: {
+				message: #"127.0.0.1 - frank [10/Oct/2000:13:55:36 -0700] "GET /apache_pb.gif HTTP/1.0" 200 2326 "http://www.example.com/start.html" "Mozilla/4.08""#
+			}
+			source: #"""
+				. = parse_apache_log(.message, format = "combined")
+				"""#
+			output: {
+				host:      "127.0.0.1"
+				user:      "frank"
+				timestamp: "2000-10-10T20:55:36Z"
+				message:   "GET /apache_pb.gif HTTP/1.0"
+				method:    "GET"
+				path:      "/apache_pb.gif"
+				protocol:  "HTTP/1.0"
+				status:    200
+				size:      2326
+				referrer:  "http://www.example.com/start.html"
+				agent:     "Mozilla/4.08"
+			}
+		},
+		{
+			title: "Error log"
+			input: {
+				message: #"[Wed Oct 11 14:32:52.123456 2000] [core:error] [pid 35708:tid 4328636416] [client 1.2.3.4:80] File does not exist"#
+			}
+			source: #"""
+				. = parse_apache_log(.message, format = "error")
+				"""#
+			output: {
+				timestamp: "2000-10-11T14:32:52.123456Z"
+				module:    "core"
+				severity:  "error"
+				pid:       35708
+				thread:    4328636416
+				client:    "1.2.3.4"
+				port:      80
+				message:   "File does not exist"
+			}
+		},
+	]
+}
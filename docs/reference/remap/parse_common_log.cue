@@ -0,0 +1,60 @@
+package metadata
+
+remap: functions: parse_common_log: {
+	arguments: [
+		{
+			name:        "value"
+			description: "The string to parse."
+			required:    true
+			type: ["string"]
+		},
+		{
+			name:        "timestamp_format"
+			description: "The [strftime](https://docs.rs/chrono/0.4.19/chrono/format/strftime/index.html) format of the timestamp."
+			required:    false
+			type: ["string"]
+			default: "%d/%b/%Y:%T %z"
+		},
+	]
+	return: ["map"]
+	category: "parse"
+	description: #"""
+		Parses a string in the [Common Log Format](https://httpd.apache.org/docs/1.3/logs.html#common).
+		Fields set to `-` are omitted from the result.
+		"""#
+	examples: [
+		{
+			title: "Success"
+			input: {
+				message: #"127.0.0.1 bob frank [10/Oct/2000:13:55:36 -0700] "GET /apache_pb.gif HTTP/1.0" 200 2326"#
+			}
+			source: #"""
+				. = parse_common_log(.message)
+				"""#
+			output: {
+				host:      "127.0.0.1"
+				identity:  "bob"
+				user:      "frank"
+				timestamp: "2000-10-10T20:55:36Z"
+				message:   "GET /apache_pb.gif HTTP/1.0"
+				method:    "GET"
+				path:      "/apache_pb.gif"
+				protocol:  "HTTP/1.0"
+				status:    200
+				size:      2326
+			}
+		},
+		{
+			title: "Error"
+			input: {
+				message: "not a common log line"
+			}
+			source: #"""
+				. = parse_common_log(.message)
+				"""#
+			output: {
+				error: remap.errors.ParseError
+			}
+		},
+	]
+}
@@ -0,0 +1,61 @@
+package metadata
+
+remap: functions: parse_grok: {
+	arguments: [
+		{
+			name:        "value"
+			description: "The string to parse."
+			required:    true
+			type: ["string"]
+		},
+		{
+			name:        "pattern"
+			description: "The [Grok pattern](https://github.com/daschl/grok/tree/master/patterns) used to parse the string."
+			required:    true
+			type: ["string"]
+		},
+		{
+			name:        "remove_empty"
+			description: "If true, named captures that matched an empty string are omitted from the result."
+			required:    false
+			type: ["boolean"]
+			default: false
+		},
+	]
+	return: ["map"]
+	category: "parse"
+	description: #"""
+		Parses a string using a Grok pattern, returning a map of the named
+		captures. Literal patterns are compiled along with the program;
+		patterns built from event data are compiled when first seen and the
+		64 most recently used are kept.
+		"""#
+	examples: [
+		{
+			title: "Success"
+			input: {
+				message: "2020-10-02T23:22:12.223222Z info Hello world"
+			}
+			source: #"""
+				. = parse_grok(.message, "%{TIMESTAMP_ISO8601:timestamp} %{LOGLEVEL:level} %{GREEDYDATA:message}")
+				"""#
+			output: {
+				timestamp: "2020-10-02T23:22:12.223222Z"
+				level:     "info"
+				message:   "Hello world"
+			}
+		},
+		{
+			title: "Error"
+			input: {
+				message: "no timestamp here"
+			}
+			source: #"""
+				. = parse_grok(.message, "%{TIMESTAMP_ISO8601:timestamp} %{GREEDYDATA:message}")
+				"""#
+			output: {
+				error: remap.errors.ParseError
+			}
+		},
+	]
+}
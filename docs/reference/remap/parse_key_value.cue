@@ -0,0 +1,64 @@
+package metadata
+
+remap: functions: parse_key_value: {
+	arguments: [
+		{
+			name:        "value"
+			description: "The string to parse."
+			required:    true
+			type: ["string"]
+		},
+		{
+			name:        "key_value_delimiter"
+			description: "The string that separates a key from its value."
+			required:    false
+			type: ["string"]
+			default: "="
+		},
+		{
+			name:        "field_delimiter"
+			description: "The string that separates key/value pairs."
+			required:    false
+			type: ["string"]
+			default: " "
+		},
+	]
+	return: ["map"]
+	category: "parse"
+	description: #"""
+		Parses a string of key/value pairs into a map. Keys and values can be
+		wrapped in double quotes to include delimiters. Keys without a value
+		are set to `true`.
+		"""#
+	examples: [
+		{
+			title: "Logfmt"
+			input: {
+				message: #"at=info method=GET path=/ status=200 msg="hello world""#
+			}
+			source: #"""
+				. = parse_key_value(.message)
+				"""#
+			output: {
+				at:     "info"
+				method: "GET"
+				path:   "/"
+				status: "200"
+				msg:    "hello world"
+			}
+		},
+		{
+			title: "Custom delimiters"
+			input: {
+				message: "user: bob, admin"
+			}
+			source: #"""
+				. = parse_key_value(.message, key_value_delimiter = ":", field_delimiter = ",")
+				"""#
+			output: {
+				user:  "bob"
+				admin: true
+			}
+		},
+	]
+}
@@ -0,0 +1,70 @@
+package metadata
+
+remap: functions: parse_nginx_log: {
+	arguments: [
+		{
+			name:        "value"
+			description: "The string to parse."
+			required:    true
+			type: ["string"]
+		},
+		{
+			name:        "format"
+			description: "The log format, one of `combined` or `error`."
+			required:    true
+			type: ["string"]
+		},
+		{
+			name:        "timestamp_format"
+			description: "The [strftime](https://docs.rs/chrono/0.4.19/chrono/format/strftime/index.html) format of the timestamp. Defaults to the format used by the given log format."
+			required:    false
+			type: ["string"]
+		},
+	]
+	return: ["map"]
+	category: "parse"
+	description: #"""
+		Parses an Nginx access or error log line. Fields set to `-` are
+		omitted from the result.
+		"""#
+	examples: [
+		{
+			title: "Combined"
+			input: {
+				message: #"172.17.0.1 - - [14/Feb/2019:12:30:03 +0000] "GET /favicon.ico HTTP/1.1" 404 153 "http://localhost/" "curl/7.64.1""#
+			}
+			source: #"""
+				. = parse_nginx_log(.message, format = "combined")
+				"""#
+			output: {
+				host:      "172.17.0.1"
+				timestamp: "2019-02-14T12:30:03Z"
+				message:   "GET /favicon.ico HTTP/1.1"
+				method:    "GET"
+				path:      "/favicon.ico"
+				protocol:  "HTTP/1.1"
+				status:    404
+				size:      153
+				referrer:  "http://localhost/"
+				agent:     "curl/7.64.1"
+			}
+		},
+		{
+			title: "Error log"
+			input: {
+				message: #"2019/02/14 12:30:03 [error] 10#10: *1 open() "/favicon.ico" failed"#
+			}
+			source: #"""
+				. = parse_nginx_log(.message, format = "error")
+				"""#
+			output: {
+				timestamp: "2019-02-14T12:30:03Z"
+				severity:  "error"
+				pid:       10
+				tid:       10
+				cid:       1
+				message:   #"open() "/favicon.ico" failed"#
+			}
+		},
+	]
+}
@@ -0,0 +1,53 @@
+package metadata
+
+remap: functions: parse_regex: {
+	arguments: [
+		{
+			name:        "value"
+			description: "The string to parse."
+			required:    true
+			type: ["string"]
+		},
+		{
+			name:        "pattern"
+			description: "The regular expression to match with, using named capture groups."
+			required:    true
+			type: ["regex"]
+		},
+	]
+	return: ["map"]
+	category: "parse"
+	description: #"""
+		Parses a string using a regular expression, returning a map of the
+		named capture groups. Groups that didn't participate in the match are
+		omitted.
+		"""#
+	examples: [
+		{
+			title: "Success"
+			input: {
+				message: "5.86.210.12 - zieme4647 5667"
+			}
+			source: #"""
+				. = parse_regex(.message, /^(?P<host>[\w\.]+) - (?P<user>\w+) (?P<bytes_in>\d+)$/)
+				"""#
+			output: {
+				host:     "5.86.210.12"
+				user:     "zieme4647"
+				bytes_in: "5667"
+			}
+		},
+		{
+			title: "Error"
+			input: {
+				message: "no match"
+			}
+			source: #"""
+				. = parse_regex(.message, /^(?P<digits>\d+)$/)
+				"""#
+			output: {
+				error: remap.errors.ParseError
+			}
+		},
+	]
+}
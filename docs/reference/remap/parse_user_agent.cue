@@ -0,0 +1,36 @@
+package metadata
+
+remap: functions: parse_user_agent: {
+	arguments: [
+		{
+			name:        "value"
+			description: "The user agent string."
+			required:    true
+			type: ["string"]
+		},
+	]
+	return: ["map"]
+	category: "parse"
+	description: #"""
+		Parses a user agent string into its browser, operating system and
+		device components. Components that cannot be determined are `null`.
+		"""#
+	examples: [
+		{
+			title: "Success"
+			input: {
+				agent: "Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/86.0.4240.75 Safari/537.36"
+			}
+			source: #"""
+				.parsed = parse_user_agent(.agent)
+				"""#
+			output: {
+				parsed: {
+					browser: {family: "Chrome", version: "86.0.4240.75", vendor: "Google"}
+					os: {family: "Windows 10", version: "NT 10.0"}
+					device: {category: "pc"}
+				}
+			}
+		},
+	]
+}
@@ -0,0 +1,34 @@
+package metadata
+
+remap: functions: reverse_dns: {
+	arguments: [
+		{
+			name:        "value"
+			description: "The IP address."
+			required:    true
+			type: ["string"]
+		},
+	]
+	return: ["string"]
+	category: "networking"
+	description: #"""
+		Looks up the host name of an IP address. Results, including failed
+		lookups, are reused for a minute. A lookup that isn't cached holds
+		up the events behind it while it is in flight, so use with care on
+		high volume pipelines.
+		"""#
+	examples: [
+		{
+			title: "Success"
+			input: {
+				address: "127.0.0.1"
+			}
+			source: #"""
+				.host = reverse_dns(.address)
+				"""#
+			output: {
+				host: "localhost"
+			}
+		},
+	]
+}
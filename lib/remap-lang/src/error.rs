@@ -76,6 +76,8 @@ impl fmt::Display for Rule {
             ident,
             if_statement,
             integer,
+            map,
+            map_entry,
             multiplication,
             not,
             null,
@@ -6,6 +6,7 @@ mod block;
 pub(super) mod function;
 pub(super) mod if_statement;
 mod literal;
+mod map;
 mod noop;
 pub(super) mod not;
 pub(super) mod path;
@@ -16,12 +17,13 @@ pub(super) use assignment::{Assignment, Target};
 pub(super) use block::Block;
 pub(super) use function::Function;
 pub(super) use if_statement::IfStatement;
+pub(super) use map::Map;
 pub(super) use not::Not;
 pub(super) use variable::Variable;
 
 pub use literal::Literal;
 pub use noop::Noop;
-pub use path::Path;
+pub use path::{Path, Segments};
 
 #[derive(thiserror::Error, Debug, PartialEq)]
 pub enum Error {
@@ -49,6 +51,14 @@ pub enum Error {
 
 pub trait Expression: Send + Sync + std::fmt::Debug + dyn_clone::DynClone {
     fn execute(&self, state: &mut State, object: &mut dyn Object) -> Result<Option<Value>>;
+
+    /// The value this expression resolves to, if it is known at compile-time.
+    ///
+    /// Functions can use this to do work once when the program is compiled,
+    /// instead of every time the expression is executed.
+    fn as_value(&self) -> Option<&Value> {
+        None
+    }
 }
 
 dyn_clone::clone_trait_object!(Expression);
@@ -76,6 +86,12 @@ macro_rules! expression_dispatch {
                     $(Expr::$expr(expression) => expression.execute(state, object)),+
                 }
             }
+
+            fn as_value(&self) -> Option<&Value> {
+                match self {
+                    $(Expr::$expr(expression) => expression.as_value()),+
+                }
+            }
         }
 
         $(
@@ -95,8 +111,35 @@ expression_dispatch![
     Function,
     IfStatement,
     Literal,
+    Map,
     Noop,
     Not,
     Path,
     Variable,
 ];
+
+impl Expr {
+    /// Simplifies the expression where its result is known at compile-time.
+    ///
+    /// This only looks at the expression itself, its child expressions are
+    /// expected to have been folded when they were parsed.
+    pub(crate) fn fold(self) -> Self {
+        match self {
+            Expr::Arithmetic(expression) => expression.fold(),
+            Expr::Block(expression) => expression.fold(),
+            Expr::IfStatement(expression) => expression.fold(),
+            Expr::Map(expression) => expression.fold(),
+            Expr::Not(expression) => expression.fold(),
+            expression => expression,
+        }
+    }
+}
+
+/// Executes an expression that doesn't depend on any state or object, returning
+/// its value, or `None` if it can't be resolved at compile-time.
+fn evaluate(expression: &dyn Expression) -> Option<Value> {
+    let mut state = State::default();
+    let mut object = std::collections::HashMap::default();
+
+    expression.execute(&mut state, &mut object).ok().flatten()
+}
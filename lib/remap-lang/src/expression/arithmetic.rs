@@ -1,4 +1,4 @@
-use super::{Expr, Expression, Object, Result, State, Value};
+use super::{Expr, Expression, Literal, Object, Result, State, Value};
 use crate::Operator;
 
 #[derive(Debug, Clone)]
@@ -12,6 +12,20 @@ impl Arithmetic {
     pub(crate) fn new(lhs: Box<Expr>, rhs: Box<Expr>, op: Operator) -> Self {
         Self { lhs, rhs, op }
     }
+
+    /// Evaluates the operation at compile-time if both operands are literals.
+    ///
+    /// Operations that fail, e.g. adding a string to an integer, are left in
+    /// place so that the error is still reported when the program runs.
+    pub(crate) fn fold(self) -> Expr {
+        if self.lhs.as_value().is_some() && self.rhs.as_value().is_some() {
+            if let Some(value) = super::evaluate(&self) {
+                return Literal::from(value).into();
+            }
+        }
+
+        self.into()
+    }
 }
 
 impl Expression for Arithmetic {
@@ -1,4 +1,4 @@
-use super::Error as E;
+use super::{Error as E, Segments};
 use crate::{Expr, Expression, Object, Result, State, Value};
 
 #[derive(thiserror::Error, Debug, PartialEq)]
@@ -9,7 +9,7 @@ pub enum Error {
 
 #[derive(Debug, Clone)]
 pub(crate) enum Target {
-    Path(Vec<Vec<String>>),
+    Path(Segments),
     Variable(String),
 }
 
@@ -37,7 +37,7 @@ impl Expression for Assignment {
                         state.variables_mut().insert(ident.clone(), value.clone());
                     }
                     Target::Path(path) => object
-                        .insert(&path, value.clone())
+                        .insert_segments(path, value.clone())
                         .map_err(|e| E::Assignment(Error::PathInsertion(e)))?,
                 }
 
@@ -9,6 +9,25 @@ impl Block {
     pub fn new(expressions: Vec<Expr>) -> Self {
         Self { expressions }
     }
+
+    /// Drops literals that aren't the last expression in the block, as they
+    /// don't have any effect, and unwraps blocks left with a single expression.
+    pub(crate) fn fold(self) -> Expr {
+        let last = self.expressions.len().saturating_sub(1);
+
+        let mut expressions = self
+            .expressions
+            .into_iter()
+            .enumerate()
+            .filter(|(i, expr)| *i == last || !matches!(expr, Expr::Literal(_) | Expr::Noop(_)))
+            .map(|(_, expr)| expr)
+            .collect::<Vec<_>>();
+
+        match expressions.len() {
+            1 => expressions.remove(0),
+            _ => Self::new(expressions).into(),
+        }
+    }
 }
 
 impl Expression for Block {
@@ -87,6 +87,14 @@ impl Function {
             })?;
 
             let argument = match argument {
+                // Literal values are validated once, here, instead of every
+                // time the function is called.
+                Argument::Expression(expr)
+                    if expr.as_value().map_or(false, |v| (param.accepts)(v)) =>
+                {
+                    Argument::Expression(expr)
+                }
+
                 // Wrap expression argument to validate its value type at
                 // runtime.
                 Argument::Expression(expr) => {
@@ -26,6 +26,21 @@ impl IfStatement {
             false_expression,
         }
     }
+
+    /// Replaces the statement with one of its branches if the condition is a
+    /// literal boolean.
+    pub(crate) fn fold(self) -> Expr {
+        let condition = match self.conditional.as_value() {
+            Some(Value::Boolean(condition)) => Some(*condition),
+            _ => None,
+        };
+
+        match condition {
+            Some(true) => *self.true_expression,
+            Some(false) => *self.false_expression,
+            None => self.into(),
+        }
+    }
 }
 
 impl Expression for IfStatement {
@@ -0,0 +1,97 @@
+use super::{Error as E, Literal};
+use crate::{Expr, Expression, Object, Result, State, Value};
+use std::collections::BTreeMap;
+
+#[derive(Debug, Clone)]
+pub(crate) struct Map {
+    expressions: BTreeMap<String, Expr>,
+}
+
+impl Map {
+    pub fn new(expressions: BTreeMap<String, Expr>) -> Self {
+        Self { expressions }
+    }
+
+    /// Resolves the map at compile-time if all of its values are literals.
+    pub(crate) fn fold(self) -> Expr {
+        if self
+            .expressions
+            .values()
+            .all(|expr| expr.as_value().is_some())
+        {
+            if let Some(value) = super::evaluate(&self) {
+                return Literal::from(value).into();
+            }
+        }
+
+        self.into()
+    }
+}
+
+impl Expression for Map {
+    fn execute(&self, state: &mut State, object: &mut dyn Object) -> Result<Option<Value>> {
+        self.expressions
+            .iter()
+            .map(|(key, expr)| {
+                expr.execute(state, object)?
+                    .ok_or_else(|| E::Missing.into())
+                    .map(|value| (key.to_owned(), value))
+            })
+            .collect::<Result<BTreeMap<_, _>>>()
+            .map(|map| Some(map.into()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::expression::{Noop, Path};
+
+    #[test]
+    fn map() {
+        let cases = vec![
+            (Ok(Some(BTreeMap::new().into())), vec![]),
+            (
+                Ok(Some(
+                    vec![
+                        ("foo".to_owned(), Value::from("bar")),
+                        ("baz".to_owned(), Value::from(1)),
+                    ]
+                    .into_iter()
+                    .collect::<BTreeMap<_, _>>()
+                    .into(),
+                )),
+                vec![
+                    ("foo", Expr::from(Literal::from("bar"))),
+                    ("baz", Expr::from(Literal::from(1))),
+                ],
+            ),
+            (
+                Err("expected expression, got none".to_owned()),
+                vec![("foo", Expr::from(Noop))],
+            ),
+            (
+                Err("path error".to_owned()),
+                vec![("foo", Expr::from(Path::from("missing")))],
+            ),
+        ];
+
+        let mut state = State::default();
+        let mut object = std::collections::HashMap::default();
+
+        for (exp, expressions) in cases {
+            let map = Map::new(
+                expressions
+                    .into_iter()
+                    .map(|(k, v)| (k.to_owned(), v))
+                    .collect(),
+            );
+
+            let got = map
+                .execute(&mut state, &mut object)
+                .map_err(|e| e.to_string());
+
+            assert_eq!(got, exp);
+        }
+    }
+}
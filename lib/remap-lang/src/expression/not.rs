@@ -1,5 +1,5 @@
 use super::Error as E;
-use crate::{value, Expr, Expression, Object, Result, State, Value};
+use crate::{value, Expr, Expression, Literal, Object, Result, State, Value};
 
 #[derive(thiserror::Error, Debug, PartialEq)]
 pub enum Error {
@@ -16,6 +16,17 @@ impl Not {
     pub fn new(expression: Box<Expr>) -> Self {
         Self { expression }
     }
+
+    /// Negates the expression at compile-time if it is a literal boolean.
+    pub(crate) fn fold(self) -> Expr {
+        if self.expression.as_value().is_some() {
+            if let Some(value) = super::evaluate(&self) {
+                return Literal::from(value).into();
+            }
+        }
+
+        self.into()
+    }
 }
 
 impl Expression for Not {
@@ -10,45 +10,102 @@ pub enum Error {
     Resolve(String),
 }
 
-#[derive(Debug, Clone)]
-pub struct Path {
+/// The segments of a path, parsed once at compile-time.
+///
+/// Next to the raw segments, this keeps the dot-escaped segments and their
+/// string representation, so that [`Object`] implementations don't have to
+/// rebuild them every time the path is resolved.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Segments {
     // TODO: Switch to String once Event API is cleaned up.
     segments: Vec<Vec<String>>,
+    escaped: Vec<Vec<String>>,
+    string: String,
 }
 
-impl<T: AsRef<str>> From<T> for Path {
-    fn from(v: T) -> Self {
+impl Segments {
+    pub fn new(segments: Vec<Vec<String>>) -> Self {
+        let escaped = segments
+            .iter()
+            .map(|c| c.iter().map(|p| p.replace(".", "\\.")).collect::<Vec<_>>())
+            .collect::<Vec<_>>();
+
+        let string = escaped
+            .iter()
+            .map(|c| c.join("."))
+            .collect::<Vec<_>>()
+            .join(".");
+
         Self {
-            segments: vec![vec![v.as_ref().to_owned()]],
+            segments,
+            escaped,
+            string,
         }
     }
+
+    /// The segments as written in the program.
+    pub fn segments(&self) -> &[Vec<String>] {
+        &self.segments
+    }
+
+    /// The segments, with any dots escaped as `\.`.
+    pub fn escaped(&self) -> &[Vec<String>] {
+        &self.escaped
+    }
+
+    /// The escaped segments joined into a single dot-delimited path.
+    pub fn as_str(&self) -> &str {
+        &self.string
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Path {
+    segments: Segments,
+}
+
+impl<T: AsRef<str>> From<T> for Path {
+    fn from(v: T) -> Self {
+        Self::new(vec![vec![v.as_ref().to_owned()]])
+    }
 }
 
 impl Path {
     pub(crate) fn new(segments: Vec<Vec<String>>) -> Self {
-        Self { segments }
+        Self {
+            segments: Segments::new(segments),
+        }
     }
 }
 
 impl Expression for Path {
     fn execute(&self, _: &mut State, object: &mut dyn Object) -> Result<Option<Value>> {
         object
-            .find(&self.segments)
+            .find_segments(&self.segments)
             .map_err(|e| E::from(Error::Resolve(e)))?
-            .ok_or_else(|| E::from(Error::Missing(segments_to_path(&self.segments))).into())
+            .ok_or_else(|| E::from(Error::Missing(self.segments.as_str().to_owned())).into())
             .map(Some)
     }
 }
 
-fn segments_to_path(segments: &[Vec<String>]) -> String {
-    segments
-        .iter()
-        .map(|c| {
-            c.iter()
-                .map(|p| p.replace(".", "\\."))
-                .collect::<Vec<_>>()
-                .join(".")
-        })
-        .collect::<Vec<_>>()
-        .join(".")
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn segments() {
+        let segments = Segments::new(vec![
+            vec!["foo[2]".to_owned()],
+            vec!["bar.baz".to_owned(), "qux".to_owned()],
+        ]);
+
+        assert_eq!(
+            segments.escaped(),
+            &[
+                vec!["foo[2]".to_owned()],
+                vec!["bar\\.baz".to_owned(), "qux".to_owned()],
+            ][..]
+        );
+        assert_eq!(segments.as_str(), "foo[2].bar\\.baz.qux");
+    }
 }
@@ -13,7 +13,7 @@ use operator::Operator;
 
 pub mod prelude;
 pub use error::{Error, RemapError};
-pub use expression::{Expression, Literal, Noop, Path};
+pub use expression::{Expression, Literal, Noop, Path, Segments};
 pub use function::{Argument, ArgumentList, Function, Parameter};
 pub use program::Program;
 pub use runtime::Runtime;
@@ -67,6 +67,26 @@ pub trait Object: std::fmt::Debug {
     /// See [`Object::insert`] for more details.
     fn find(&self, path: &[Vec<String>]) -> std::result::Result<Option<Value>, String>;
 
+    /// Insert a given [`Value`] at a path parsed ahead of time.
+    ///
+    /// This is what compiled programs call. It defaults to [`Object::insert`],
+    /// but can be overridden to make use of the pre-escaped representations
+    /// kept by [`Segments`].
+    fn insert_segments(
+        &mut self,
+        path: &Segments,
+        value: Value,
+    ) -> std::result::Result<(), String> {
+        self.insert(path.segments(), value)
+    }
+
+    /// Find a value for a path parsed ahead of time.
+    ///
+    /// See [`Object::insert_segments`] for more details.
+    fn find_segments(&self, path: &Segments) -> std::result::Result<Option<Value>, String> {
+        self.find(path.segments())
+    }
+
     /// Get the list of paths in the object.
     ///
     /// Paths are represented similar to what's documented in [`Object::insert`].
@@ -129,7 +149,7 @@ fn vec_path_to_string(path: &[Vec<String>]) -> String {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use std::collections::HashMap;
+    use std::collections::{BTreeMap, HashMap};
 
     #[derive(Debug, Clone)]
     struct RegexPrinter;
@@ -227,6 +247,34 @@ mod tests {
                 r#"if false { 1 } else if false { 2 } else if false { 3 } else { 4 }"#,
                 Ok(Some(4.into())),
             ),
+            (
+                r#"{ "foo": "bar", "baz": 1 + 1 }"#,
+                Ok(Some(
+                    vec![
+                        ("foo".to_owned(), Value::from("bar")),
+                        ("baz".to_owned(), Value::from(2)),
+                    ]
+                    .into_iter()
+                    .collect::<BTreeMap<_, _>>()
+                    .into(),
+                )),
+            ),
+            (
+                r#"$foo = true
+                   .bar = {
+                       "foo": $foo,
+                       "empty": {},
+                   }"#,
+                Ok(Some(
+                    vec![
+                        ("foo".to_owned(), Value::from(true)),
+                        ("empty".to_owned(), BTreeMap::new().into()),
+                    ]
+                    .into_iter()
+                    .collect::<BTreeMap<_, _>>()
+                    .into(),
+                )),
+            ),
             (
                 r#"regex_printer(/escaped\/forward slash/)"#,
                 Ok(Some("regex: escaped/forward slash".into())),
@@ -243,4 +291,43 @@ mod tests {
             assert_eq!(expectation, result);
         }
     }
+
+    #[test]
+    fn constant_folding() {
+        let cases = vec![
+            (r#"1 + 2 * 3"#, Some(Value::from(7))),
+            (r#""foo" + "bar""#, Some("foobar".into())),
+            (r#"!(1 == 2)"#, Some(true.into())),
+            (r#"if 1 > 2 { "yes" } else { "no" }"#, Some("no".into())),
+            (
+                r#"{ "foo": 1 + 1 }"#,
+                Some(
+                    vec![("foo".to_owned(), Value::from(2))]
+                        .into_iter()
+                        .collect::<BTreeMap<_, _>>()
+                        .into(),
+                ),
+            ),
+            (
+                r#"{ "ignored"
+                  false || true }"#,
+                Some(true.into()),
+            ),
+            // Not folded, as these depend on the object or fail at runtime.
+            (r#".foo + 1"#, None),
+            (r#"true + 1"#, None),
+            (r#"if .foo { "yes" }"#, None),
+            (r#"{ "foo": .foo }"#, None),
+        ];
+
+        for (script, expectation) in cases {
+            let program = Program::new(script, &[]).unwrap();
+            let folded = match program.expressions.as_slice() {
+                [expression] => expression.as_value().cloned(),
+                _ => panic!("expected a single expression: {}", script),
+            };
+
+            assert_eq!(expectation, folded, "{}", script);
+        }
+    }
 }
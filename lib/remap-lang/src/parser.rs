@@ -2,13 +2,14 @@
 
 use crate::{
     expression::{
-        Arithmetic, Assignment, Block, Function, IfStatement, Literal, Noop, Not, Path, Target,
-        Variable,
+        Arithmetic, Assignment, Block, Function, IfStatement, Literal, Map, Noop, Not, Path,
+        Segments, Target, Variable,
     },
     Argument, Error, Expr, Function as Fn, Operator, Result, Value,
 };
 use pest::iterators::{Pair, Pairs};
 use regex::{Regex, RegexBuilder};
+use std::collections::BTreeMap;
 use std::str::FromStr;
 
 #[derive(pest_derive::Parser)]
@@ -39,7 +40,7 @@ macro_rules! operation_fns {
                                     Box::new(lhs),
                                     Box::new(self.[<$next _from_pairs>](pair.into_inner())?),
                                     op.clone(),
-                                ));
+                                )).fold();
                             }
                         }
                     }
@@ -105,9 +106,9 @@ impl Parser<'_> {
                     .as_str()
                     .to_owned(),
             )),
-            R::path => Ok(Target::Path(
+            R::path => Ok(Target::Path(Segments::new(
                 self.path_segments_from_pairs(pair.into_inner())?,
-            )),
+            ))),
             _ => Err(e(R::target)),
         }
     }
@@ -120,7 +121,7 @@ impl Parser<'_> {
             expressions.push(self.expression_from_pair(pair)?);
         }
 
-        Ok(Block::new(expressions).into())
+        Ok(Expr::from(Block::new(expressions)).fold())
     }
 
     /// Parse if-statement expressions.
@@ -162,14 +163,16 @@ impl Parser<'_> {
                 Box::new(conditional),
                 Box::new(true_expression),
                 Box::new(false_expression),
-            ));
+            ))
+            .fold();
         }
 
         Ok(Expr::from(IfStatement::new(
             Box::new(conditional),
             Box::new(true_expression),
             Box::new(false_expression),
-        )))
+        ))
+        .fold())
     }
 
     /// Parse not operator, or fall-through to primary values or function calls.
@@ -187,7 +190,7 @@ impl Parser<'_> {
         }
 
         if count % 2 != 0 {
-            expression = Expr::from(Not::new(Box::new(expression)))
+            expression = Expr::from(Not::new(Box::new(expression))).fold()
         }
 
         Ok(expression)
@@ -202,10 +205,30 @@ impl Parser<'_> {
             R::variable => self.variable_from_pair(pair),
             R::path => self.path_from_pair(pair),
             R::group => self.expression_from_pair(pair.into_inner().next().ok_or(e(R::group))?),
+            R::map => self.map_from_pair(pair),
             _ => Err(e(R::primary)),
         }
     }
 
+    /// Parse a [`Map`] expression, e.g. `{ "foo": .bar }`
+    fn map_from_pair(&self, pair: Pair<R>) -> Result<Expr> {
+        let mut expressions = BTreeMap::new();
+
+        for entry in pair.into_inner() {
+            let mut inner = entry.into_inner();
+            let key = inner
+                .next()
+                .and_then(|pair| pair.into_inner().next())
+                .ok_or(e(R::map_entry))?;
+            let key = self.escaped_string_from_pair(key)?;
+            let value = self.expression_from_pair(inner.next().ok_or(e(R::map_entry))?)?;
+
+            expressions.insert(key, value);
+        }
+
+        Ok(Expr::from(Map::new(expressions)).fold())
+    }
+
     /// Parse a [`Value`] into a [`Literal`] expression.
     fn value_from_pair(&self, pair: Pair<R>) -> Result<Expr> {
         Ok(match pair.as_rule() {
@@ -465,8 +488,9 @@ mod tests {
                 ],
             ),
             (
+                // The block is parsed as the start of a map literal.
                 "if { del(.foo) } else { del(.bar) }",
-                vec![" 1:4\n", "= expected not"],
+                vec![" 1:6\n", "= expected string"],
             ),
             (
                 "if .foo > .bar { del(.foo) } else { .bar = .baz",
@@ -7,6 +7,10 @@ use pest::Parser;
 ///
 /// You can create a program using [`Program::from_str`]. The provided string
 /// will be parsed. If parsing fails, an [`Error`] is returned.
+///
+/// While parsing, parts of the program that don't depend on the object it
+/// runs against are evaluated once, such as arithmetic on literals, or
+/// if-statements with a literal condition.
 #[derive(Debug, Clone)]
 pub struct Program {
     pub(crate) expressions: Vec<Expr>,
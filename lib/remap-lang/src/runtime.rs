@@ -17,13 +17,14 @@ impl Runtime {
         object: &mut impl Object,
         program: &Program,
     ) -> Result<Option<Value>, RemapError> {
-        let mut values = program
-            .expressions
-            .iter()
-            .map(|expression| expression.execute(&mut self.state, object))
-            .collect::<crate::Result<Vec<Option<Value>>>>()
-            .map_err(RemapError)?;
+        let mut value = None;
 
-        Ok(values.pop().flatten())
+        for expression in &program.expressions {
+            value = expression
+                .execute(&mut self.state, object)
+                .map_err(RemapError)?;
+        }
+
+        Ok(value)
     }
 }
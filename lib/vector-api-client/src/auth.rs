@@ -0,0 +1,20 @@
+/// Credentials for an API server that requires authentication
+#[derive(Debug, Clone)]
+pub enum Auth {
+    /// HTTP basic authentication
+    Basic { user: String, password: String },
+    /// A bearer token
+    Bearer { token: String },
+}
+
+impl Auth {
+    /// Returns the value of the `Authorization` header carrying these credentials
+    pub fn header_value(&self) -> String {
+        match self {
+            Auth::Basic { user, password } => {
+                format!("Basic {}", base64::encode(format!("{}:{}", user, password)))
+            }
+            Auth::Bearer { token } => format!("Bearer {}", token),
+        }
+    }
+}
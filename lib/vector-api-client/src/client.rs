@@ -1,3 +1,4 @@
+use crate::Auth;
 use anyhow::Context;
 use graphql_client::GraphQLQuery;
 use url::Url;
@@ -10,12 +11,19 @@ pub type QueryResult<T> =
 #[derive(Debug)]
 pub struct Client {
     url: Url,
+    auth: Option<Auth>,
 }
 
 impl Client {
     /// Returns a new GraphQL query client, bound to the provided URL
     pub fn new(url: Url) -> Self {
-        Self { url }
+        Self { url, auth: None }
+    }
+
+    /// Returns a new GraphQL query client, bound to the provided URL and sending `auth`
+    /// with every query
+    pub fn new_with_auth(url: Url, auth: Option<Auth>) -> Self {
+        Self { url, auth }
     }
 
     /// Issue a GraphQL query using Reqwest, serializing the response to the associated
@@ -26,9 +34,12 @@ impl Client {
     ) -> QueryResult<T> {
         let client = reqwest::Client::new();
 
-        client
-            .post(self.url.clone())
-            .json(request_body)
+        let mut request = client.post(self.url.clone()).json(request_body);
+        if let Some(auth) = &self.auth {
+            request = request.header(reqwest::header::AUTHORIZATION, auth.header_value());
+        }
+
+        request
             .send()
             .await
             .with_context(|| {
@@ -38,6 +49,8 @@ impl Client {
                     &self.url.as_str()
                 )
             })?
+            .error_for_status()
+            .with_context(|| format!("Couldn't query {}", &self.url.as_str()))?
             .json()
             .await
             .with_context(|| {
@@ -0,0 +1,50 @@
+//! Event subscriptions
+
+use crate::SubscriptionResult;
+use async_trait::async_trait;
+use graphql_client::GraphQLQuery;
+
+/// OutputEventsSubscription samples the events leaving the components matching
+/// the given names or globs
+#[derive(GraphQLQuery, Debug, Copy, Clone)]
+#[graphql(
+    schema_path = "graphql/schema.json",
+    query_path = "graphql/subscriptions/output_events.graphql",
+    response_derives = "Debug"
+)]
+pub struct OutputEventsSubscription;
+
+/// Extension methods for event subscriptions
+#[async_trait]
+pub trait EventsSubscriptionExt {
+    /// Executes an output events subscription
+    async fn output_events_subscription(
+        &self,
+        component_names: Vec<String>,
+        interval: i64,
+        limit: i64,
+        encoding: output_events_subscription::EventEncodingType,
+    ) -> crate::SubscriptionResult<OutputEventsSubscription>;
+}
+
+#[async_trait]
+impl EventsSubscriptionExt for crate::SubscriptionClient {
+    /// Executes an output events subscription
+    async fn output_events_subscription(
+        &self,
+        component_names: Vec<String>,
+        interval: i64,
+        limit: i64,
+        encoding: output_events_subscription::EventEncodingType,
+    ) -> SubscriptionResult<OutputEventsSubscription> {
+        let request_body =
+            OutputEventsSubscription::build_query(output_events_subscription::Variables {
+                component_names,
+                interval,
+                limit,
+                encoding,
+            });
+
+        self.start::<OutputEventsSubscription>(&request_body).await
+    }
+}
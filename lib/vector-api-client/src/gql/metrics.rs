@@ -24,6 +24,35 @@ pub struct UptimeSubscription;
 )]
 pub struct EventsProcessedTotalSubscription;
 
+/// ComponentEventsInThroughputsSubscription contains the events received per second by
+/// each transform and sink
+#[derive(GraphQLQuery, Debug, Copy, Clone)]
+#[graphql(
+    schema_path = "graphql/schema.json",
+    query_path = "graphql/subscriptions/component_events_in_throughputs.graphql",
+    response_derives = "Debug"
+)]
+pub struct ComponentEventsInThroughputsSubscription;
+
+/// ComponentEventsOutThroughputsSubscription contains the events sent per second by
+/// each source and transform
+#[derive(GraphQLQuery, Debug, Copy, Clone)]
+#[graphql(
+    schema_path = "graphql/schema.json",
+    query_path = "graphql/subscriptions/component_events_out_throughputs.graphql",
+    response_derives = "Debug"
+)]
+pub struct ComponentEventsOutThroughputsSubscription;
+
+/// ComponentErrorsTotalsSubscription contains the errors of all kinds of each component
+#[derive(GraphQLQuery, Debug, Copy, Clone)]
+#[graphql(
+    schema_path = "graphql/schema.json",
+    query_path = "graphql/subscriptions/component_errors_totals.graphql",
+    response_derives = "Debug"
+)]
+pub struct ComponentErrorsTotalsSubscription;
+
 /// Extension methods for metrics subscriptions
 #[async_trait]
 pub trait MetricsSubscriptionExt {
@@ -35,6 +64,24 @@ pub trait MetricsSubscriptionExt {
         &self,
         interval: i64,
     ) -> crate::SubscriptionResult<EventsProcessedTotalSubscription>;
+
+    /// Executes a subscription to the events received per second by each component
+    async fn component_events_in_throughputs_subscription(
+        &self,
+        interval: i64,
+    ) -> crate::SubscriptionResult<ComponentEventsInThroughputsSubscription>;
+
+    /// Executes a subscription to the events sent per second by each component
+    async fn component_events_out_throughputs_subscription(
+        &self,
+        interval: i64,
+    ) -> crate::SubscriptionResult<ComponentEventsOutThroughputsSubscription>;
+
+    /// Executes a subscription to the errors of each component
+    async fn component_errors_totals_subscription(
+        &self,
+        interval: i64,
+    ) -> crate::SubscriptionResult<ComponentErrorsTotalsSubscription>;
 }
 
 #[async_trait]
@@ -58,4 +105,43 @@ impl MetricsSubscriptionExt for crate::SubscriptionClient {
         self.start::<EventsProcessedTotalSubscription>(&request_body)
             .await
     }
+
+    /// Executes a subscription to the events received per second by each component
+    async fn component_events_in_throughputs_subscription(
+        &self,
+        interval: i64,
+    ) -> SubscriptionResult<ComponentEventsInThroughputsSubscription> {
+        let request_body = ComponentEventsInThroughputsSubscription::build_query(
+            component_events_in_throughputs_subscription::Variables { interval },
+        );
+
+        self.start::<ComponentEventsInThroughputsSubscription>(&request_body)
+            .await
+    }
+
+    /// Executes a subscription to the events sent per second by each component
+    async fn component_events_out_throughputs_subscription(
+        &self,
+        interval: i64,
+    ) -> SubscriptionResult<ComponentEventsOutThroughputsSubscription> {
+        let request_body = ComponentEventsOutThroughputsSubscription::build_query(
+            component_events_out_throughputs_subscription::Variables { interval },
+        );
+
+        self.start::<ComponentEventsOutThroughputsSubscription>(&request_body)
+            .await
+    }
+
+    /// Executes a subscription to the errors of each component
+    async fn component_errors_totals_subscription(
+        &self,
+        interval: i64,
+    ) -> SubscriptionResult<ComponentErrorsTotalsSubscription> {
+        let request_body = ComponentErrorsTotalsSubscription::build_query(
+            component_errors_totals_subscription::Variables { interval },
+        );
+
+        self.start::<ComponentErrorsTotalsSubscription>(&request_body)
+            .await
+    }
 }
@@ -1,9 +1,11 @@
 //! Queries, subscriptions, and extension methods for executing them
 
 mod components;
+mod events;
 mod health;
 mod metrics;
 
 pub use components::*;
+pub use events::*;
 pub use health::*;
 pub use metrics::*;
@@ -11,10 +11,12 @@
 
 #![deny(missing_debug_implementations, missing_copy_implementations)]
 
+mod auth;
 mod client;
 /// GraphQL queries
 pub mod gql;
 mod subscription;
 
+pub use auth::*;
 pub use client::*;
 pub use subscription::*;
@@ -1,3 +1,4 @@
+use crate::Auth;
 use futures::{SinkExt, Stream};
 use graphql_client::GraphQLQuery;
 use serde::{Deserialize, Serialize};
@@ -17,7 +18,12 @@ use tokio::{
 };
 use tokio_tungstenite::{
     connect_async,
-    tungstenite::{self, Message},
+    tungstenite::{
+        self,
+        client::IntoClientRequest,
+        http::{self, header::AUTHORIZATION, HeaderValue},
+        Message,
+    },
     WebSocketStream,
 };
 use url::Url;
@@ -241,7 +247,22 @@ impl SubscriptionClient {
 pub async fn connect_subscription_client(
     url: &Url,
 ) -> Result<SubscriptionClient, tungstenite::error::Error> {
-    let (tx, _) = connect_async(url).await?;
+    connect_subscription_client_with_auth(url, None).await
+}
+
+/// Connect to a GraphQL subscription endpoint that requires authentication, sending `auth`
+/// when opening the connection
+pub async fn connect_subscription_client_with_auth(
+    url: &Url,
+    auth: Option<&Auth>,
+) -> Result<SubscriptionClient, tungstenite::error::Error> {
+    let mut request = url.into_client_request()?;
+    if let Some(auth) = auth {
+        let value = HeaderValue::from_str(&auth.header_value()).map_err(http::Error::from)?;
+        request.headers_mut().insert(AUTHORIZATION, value);
+    }
+
+    let (tx, _) = connect_async(request).await?;
     let client = SubscriptionClient::new(tx);
 
     Ok(client)
@@ -0,0 +1,24 @@
+//! The flush handles of the running sinks, through which the API flushes
+//! their batches.
+
+use crate::sinks::util::flush::FlushHandle;
+use lazy_static::lazy_static;
+use std::{collections::HashMap, sync::RwLock};
+
+lazy_static! {
+    static ref HANDLES: RwLock<HashMap<String, FlushHandle>> = RwLock::new(HashMap::new());
+}
+
+/// Replaces the flush handles with those of the running topology.
+pub fn update(handles: &HashMap<String, FlushHandle>) {
+    *HANDLES.write().expect("Flush handles lock poisoned") = handles.clone();
+}
+
+/// The flush handle of the sink `name`.
+pub fn get(name: &str) -> Option<FlushHandle> {
+    HANDLES
+        .read()
+        .expect("Flush handles lock poisoned")
+        .get(name)
+        .cloned()
+}
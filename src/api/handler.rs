@@ -1,8 +1,26 @@
 use serde_json::json;
 use warp::reply::json;
-use warp::{Rejection, Reply};
+use warp::{http::StatusCode, reject::Reject, Rejection, Reply};
+
+/// Rejection of a request that isn't authenticated as `api.auth` requires
+#[derive(Debug)]
+pub struct Unauthorized;
+
+impl Reject for Unauthorized {}
 
 // Health handler, responds with { ok: true }
 pub async fn health() -> Result<impl Reply, Rejection> {
     Ok(json(&json!({"ok": true})))
 }
+
+// Responds to unauthenticated requests with a 401, passing on any other rejection
+pub async fn unauthorized(rejection: Rejection) -> Result<impl Reply, Rejection> {
+    if rejection.find::<Unauthorized>().is_some() {
+        Ok(warp::reply::with_status(
+            json(&json!({"error": "Unauthorized"})),
+            StatusCode::UNAUTHORIZED,
+        ))
+    } else {
+        Err(rejection)
+    }
+}
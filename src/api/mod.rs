@@ -1,6 +1,9 @@
+mod flushes;
 mod handler;
+mod outputs;
 mod schema;
 mod server;
+mod tap;
 
 pub use schema::build_schema;
 pub use server::Server;
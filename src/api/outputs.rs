@@ -0,0 +1,36 @@
+//! The outputs of the running components, through which the API taps,
+//! pauses and resumes them.
+
+use crate::topology::fanout::ControlChannel;
+use glob::Pattern;
+use lazy_static::lazy_static;
+use std::{collections::HashMap, sync::RwLock};
+
+lazy_static! {
+    static ref OUTPUTS: RwLock<HashMap<String, ControlChannel>> = RwLock::new(HashMap::new());
+}
+
+/// Replaces the outputs with those of the running topology.
+pub fn update(outputs: &HashMap<String, ControlChannel>) {
+    *OUTPUTS.write().expect("Outputs lock poisoned") = outputs.clone();
+}
+
+/// The output of the component `name`.
+pub fn get(name: &str) -> Option<ControlChannel> {
+    OUTPUTS
+        .read()
+        .expect("Outputs lock poisoned")
+        .get(name)
+        .cloned()
+}
+
+/// The outputs of the components matching any of `patterns`.
+pub fn matching(patterns: &[Pattern]) -> Vec<(String, ControlChannel)> {
+    OUTPUTS
+        .read()
+        .expect("Outputs lock poisoned")
+        .iter()
+        .filter(|(name, _)| patterns.iter().any(|pattern| pattern.matches(name)))
+        .map(|(name, control)| (name.clone(), control.clone()))
+        .collect()
+}
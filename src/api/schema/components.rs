@@ -1,6 +1,6 @@
-use super::{broker::Broker, metrics};
-use crate::config::{Config, DataType};
-use async_graphql::{Enum, Interface, Object, Subscription};
+use super::{broker::Broker, health, metrics};
+use crate::config::{redacted_json, Config, DataType};
+use async_graphql::{Enum, Interface, Object, SimpleObject, Subscription};
 use lazy_static::lazy_static;
 use std::{
     collections::{HashMap, HashSet},
@@ -31,6 +31,8 @@ impl From<DataType> for SourceOutputType {
 #[derive(Clone)]
 pub struct SourceData {
     name: String,
+    component_type: String,
+    config: String,
     output_type: DataType,
 }
 
@@ -44,6 +46,16 @@ impl Source {
         self.0.name.clone()
     }
 
+    /// Source type, e.g. `file`
+    async fn component_type(&self) -> String {
+        self.0.component_type.clone()
+    }
+
+    /// Source configuration, serialized as JSON, with secrets redacted
+    async fn config(&self) -> String {
+        self.0.config.clone()
+    }
+
     /// Source output type
     async fn output_type(&self) -> SourceOutputType {
         self.0.output_type.into()
@@ -69,11 +81,28 @@ impl Source {
     async fn events_processed_total(&self) -> Option<metrics::EventsProcessedTotal> {
         metrics::component_events_processed_total(self.0.name.clone())
     }
+
+    /// Errors of all kinds of the current source
+    async fn errors_total(&self) -> Option<metrics::ErrorsTotal> {
+        metrics::component_errors_total(&self.0.name)
+    }
+
+    /// Bytes received by the current source
+    async fn received_bytes_total(&self) -> Option<metrics::ReceivedBytesTotal> {
+        metrics::component_received_bytes_total(&self.0.name)
+    }
+
+    /// Events sent per second by the current source, over the last second
+    async fn events_out_throughput(&self) -> Option<f64> {
+        metrics::component_throughput(&self.0.name, "events_out_total")
+    }
 }
 
 #[derive(Clone)]
 pub struct InputsData {
     name: String,
+    component_type: String,
+    config: String,
     inputs: Vec<String>,
 }
 
@@ -87,6 +116,21 @@ impl Transform {
         self.0.name.clone()
     }
 
+    /// Transform type, e.g. `remap`
+    async fn component_type(&self) -> String {
+        self.0.component_type.clone()
+    }
+
+    /// Transform configuration, serialized as JSON, with secrets redacted
+    async fn config(&self) -> String {
+        self.0.config.clone()
+    }
+
+    /// Names of the transform's inputs
+    async fn inputs(&self) -> Vec<String> {
+        self.0.inputs.clone()
+    }
+
     /// Source inputs
     async fn sources(&self) -> Vec<Source> {
         self.0
@@ -114,6 +158,21 @@ impl Transform {
     async fn events_processed_total(&self) -> Option<metrics::EventsProcessedTotal> {
         metrics::component_events_processed_total(self.0.name.clone())
     }
+
+    /// Errors of all kinds of the current transform
+    async fn errors_total(&self) -> Option<metrics::ErrorsTotal> {
+        metrics::component_errors_total(&self.0.name)
+    }
+
+    /// Events received per second by the current transform, over the last second
+    async fn events_in_throughput(&self) -> Option<f64> {
+        metrics::component_throughput(&self.0.name, "events_in_total")
+    }
+
+    /// Events sent per second by the current transform, over the last second
+    async fn events_out_throughput(&self) -> Option<f64> {
+        metrics::component_throughput(&self.0.name, "events_out_total")
+    }
 }
 
 #[derive(Clone)]
@@ -126,6 +185,21 @@ impl Sink {
         self.0.name.clone()
     }
 
+    /// Sink type, e.g. `elasticsearch`
+    async fn component_type(&self) -> String {
+        self.0.component_type.clone()
+    }
+
+    /// Sink configuration, serialized as JSON, with secrets redacted
+    async fn config(&self) -> String {
+        self.0.config.clone()
+    }
+
+    /// Names of the sink's inputs
+    async fn inputs(&self) -> Vec<String> {
+        self.0.inputs.clone()
+    }
+
     /// Source inputs
     async fn sources(&self) -> Vec<Source> {
         self.0
@@ -160,15 +234,54 @@ impl Sink {
     async fn events_processed_total(&self) -> Option<metrics::EventsProcessedTotal> {
         metrics::component_events_processed_total(self.0.name.clone())
     }
+
+    /// Sink health, as reported by its circuit breaker
+    async fn health(&self) -> health::HealthStatus {
+        health::sink_health(&self.0.name)
+    }
+
+    /// Errors of all kinds of the current sink
+    async fn errors_total(&self) -> Option<metrics::ErrorsTotal> {
+        metrics::component_errors_total(&self.0.name)
+    }
+
+    /// Bytes sent by the current sink
+    async fn sent_bytes_total(&self) -> Option<metrics::SentBytesTotal> {
+        metrics::component_sent_bytes_total(&self.0.name)
+    }
+
+    /// Events received per second by the current sink, over the last second
+    async fn events_in_throughput(&self) -> Option<f64> {
+        metrics::component_throughput(&self.0.name, "events_in_total")
+    }
+
+    /// Fill level of the current sink's buffer
+    async fn buffer(&self) -> Option<metrics::BufferUsage> {
+        metrics::component_buffer_usage(&self.0.name)
+    }
+
+    /// Requests in flight of the current sink
+    async fn requests_in_flight(&self) -> Option<i64> {
+        metrics::component_requests_in_flight(&self.0.name)
+    }
+
+    /// Current concurrency limit of the current sink, set by the adaptive
+    /// concurrency controller
+    async fn concurrency_limit(&self) -> Option<i64> {
+        metrics::component_concurrency_limit(&self.0.name)
+    }
 }
 
 #[derive(Clone, Interface)]
 #[graphql(
     field(name = "name", type = "String"),
+    field(name = "component_type", type = "String"),
+    field(name = "config", type = "String"),
     field(
         name = "events_processed_total",
         type = "Option<metrics::EventsProcessedTotal>"
-    )
+    ),
+    field(name = "errors_total", type = "Option<metrics::ErrorsTotal>")
 )]
 pub enum Component {
     Source(Source),
@@ -176,6 +289,61 @@ pub enum Component {
     Sink(Sink),
 }
 
+impl Component {
+    /// Named apart from the interface's `name` resolver
+    fn component_name(&self) -> &str {
+        match self {
+            Component::Source(s) => &s.0.name,
+            Component::Transform(t) => &t.0.name,
+            Component::Sink(s) => &s.0.name,
+        }
+    }
+
+    fn input_names(&self) -> &[String] {
+        match self {
+            Component::Source(_) => &[],
+            Component::Transform(t) => &t.0.inputs,
+            Component::Sink(s) => &s.0.inputs,
+        }
+    }
+}
+
+#[derive(SimpleObject)]
+pub struct Edge {
+    /// Name of the component sending events
+    from: String,
+    /// Name of the component receiving them
+    to: String,
+}
+
+pub struct Graph {
+    components: Vec<Component>,
+}
+
+#[Object]
+impl Graph {
+    /// Components of the topology
+    async fn nodes(&self) -> Vec<Component> {
+        self.components.clone()
+    }
+
+    /// Connections between the components, from each input to the component it feeds
+    async fn edges(&self) -> Vec<Edge> {
+        let mut edges = self
+            .components
+            .iter()
+            .flat_map(|component| {
+                component.input_names().iter().map(move |input| Edge {
+                    from: input.clone(),
+                    to: component.component_name().to_owned(),
+                })
+            })
+            .collect::<Vec<_>>();
+        edges.sort_by(|a, b| (&a.from, &a.to).cmp(&(&b.from, &b.to)));
+        edges
+    }
+}
+
 #[derive(Clone)]
 pub struct ComponentAdded(Component);
 
@@ -211,6 +379,13 @@ impl ComponentsQuery {
     async fn sinks(&self) -> Vec<Sink> {
         get_sinks()
     }
+
+    /// Configured topology, as a graph of the components and their connections
+    async fn graph(&self) -> Graph {
+        let mut components = filter_components(|(_name, components)| Some(components.clone()));
+        components.sort_by(|a, b| a.component_name().cmp(b.component_name()));
+        Graph { components }
+    }
 }
 
 #[derive(Default)]
@@ -259,6 +434,14 @@ fn get_sinks() -> Vec<Sink> {
     })
 }
 
+/// Returns the names of the current sinks
+pub fn sink_names() -> Vec<String> {
+    filter_components(|(name, components)| match components {
+        Component::Sink(_) => Some(name.clone()),
+        _ => None,
+    })
+}
+
 /// Returns the current component names as a HashSet
 fn get_component_names() -> HashSet<String> {
     COMPONENTS
@@ -269,6 +452,14 @@ fn get_component_names() -> HashSet<String> {
         .collect::<HashSet<String>>()
 }
 
+/// Returns whether the named component is a source
+pub fn is_source(name: &str) -> bool {
+    matches!(
+        COMPONENTS.read().expect(INVARIANT).get(name),
+        Some(Component::Source(_))
+    )
+}
+
 /// Update the 'global' configuration that will be consumed by component queries
 pub fn update_config(config: &Config) {
     let mut new_components = HashMap::new();
@@ -279,6 +470,8 @@ pub fn update_config(config: &Config) {
             name.to_owned(),
             Component::Source(Source(SourceData {
                 name: name.to_owned(),
+                component_type: source.source_type().to_owned(),
+                config: redacted_json(source),
                 output_type: source.output_type(),
             })),
         );
@@ -290,6 +483,8 @@ pub fn update_config(config: &Config) {
             name.to_string(),
             Component::Transform(Transform(InputsData {
                 name: name.to_owned(),
+                component_type: transform.inner.transform_type().to_owned(),
+                config: redacted_json(transform),
                 inputs: transform.inputs.clone(),
             })),
         );
@@ -301,6 +496,8 @@ pub fn update_config(config: &Config) {
             name.to_string(),
             Component::Sink(Sink(InputsData {
                 name: name.to_owned(),
+                component_type: sink.inner.sink_type().to_owned(),
+                config: redacted_json(sink),
                 inputs: sink.inputs.clone(),
             })),
         );
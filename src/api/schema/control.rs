@@ -0,0 +1,144 @@
+use super::components;
+use crate::{
+    api::{flushes, outputs},
+    config::{self, api},
+    signal::ReloadRequest,
+    topology::fanout::ControlMessage,
+};
+use async_graphql::{Context, FieldResult, Object, SimpleObject};
+use futures::{channel::mpsc, SinkExt};
+use http::HeaderMap;
+use tokio::sync::oneshot;
+
+/// Whether a request may run mutations, which the server decides for each
+/// request from the `api` options and the request's credentials.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum MutationAccess {
+    Allowed,
+    Disabled,
+    Unauthenticated,
+    Unauthorized,
+}
+
+impl MutationAccess {
+    pub fn check(options: &api::Options, headers: &HeaderMap) -> Self {
+        if !options.allow_mutations {
+            return MutationAccess::Disabled;
+        }
+
+        match &options.auth {
+            None => MutationAccess::Unauthenticated,
+            Some(auth) if auth.verify(headers) => MutationAccess::Allowed,
+            Some(_) => MutationAccess::Unauthorized,
+        }
+    }
+}
+
+/// Fails unless the request may run mutations. Requests the server didn't
+/// check, such as those over a websocket, never may.
+fn authorize(ctx: &Context<'_>) -> FieldResult<()> {
+    match ctx.data_opt::<MutationAccess>() {
+        Some(MutationAccess::Allowed) => Ok(()),
+        Some(MutationAccess::Disabled) => {
+            Err("Mutations are disabled; see `api.allow_mutations`.".into())
+        }
+        Some(MutationAccess::Unauthenticated) => {
+            Err("Mutations require `api.auth` to be configured.".into())
+        }
+        Some(MutationAccess::Unauthorized) | None => Err("Unauthorized.".into()),
+    }
+}
+
+#[derive(SimpleObject)]
+pub struct ConfigReload {
+    /// Whether the configuration was applied
+    applied: bool,
+    /// The errors that prevented the configuration from being applied
+    errors: Vec<String>,
+}
+
+impl From<Result<(), Vec<String>>> for ConfigReload {
+    fn from(result: Result<(), Vec<String>>) -> Self {
+        match result {
+            Ok(()) => Self {
+                applied: true,
+                errors: vec![],
+            },
+            Err(errors) => Self {
+                applied: false,
+                errors,
+            },
+        }
+    }
+}
+
+#[derive(Default)]
+pub struct ControlMutation;
+
+#[Object]
+impl ControlMutation {
+    /// Reloads the configuration from the given TOML, or from the config
+    /// files when omitted, as on SIGHUP
+    async fn reload_config(
+        &self,
+        ctx: &Context<'_>,
+        config: Option<String>,
+    ) -> FieldResult<ConfigReload> {
+        authorize(ctx)?;
+
+        let config = match config {
+            Some(config) => match config::load_from_str(&config) {
+                Ok(config) => Some(config),
+                Err(errors) => {
+                    return Ok(ConfigReload {
+                        applied: false,
+                        errors,
+                    })
+                }
+            },
+            None => None,
+        };
+
+        let (reply, rx) = oneshot::channel();
+        ctx.data_unchecked::<mpsc::Sender<ReloadRequest>>()
+            .clone()
+            .send(ReloadRequest { config, reply })
+            .await
+            .map_err(|_| "Vector is shutting down.")?;
+        let result = rx.await.map_err(|_| "Vector is shutting down.")?;
+
+        Ok(result.into())
+    }
+
+    /// Stops the named source from sending events, applying back pressure
+    /// to it until resumed
+    async fn pause_source(&self, ctx: &Context<'_>, name: String) -> FieldResult<bool> {
+        authorize(ctx)?;
+        send_to_source(&name, ControlMessage::Pause)
+    }
+
+    /// Resumes a paused source
+    async fn resume_source(&self, ctx: &Context<'_>, name: String) -> FieldResult<bool> {
+        authorize(ctx)?;
+        send_to_source(&name, ControlMessage::Resume)
+    }
+
+    /// Sends the open batches of the named sink right away. Only sinks that
+    /// batch events can be flushed
+    async fn flush_sink(&self, ctx: &Context<'_>, name: String) -> FieldResult<bool> {
+        authorize(ctx)?;
+        if flushes::get(&name).map_or(false, |flush| flush.request()) {
+            Ok(true)
+        } else {
+            Err(format!("No batching sink named \"{}\" is running.", name).into())
+        }
+    }
+}
+
+fn send_to_source(name: &str, message: ControlMessage) -> FieldResult<bool> {
+    let control = outputs::get(name).filter(|_| components::is_source(name));
+    match control.map(|control| control.unbounded_send(message)) {
+        Some(Ok(())) => Ok(true),
+        _ => Err(format!("No source named \"{}\" is running.", name).into()),
+    }
+}
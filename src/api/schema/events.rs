@@ -0,0 +1,97 @@
+use crate::{
+    api::tap::{self, TapEvent},
+    event::Event,
+    sinks::util::encoding::{Encoder, StandardEncodings},
+};
+use async_graphql::{validators::IntRange, Enum, FieldResult, SimpleObject, Subscription};
+use async_stream::stream;
+use glob::Pattern;
+use tokio::{stream::Stream, time::Duration};
+
+#[derive(Enum, Eq, PartialEq, Copy, Clone)]
+pub enum EventEncodingType {
+    /// JSON object of the event's fields, or of the metric
+    Json,
+    /// `key=value` pairs of the event's fields. Metrics are rendered as text
+    Logfmt,
+}
+
+impl Default for EventEncodingType {
+    fn default() -> Self {
+        Self::Json
+    }
+}
+
+#[derive(SimpleObject)]
+pub struct OutputEvent {
+    /// Name of the component the event left
+    component_name: String,
+    /// The event, in the requested encoding
+    encoded: String,
+}
+
+impl OutputEvent {
+    fn new(tapped: TapEvent, encoding: EventEncodingType) -> Self {
+        let codec = match (encoding, &tapped.event) {
+            (EventEncodingType::Json, _) => StandardEncodings::Json,
+            (EventEncodingType::Logfmt, Event::Log(_)) => StandardEncodings::Logfmt,
+            (EventEncodingType::Logfmt, Event::Metric(_)) => StandardEncodings::Text,
+        };
+        let encoded = codec
+            .encode_event(tapped.event)
+            .map(|bytes| String::from_utf8_lossy(&bytes).into_owned())
+            .unwrap_or_else(|error| format!("Couldn't encode event: {}", error));
+
+        Self {
+            component_name: tapped.component_name,
+            encoded,
+        }
+    }
+}
+
+#[derive(Default)]
+pub struct EventsSubscription;
+
+#[Subscription]
+impl EventsSubscription {
+    /// A sample of the events leaving the components matching any of the
+    /// given names, which can be globs. At most `limit` events are sent every
+    /// `interval` milliseconds, and the rest are skipped
+    async fn output_events(
+        &self,
+        component_names: Vec<String>,
+        #[graphql(default = 500, validator(IntRange(min = "100", max = "60_000")))] interval: i32,
+        #[graphql(default = 100, validator(IntRange(min = "1", max = "10_000")))] limit: i32,
+        #[graphql(default)] encoding: EventEncodingType,
+    ) -> FieldResult<impl Stream<Item = OutputEvent>> {
+        let patterns = component_names
+            .iter()
+            .map(|name| Pattern::new(name))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let limit = limit as usize;
+        let (tap, mut rx) = tap::tap(&patterns, limit);
+        if tap.is_empty() {
+            return Err("No running source or transform matches the component names.".into());
+        }
+        let mut interval = tokio::time::interval(Duration::from_millis(interval as u64));
+
+        Ok(stream! {
+            // The taps stay in place for as long as the subscription lasts.
+            let _tap = tap;
+
+            'sample: loop {
+                interval.tick().await;
+                for _ in 0..limit {
+                    match rx.try_next() {
+                        Ok(Some(tapped)) => yield OutputEvent::new(tapped, encoding),
+                        // Every tapped component was removed or rebuilt.
+                        Ok(None) => break 'sample,
+                        // Nothing more was sampled this interval.
+                        Err(_) => break,
+                    }
+                }
+            }
+        })
+    }
+}
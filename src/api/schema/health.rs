@@ -1,4 +1,6 @@
-use async_graphql::{validators::IntRange, Object, SimpleObject, Subscription};
+use super::{components, metrics};
+use crate::sinks::util::service::SinkHealth;
+use async_graphql::{validators::IntRange, Enum, Object, SimpleObject, Subscription};
 use chrono::{DateTime, Utc};
 use tokio::{
     stream::{Stream, StreamExt},
@@ -16,6 +18,43 @@ impl Heartbeat {
     }
 }
 
+#[derive(Enum, Eq, PartialEq, Copy, Clone)]
+pub enum HealthStatus {
+    /// Requests are succeeding
+    Healthy,
+    /// Requests are failing, or the sink is being probed after failing
+    Degraded,
+    /// The sink's circuit breaker is open
+    Unhealthy,
+}
+
+impl From<SinkHealth> for HealthStatus {
+    fn from(health: SinkHealth) -> Self {
+        match health {
+            SinkHealth::Healthy => HealthStatus::Healthy,
+            SinkHealth::Degraded => HealthStatus::Degraded,
+            SinkHealth::Unhealthy => HealthStatus::Unhealthy,
+        }
+    }
+}
+
+/// Returns the health of the named sink. Sinks that haven't reported any
+/// change in health are healthy.
+pub fn sink_health(name: &str) -> HealthStatus {
+    metrics::component_sink_health(name)
+        .map(SinkHealth::from_gauge)
+        .unwrap_or(SinkHealth::Healthy)
+        .into()
+}
+
+#[derive(SimpleObject)]
+pub struct SinkHealthStatus {
+    /// Sink name
+    name: String,
+    /// Sink health
+    status: HealthStatus,
+}
+
 #[derive(Default)]
 pub struct HealthQuery;
 
@@ -25,6 +64,17 @@ impl HealthQuery {
     async fn health(&self) -> bool {
         true
     }
+
+    /// Health of each sink, as reported by its circuit breaker
+    async fn sinks_health(&self) -> Vec<SinkHealthStatus> {
+        components::sink_names()
+            .into_iter()
+            .map(|name| SinkHealthStatus {
+                status: sink_health(&name),
+                name,
+            })
+            .collect()
+    }
 }
 
 #[derive(Default)]
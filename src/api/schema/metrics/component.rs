@@ -0,0 +1,127 @@
+use crate::{buffers, event::Metric};
+use async_graphql::SimpleObject;
+
+#[derive(SimpleObject)]
+pub struct ComponentErrorsTotal {
+    /// Component name
+    name: String,
+    /// Total number of errors
+    errors_total: f64,
+}
+
+impl ComponentErrorsTotal {
+    pub fn new(name: String, m: &Metric) -> Self {
+        Self {
+            name,
+            errors_total: super::counter_value(m),
+        }
+    }
+}
+
+#[derive(SimpleObject)]
+pub struct ComponentReceivedBytesTotal {
+    /// Source name
+    name: String,
+    /// Total number of bytes received
+    received_bytes_total: f64,
+}
+
+impl ComponentReceivedBytesTotal {
+    pub fn new(name: String, m: &Metric) -> Self {
+        Self {
+            name,
+            received_bytes_total: super::counter_value(m),
+        }
+    }
+}
+
+#[derive(SimpleObject)]
+pub struct ComponentSentBytesTotal {
+    /// Sink name
+    name: String,
+    /// Total number of bytes sent
+    sent_bytes_total: f64,
+}
+
+impl ComponentSentBytesTotal {
+    pub fn new(name: String, m: &Metric) -> Self {
+        Self {
+            name,
+            sent_bytes_total: super::counter_value(m),
+        }
+    }
+}
+
+#[derive(SimpleObject)]
+pub struct ComponentThroughput {
+    /// Component name
+    name: String,
+    /// Events per second
+    throughput: f64,
+}
+
+impl ComponentThroughput {
+    pub fn new(name: String, throughput: f64) -> Self {
+        Self { name, throughput }
+    }
+}
+
+#[derive(SimpleObject)]
+pub struct BufferUsage {
+    /// Number of events in the buffer
+    events: i64,
+    /// Size in bytes of the events in the buffer. On-disk buffers count the
+    /// encoded size of events, memory buffers their approximate size in memory
+    byte_size: i64,
+}
+
+impl From<&buffers::BufferUsage> for BufferUsage {
+    fn from(usage: &buffers::BufferUsage) -> Self {
+        Self {
+            events: usage.events() as i64,
+            byte_size: usage.byte_size() as i64,
+        }
+    }
+}
+
+#[derive(SimpleObject)]
+pub struct ComponentBufferUsage {
+    /// Sink name
+    name: String,
+    /// Number of events in the buffer
+    events: i64,
+    /// Size in bytes of the events in the buffer. On-disk buffers count the
+    /// encoded size of events, memory buffers their approximate size in memory
+    byte_size: i64,
+}
+
+impl ComponentBufferUsage {
+    pub fn new(name: String, usage: BufferUsage) -> Self {
+        Self {
+            name,
+            events: usage.events,
+            byte_size: usage.byte_size,
+        }
+    }
+}
+
+#[derive(SimpleObject)]
+pub struct ComponentConcurrency {
+    /// Sink name
+    name: String,
+    /// Number of requests in flight
+    requests_in_flight: Option<i64>,
+    /// Current limit of requests in flight set by the adaptive concurrency
+    /// controller
+    concurrency_limit: Option<i64>,
+}
+
+impl ComponentConcurrency {
+    pub fn new(name: String) -> Self {
+        Self {
+            requests_in_flight: super::component_requests_in_flight(&name),
+            concurrency_limit: super::component_concurrency_limit(&name),
+            name,
+        }
+    }
+}
@@ -0,0 +1,33 @@
+use crate::event::{Metric, MetricValue};
+use async_graphql::Object;
+use chrono::{DateTime, Utc};
+
+pub struct ErrorsTotal(Metric);
+
+impl ErrorsTotal {
+    pub fn new(m: Metric) -> Self {
+        Self(m)
+    }
+}
+
+#[Object]
+impl ErrorsTotal {
+    /// Metric timestamp
+    pub async fn timestamp(&self) -> Option<DateTime<Utc>> {
+        self.0.timestamp
+    }
+
+    /// Total number of errors
+    pub async fn errors_total(&self) -> f64 {
+        match self.0.value {
+            MetricValue::Counter { value } => value,
+            _ => 0.00,
+        }
+    }
+}
+
+impl From<Metric> for ErrorsTotal {
+    fn from(m: Metric) -> Self {
+        Self(m)
+    }
+}
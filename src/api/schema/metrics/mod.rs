@@ -1,21 +1,37 @@
 mod bytes_processed;
+mod component;
+mod errors;
 mod events_processed;
 mod host;
+mod received_bytes;
+mod sent_bytes;
+mod throughput;
 mod uptime;
 
-use crate::event::{Event, Metric};
+use super::components;
+use crate::buffers;
+use crate::event::{Event, Metric, MetricValue};
 use crate::metrics::{capture_metrics, get_controller, Controller};
 use async_graphql::{validators::IntRange, Interface, Object, Subscription};
 use async_stream::stream;
 use chrono::{DateTime, Utc};
 use lazy_static::lazy_static;
+use std::collections::{btree_map::Entry, BTreeMap};
 use std::sync::Arc;
 use tokio::stream::{Stream, StreamExt};
 use tokio::time::Duration;
 
 pub use bytes_processed::ProcessedBytesTotal;
+pub use component::{
+    BufferUsage, ComponentBufferUsage, ComponentConcurrency, ComponentErrorsTotal,
+    ComponentReceivedBytesTotal, ComponentSentBytesTotal, ComponentThroughput,
+};
+pub use errors::ErrorsTotal;
 pub use events_processed::EventsProcessedTotal;
 pub use host::HostMetrics;
+pub use received_bytes::ReceivedBytesTotal;
+pub use sent_bytes::SentBytesTotal;
+pub use throughput::{component_throughput, sample_throughputs};
 pub use uptime::Uptime;
 
 lazy_static! {
@@ -80,6 +96,87 @@ impl MetricsSubscription {
         })
     }
 
+    /// Errors of each component
+    async fn component_errors_totals(
+        &self,
+        #[graphql(default = 1000, validator(IntRange(min = "100", max = "60_000")))] interval: i32,
+    ) -> impl Stream<Item = ComponentErrorsTotal> {
+        component_counter_totals_stream(interval, is_errors_total)
+            .map(|(name, m)| ComponentErrorsTotal::new(name, &m))
+    }
+
+    /// Bytes received by each source
+    async fn component_received_bytes_totals(
+        &self,
+        #[graphql(default = 1000, validator(IntRange(min = "100", max = "60_000")))] interval: i32,
+    ) -> impl Stream<Item = ComponentReceivedBytesTotal> {
+        component_counter_totals_stream(interval, is_received_bytes_total)
+            .map(|(name, m)| ComponentReceivedBytesTotal::new(name, &m))
+    }
+
+    /// Bytes sent by each sink
+    async fn component_sent_bytes_totals(
+        &self,
+        #[graphql(default = 1000, validator(IntRange(min = "100", max = "60_000")))] interval: i32,
+    ) -> impl Stream<Item = ComponentSentBytesTotal> {
+        component_counter_totals_stream(interval, is_sent_bytes_total)
+            .map(|(name, m)| ComponentSentBytesTotal::new(name, &m))
+    }
+
+    /// Events received per second by each transform and sink, over the interval
+    async fn component_events_in_throughputs(
+        &self,
+        #[graphql(default = 1000, validator(IntRange(min = "100", max = "60_000")))] interval: i32,
+    ) -> impl Stream<Item = ComponentThroughput> {
+        throughput::component_throughputs(interval, "events_in_total")
+            .map(|(name, throughput)| ComponentThroughput::new(name, throughput))
+    }
+
+    /// Events sent per second by each source and transform, over the interval
+    async fn component_events_out_throughputs(
+        &self,
+        #[graphql(default = 1000, validator(IntRange(min = "100", max = "60_000")))] interval: i32,
+    ) -> impl Stream<Item = ComponentThroughput> {
+        throughput::component_throughputs(interval, "events_out_total")
+            .map(|(name, throughput)| ComponentThroughput::new(name, throughput))
+    }
+
+    /// Fill level of the buffer of each sink
+    async fn component_buffer_usages(
+        &self,
+        #[graphql(default = 1000, validator(IntRange(min = "100", max = "60_000")))] interval: i32,
+    ) -> impl Stream<Item = ComponentBufferUsage> {
+        let mut interval = tokio::time::interval(Duration::from_millis(interval as u64));
+
+        stream! {
+            loop {
+                interval.tick().await;
+                for name in components::sink_names() {
+                    if let Some(usage) = component_buffer_usage(&name) {
+                        yield ComponentBufferUsage::new(name, usage);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Requests in flight and concurrency limit of each sink
+    async fn component_concurrencies(
+        &self,
+        #[graphql(default = 1000, validator(IntRange(min = "100", max = "60_000")))] interval: i32,
+    ) -> impl Stream<Item = ComponentConcurrency> {
+        let mut interval = tokio::time::interval(Duration::from_millis(interval as u64));
+
+        stream! {
+            loop {
+                interval.tick().await;
+                for name in components::sink_names() {
+                    yield ComponentConcurrency::new(name);
+                }
+            }
+        }
+    }
+
     /// All metrics
     async fn metrics(
         &self,
@@ -111,6 +208,137 @@ fn get_metrics(interval: i32) -> impl Stream<Item = Metric> {
     }
 }
 
+/// Returns the metrics of all components, captured now
+fn capture_component_metrics() -> impl Iterator<Item = Metric> {
+    capture_metrics(&GLOBAL_CONTROLLER).filter_map(|ev| match ev {
+        Event::Metric(m)
+            if m.tags
+                .as_ref()
+                .map_or(false, |t| t.contains_key("component_name")) =>
+        {
+            Some(m)
+        }
+        _ => None,
+    })
+}
+
+/// Returns the value of a counter metric
+fn counter_value(m: &Metric) -> f64 {
+    match m.value {
+        MetricValue::Counter { value } => value,
+        _ => 0.00,
+    }
+}
+
+/// Sums the counters matching `predicate` by component name. A component can
+/// have several counters matching, such as for different kinds of errors
+fn component_counter_totals(
+    metrics: impl Iterator<Item = Metric>,
+    predicate: impl Fn(&Metric) -> bool,
+) -> BTreeMap<String, Metric> {
+    let mut totals = BTreeMap::new();
+    for m in metrics {
+        if !matches!(m.value, MetricValue::Counter { .. }) || !predicate(&m) {
+            continue;
+        }
+        let name = match m.tags.as_ref().and_then(|t| t.get("component_name")) {
+            Some(name) => name.clone(),
+            None => continue,
+        };
+        match totals.entry(name) {
+            Entry::Vacant(entry) => {
+                entry.insert(m);
+            }
+            Entry::Occupied(mut entry) => {
+                let value = counter_value(&m);
+                if let MetricValue::Counter { value: total } = &mut entry.get_mut().value {
+                    *total += value;
+                }
+            }
+        }
+    }
+    totals
+}
+
+/// Returns a stream of the counter totals by component name, collected at the
+/// provided millisecond interval
+fn component_counter_totals_stream(
+    interval: i32,
+    predicate: fn(&Metric) -> bool,
+) -> impl Stream<Item = (String, Metric)> {
+    let mut interval = tokio::time::interval(Duration::from_millis(interval as u64));
+
+    stream! {
+        loop {
+            interval.tick().await;
+            for total in component_counter_totals(capture_component_metrics(), predicate) {
+                yield total;
+            }
+        }
+    }
+}
+
+/// Returns the counter total of a component
+fn component_counter_total(component_name: &str, predicate: fn(&Metric) -> bool) -> Option<Metric> {
+    let metrics =
+        capture_component_metrics().filter(|m| m.tag_matches("component_name", component_name));
+    component_counter_totals(metrics, predicate).remove(component_name)
+}
+
+/// Returns the value of a gauge of a component
+fn component_gauge(component_name: &str, metric_name: &str) -> Option<f64> {
+    capture_component_metrics().find_map(|m| match m.value {
+        MetricValue::Gauge { value }
+            if m.name == metric_name && m.tag_matches("component_name", component_name) =>
+        {
+            Some(value)
+        }
+        _ => None,
+    })
+}
+
+fn is_errors_total(m: &Metric) -> bool {
+    m.name.ends_with("_errors_total")
+}
+
+fn is_received_bytes_total(m: &Metric) -> bool {
+    m.name == "processed_bytes_total" && m.tag_matches("component_kind", "source")
+}
+
+fn is_sent_bytes_total(m: &Metric) -> bool {
+    m.name == "processed_bytes_total" && m.tag_matches("component_kind", "sink")
+}
+
+/// Get the errors of all kinds by component name
+pub fn component_errors_total(component_name: &str) -> Option<ErrorsTotal> {
+    component_counter_total(component_name, is_errors_total).map(ErrorsTotal::new)
+}
+
+/// Get the bytes received by source name
+pub fn component_received_bytes_total(component_name: &str) -> Option<ReceivedBytesTotal> {
+    component_counter_total(component_name, is_received_bytes_total).map(ReceivedBytesTotal::new)
+}
+
+/// Get the bytes sent by sink name
+pub fn component_sent_bytes_total(component_name: &str) -> Option<SentBytesTotal> {
+    component_counter_total(component_name, is_sent_bytes_total).map(SentBytesTotal::new)
+}
+
+/// Get the fill level of a sink's buffer, by sink name
+pub fn component_buffer_usage(component_name: &str) -> Option<BufferUsage> {
+    buffers::usage(component_name).map(|usage| usage.as_ref().into())
+}
+
+/// Get the requests in flight of a sink, by sink name
+pub fn component_requests_in_flight(component_name: &str) -> Option<i64> {
+    component_gauge(component_name, "auto_concurrency_current_in_flight").map(|v| v as i64)
+}
+
+/// Get the current concurrency limit of a sink, by sink name
+pub fn component_concurrency_limit(component_name: &str) -> Option<i64> {
+    component_gauge(component_name, "auto_concurrency_current_limit").map(|v| v as i64)
+}
+
 /// Get the events processed by component name
 pub fn component_events_processed_total(component_name: String) -> Option<EventsProcessedTotal> {
     let key = String::from("component_name");
@@ -127,3 +355,44 @@ pub fn component_events_processed_total(component_name: String) -> Option<Events
         })
         .map(|ev| EventsProcessedTotal::new(ev.into_metric()))
 }
+
+/// Get the health reported by a sink's circuit breaker, by component name
+pub fn component_sink_health(component_name: &str) -> Option<f64> {
+    component_gauge(component_name, "sink_health")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::event::metric::MetricKind;
+
+    fn counter(name: &str, component_name: &str, value: f64) -> Metric {
+        Metric {
+            name: name.to_owned(),
+            namespace: None,
+            timestamp: None,
+            tags: Some(
+                vec![("component_name".to_owned(), component_name.to_owned())]
+                    .into_iter()
+                    .collect(),
+            ),
+            kind: MetricKind::Absolute,
+            value: MetricValue::Counter { value },
+        }
+    }
+
+    #[test]
+    fn sums_counters_by_component() {
+        let metrics = vec![
+            counter("processing_errors_total", "in", 2.0),
+            counter("http_errors_total", "in", 1.0),
+            counter("events_processed_total", "in", 10.0),
+            counter("processing_errors_total", "out", 4.0),
+        ];
+
+        let totals = component_counter_totals(metrics.into_iter(), is_errors_total);
+        assert_eq!(totals.len(), 2);
+        assert_eq!(counter_value(&totals["in"]), 3.0);
+        assert_eq!(counter_value(&totals["out"]), 4.0);
+    }
+}
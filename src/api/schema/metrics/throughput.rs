@@ -0,0 +1,72 @@
+use super::{capture_component_metrics, component_counter_totals, counter_value};
+use async_stream::stream;
+use lazy_static::lazy_static;
+use std::{
+    collections::HashMap,
+    sync::RwLock,
+    time::{Duration, Instant},
+};
+use tokio::stream::{Stream, StreamExt};
+
+/// Milliseconds between the samples of the throughputs reported by component
+/// queries.
+const SAMPLE_INTERVAL: i32 = 1000;
+
+lazy_static! {
+    static ref SAMPLES: RwLock<HashMap<(String, &'static str), f64>> = RwLock::new(HashMap::new());
+}
+
+/// Returns a stream of the per-second rates of the counter `metric_name` of
+/// each component, between captures at the provided millisecond interval
+pub fn component_throughputs(
+    interval: i32,
+    metric_name: &'static str,
+) -> impl Stream<Item = (String, f64)> {
+    let mut interval = tokio::time::interval(Duration::from_millis(interval as u64));
+    let mut previous = HashMap::new();
+    let mut last_capture = Instant::now();
+
+    stream! {
+        loop {
+            interval.tick().await;
+            let elapsed = last_capture.elapsed().as_secs_f64();
+            last_capture = Instant::now();
+
+            let totals =
+                component_counter_totals(capture_component_metrics(), |m| m.name == metric_name);
+            for (name, total) in totals {
+                let total = counter_value(&total);
+                // The first capture of a counter only sets the baseline.
+                if let Some(previous) = previous.insert(name.clone(), total) {
+                    yield (name, (total - previous).max(0.0) / elapsed);
+                }
+            }
+        }
+    }
+}
+
+/// Samples the throughputs of the components, to be reported by component
+/// queries. Runs until dropped
+pub async fn sample_throughputs() {
+    let samples = component_throughputs(SAMPLE_INTERVAL, "events_in_total")
+        .map(|(name, throughput)| ((name, "events_in_total"), throughput))
+        .merge(
+            component_throughputs(SAMPLE_INTERVAL, "events_out_total")
+                .map(|(name, throughput)| ((name, "events_out_total"), throughput)),
+        );
+    let mut samples = Box::pin(samples);
+
+    while let Some((key, throughput)) = samples.next().await {
+        SAMPLES.write().unwrap().insert(key, throughput);
+    }
+}
+
+/// Returns the last sampled throughput of the counter `metric_name` of a
+/// component
+pub fn component_throughput(component_name: &str, metric_name: &'static str) -> Option<f64> {
+    SAMPLES
+        .read()
+        .unwrap()
+        .get(&(component_name.to_owned(), metric_name))
+        .copied()
+}
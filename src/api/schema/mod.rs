@@ -1,9 +1,14 @@
 mod broker;
 pub mod components;
+mod control;
+mod events;
 mod health;
 mod metrics;
 
-use async_graphql::{EmptyMutation, MergedObject, MergedSubscription, Schema, SchemaBuilder};
+use async_graphql::{MergedObject, MergedSubscription, Schema, SchemaBuilder};
+
+pub use control::MutationAccess;
+pub use metrics::sample_throughputs;
 
 #[derive(MergedObject, Default)]
 pub struct Query(
@@ -12,14 +17,22 @@ pub struct Query(
     metrics::MetricsQuery,
 );
 
+#[derive(MergedObject, Default)]
+pub struct Mutation(control::ControlMutation);
+
 #[derive(MergedSubscription, Default)]
 pub struct Subscription(
     health::HealthSubscription,
     metrics::MetricsSubscription,
     components::ComponentsSubscription,
+    events::EventsSubscription,
 );
 
 /// Build a new GraphQL schema, comprised of Query, Mutation and Subscription types
-pub fn build_schema() -> SchemaBuilder<Query, EmptyMutation, Subscription> {
-    Schema::build(Query::default(), EmptyMutation, Subscription::default())
+pub fn build_schema() -> SchemaBuilder<Query, Mutation, Subscription> {
+    Schema::build(
+        Query::default(),
+        Mutation::default(),
+        Subscription::default(),
+    )
 }
@@ -1,11 +1,18 @@
-use super::{handler, schema};
-use crate::config;
+use super::{flushes, handler, outputs, schema};
+#[cfg(feature = "sinks-prometheus")]
+use crate::telemetry::prometheus;
+use crate::{
+    config, http::Auth, signal::ReloadRequest, sinks::util::flush::FlushHandle,
+    tls::MaybeTlsSettings, topology::fanout::ControlChannel,
+};
 use async_graphql::{
     http::{playground_source, GraphQLPlaygroundConfig},
     Request, Schema,
 };
 use async_graphql_warp::{graphql_subscription, Response as GQLResponse};
-use std::{convert::Infallible, net::SocketAddr};
+use futures::{channel::mpsc, future, FutureExt};
+use http::HeaderMap;
+use std::{collections::HashMap, convert::Infallible, net::SocketAddr};
 use tokio::sync::oneshot;
 use warp::filters::BoxedFilter;
 use warp::{http::Response, Filter, Reply};
@@ -16,26 +23,39 @@ pub struct Server {
 }
 
 impl Server {
-    /// Start the API server. This creates the routes and spawns a Warp server. The server is
-    /// gracefully shut down when Self falls out of scope by way of the oneshot sender closing
-    pub fn start(config: &config::Config) -> Self {
-        let routes = make_routes(config.api.playground);
+    /// Start the API server. This creates the routes and spawns a Warp server, serving TLS if
+    /// configured. The server is gracefully shut down when Self falls out of scope by way of
+    /// the oneshot sender closing. Config reloads requested through mutations are sent to
+    /// `reloads`
+    pub async fn start(
+        config: &config::Config,
+        reloads: mpsc::Sender<ReloadRequest>,
+    ) -> crate::Result<Self> {
+        let routes = make_routes(&config.api, &config.telemetry, reloads);
+
+        let tls = MaybeTlsSettings::from_config(&config.api.tls, true)?;
+        let listener = tls
+            .bind(&config.api.bind.expect("Invalid socket address"))
+            .await?;
+        let addr = listener.local_addr()?;
 
         let (_shutdown, rx) = oneshot::channel();
-        let (addr, server) = warp::serve(routes).bind_with_graceful_shutdown(
-            config.api.bind.expect("Invalid socket address"),
-            async {
-                rx.await.ok();
-            },
-        );
+        let shutdown = rx.map(|_| ()).shared();
+        let server = warp::serve(routes)
+            .serve_incoming_with_graceful_shutdown(listener.accept_stream(), shutdown.clone());
 
         // Update component schema with the config before starting the server
         schema::components::update_config(config);
 
-        // Spawn the server in the background
+        // Spawn the server in the background, along with the sampling of the throughputs
+        // reported by component queries
         tokio::spawn(server);
+        tokio::spawn(future::select(
+            Box::pin(schema::sample_throughputs()),
+            shutdown,
+        ));
 
-        Self { addr, _shutdown }
+        Ok(Self { addr, _shutdown })
     }
 
     /// Returns a copy of the SocketAddr that the server was started on
@@ -49,11 +69,26 @@ impl Server {
     pub fn update_config(&self, config: &config::Config) {
         schema::components::update_config(config)
     }
+
+    /// Update the component outputs that can be tapped, paused and resumed, after the
+    /// topology starts or reloads
+    pub fn update_outputs(&self, outputs: &HashMap<String, ControlChannel>) {
+        outputs::update(outputs)
+    }
+
+    /// Update the flush handles of the sinks, after the topology starts or reloads
+    pub fn update_flush_handles(&self, handles: &HashMap<String, FlushHandle>) {
+        flushes::update(handles)
+    }
 }
 
-fn make_routes(playground: bool) -> BoxedFilter<(impl Reply,)> {
+fn make_routes(
+    options: &config::api::Options,
+    telemetry: &config::telemetry::Options,
+    reloads: mpsc::Sender<ReloadRequest>,
+) -> BoxedFilter<(impl Reply,)> {
     // Build the GraphQL schema
-    let schema = schema::build_schema().finish();
+    let schema = schema::build_schema().data(reloads).finish();
 
     // Routes...
 
@@ -63,17 +98,28 @@ fn make_routes(playground: bool) -> BoxedFilter<(impl Reply,)> {
     // 404
     let not_found = warp::any().and_then(|| async { Err(warp::reject::not_found()) });
 
-    // GraphQL query and subscription handler
-    let graphql_handler = warp::path("graphql").and(graphql_subscription(schema.clone()).or(
-        async_graphql_warp::graphql(schema).and_then(
-            |(schema, request): (Schema<_, _, _>, Request)| async move {
-                Ok::<_, Infallible>(GQLResponse::from(schema.execute(request).await))
-            },
-        ),
-    ));
+    // GraphQL query and subscription handler, which requires authentication if configured.
+    // Whether a request may run mutations is decided here, from its headers
+    let mutation_options = options.clone();
+    let graphql_handler = warp::path("graphql")
+        .and(authenticated(options.auth.clone()))
+        .and(
+            graphql_subscription(schema.clone()).or(async_graphql_warp::graphql(schema)
+                .and(warp::header::headers_cloned())
+                .and_then(
+                    move |(schema, request): (Schema<_, _, _>, Request), headers: HeaderMap| {
+                        let access = schema::MutationAccess::check(&mutation_options, &headers);
+                        async move {
+                            Ok::<_, Infallible>(GQLResponse::from(
+                                schema.execute(request.data(access)).await,
+                            ))
+                        }
+                    },
+                )),
+        );
 
     // GraphQL playground
-    let graphql_playground = if playground {
+    let graphql_playground = if options.playground {
         warp::path("playground")
             .map(move || {
                 Response::builder()
@@ -87,10 +133,33 @@ fn make_routes(playground: bool) -> BoxedFilter<(impl Reply,)> {
         not_found.boxed()
     };
 
+    // Internal metrics in the Prometheus exposition format, which require authentication
+    // like GraphQL requests
+    #[cfg(feature = "sinks-prometheus")]
+    let metrics = {
+        let prometheus_options = telemetry.prometheus.clone().unwrap_or_default();
+        warp::path("metrics")
+            .and(warp::get())
+            .and(authenticated(options.auth.clone()))
+            .map(move || {
+                Response::builder()
+                    .header("content-type", prometheus::CONTENT_TYPE)
+                    .body(prometheus::render(&prometheus_options))
+            })
+            .boxed()
+    };
+    #[cfg(not(feature = "sinks-prometheus"))]
+    let metrics = {
+        let _ = telemetry;
+        not_found.boxed()
+    };
+
     health
         .or(graphql_handler)
         .or(graphql_playground)
+        .or(metrics)
         .or(not_found)
+        .recover(handler::unauthorized)
         .with(
             warp::cors()
                 .allow_any_origin()
@@ -103,6 +172,7 @@ fn make_routes(playground: bool) -> BoxedFilter<(impl Reply,)> {
                     "Access-Control-Allow-Origin",
                     "Access-Control-Request-Headers",
                     "Content-Type",
+                    "Authorization",
                     "X-Apollo-Tracing", // for Apollo GraphQL clients
                     "Pragma",
                     "Host",
@@ -113,3 +183,21 @@ fn make_routes(playground: bool) -> BoxedFilter<(impl Reply,)> {
         )
         .boxed()
 }
+
+/// Rejects requests that don't carry the credentials in `auth`, if any. Websocket
+/// subscriptions are checked when the connection is upgraded
+fn authenticated(auth: Option<Auth>) -> BoxedFilter<()> {
+    warp::header::headers_cloned()
+        .and_then(move |headers: HeaderMap| {
+            let authorized = auth.as_ref().map_or(true, |auth| auth.verify(&headers));
+            async move {
+                if authorized {
+                    Ok(())
+                } else {
+                    Err(warp::reject::custom(handler::Unauthorized))
+                }
+            }
+        })
+        .untuple_one()
+        .boxed()
+}
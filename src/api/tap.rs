@@ -0,0 +1,152 @@
+//! Taps on the outputs of running components.
+//!
+//! A tap is added to the `Fanout` of each tapped component as one more
+//! output, so components nobody is tapping pay nothing for it. Tapped events
+//! are copied into a bounded channel without ever waiting: once it is full,
+//! further events are left out of the sample rather than slowing the
+//! component down.
+
+use super::outputs;
+use crate::{
+    topology::fanout::{ControlChannel, ControlMessage},
+    Event,
+};
+use futures::channel::mpsc;
+use futures01::{AsyncSink, Poll, Sink, StartSend};
+use glob::Pattern;
+use uuid::Uuid;
+
+/// An event leaving a tapped component.
+#[derive(Debug, Clone)]
+pub struct TapEvent {
+    pub component_name: String,
+    pub event: Event,
+}
+
+/// Taps the outputs of the components matching any of `patterns`, sampling
+/// their events into a channel holding up to `capacity` of them. The taps
+/// are removed when the returned `Tap` is dropped.
+pub fn tap(patterns: &[Pattern], capacity: usize) -> (Tap, mpsc::Receiver<TapEvent>) {
+    let (tx, rx) = mpsc::channel(capacity);
+    let output_name = format!("_tap_{}", Uuid::new_v4());
+
+    let outputs = outputs::matching(patterns)
+        .into_iter()
+        .filter_map(|(name, control)| {
+            let sink = TapSink {
+                component_name: name,
+                tx: tx.clone(),
+            };
+            control
+                .unbounded_send(ControlMessage::Add(output_name.clone(), Box::new(sink)))
+                .ok()
+                .map(|()| control)
+        })
+        .collect();
+
+    (
+        Tap {
+            output_name,
+            outputs,
+        },
+        rx,
+    )
+}
+
+/// The taps added by a single call to `tap`.
+pub struct Tap {
+    output_name: String,
+    outputs: Vec<ControlChannel>,
+}
+
+impl Tap {
+    /// Whether any component matched.
+    pub fn is_empty(&self) -> bool {
+        self.outputs.is_empty()
+    }
+}
+
+impl Drop for Tap {
+    fn drop(&mut self) {
+        for control in &self.outputs {
+            // The component may have been removed or rebuilt since, taking
+            // the tap with it.
+            let _ = control.unbounded_send(ControlMessage::Remove(self.output_name.clone()));
+        }
+    }
+}
+
+struct TapSink {
+    component_name: String,
+    tx: mpsc::Sender<TapEvent>,
+}
+
+impl Sink for TapSink {
+    type SinkItem = Event;
+    type SinkError = ();
+
+    fn start_send(&mut self, event: Event) -> StartSend<Self::SinkItem, Self::SinkError> {
+        // Dropped if the sample is full, or the subscriber is gone and the
+        // tap about to be removed.
+        let _ = self.tx.try_send(TapEvent {
+            component_name: self.component_name.clone(),
+            event,
+        });
+        Ok(AsyncSink::Ready)
+    }
+
+    fn poll_complete(&mut self) -> Poll<(), Self::SinkError> {
+        Ok(().into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{config::log_schema, topology::fanout::Fanout};
+    use futures::{compat::Future01CompatExt, StreamExt};
+    use futures01::Sink as _;
+    use std::collections::HashMap;
+
+    #[tokio::test]
+    async fn samples_matching_outputs() {
+        let (in_fanout, in_control) = Fanout::new();
+        let (other_fanout, other_control) = Fanout::new();
+        let mut outputs = HashMap::new();
+        outputs.insert("in".to_string(), in_control);
+        outputs.insert("other".to_string(), other_control);
+        outputs::update(&outputs);
+
+        let (tap, rx) = tap(&[Pattern::new("i*").unwrap()], 1);
+        assert!(!tap.is_empty());
+
+        let events = (0..5).map(|i| Event::from(format!("line {}", i)));
+        let in_fanout = in_fanout
+            .send_all(futures01::stream::iter_ok(events.clone()))
+            .compat()
+            .await
+            .unwrap()
+            .0;
+        let _other_fanout = other_fanout
+            .send_all(futures01::stream::iter_ok(events))
+            .compat()
+            .await
+            .unwrap();
+
+        // Dropping the tap removes it from the fanout, ending the stream.
+        drop(tap);
+        let _in_fanout = in_fanout.send(Event::from("after")).compat().await.unwrap();
+
+        // Only the events that fit in the sample, from the matching
+        // component.
+        let tapped = rx.collect::<Vec<_>>().await;
+        assert!(!tapped.is_empty() && tapped.len() < 5);
+        for (i, tapped) in tapped.into_iter().enumerate() {
+            assert_eq!(tapped.component_name, "in");
+            assert_eq!(
+                tapped.event.as_log()[log_schema().message_key()],
+                format!("line {}", i).into()
+            );
+        }
+    }
+}
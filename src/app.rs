@@ -2,28 +2,34 @@ use crate::cli::{handle_config_errors, Color, LogFormat, Opts, RootOpts, SubComm
 use crate::signal::SignalTo;
 use crate::topology::RunningTopology;
 use crate::{
-    config, generate, heartbeat, list, metrics, signal, topology, trace, unit_test, validate,
+    config, diagnose, generate, graph, heartbeat, list, metrics, signal, telemetry, topology,
+    trace, unit_test, validate, vrl,
 };
 use std::cmp::max;
 use std::path::PathBuf;
 
 use futures::{
     compat::{Future01CompatExt, Stream01CompatExt},
-    StreamExt,
+    future, stream, StreamExt,
 };
 use futures01::sync::mpsc;
 
-#[cfg(feature = "api-client")]
-use crate::top;
+#[cfg(feature = "sinks-prometheus")]
+use crate::internal_events::{TelemetryServerStartFailed, TelemetryServerStarted};
 #[cfg(feature = "api")]
-use crate::{api, internal_events::ApiStarted};
+use crate::{
+    api,
+    internal_events::{ApiStartFailed, ApiStarted},
+};
+#[cfg(feature = "api-client")]
+use crate::{tap, top};
 
 #[cfg(windows)]
 use crate::service;
 
 use crate::internal_events::{
-    VectorConfigLoadFailed, VectorQuit, VectorRecoveryFailed, VectorReloadFailed, VectorReloaded,
-    VectorStarted, VectorStopped,
+    OtlpExporterStartFailed, OtlpExporterStarted, VectorConfigLoadFailed, VectorQuit,
+    VectorRecoveryFailed, VectorReloadFailed, VectorReloaded, VectorStarted, VectorStopped,
 };
 use tokio::runtime;
 use tokio::runtime::Runtime;
@@ -34,6 +40,7 @@ pub struct ApplicationConfig {
     pub graceful_crash: mpsc::UnboundedReceiver<()>,
     #[cfg(feature = "api")]
     pub api: config::api::Options,
+    pub telemetry: config::telemetry::Options,
 }
 
 pub struct Application {
@@ -101,6 +108,7 @@ impl Application {
             runtime::Builder::new()
                 .threaded_scheduler()
                 .enable_all()
+                .on_thread_start(crate::dns::mark_runtime_worker)
                 .core_threads(threads)
                 .build()
                 .expect("Unable to create async runtime")
@@ -116,10 +124,15 @@ impl Application {
                     let code = match s {
                         SubCommand::Validate(v) => validate::validate(&v, color).await,
                         SubCommand::List(l) => list::cmd(&l),
+                        SubCommand::Graph(g) => graph::cmd(&g),
+                        SubCommand::Diagnose(d) => diagnose::cmd(&d).await,
                         SubCommand::Test(t) => unit_test::cmd(&t).await,
                         SubCommand::Generate(g) => generate::cmd(&g),
                         #[cfg(feature = "api-client")]
                         SubCommand::Top(t) => top::cmd(&t).await,
+                        #[cfg(feature = "api-client")]
+                        SubCommand::Tap(t) => tap::cmd(&t).await,
+                        SubCommand::Vrl(v) => vrl::cmd(&v),
                         #[cfg(windows)]
                         SubCommand::Service(s) => service::cmd(&s),
                     };
@@ -152,12 +165,13 @@ impl Application {
                     .expect("Couldn't set schema");
 
                 let diff = config::ConfigDiff::initial(&config);
-                let pieces = topology::build_or_log_errors(&config, &diff)
+                let pieces = topology::build_or_log_errors(&config, &diff, &Default::default())
                     .await
                     .ok_or(exitcode::CONFIG)?;
 
                 #[cfg(feature = "api")]
-                let api = config.api;
+                let api = config.api.clone();
+                let telemetry = config.telemetry.clone();
 
                 let result = topology::start_validated(config, diff, pieces, require_healthy).await;
                 let (topology, graceful_crash) = result.ok_or(exitcode::CONFIG)?;
@@ -168,6 +182,7 @@ impl Application {
                     graceful_crash,
                     #[cfg(feature = "api")]
                     api,
+                    telemetry,
                 })
             })
         }?;
@@ -191,25 +206,84 @@ impl Application {
 
         #[cfg(feature = "api")]
         let api_config = self.config.api;
+        let telemetry_config = self.config.telemetry;
 
         rt.block_on(async move {
             emit!(VectorStarted);
             tokio::spawn(heartbeat::heartbeat());
 
+            // Reloads requested through the API arrive alongside the signals.
+            let (reload_tx, reload_rx) = futures::channel::mpsc::channel(1);
+
             #[cfg(feature = "api")]
             // assigned to prevent the API terminating when falling out of scope
             let api_server = if api_config.enabled {
-                emit!(ApiStarted {
-                    addr: api_config.bind.unwrap(),
-                    playground: api_config.playground
-                });
-
-                Some(api::Server::start(topology.config()))
+                match api::Server::start(topology.config(), reload_tx).await {
+                    Ok(api_server) => {
+                        emit!(ApiStarted {
+                            addr: api_server.addr(),
+                            playground: api_config.playground,
+                            tls: api_config
+                                .tls
+                                .as_ref()
+                                .and_then(|tls| tls.enabled)
+                                .unwrap_or(false),
+                        });
+
+                        api_server.update_outputs(topology.outputs());
+                        api_server.update_flush_handles(topology.flush_handles());
+                        Some(api_server)
+                    }
+                    // Vector keeps running without the API.
+                    Err(error) => {
+                        emit!(ApiStartFailed { error });
+                        None
+                    }
+                }
             } else {
                 None
             };
+            #[cfg(not(feature = "api"))]
+            drop(reload_tx);
+
+            #[cfg(feature = "sinks-prometheus")]
+            // assigned to keep serving the metrics until Vector stops
+            let _telemetry_server = telemetry_config.prometheus.as_ref().and_then(|options| {
+                match telemetry::prometheus::Server::start(options) {
+                    Ok(server) => {
+                        emit!(TelemetryServerStarted { addr: server.addr() });
+                        Some(server)
+                    }
+                    // Vector keeps running without serving its metrics.
+                    Err(error) => {
+                        emit!(TelemetryServerStartFailed { error });
+                        None
+                    }
+                }
+            });
+
+            // assigned to keep exporting spans until Vector stops
+            let _span_exporter = telemetry_config.otlp.as_ref().and_then(|options| {
+                match telemetry::otlp::Exporter::start(options) {
+                    Ok(exporter) => {
+                        emit!(OtlpExporterStarted {
+                            endpoint: &options.endpoint,
+                            sample_rate: options.sample_rate,
+                        });
+                        Some(exporter)
+                    }
+                    // Vector keeps running without exporting its spans.
+                    Err(error) => {
+                        emit!(OtlpExporterStartFailed { error });
+                        None
+                    }
+                }
+            });
 
-            let signals = signal::signals();
+            let signals = stream::select(
+                signal::signals(),
+                reload_rx.map(SignalTo::ReloadFromApi),
+            );
             tokio::pin!(signals);
             let mut sources_finished = topology.sources_finished();
             let mut graceful_crash = graceful_crash.compat();
@@ -217,13 +291,28 @@ impl Application {
             let signal = loop {
                 tokio::select! {
                 Some(signal) = signals.next() => {
-                    if signal == SignalTo::Reload {
-                        // Reload paths
-                        config_paths = config::process_paths(&opts.config_paths).unwrap_or(config_paths);
-                        // Reload config
-                        let new_config = config::load_from_paths(&config_paths).map_err(handle_config_errors).ok();
+                    let (new_config, reply) = match signal {
+                        SignalTo::Reload => {
+                            // Reload paths
+                            config_paths = config::process_paths(&opts.config_paths).unwrap_or(config_paths);
+                            // Reload config
+                            (config::load_from_paths(&config_paths), None)
+                        }
+                        SignalTo::ReloadFromApi(request) => {
+                            let new_config = match request.config {
+                                Some(config) => Ok(config),
+                                None => {
+                                    config_paths = config::process_paths(&opts.config_paths).unwrap_or(config_paths);
+                                    config::load_from_paths(&config_paths)
+                                }
+                            };
+                            (new_config, Some(request.reply))
+                        }
+                        signal => break signal,
+                    };
 
-                        if let Some(new_config) = new_config {
+                    let result = match new_config {
+                        Ok(new_config) => {
                             match topology
                                 .reload_config_and_respawn(new_config, opts.require_healthy)
                                 .await
@@ -231,25 +320,48 @@ impl Application {
                                 Ok(true) => {
                                     #[cfg(feature="api")]
                                     if let Some(ref api_server) = api_server {
-                                        api_server.update_config(topology.config())
+                                        api_server.update_config(topology.config());
+                                        api_server.update_outputs(topology.outputs());
+                                        api_server.update_flush_handles(topology.flush_handles());
                                     }
 
-                                    emit!(VectorReloaded { config_paths: &config_paths })
+                                    emit!(VectorReloaded { config_paths: &config_paths });
+                                    Ok(())
+                                },
+                                Ok(false) => {
+                                    // Components may have been rebuilt while
+                                    // restoring the previous config.
+                                    #[cfg(feature="api")]
+                                    if let Some(ref api_server) = api_server {
+                                        api_server.update_outputs(topology.outputs());
+                                        api_server.update_flush_handles(topology.flush_handles());
+                                    }
+
+                                    emit!(VectorReloadFailed);
+                                    Err(vec!["Reload failed; the previous configuration was restored, see the logs for details.".to_owned()])
                                 },
-                                Ok(false) => emit!(VectorReloadFailed),
                                 // Trigger graceful shutdown for what remains of the topology
                                 Err(()) => {
                                     emit!(VectorReloadFailed);
                                     emit!(VectorRecoveryFailed);
+                                    if let Some(reply) = reply {
+                                        let _ = reply.send(Err(vec!["Reload failed and the previous configuration could not be restored; shutting down.".to_owned()]));
+                                    }
                                     break SignalTo::Shutdown;
                                 }
                             }
-                            sources_finished = topology.sources_finished();
-                        } else {
+                        }
+                        Err(errors) => {
+                            handle_config_errors(errors.clone());
                             emit!(VectorConfigLoadFailed);
+                            Err(errors)
                         }
-                    } else {
-                        break signal;
+                    };
+                    sources_finished = topology.sources_finished();
+
+                    if let Some(reply) = reply {
+                        // The requester may have gone away.
+                        let _ = reply.send(result);
                     }
                 }
                 // Trigger graceful shutdown if a component crashed, or all sources have ended.
@@ -264,7 +376,8 @@ impl Application {
                     emit!(VectorStopped);
                     tokio::select! {
                     _ = topology.stop().compat() => (), // Graceful shutdown finished
-                    _ = signals.next() => {
+                    // Reloads requested now are dropped, which their requesters see.
+                    _ = signals.filter(|signal| future::ready(!matches!(signal, SignalTo::ReloadFromApi(_)))).next() => {
                         // It is highly unlikely that this event will exit from topology.
                         emit!(VectorQuit);
                         // Dropping the shutdown future will immediately shut the server down
@@ -276,7 +389,7 @@ impl Application {
                     emit!(VectorQuit);
                     drop(topology);
                 }
-                SignalTo::Reload => unreachable!(),
+                SignalTo::Reload | SignalTo::ReloadFromApi(_) => unreachable!(),
             }
         });
     }
@@ -25,7 +25,7 @@ use std::{
 };
 
 use super::{DataDirOpenError, Error};
-use crate::buffers::Acker;
+use crate::buffers::{Acker, BufferUsage};
 
 #[derive(Copy, Clone, Debug)]
 struct Key(pub usize);
@@ -52,6 +52,7 @@ pub struct Writer {
     batch_size: usize,
     max_size: usize,
     current_size: Arc<AtomicUsize>,
+    usage: Arc<BufferUsage>,
 }
 
 // Writebatch isn't Send, but the leveldb docs explicitly say that it's okay to share across threads
@@ -68,6 +69,7 @@ impl Clone for Writer {
             batch_size: 0,
             max_size: self.max_size,
             current_size: Arc::clone(&self.current_size),
+            usage: Arc::clone(&self.usage),
         }
     }
 }
@@ -105,6 +107,7 @@ impl Sink for Writer {
 
         self.writebatch.put(Key(key), &value);
         self.batch_size += 1;
+        self.usage.add(1);
 
         if self.batch_size >= 100 {
             self.poll_complete()?;
@@ -126,6 +129,10 @@ impl Sink for Writer {
 }
 
 impl Writer {
+    pub fn usage(&self) -> Arc<BufferUsage> {
+        Arc::clone(&self.usage)
+    }
+
     fn write_batch(&mut self) {
         self.db
             .write(WriteOptions::new(), &self.writebatch)
@@ -154,6 +161,7 @@ pub struct Reader {
     write_notifier: Arc<AtomicTask>,
     blocked_write_tasks: Arc<Mutex<Vec<Task>>>,
     current_size: Arc<AtomicUsize>,
+    usage: Arc<BufferUsage>,
     ack_counter: Arc<AtomicUsize>,
     unacked_sizes: VecDeque<usize>,
     buffer: Vec<Vec<u8>>,
@@ -243,6 +251,7 @@ impl Reader {
 
             let size_deleted = self.unacked_sizes.drain(..num_to_delete).sum();
             self.current_size.fetch_sub(size_deleted, Ordering::Relaxed);
+            self.usage.remove(num_to_delete);
         }
 
         for task in self.blocked_write_tasks.lock().unwrap().drain(..) {
@@ -278,6 +287,7 @@ impl super::DiskBuffer for Buffer {
 
         let initial_size = db.value_iter(ReadOptions::new()).map(|v| v.len()).sum();
         let current_size = Arc::new(AtomicUsize::new(initial_size));
+        let usage = BufferUsage::disk(tail - head, Arc::clone(&current_size));
 
         let write_notifier = Arc::new(AtomicTask::new());
 
@@ -295,6 +305,7 @@ impl super::DiskBuffer for Buffer {
             batch_size: 0,
             max_size,
             current_size: Arc::clone(&current_size),
+            usage: Arc::clone(&usage),
         };
 
         let reader = Reader {
@@ -304,6 +315,7 @@ impl super::DiskBuffer for Buffer {
             read_offset: head,
             delete_offset: head,
             current_size,
+            usage,
             ack_counter,
             unacked_sizes: VecDeque::new(),
             buffer: Vec::new(),
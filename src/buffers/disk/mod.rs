@@ -61,6 +61,12 @@ impl Sink for Writer {
     }
 }
 
+impl Writer {
+    pub fn usage(&self) -> std::sync::Arc<super::BufferUsage> {
+        self.inner.usage()
+    }
+}
+
 pub fn open(
     data_dir: &Path,
     name: &str,
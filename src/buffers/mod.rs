@@ -1,4 +1,4 @@
-use crate::Event;
+use crate::{internal_events::EventLatency, Event};
 use futures01::{sync::mpsc, task::AtomicTask, AsyncSink, Poll, Sink, StartSend, Stream};
 use serde::{Deserialize, Serialize};
 use std::path::PathBuf;
@@ -6,9 +6,13 @@ use std::sync::{
     atomic::{AtomicUsize, Ordering},
     Arc,
 };
+use std::time::Instant;
 
 #[cfg(feature = "leveldb")]
 pub mod disk;
+mod usage;
+
+pub use usage::{usage, BufferUsage};
 
 #[derive(Deserialize, Serialize, Debug)]
 #[serde(tag = "type")]
@@ -51,7 +55,8 @@ impl Default for WhenFull {
 }
 
 pub enum BufferInputCloner {
-    Memory(mpsc::Sender<Event>, WhenFull),
+    /// The usage is tracked for the buffers of sinks.
+    Memory(mpsc::Sender<Event>, WhenFull, Option<Arc<BufferUsage>>),
     #[cfg(feature = "leveldb")]
     Disk(disk::Writer, WhenFull),
 }
@@ -59,14 +64,21 @@ pub enum BufferInputCloner {
 impl BufferInputCloner {
     pub fn get(&self) -> Box<dyn Sink<SinkItem = Event, SinkError = ()> + Send> {
         match self {
-            BufferInputCloner::Memory(tx, when_full) => {
+            BufferInputCloner::Memory(tx, when_full, usage) => {
                 let inner = tx
                     .clone()
                     .sink_map_err(|error| error!(message = "Sender error.", %error));
+                let inner: Box<dyn Sink<SinkItem = Event, SinkError = ()> + Send> = match usage {
+                    Some(usage) => Box::new(usage::CountingSender {
+                        inner,
+                        usage: Arc::clone(usage),
+                    }),
+                    None => Box::new(inner),
+                };
                 if when_full == &WhenFull::DropNewest {
                     Box::new(DropWhenFull { inner })
                 } else {
-                    Box::new(inner)
+                    inner
                 }
             }
 
@@ -90,6 +102,14 @@ impl BufferConfig {
         500
     }
 
+    pub fn when_full(&self) -> WhenFull {
+        match self {
+            BufferConfig::Memory { when_full, .. } => *when_full,
+            #[cfg(feature = "leveldb")]
+            BufferConfig::Disk { when_full, .. } => *when_full,
+        }
+    }
+
     #[cfg_attr(not(feature = "leveldb"), allow(unused))]
     pub fn build(
         &self,
@@ -109,8 +129,10 @@ impl BufferConfig {
                 when_full,
             } => {
                 let (tx, rx) = mpsc::channel(*max_events);
-                let tx = BufferInputCloner::Memory(tx, *when_full);
-                let rx = Box::new(rx);
+                let usage = BufferUsage::memory();
+                usage::register(sink_name, &usage);
+                let tx = BufferInputCloner::Memory(tx, *when_full, Some(Arc::clone(&usage)));
+                let rx = Box::new(rx.inspect(move |event| usage.remove_event(event.size_of())));
                 Ok((tx, rx, Acker::Null))
             }
 
@@ -126,6 +148,7 @@ impl BufferConfig {
 
                 let (tx, rx, acker) = disk::open(&data_dir, buffer_dir.as_ref(), *max_size)
                     .map_err(|error| error.to_string())?;
+                usage::register(sink_name, &tx.usage());
                 let tx = BufferInputCloner::Disk(tx, *when_full);
                 let rx = Box::new(rx);
                 Ok((tx, rx, acker))
@@ -160,6 +183,18 @@ impl Acker {
         }
     }
 
+    /// Acks the next `num` events like `ack`, recording the time spent in
+    /// Vector by those of them that were stamped with their `ingress`.
+    pub fn ack_with_ingress(&self, num: usize, ingress: impl IntoIterator<Item = Instant>) {
+        let now = Instant::now();
+        for ingress in ingress {
+            emit!(EventLatency {
+                latency: now.saturating_duration_since(ingress)
+            });
+        }
+        self.ack(num);
+    }
+
     pub fn new_for_testing() -> (Self, Arc<AtomicUsize>) {
         let ack_counter = Arc::new(AtomicUsize::new(0));
         let notifier = Arc::new(AtomicTask::new());
@@ -197,7 +232,8 @@ impl<S: Sink> Sink for DropWhenFull<S> {
 
 #[cfg(test)]
 mod test {
-    use super::{Acker, BufferConfig, DropWhenFull, WhenFull};
+    use super::{usage, Acker, BufferConfig, DropWhenFull, WhenFull};
+    use crate::{test_util::random_events_with_stream, Event};
     use futures::compat::Future01CompatExt;
     use futures01::{future, sync::mpsc, task::AtomicTask, Async, AsyncSink, Sink, Stream};
     use std::sync::{atomic::AtomicUsize, Arc};
@@ -227,6 +263,36 @@ mod test {
         .unwrap();
     }
 
+    #[tokio::test]
+    async fn memory_buffer_usage() {
+        let config = BufferConfig::Memory {
+            max_events: 10,
+            when_full: WhenFull::Block,
+        };
+        let (tx, mut rx, _acker) = config.build(&None, "memory_buffer_usage").unwrap();
+        let (events, _) = random_events_with_stream(16, 3);
+        let sizes = events.iter().map(Event::size_of).collect::<Vec<_>>();
+
+        future::lazy(move || {
+            let mut tx = tx.get();
+            for event in events {
+                assert_eq!(tx.start_send(event), Ok(AsyncSink::Ready));
+            }
+            let usage = usage("memory_buffer_usage").unwrap();
+            assert_eq!(usage.events(), 3);
+            assert_eq!(usage.byte_size(), sizes.iter().sum());
+
+            assert!(matches!(rx.poll(), Ok(Async::Ready(Some(Event::Log(_))))));
+            assert_eq!(usage.events(), 2);
+            assert_eq!(usage.byte_size(), sizes[1..].iter().sum());
+
+            future::ok::<(), ()>(())
+        })
+        .compat()
+        .await
+        .unwrap();
+    }
+
     #[test]
     fn ack_with_none() {
         let counter = Arc::new(AtomicUsize::new(0));
@@ -0,0 +1,147 @@
+//! Fill levels of the sinks' buffers.
+//!
+//! A buffer is written to by the tasks of the sink's inputs and read by the
+//! sink's task, so its fill level can't be reported through the metrics of
+//! either. Instead, both ends share a `BufferUsage`, which is registered under
+//! the sink's name when the buffer is built.
+//!
+//! On-disk buffers count the encoded size of their events, while memory
+//! buffers count the approximate size of their events in memory, as given by
+//! `Event::size_of`.
+
+use crate::event::Event;
+use futures01::{AsyncSink, Poll, Sink, StartSend};
+use lazy_static::lazy_static;
+use std::{
+    collections::HashMap,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc, Mutex, Weak,
+    },
+};
+
+lazy_static! {
+    static ref USAGES: Mutex<HashMap<String, Vec<Weak<BufferUsage>>>> = Mutex::new(HashMap::new());
+}
+
+#[derive(Debug, Default)]
+pub struct BufferUsage {
+    events: AtomicUsize,
+    /// Shared with the writer of on-disk buffers, which keeps track of it.
+    byte_size: Arc<AtomicUsize>,
+}
+
+impl BufferUsage {
+    pub(super) fn memory() -> Arc<Self> {
+        Arc::new(Self::default())
+    }
+
+    #[cfg(feature = "leveldb")]
+    pub(super) fn disk(events: usize, byte_size: Arc<AtomicUsize>) -> Arc<Self> {
+        Arc::new(Self {
+            events: AtomicUsize::new(events),
+            byte_size,
+        })
+    }
+
+    /// Number of events in the buffer.
+    pub fn events(&self) -> usize {
+        self.events.load(Ordering::Relaxed)
+    }
+
+    /// Size in bytes of the events in the buffer.
+    pub fn byte_size(&self) -> usize {
+        self.byte_size.load(Ordering::Relaxed)
+    }
+
+    pub(super) fn add(&self, events: usize) {
+        self.events.fetch_add(events, Ordering::Relaxed);
+    }
+
+    pub(super) fn remove(&self, events: usize) {
+        self.events.fetch_sub(events, Ordering::Relaxed);
+    }
+
+    /// Counts an event of `byte_size` bytes entering a memory buffer.
+    pub(super) fn add_event(&self, byte_size: usize) {
+        self.add(1);
+        self.byte_size.fetch_add(byte_size, Ordering::Relaxed);
+    }
+
+    /// Counts an event of `byte_size` bytes leaving a memory buffer.
+    pub(super) fn remove_event(&self, byte_size: usize) {
+        self.remove(1);
+        self.byte_size.fetch_sub(byte_size, Ordering::Relaxed);
+    }
+}
+
+/// Registers the usage of a buffer built for the sink `sink_name`. The
+/// buffer of a sink being rebuilt overlaps with the one it replaces, and is
+/// dropped if the reload fails, so earlier buffers are kept until dropped.
+pub(super) fn register(sink_name: &str, usage: &Arc<BufferUsage>) {
+    let mut usages = USAGES.lock().unwrap();
+    let buffers = usages.entry(sink_name.to_owned()).or_default();
+    buffers.retain(|buffer| buffer.strong_count() > 0);
+    buffers.push(Arc::downgrade(usage));
+}
+
+/// Returns the usage of the most recently built buffer of the sink
+/// `sink_name`, if it is still in use.
+pub fn usage(sink_name: &str) -> Option<Arc<BufferUsage>> {
+    USAGES
+        .lock()
+        .unwrap()
+        .get(sink_name)?
+        .iter()
+        .rev()
+        .find_map(Weak::upgrade)
+}
+
+/// Counts the events accepted by a memory buffer's sender.
+pub(super) struct CountingSender<S> {
+    pub(super) inner: S,
+    pub(super) usage: Arc<BufferUsage>,
+}
+
+impl<S: Sink<SinkItem = Event>> Sink for CountingSender<S> {
+    type SinkItem = Event;
+    type SinkError = S::SinkError;
+
+    fn start_send(&mut self, item: Self::SinkItem) -> StartSend<Self::SinkItem, Self::SinkError> {
+        // Counted before sending, as the receiver may take the event right away.
+        let byte_size = item.size_of();
+        self.usage.add_event(byte_size);
+        let result = self.inner.start_send(item);
+        if !matches!(result, Ok(AsyncSink::Ready)) {
+            self.usage.remove_event(byte_size);
+        }
+        result
+    }
+
+    fn poll_complete(&mut self) -> Poll<(), Self::SinkError> {
+        self.inner.poll_complete()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn usage_of_latest_live_buffer() {
+        let first = BufferUsage::memory();
+        first.add(1);
+        register("usage_sink", &first);
+
+        let second = BufferUsage::memory();
+        second.add(2);
+        register("usage_sink", &second);
+        assert_eq!(usage("usage_sink").unwrap().events(), 2);
+
+        drop(second);
+        assert_eq!(usage("usage_sink").unwrap().events(), 1);
+
+        drop(first);
+        assert!(usage("usage_sink").is_none());
+    }
+}
@@ -1,6 +1,6 @@
+use crate::{diagnose, generate, get_version, graph, list, unit_test, validate, vrl};
 #[cfg(feature = "api-client")]
-use crate::top;
-use crate::{generate, get_version, list, unit_test, validate};
+use crate::{tap, top};
 use std::path::PathBuf;
 use structopt::{clap::AppSettings, StructOpt};
 
@@ -32,7 +32,10 @@ impl Opts {
         let (quiet_level, verbose_level) = match self.sub_command {
             Some(SubCommand::Validate(_))
             | Some(SubCommand::Generate(_))
-            | Some(SubCommand::List(_)) => {
+            | Some(SubCommand::List(_))
+            | Some(SubCommand::Graph(_))
+            | Some(SubCommand::Diagnose(_))
+            | Some(SubCommand::Vrl(_)) => {
                 if self.root.verbose == 0 {
                     (self.root.quiet + 1, self.root.verbose)
                 } else {
@@ -110,6 +113,13 @@ pub enum SubCommand {
     /// List available components, then exit.
     List(list::Opts),
 
+    /// Print the topology of a config as a Graphviz DOT or Mermaid diagram, then exit.
+    Graph(graph::Opts),
+
+    /// Gather the version, host, redacted config, validation results and metrics of Vector, and
+    /// the state of a running instance, into a tarball for support, then exit.
+    Diagnose(diagnose::Opts),
+
     /// Run Vector config unit tests, then exit. This command is experimental and therefore subject to change.
     /// For guidance on how to write unit tests check out: https://vector.dev/docs/setup/guides/unit-testing/
     Test(unit_test::Opts),
@@ -118,11 +128,53 @@ pub enum SubCommand {
     #[cfg(feature = "api-client")]
     Top(top::Opts),
 
+    /// Print a sample of the events leaving components, for a local or remote Vector instance
+    #[cfg(feature = "api-client")]
+    Tap(tap::Opts),
+
+    /// Run remap programs interactively, or against newline delimited JSON events.
+    Vrl(vrl::Opts),
+
     /// Manage the vector service.
     #[cfg(windows)]
     Service(service::Opts),
 }
 
+/// Credentials for a Vector API server that requires authentication, taken by the commands
+/// that connect to one.
+#[cfg(feature = "api-client")]
+#[derive(StructOpt, Debug)]
+#[structopt(rename_all = "kebab-case")]
+pub struct ApiAuthOpts {
+    /// Bearer token to authenticate with
+    #[structopt(long, env = "VECTOR_API_TOKEN")]
+    token: Option<String>,
+
+    /// User to authenticate as, with basic authentication
+    #[structopt(long, conflicts_with = "token")]
+    user: Option<String>,
+
+    /// Password of `--user`
+    #[structopt(long, env = "VECTOR_API_PASSWORD")]
+    password: Option<String>,
+}
+
+#[cfg(feature = "api-client")]
+impl ApiAuthOpts {
+    pub fn auth(&self) -> Option<vector_api_client::Auth> {
+        match (&self.token, &self.user) {
+            (Some(token), _) => Some(vector_api_client::Auth::Bearer {
+                token: token.clone(),
+            }),
+            (None, Some(user)) => Some(vector_api_client::Auth::Basic {
+                user: user.clone(),
+                password: self.password.clone().unwrap_or_default(),
+            }),
+            (None, None) => None,
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub enum Color {
     Auto,
@@ -1,7 +1,8 @@
+use crate::{http::Auth, tls::TlsConfig};
 use serde::{Deserialize, Serialize};
 use std::net::{Ipv4Addr, SocketAddr};
 
-#[derive(Debug, Deserialize, Serialize, PartialEq, Copy, Clone)]
+#[derive(Debug, Deserialize, Serialize, PartialEq, Clone)]
 #[serde(default)]
 pub struct Options {
     #[serde(default = "default_enabled")]
@@ -12,6 +13,18 @@ pub struct Options {
 
     #[serde(default = "default_playground")]
     pub playground: bool,
+
+    /// Mutations are only accepted with this set, and from requests
+    /// authenticated with `auth`.
+    #[serde(default)]
+    pub allow_mutations: bool,
+
+    /// Required of requests to `/graphql`, over HTTP and websockets.
+    #[serde(default)]
+    pub auth: Option<Auth>,
+
+    #[serde(default)]
+    pub tls: Option<TlsConfig>,
 }
 
 impl Default for Options {
@@ -20,6 +33,9 @@ impl Default for Options {
             enabled: default_enabled(),
             playground: default_playground(),
             bind: default_bind(),
+            allow_mutations: false,
+            auth: None,
+            tls: None,
         }
     }
 }
@@ -58,10 +74,27 @@ impl Options {
             },
         };
 
+        let auth = match (self.auth.take(), other.auth) {
+            (None, b) => b,
+            (Some(a), None) => Some(a),
+            (Some(a), Some(b)) if a == b => Some(a),
+            (Some(_), Some(_)) => return Err("Conflicting `api` auth.".to_owned()),
+        };
+
+        let tls = match (self.tls.take(), other.tls) {
+            (None, b) => b,
+            (Some(a), None) => Some(a),
+            (Some(a), Some(b)) if a == b => Some(a),
+            (Some(_), Some(_)) => return Err("Conflicting `api` TLS settings.".to_owned()),
+        };
+
         let options = Options {
             bind,
             enabled: self.enabled | other.enabled,
             playground: self.playground & other.playground,
+            allow_mutations: self.allow_mutations | other.allow_mutations,
+            auth,
+            tls,
         };
 
         *self = options;
@@ -75,6 +108,7 @@ fn bool_merge() {
         enabled: true,
         bind: None,
         playground: false,
+        ..Options::default()
     };
 
     a.merge(Options::default()).unwrap();
@@ -85,6 +119,7 @@ fn bool_merge() {
             enabled: true,
             bind: default_bind(),
             playground: false,
+            ..Options::default()
         }
     );
 }
@@ -96,6 +131,7 @@ fn bind_merge() {
         enabled: true,
         bind: Some(address),
         playground: true,
+        ..Options::default()
     };
 
     a.merge(Options::default()).unwrap();
@@ -106,6 +142,7 @@ fn bind_merge() {
             enabled: true,
             bind: Some(address),
             playground: true,
+            ..Options::default()
         }
     );
 }
@@ -124,3 +161,39 @@ fn bind_conflict() {
 
     assert!(a.merge(b).is_err());
 }
+
+#[test]
+fn auth_merge() {
+    let auth = Auth::Bearer {
+        token: "secret".to_owned(),
+    };
+    let mut a = Options {
+        allow_mutations: true,
+        auth: Some(auth.clone()),
+        ..Options::default()
+    };
+
+    a.merge(Options::default()).unwrap();
+
+    assert!(a.allow_mutations);
+    assert_eq!(a.auth, Some(auth));
+}
+
+#[test]
+fn auth_conflict() {
+    let mut a = Options {
+        auth: Some(Auth::Bearer {
+            token: "secret".to_owned(),
+        }),
+        ..Options::default()
+    };
+
+    let b = Options {
+        auth: Some(Auth::Bearer {
+            token: "other".to_owned(),
+        }),
+        ..Options::default()
+    };
+
+    assert!(a.merge(b).is_err());
+}
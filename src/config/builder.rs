@@ -1,9 +1,10 @@
 #[cfg(feature = "api")]
 use super::api;
 use super::{
-    compiler, default_data_dir, Config, GlobalOptions, SinkConfig, SinkOuter, SourceConfig,
-    TestDefinition, TransformConfig, TransformOuter,
+    compiler, default_data_dir, telemetry, Config, GlobalOptions, SinkConfig, SinkOuter,
+    SourceConfig, TestDefinition, TransformConfig, TransformOuter,
 };
+use crate::enrichment_tables;
 use indexmap::IndexMap;
 use serde::{Deserialize, Serialize};
 
@@ -16,12 +17,16 @@ pub struct ConfigBuilder {
     #[serde(default)]
     pub api: api::Options,
     #[serde(default)]
+    pub telemetry: telemetry::Options,
+    #[serde(default)]
     pub sources: IndexMap<String, Box<dyn SourceConfig>>,
     #[serde(default)]
     pub sinks: IndexMap<String, SinkOuter>,
     #[serde(default)]
     pub transforms: IndexMap<String, TransformOuter>,
     #[serde(default)]
+    pub enrichment_tables: IndexMap<String, enrichment_tables::TableConfig>,
+    #[serde(default)]
     pub tests: Vec<TestDefinition>,
 }
 
@@ -56,6 +61,7 @@ impl ConfigBuilder {
         let sink = SinkOuter {
             buffer: Default::default(),
             healthcheck: true,
+            proxy: Default::default(),
             inner: Box::new(sink),
             inputs,
         };
@@ -86,6 +92,10 @@ impl ConfigBuilder {
             errors.push(error);
         }
 
+        if let Err(error) = self.telemetry.merge(with.telemetry) {
+            errors.push(error);
+        }
+
         if self.global.data_dir.is_none() || self.global.data_dir == default_data_dir() {
             self.global.data_dir = with.global.data_dir;
         } else if with.global.data_dir != default_data_dir()
@@ -96,6 +106,13 @@ impl ConfigBuilder {
             errors.push("conflicting values for 'data_dir' found".to_owned());
         }
 
+        if self.global.proxy == Default::default() {
+            self.global.proxy = with.global.proxy;
+        } else if with.global.proxy != Default::default() && self.global.proxy != with.global.proxy
+        {
+            errors.push("conflicting values for 'proxy' found".to_owned());
+        }
+
         // If the user has multiple config files, we must *merge* log schemas until we meet a
         // conflict, then we are allowed to error.
         if let Err(merge_errors) = self.global.log_schema.merge(with.global.log_schema) {
@@ -117,6 +134,11 @@ impl ConfigBuilder {
                 errors.push(format!("duplicate transform name found: {}", k));
             }
         });
+        with.enrichment_tables.keys().for_each(|k| {
+            if self.enrichment_tables.contains_key(k) {
+                errors.push(format!("duplicate enrichment table name found: {}", k));
+            }
+        });
         with.tests.iter().for_each(|wt| {
             if self.tests.iter().any(|t| t.name == wt.name) {
                 errors.push(format!("duplicate test name found: {}", wt.name));
@@ -129,6 +151,7 @@ impl ConfigBuilder {
         self.sources.extend(with.sources);
         self.sinks.extend(with.sinks);
         self.transforms.extend(with.transforms);
+        self.enrichment_tables.extend(with.enrichment_tables);
         self.tests.extend(with.tests);
 
         Ok(())
@@ -6,9 +6,11 @@ pub fn compile(raw: ConfigBuilder) -> Result<Config, Vec<String>> {
         global: raw.global,
         #[cfg(feature = "api")]
         api: raw.api,
+        telemetry: raw.telemetry,
         sources: raw.sources,
         sinks: raw.sinks,
         transforms: raw.transforms,
+        enrichment_tables: raw.enrichment_tables,
         tests: raw.tests,
         expansions: Default::default(),
     };
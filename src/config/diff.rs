@@ -14,10 +14,23 @@ impl ConfigDiff {
     }
 
     pub fn new(old: &Config, new: &Config) -> Self {
+        let mut sinks = Difference::new(&old.sinks, &new.sinks);
+
+        // Sinks are built with the global proxy settings, so they all have to
+        // be rebuilt when those change.
+        if old.global.proxy != new.global.proxy {
+            sinks.to_change.extend(
+                old.sinks
+                    .keys()
+                    .filter(|name| new.sinks.contains_key(*name))
+                    .cloned(),
+            );
+        }
+
         ConfigDiff {
             sources: Difference::new(&old.sources, &new.sources),
             transforms: Difference::new(&old.transforms, &new.transforms),
-            sinks: Difference::new(&old.sinks, &new.sinks),
+            sinks,
         }
     }
 
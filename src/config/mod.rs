@@ -1,6 +1,14 @@
 use crate::{
-    buffers::Acker, conditions, event::Metric, shutdown::ShutdownSignal, sinks, sources,
-    transforms, Pipeline,
+    buffers::{Acker, WhenFull},
+    conditions,
+    enrichment_tables::EnrichmentTables,
+    event::Metric,
+    shutdown::ShutdownSignal,
+    sinks::{
+        self,
+        util::flush::{FlushHandle, FlushSignal},
+    },
+    sources, transforms, Pipeline,
 };
 use async_trait::async_trait;
 use component::ComponentDescription;
@@ -19,6 +27,9 @@ pub mod component;
 mod diff;
 mod loading;
 mod log_schema;
+mod proxy;
+mod redaction;
+pub mod telemetry;
 mod unit_test;
 mod validation;
 mod vars;
@@ -28,6 +39,8 @@ pub use builder::ConfigBuilder;
 pub use diff::ConfigDiff;
 pub use loading::{load_from_paths, load_from_str, process_paths, CONFIG_PATHS};
 pub use log_schema::{log_schema, LogSchema, LOG_SCHEMA};
+pub use proxy::{ProxyConfig, ProxyError};
+pub use redaction::{redacted_json, REDACTED};
 pub use unit_test::build_unit_tests_main as build_unit_tests;
 pub use validation::warnings;
 
@@ -36,9 +49,11 @@ pub struct Config {
     pub global: GlobalOptions,
     #[cfg(feature = "api")]
     pub api: api::Options,
+    pub telemetry: telemetry::Options,
     pub sources: IndexMap<String, Box<dyn SourceConfig>>,
     pub sinks: IndexMap<String, SinkOuter>,
     pub transforms: IndexMap<String, TransformOuter>,
+    pub enrichment_tables: IndexMap<String, crate::enrichment_tables::TableConfig>,
     tests: Vec<TestDefinition>,
     expansions: IndexMap<String, Vec<String>>,
 }
@@ -52,6 +67,11 @@ pub struct GlobalOptions {
         default
     )]
     pub log_schema: LogSchema,
+    #[serde(
+        skip_serializing_if = "crate::serde::skip_serializing_if_default",
+        default
+    )]
+    pub proxy: ProxyConfig,
 }
 
 pub fn default_data_dir() -> Option<PathBuf> {
@@ -173,6 +193,11 @@ pub struct SinkOuter {
     pub buffer: crate::buffers::BufferConfig,
     #[serde(default = "healthcheck_default")]
     pub healthcheck: bool,
+    #[serde(
+        skip_serializing_if = "crate::serde::skip_serializing_if_default",
+        default
+    )]
+    pub proxy: ProxyConfig,
     pub inputs: Vec<String>,
     #[serde(flatten)]
     pub inner: Box<dyn SinkConfig>,
@@ -201,17 +226,40 @@ pub trait SinkConfig: core::fmt::Debug + Send + Sync {
 #[derive(Debug, Clone)]
 pub struct SinkContext {
     pub(super) acker: Acker,
+    pub(super) flush: FlushHandle,
+    pub(super) proxy: ProxyConfig,
+    pub(super) when_full: WhenFull,
 }
 
 impl SinkContext {
     #[cfg(test)]
     pub fn new_test() -> Self {
-        Self { acker: Acker::Null }
+        Self {
+            acker: Acker::Null,
+            flush: FlushHandle::default(),
+            proxy: ProxyConfig::default(),
+            when_full: WhenFull::Block,
+        }
     }
 
     pub fn acker(&self) -> Acker {
         self.acker.clone()
     }
+
+    /// A signal for a batching sink to receive the flushes requested for it.
+    pub fn flush_signal(&self) -> FlushSignal {
+        self.flush.signal()
+    }
+
+    /// The sink's proxy settings, merged with the global ones.
+    pub fn proxy(&self) -> &ProxyConfig {
+        &self.proxy
+    }
+
+    /// What the sink's buffer does when it is full.
+    pub fn when_full(&self) -> WhenFull {
+        self.when_full
+    }
 }
 
 pub type SinkDescription = ComponentDescription<Box<dyn SinkConfig>>;
@@ -230,6 +278,15 @@ pub struct TransformOuter {
 pub trait TransformConfig: core::fmt::Debug + Send + Sync + dyn_clone::DynClone {
     async fn build(&self) -> crate::Result<transforms::Transform>;
 
+    /// Builds the transform with the resources of the topology it runs in.
+    /// Only transforms using those resources need to override this.
+    async fn build_with_context(
+        &self,
+        _cx: &TransformContext,
+    ) -> crate::Result<transforms::Transform> {
+        self.build().await
+    }
+
     fn input_type(&self) -> DataType;
 
     fn output_type(&self) -> DataType;
@@ -246,6 +303,18 @@ pub trait TransformConfig: core::fmt::Debug + Send + Sync + dyn_clone::DynClone
 
 dyn_clone::clone_trait_object!(TransformConfig);
 
+#[derive(Debug, Clone, Default)]
+pub struct TransformContext {
+    pub(super) enrichment_tables: EnrichmentTables,
+}
+
+impl TransformContext {
+    /// The enrichment tables of the topology.
+    pub fn enrichment_tables(&self) -> &EnrichmentTables {
+        &self.enrichment_tables
+    }
+}
+
 pub type TransformDescription = ComponentDescription<Box<dyn TransformConfig>>;
 
 inventory::collect!(TransformDescription);
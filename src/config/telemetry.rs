@@ -0,0 +1,141 @@
+#[cfg(feature = "sinks-prometheus")]
+use crate::sinks::prometheus::{default_histogram_buckets, default_summary_quantiles};
+use crate::tls::TlsOptions;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+#[cfg(feature = "sinks-prometheus")]
+use std::net::{Ipv4Addr, SocketAddr};
+
+/// How Vector reports its own telemetry, besides the `internal_metrics` and
+/// `internal_logs` sources.
+#[derive(Debug, Default, Deserialize, Serialize, PartialEq, Clone)]
+#[serde(default, deny_unknown_fields)]
+pub struct Options {
+    /// Serves the internal metrics for Prometheus to scrape.
+    #[cfg(feature = "sinks-prometheus")]
+    pub prometheus: Option<PrometheusOptions>,
+    /// Exports sampled spans as OpenTelemetry traces.
+    pub otlp: Option<OtlpOptions>,
+    /// Records the time logs spend in Vector, from their source until their
+    /// sinks ack them, as the `end_to_end_latency_nanoseconds` histogram.
+    /// Metrics are never timed. Only these sinks record it:
+    /// `aws_cloudwatch_logs`, `aws_kinesis_firehose`, `aws_kinesis_streams`,
+    /// `aws_s3`, `azure_monitor_logs`, `blackhole`, `clickhouse`, `console`,
+    /// `datadog_logs`, `elasticsearch`, `file`, `gcp_cloud_storage`,
+    /// `gcp_pubsub`, `gcp_stackdriver_logs`, `honeycomb`, `http`,
+    /// `humio_logs`, `influxdb_logs`, `logdna`, `loki`, `new_relic_logs`,
+    /// `sematext_logs` and `splunk_hec`. `kafka`, `papertrail`, `pulsar`,
+    /// `socket` and `vector` don't.
+    pub end_to_end_latency: bool,
+}
+
+#[cfg(feature = "sinks-prometheus")]
+#[derive(Debug, Deserialize, Serialize, PartialEq, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct PrometheusOptions {
+    /// Address of the standalone listener serving `/metrics`. With the API
+    /// enabled, the metrics are served at its `/metrics` as well.
+    #[serde(default = "default_address")]
+    pub address: SocketAddr,
+    #[serde(default = "default_histogram_buckets")]
+    pub buckets: Vec<f64>,
+    #[serde(default = "default_summary_quantiles")]
+    pub quantiles: Vec<f64>,
+}
+
+#[cfg(feature = "sinks-prometheus")]
+impl Default for PrometheusOptions {
+    fn default() -> Self {
+        Self {
+            address: default_address(),
+            buckets: default_histogram_buckets(),
+            quantiles: default_summary_quantiles(),
+        }
+    }
+}
+
+/// By default, the metrics are served at 127.0.0.1:9599, next to the default
+/// port of the `prometheus` sink
+#[cfg(feature = "sinks-prometheus")]
+pub fn default_address() -> SocketAddr {
+    SocketAddr::new(Ipv4Addr::new(127, 0, 0, 1).into(), 9599)
+}
+
+#[derive(Debug, Deserialize, Serialize, PartialEq, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct OtlpOptions {
+    /// OTLP/HTTP traces endpoint, such as `http://localhost:4318/v1/traces`.
+    pub endpoint: String,
+    /// Share of traces exported, from 0 to 1. Traces are sampled as a whole.
+    #[serde(default = "default_sample_rate")]
+    pub sample_rate: f64,
+    /// Names of the spans exported. Spans nested in an exported span join its
+    /// trace; others start a trace of their own.
+    #[serde(default = "default_spans")]
+    pub spans: Vec<String>,
+    #[serde(default)]
+    pub headers: BTreeMap<String, String>,
+    pub tls: Option<TlsOptions>,
+}
+
+fn default_sample_rate() -> f64 {
+    0.1
+}
+
+/// By default, the lifetimes of the batches of sinks, from their first event
+/// until they're sent, and the requests of sinks are exported
+fn default_spans() -> Vec<String> {
+    vec!["batch".to_owned(), "request".to_owned()]
+}
+
+impl Options {
+    pub fn merge(&mut self, other: Self) -> Result<(), String> {
+        self.end_to_end_latency |= other.end_to_end_latency;
+
+        self.otlp = match (self.otlp.take(), other.otlp) {
+            (None, b) => b,
+            (Some(a), None) => Some(a),
+            (Some(a), Some(b)) if a == b => Some(a),
+            (Some(_), Some(_)) => return Err("Conflicting `telemetry.otlp` settings.".to_owned()),
+        };
+
+        #[cfg(feature = "sinks-prometheus")]
+        {
+            self.prometheus = match (self.prometheus.take(), other.prometheus) {
+                (None, b) => b,
+                (Some(a), None) => Some(a),
+                (Some(a), Some(b)) if a == b => Some(a),
+                (Some(_), Some(_)) => {
+                    return Err("Conflicting `telemetry.prometheus` settings.".to_owned())
+                }
+            };
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(all(test, feature = "sinks-prometheus"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn prometheus_merge() {
+        let mut a = Options::default();
+        a.merge(Options {
+            prometheus: Some(PrometheusOptions::default()),
+            ..Options::default()
+        })
+        .unwrap();
+        assert_eq!(a.prometheus, Some(PrometheusOptions::default()));
+
+        let other = Options {
+            prometheus: Some(PrometheusOptions {
+                buckets: vec![1.0],
+                ..PrometheusOptions::default()
+            }),
+            ..Options::default()
+        };
+        assert!(a.merge(other).is_err());
+    }
+}
@@ -1,7 +1,8 @@
 use super::{Config, ConfigBuilder, TestCondition, TestDefinition, TestInput, TestInputValue};
-use crate::config::TransformConfig;
+use crate::config::{TransformConfig, TransformContext};
 use crate::{
     conditions::{Condition, ConditionConfig},
+    enrichment_tables::EnrichmentTables,
     event::{Event, Value},
     transforms::Transform,
 };
@@ -28,17 +29,25 @@ async fn build_unit_tests(builder: ConfigBuilder) -> Result<Vec<UnitTest>, Vec<S
         global: builder.global,
         #[cfg(feature = "api")]
         api: builder.api,
+        telemetry: builder.telemetry,
         sources: builder.sources,
         sinks: builder.sinks,
         transforms: builder.transforms,
+        enrichment_tables: builder.enrichment_tables,
         tests: builder.tests,
         expansions: Default::default(),
     };
 
     super::compiler::expand_macros(&mut config)?;
 
+    // Every run loads its own tables, so that tests don't share them.
+    let cx = TransformContext {
+        enrichment_tables: EnrichmentTables::load(&config.enrichment_tables, &Default::default())?
+            .commit(),
+    };
+
     for test in &config.tests {
-        match build_unit_test(test, &config).await {
+        match build_unit_test(test, &config, &cx).await {
             Ok(t) => tests.push(t),
             Err(errs) => {
                 let mut test_err = errs.join("\n");
@@ -385,6 +394,7 @@ fn build_inputs(
 async fn build_unit_test(
     definition: &TestDefinition,
     config: &Config,
+    cx: &TransformContext,
 ) -> Result<UnitTest, Vec<String>> {
     let mut errors = vec![];
 
@@ -451,7 +461,7 @@ async fn build_unit_test(
     let mut transforms: IndexMap<String, UnitTestTransform> = IndexMap::new();
     for (name, transform_config) in &config.transforms {
         if let Some(outputs) = transform_outputs.remove(name) {
-            match transform_config.inner.build().await {
+            match transform_config.inner.build_with_context(cx).await {
                 Ok(transform) => {
                     transforms.insert(
                         name.clone(),
@@ -94,6 +94,21 @@ pub fn warnings(config: &Config) -> Option<Vec<String>> {
         }
     }
 
+    #[cfg(feature = "api")]
+    if config.api.auth.is_some()
+        && !config
+            .api
+            .tls
+            .as_ref()
+            .and_then(|tls| tls.enabled)
+            .unwrap_or(false)
+    {
+        warnings.push(
+            "API authentication is configured without TLS, so its credentials are sent in plaintext"
+                .to_owned(),
+        );
+    }
+
     if warnings.is_empty() {
         None
     } else {
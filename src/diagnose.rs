@@ -0,0 +1,312 @@
+#[cfg(feature = "api-client")]
+use crate::cli::ApiAuthOpts;
+use crate::{
+    config::{self, Config},
+    get_hostname, get_version,
+    metrics::{capture_metrics, get_controller},
+    validate,
+};
+use chrono::Utc;
+use flate2::{write::GzEncoder, Compression};
+use serde_json::{json, Value};
+use std::{
+    fs::File,
+    io::{self, Write},
+    path::PathBuf,
+};
+use structopt::StructOpt;
+use tar::{EntryType, Header};
+#[cfg(feature = "api-client")]
+use url::Url;
+
+#[derive(StructOpt, Debug)]
+#[structopt(rename_all = "kebab-case")]
+pub struct Opts {
+    /// File to write the bundle to. Defaults to `vector-diagnose-<timestamp>.tar.gz` in the
+    /// current directory.
+    #[structopt(short, long)]
+    output: Option<PathBuf>,
+
+    /// Disables environment checks. That includes component checks and health checks.
+    #[structopt(long)]
+    no_environment: bool,
+
+    /// Vector GraphQL API server endpoint of a running instance to query, over `http` or
+    /// `https`. Defaults to the local API address
+    #[cfg(feature = "api-client")]
+    #[structopt(short, long)]
+    url: Option<Url>,
+
+    #[cfg(feature = "api-client")]
+    #[structopt(flatten)]
+    auth: ApiAuthOpts,
+
+    /// Any number of Vector config files to include. If none are specified the
+    /// default config path `/etc/vector/vector.toml` will be targeted.
+    paths: Vec<PathBuf>,
+}
+
+/// The running instance is asked for its health, its components with their
+/// metrics and configs, and the connections between them.
+#[cfg(feature = "api-client")]
+const API_QUERY: &str = r#"
+query Diagnose {
+  health
+  sinksHealth { name status }
+  components {
+    __typename
+    name
+    componentType
+    config
+    eventsProcessedTotal { eventsProcessedTotal }
+    errorsTotal { errorsTotal }
+  }
+  graph { edges { from to } }
+}
+"#;
+
+/// Gathers what a support ticket needs into a tarball: the version of
+/// Vector, the host, the config with its secrets redacted, the results of
+/// validating it, the internal metrics, and the state of a running instance.
+pub async fn cmd(opts: &Opts) -> exitcode::ExitCode {
+    let now = Utc::now();
+    let name = format!("vector-diagnose-{}", now.format("%Y%m%dT%H%M%SZ"));
+    let mut bundle = Bundle::new(&name, now.timestamp() as u64);
+
+    bundle.add("info.json", pretty(&info()));
+
+    let paths = config::process_paths(&opts.paths).unwrap_or_default();
+    match config::load_from_paths(&paths) {
+        Ok(config) => {
+            bundle.add("config.json", redacted_config(&config));
+            if let Some(warnings) = config::warnings(&config) {
+                bundle.add("config_warnings.txt", lines(warnings));
+            }
+            if !opts.no_environment {
+                let (validated, mut report) = validate::check_environment(config).await;
+                report.push_str(if validated {
+                    "Validated\n"
+                } else {
+                    "Validation failed\n"
+                });
+                bundle.add("validation.txt", report);
+            }
+        }
+        Err(errors) => {
+            eprintln!("Failed to load {:?}", paths);
+            bundle.add("config_errors.txt", lines(errors));
+        }
+    }
+
+    #[cfg(feature = "api-client")]
+    query_api(opts, &mut bundle).await;
+
+    // Captured last, to include the metrics of the checks
+    bundle.add("internal_metrics.json", internal_metrics());
+
+    let path = opts
+        .output
+        .clone()
+        .unwrap_or_else(|| PathBuf::from(format!("{}.tar.gz", name)));
+    match File::create(&path).and_then(|file| bundle.write(file)) {
+        Ok(()) => {
+            println!("Wrote {}", path.display());
+            exitcode::OK
+        }
+        Err(error) => {
+            eprintln!("Couldn't write {}: {}", path.display(), error);
+            exitcode::IOERR
+        }
+    }
+}
+
+fn info() -> Value {
+    json!({
+        "version": get_version(),
+        "hostname": get_hostname().ok(),
+        "os": std::env::consts::OS,
+        "arch": std::env::consts::ARCH,
+        "cpus": num_cpus::get(),
+        "timestamp": Utc::now().to_rfc3339(),
+    })
+}
+
+fn redacted_config(config: &Config) -> String {
+    #[allow(unused_mut)]
+    let mut value = json!({
+        "global": config.global,
+        "telemetry": config.telemetry,
+        "sources": config.sources,
+        "transforms": config.transforms,
+        "sinks": config.sinks,
+        "enrichment_tables": config.enrichment_tables,
+    });
+    #[cfg(feature = "api")]
+    {
+        value["api"] = json!(config.api);
+    }
+
+    let redacted = config::redacted_json(&value);
+    serde_json::from_str(&redacted)
+        .map(|value: Value| pretty(&value))
+        .unwrap_or(redacted)
+}
+
+fn internal_metrics() -> String {
+    match get_controller() {
+        Ok(controller) => lines(
+            capture_metrics(controller)
+                .map(|event| serde_json::to_string(&event.into_metric()).unwrap_or_default()),
+        ),
+        Err(error) => format!("Metrics aren't available: {}\n", error),
+    }
+}
+
+#[cfg(feature = "api-client")]
+async fn query_api(opts: &Opts, bundle: &mut Bundle) {
+    let url = opts.url.clone().unwrap_or_else(|| {
+        let addr = config::api::default_bind().unwrap();
+        Url::parse(&*format!("http://{}/graphql", addr))
+            .expect("Couldn't parse default API URL. Please report this.")
+    });
+    let auth = opts.auth.auth().map(|auth| auth.header_value());
+    let client = reqwest::Client::new();
+    let request = |request: reqwest::RequestBuilder| match &auth {
+        Some(auth) => request.header(reqwest::header::AUTHORIZATION, auth.as_str()),
+        None => request,
+    };
+
+    let health = url.join("health").expect("Health URL is valid");
+    if let Err(error) = client
+        .get(health)
+        .send()
+        .await
+        .and_then(|response| response.error_for_status())
+    {
+        bundle.add(
+            "api/unreachable.txt",
+            format!("Vector API server at {} not reachable: {}\n", url, error),
+        );
+        return;
+    }
+
+    let query = request(client.post(url.clone()))
+        .json(&json!({ "query": API_QUERY }))
+        .send()
+        .await;
+    bundle.add("api/graphql.json", response_text(query).await);
+
+    // Only served when `telemetry.prometheus` is configured
+    let metrics = url.join("metrics").expect("Metrics URL is valid");
+    let metrics = request(client.get(metrics)).send().await;
+    bundle.add("api/metrics.txt", response_text(metrics).await);
+}
+
+#[cfg(feature = "api-client")]
+async fn response_text(response: reqwest::Result<reqwest::Response>) -> String {
+    let response = match response.and_then(|response| response.error_for_status()) {
+        Ok(response) => response,
+        Err(error) => return format!("{}\n", error),
+    };
+    match response.text().await {
+        Ok(text) => serde_json::from_str(&text)
+            .map(|value: Value| pretty(&value))
+            .unwrap_or(text),
+        Err(error) => format!("{}\n", error),
+    }
+}
+
+fn pretty(value: &Value) -> String {
+    serde_json::to_string_pretty(value).expect("JSON values serialize") + "\n"
+}
+
+fn lines<I: IntoIterator>(lines: I) -> String
+where
+    I::Item: std::fmt::Display,
+{
+    lines
+        .into_iter()
+        .map(|line| format!("{}\n", line))
+        .collect()
+}
+
+/// Files written as a gzipped tarball, in a directory named after the bundle.
+struct Bundle {
+    name: String,
+    mtime: u64,
+    files: Vec<(String, Vec<u8>)>,
+}
+
+impl Bundle {
+    fn new(name: &str, mtime: u64) -> Self {
+        Self {
+            name: name.to_owned(),
+            mtime,
+            files: Vec::new(),
+        }
+    }
+
+    fn add(&mut self, path: &str, contents: impl Into<Vec<u8>>) {
+        self.files
+            .push((format!("{}/{}", self.name, path), contents.into()));
+    }
+
+    fn write(&self, writer: impl Write) -> io::Result<()> {
+        let mut builder = tar::Builder::new(GzEncoder::new(writer, Compression::default()));
+        for (path, contents) in &self.files {
+            let mut header = Header::new_ustar();
+            header.set_entry_type(EntryType::Regular);
+            header.set_mode(0o644);
+            header.set_mtime(self.mtime);
+            header.set_size(contents.len() as u64);
+            builder.append_data(&mut header, path, &contents[..])?;
+        }
+        builder.into_inner()?.finish()?.flush()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use flate2::read::GzDecoder;
+    use std::io::Read;
+    use tar::Archive;
+
+    #[test]
+    fn writes_tarball() {
+        let mut bundle = Bundle::new("vector-diagnose-test", 1_600_000_000);
+        bundle.add("info.json", "{}\n");
+        bundle.add("empty.txt", "");
+
+        let mut gzipped = Vec::new();
+        bundle.write(&mut gzipped).unwrap();
+
+        let mut archive = Archive::new(GzDecoder::new(&gzipped[..]));
+        let entries = archive
+            .entries()
+            .unwrap()
+            .map(|entry| {
+                let mut entry = entry.unwrap();
+                let header = entry.header();
+                assert_eq!(header.entry_type(), EntryType::Regular);
+                assert_eq!(header.mode().unwrap(), 0o644);
+                assert_eq!(header.mtime().unwrap(), 1_600_000_000);
+                let path = entry.path().unwrap().to_string_lossy().into_owned();
+                let mut contents = String::new();
+                entry.read_to_string(&mut contents).unwrap();
+                (path, contents)
+            })
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            entries,
+            vec![
+                (
+                    "vector-diagnose-test/info.json".to_owned(),
+                    "{}\n".to_owned()
+                ),
+                ("vector-diagnose-test/empty.txt".to_owned(), String::new()),
+            ]
+        );
+    }
+}
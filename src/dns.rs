@@ -3,6 +3,7 @@ use futures01::Future;
 use hyper::client::connect::dns::Name as Name13;
 use snafu::ResultExt;
 use std::{
+    cell::Cell,
     net::{IpAddr, Ipv4Addr, SocketAddr, ToSocketAddrs},
     task::{Context, Poll},
 };
@@ -13,6 +14,33 @@ pub type ResolverFuture = Box<dyn Future<Item = LookupIp, Error = DnsError> + Se
 
 pub struct LookupIp(std::vec::IntoIter<SocketAddr>);
 
+thread_local! {
+    static RUNTIME_WORKER: Cell<bool> = Cell::new(false);
+}
+
+/// Marks the calling thread as one started by the multi-threaded runtime.
+/// Meant for `runtime::Builder::on_thread_start`.
+pub fn mark_runtime_worker() {
+    RUNTIME_WORKER.with(|worker| worker.set(true));
+}
+
+/// Runs a blocking call from synchronous code that may be running in a task.
+///
+/// On threads of the multi-threaded runtime the worker hands its other tasks
+/// over to another thread until the call returns. Anywhere else, such as on
+/// the basic scheduler where `tokio::task::block_in_place` panics, the call
+/// simply blocks the thread.
+pub fn block_in_place<F, R>(f: F) -> R
+where
+    F: FnOnce() -> R,
+{
+    if RUNTIME_WORKER.with(Cell::get) {
+        tokio::task::block_in_place(f)
+    } else {
+        f()
+    }
+}
+
 #[derive(Debug, Clone, Copy)]
 pub struct Resolver;
 
@@ -48,6 +76,23 @@ impl Resolver {
             .context(UnableLookup)
         }
     }
+
+    /// Resolves the host name of the given address.
+    ///
+    /// Unlike [`Resolver::lookup_ip`] this blocks the calling thread. Callers
+    /// running in a task should wrap it in [`block_in_place`], and cache its
+    /// results.
+    pub fn reverse_lookup(self, ip: IpAddr) -> Result<String, DnsError> {
+        let host = dns_lookup::lookup_addr(&ip).context(UnableReverseLookup)?;
+
+        // Without a matching record, the address is returned in its numeric
+        // form.
+        if host.parse::<IpAddr>().is_ok() {
+            Err(DnsError::NoHostname { ip })
+        } else {
+            Ok(host)
+        }
+    }
 }
 
 impl Iterator for LookupIp {
@@ -78,6 +123,10 @@ pub enum DnsError {
     UnableLookup { source: tokio::io::Error },
     #[snafu(display("Failed to join with resolving future: {}", source))]
     JoinError { source: tokio::task::JoinError },
+    #[snafu(display("Unable to resolve address: {}", source))]
+    UnableReverseLookup { source: tokio::io::Error },
+    #[snafu(display("No host name found for address {}", ip))]
+    NoHostname { ip: IpAddr },
 }
 
 #[cfg(test)]
@@ -108,4 +157,13 @@ mod tests {
     async fn resolve_ipv6() {
         assert!(resolve("::1").await);
     }
+
+    // Depends on the host's resolver configuration.
+    #[ignore]
+    #[test]
+    fn reverse_lookup_localhost() {
+        assert!(Resolver
+            .reverse_lookup("127.0.0.1".parse().unwrap())
+            .is_ok());
+    }
 }
@@ -0,0 +1,610 @@
+//! Enrichment tables hold reference data loaded from CSV or JSON files, such as
+//! service owners by hostname, that events can be joined against.
+//!
+//! Tables are configured in the top-level `enrichment_tables` section and
+//! loaded when the topology is built. Components get them through their
+//! context, and a background task reloads each table once its file changes.
+//! They are queried by the `get_enrichment_table_record` remap function and
+//! the `enrichment_table` transform.
+
+use crate::{
+    event::Value,
+    internal_events::{EnrichmentTableReloadFailed, EnrichmentTableReloaded},
+};
+use indexmap::IndexMap;
+use serde::{Deserialize, Serialize};
+use snafu::{ResultExt, Snafu};
+use std::{
+    collections::{BTreeMap, HashMap},
+    fs::File,
+    io::BufReader,
+    path::{Path, PathBuf},
+    sync::{Arc, RwLock, Weak},
+    time::{Duration, SystemTime},
+};
+use tokio::task::spawn_blocking;
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct TableConfig {
+    pub path: PathBuf,
+    pub format: Format,
+    #[serde(default = "default_delimiter")]
+    pub delimiter: char,
+    #[serde(default = "crate::serde::default_true")]
+    pub case_sensitive: bool,
+    #[serde(default)]
+    pub indexes: Vec<Vec<String>>,
+    #[serde(default = "default_reload_interval_secs")]
+    pub reload_interval_secs: u64,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum Format {
+    /// Comma separated values, with the field names in the first row.
+    Csv,
+    /// An array of objects.
+    Json,
+}
+
+fn default_delimiter() -> char {
+    ','
+}
+
+fn default_reload_interval_secs() -> u64 {
+    10
+}
+
+#[derive(Debug, Snafu)]
+pub enum EnrichmentTableError {
+    #[snafu(display("Unable to open {:?}: {}", path, source))]
+    Open {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+    #[snafu(display("Unable to parse CSV in {:?}: {}", path, source))]
+    ParseCsv { path: PathBuf, source: csv::Error },
+    #[snafu(display("Unable to parse JSON in {:?}: {}", path, source))]
+    ParseJson {
+        path: PathBuf,
+        source: serde_json::Error,
+    },
+    #[snafu(display("Expected {:?} to contain an array of objects", path))]
+    InvalidJson { path: PathBuf },
+    #[snafu(display("Delimiter {:?} is not a single byte character", delimiter))]
+    InvalidDelimiter { delimiter: char },
+    #[snafu(display("Unknown enrichment table {:?}", name))]
+    UnknownTable { name: String },
+    #[snafu(display("No matching record found"))]
+    NoRecord,
+    #[snafu(display("More than one matching record found"))]
+    MultipleRecords,
+}
+
+/// The tables of a topology, by name.
+#[derive(Clone, Debug, Default)]
+pub struct EnrichmentTables {
+    tables: HashMap<String, Arc<Table>>,
+}
+
+impl EnrichmentTables {
+    /// Loads the configured tables.
+    ///
+    /// Tables of `current` whose config is unchanged are shared as they are.
+    /// Tables whose config changed keep their identity, so that components
+    /// holding on to them see the new data, but the data is only swapped in
+    /// once the returned tables are committed. If any table fails to load,
+    /// nothing changes.
+    pub fn load(
+        configs: &IndexMap<String, TableConfig>,
+        current: &EnrichmentTables,
+    ) -> Result<LoadedTables, Vec<String>> {
+        let mut tables = HashMap::new();
+        let mut replaced = Vec::new();
+        let mut errors = Vec::new();
+
+        for (name, config) in configs {
+            let existing = current.tables.get(name);
+            if let Some(table) = existing.filter(|table| table.config() == *config) {
+                tables.insert(name.clone(), Arc::clone(table));
+                continue;
+            }
+
+            let loaded = match Loaded::load(config.clone()) {
+                Ok(loaded) => loaded,
+                Err(error) => {
+                    errors.push(format!("Enrichment table {:?}: {}", name, error));
+                    continue;
+                }
+            };
+
+            let table = match existing {
+                Some(table) => {
+                    replaced.push((Arc::clone(table), loaded));
+                    Arc::clone(table)
+                }
+                None => {
+                    let table = Arc::new(Table::new(name.clone(), loaded));
+                    tokio::spawn(watch(Arc::downgrade(&table)));
+                    table
+                }
+            };
+            tables.insert(name.clone(), table);
+        }
+
+        if errors.is_empty() {
+            Ok(LoadedTables {
+                tables: Self { tables },
+                replaced,
+            })
+        } else {
+            Err(errors)
+        }
+    }
+
+    /// Returns the table with the given name.
+    pub fn get(&self, name: &str) -> Result<Arc<Table>, EnrichmentTableError> {
+        self.tables
+            .get(name)
+            .cloned()
+            .ok_or_else(|| EnrichmentTableError::UnknownTable {
+                name: name.to_owned(),
+            })
+    }
+}
+
+/// Tables loaded for a topology that isn't running yet.
+#[derive(Debug, Default)]
+pub struct LoadedTables {
+    tables: EnrichmentTables,
+    /// The new data of tables shared with the running topology.
+    replaced: Vec<(Arc<Table>, Loaded)>,
+}
+
+impl LoadedTables {
+    /// The tables to build components with.
+    pub fn tables(&self) -> &EnrichmentTables {
+        &self.tables
+    }
+
+    /// Swaps the new data into tables shared with the running topology, once
+    /// the topology built with them has started.
+    pub fn commit(self) -> EnrichmentTables {
+        for (table, loaded) in self.replaced {
+            table.swap(loaded);
+        }
+        self.tables
+    }
+}
+
+/// Reloads the table whenever its file changes, until the table is dropped.
+async fn watch(table: Weak<Table>) {
+    loop {
+        let interval = match table.upgrade() {
+            Some(table) => table.reload_interval(),
+            None => return,
+        };
+        tokio::time::delay_for(interval).await;
+
+        match table.upgrade() {
+            Some(table) => table.reload_if_modified().await,
+            None => return,
+        }
+    }
+}
+
+/// A loaded table, reloaded from its file when it changes.
+#[derive(Debug)]
+pub struct Table {
+    name: String,
+    loaded: RwLock<Arc<Loaded>>,
+}
+
+/// The records of a table, along with where they were loaded from.
+#[derive(Debug)]
+struct Loaded {
+    config: TableConfig,
+    modified: Option<SystemTime>,
+    records: Records,
+}
+
+impl Loaded {
+    fn load(config: TableConfig) -> Result<Self, EnrichmentTableError> {
+        let modified = modified(&config.path);
+        let records = config.load()?;
+        Ok(Self {
+            config,
+            modified,
+            records,
+        })
+    }
+}
+
+impl Table {
+    fn new(name: String, loaded: Loaded) -> Self {
+        Self {
+            name,
+            loaded: RwLock::new(Arc::new(loaded)),
+        }
+    }
+
+    /// Loads a table outside of the set of configured tables.
+    #[cfg(test)]
+    pub fn load(name: &str, config: TableConfig) -> Result<Self, EnrichmentTableError> {
+        Ok(Self::new(name.to_owned(), Loaded::load(config)?))
+    }
+
+    fn current(&self) -> Arc<Loaded> {
+        Arc::clone(&self.loaded.read().expect("poisoned lock"))
+    }
+
+    fn config(&self) -> TableConfig {
+        self.current().config.clone()
+    }
+
+    fn reload_interval(&self) -> Duration {
+        // Checked at least once a second, even when configured not to wait.
+        Duration::from_secs(self.current().config.reload_interval_secs.max(1))
+    }
+
+    fn swap(&self, loaded: Loaded) {
+        *self.loaded.write().expect("poisoned lock") = Arc::new(loaded);
+    }
+
+    /// Finds the single record whose fields match all of the given values.
+    pub fn find_record(
+        &self,
+        condition: &BTreeMap<String, String>,
+    ) -> Result<BTreeMap<String, Value>, EnrichmentTableError> {
+        self.current().records.find(condition).map(Clone::clone)
+    }
+
+    /// Reloads the table if its file was modified since it was last loaded.
+    ///
+    /// The file is read on the blocking thread pool, and lookups keep using
+    /// the current records until the new ones are swapped in.
+    async fn reload_if_modified(&self) {
+        let current = self.current();
+        let config = current.config.clone();
+        let known = current.modified;
+
+        let reloaded = spawn_blocking(move || {
+            if modified(&config.path) == known {
+                None
+            } else {
+                Some(Loaded::load(config))
+            }
+        })
+        .await;
+
+        match reloaded {
+            Ok(Some(Ok(loaded))) => {
+                let mut current_loaded = self.loaded.write().expect("poisoned lock");
+                // The config may have been replaced by a reload in the meantime.
+                if Arc::ptr_eq(&current_loaded, &current) {
+                    *current_loaded = Arc::new(loaded);
+                    drop(current_loaded);
+                    emit!(EnrichmentTableReloaded { name: &self.name });
+                }
+            }
+            Ok(Some(Err(error))) => emit!(EnrichmentTableReloadFailed {
+                name: &self.name,
+                error
+            }),
+            Ok(None) | Err(_) => (),
+        }
+    }
+}
+
+fn modified(path: &Path) -> Option<SystemTime> {
+    std::fs::metadata(path)
+        .and_then(|metadata| metadata.modified())
+        .ok()
+}
+
+impl TableConfig {
+    fn load(&self) -> Result<Records, EnrichmentTableError> {
+        let file = File::open(&self.path).context(Open { path: &self.path })?;
+
+        let rows = match self.format {
+            Format::Csv => read_csv(file, self.delimiter, &self.path)?,
+            Format::Json => read_json(file, &self.path)?,
+        };
+
+        Ok(Records::new(rows, self.case_sensitive, &self.indexes))
+    }
+}
+
+fn read_csv(
+    file: File,
+    delimiter: char,
+    path: &Path,
+) -> Result<Vec<BTreeMap<String, Value>>, EnrichmentTableError> {
+    if !delimiter.is_ascii() {
+        return Err(EnrichmentTableError::InvalidDelimiter { delimiter });
+    }
+
+    let mut reader = csv::ReaderBuilder::new()
+        .delimiter(delimiter as u8)
+        .from_reader(file);
+    let headers = reader.headers().context(ParseCsv { path })?.clone();
+
+    reader
+        .records()
+        .map(|record| {
+            let record = record.context(ParseCsv { path })?;
+
+            Ok(headers
+                .iter()
+                .zip(record.iter())
+                .map(|(field, value)| (field.to_owned(), Value::from(value)))
+                .collect())
+        })
+        .collect()
+}
+
+fn read_json(
+    file: File,
+    path: &Path,
+) -> Result<Vec<BTreeMap<String, Value>>, EnrichmentTableError> {
+    let json: serde_json::Value =
+        serde_json::from_reader(BufReader::new(file)).context(ParseJson { path })?;
+
+    match json {
+        serde_json::Value::Array(rows) => rows
+            .into_iter()
+            .map(|row| match Value::from(row) {
+                Value::Map(row) => Ok(row),
+                _ => InvalidJson { path }.fail(),
+            })
+            .collect(),
+        _ => InvalidJson { path }.fail(),
+    }
+}
+
+/// The rows of a table, along with the indexes over them.
+#[derive(Debug, Default)]
+struct Records {
+    rows: Vec<BTreeMap<String, Value>>,
+    case_sensitive: bool,
+
+    /// Row positions, keyed by the sorted names of the indexed fields, and
+    /// then by the values of those fields.
+    indexes: HashMap<Vec<String>, HashMap<Vec<String>, Vec<usize>>>,
+}
+
+impl Records {
+    fn new(
+        rows: Vec<BTreeMap<String, Value>>,
+        case_sensitive: bool,
+        indexes: &[Vec<String>],
+    ) -> Self {
+        let mut records = Self {
+            rows,
+            case_sensitive,
+            indexes: HashMap::new(),
+        };
+
+        for fields in indexes {
+            let mut fields = fields.clone();
+            fields.sort();
+            fields.dedup();
+
+            let mut index: HashMap<Vec<String>, Vec<usize>> = HashMap::new();
+            for (position, row) in records.rows.iter().enumerate() {
+                let key = fields
+                    .iter()
+                    .map(|field| {
+                        row.get(field)
+                            .map(|value| records.normalize(value.to_string_lossy()))
+                    })
+                    .collect::<Option<Vec<_>>>();
+
+                if let Some(key) = key {
+                    index.entry(key).or_default().push(position);
+                }
+            }
+
+            records.indexes.insert(fields, index);
+        }
+
+        records
+    }
+
+    fn normalize(&self, value: String) -> String {
+        if self.case_sensitive {
+            value
+        } else {
+            value.to_lowercase()
+        }
+    }
+
+    fn find(
+        &self,
+        condition: &BTreeMap<String, String>,
+    ) -> Result<&BTreeMap<String, Value>, EnrichmentTableError> {
+        let fields = condition.keys().cloned().collect::<Vec<_>>();
+        let values = condition
+            .values()
+            .map(|value| self.normalize(value.clone()))
+            .collect::<Vec<_>>();
+
+        // At most two positions are needed to tell whether a record is unique.
+        let positions = match self.indexes.get(&fields) {
+            Some(index) => index
+                .get(&values)
+                .map(|positions| positions.iter().take(2).copied().collect())
+                .unwrap_or_default(),
+            None => self
+                .rows
+                .iter()
+                .enumerate()
+                .filter(|(_, row)| {
+                    fields.iter().zip(&values).all(|(field, value)| {
+                        row.get(field).map_or(false, |field| {
+                            self.normalize(field.to_string_lossy()) == *value
+                        })
+                    })
+                })
+                .map(|(position, _)| position)
+                .take(2)
+                .collect::<Vec<_>>(),
+        };
+
+        match positions.as_slice() {
+            [position] => Ok(&self.rows[*position]),
+            [] => NoRecord.fail(),
+            _ => MultipleRecords.fail(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::temp_file;
+    use std::fs;
+
+    fn config(path: PathBuf, format: Format) -> TableConfig {
+        TableConfig {
+            path,
+            format,
+            delimiter: default_delimiter(),
+            case_sensitive: true,
+            indexes: vec![],
+            reload_interval_secs: default_reload_interval_secs(),
+        }
+    }
+
+    fn condition(fields: &[(&str, &str)]) -> BTreeMap<String, String> {
+        fields
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect()
+    }
+
+    fn owners_csv() -> PathBuf {
+        let path = temp_file();
+        fs::write(
+            &path,
+            "host,team,region\nweb-1,frontend,eu\nweb-2,frontend,us\nDB-1,storage,eu\n",
+        )
+        .unwrap();
+        path
+    }
+
+    #[test]
+    fn csv_lookup() {
+        let table = Table::load("owners", config(owners_csv(), Format::Csv)).unwrap();
+
+        let record = table.find_record(&condition(&[("host", "web-2")])).unwrap();
+        assert_eq!(record["team"], Value::from("frontend"));
+        assert_eq!(record["region"], Value::from("us"));
+
+        assert!(matches!(
+            table.find_record(&condition(&[("host", "db-1")])),
+            Err(EnrichmentTableError::NoRecord)
+        ));
+        assert!(matches!(
+            table.find_record(&condition(&[("team", "frontend")])),
+            Err(EnrichmentTableError::MultipleRecords)
+        ));
+        assert!(table
+            .find_record(&condition(&[("team", "frontend"), ("region", "eu")]))
+            .is_ok());
+    }
+
+    #[test]
+    fn json_lookup() {
+        let path = temp_file();
+        fs::write(
+            &path,
+            r#"[{"account": 1, "tier": "gold"}, {"account": 2, "tier": "silver"}]"#,
+        )
+        .unwrap();
+        let table = Table::load("tiers", config(path, Format::Json)).unwrap();
+
+        let record = table.find_record(&condition(&[("account", "2")])).unwrap();
+        assert_eq!(record["tier"], Value::from("silver"));
+    }
+
+    #[test]
+    fn invalid_json() {
+        let path = temp_file();
+        fs::write(&path, r#"{"account": 1}"#).unwrap();
+
+        assert!(matches!(
+            Table::load("tiers", config(path, Format::Json)),
+            Err(EnrichmentTableError::InvalidJson { .. })
+        ));
+    }
+
+    #[test]
+    fn case_insensitive_indexed_lookup() {
+        let config = TableConfig {
+            case_sensitive: false,
+            indexes: vec![vec!["region".to_owned(), "host".to_owned()]],
+            ..config(owners_csv(), Format::Csv)
+        };
+        let table = Table::load("owners", config).unwrap();
+
+        let loaded = table.current();
+        let index = &loaded.records.indexes[&vec!["host".to_owned(), "region".to_owned()]];
+        assert_eq!(index[&vec!["db-1".to_owned(), "eu".to_owned()]], vec![2]);
+
+        let record = table
+            .find_record(&condition(&[("host", "db-1"), ("region", "EU")]))
+            .unwrap();
+        assert_eq!(record["team"], Value::from("storage"));
+
+        // Lookups without a matching index are case insensitive too.
+        let record = table.find_record(&condition(&[("host", "Web-1")])).unwrap();
+        assert_eq!(record["team"], Value::from("frontend"));
+    }
+
+    #[tokio::test]
+    async fn reloads_modified_file() {
+        let path = owners_csv();
+        let table = Table::load("owners", config(path.clone(), Format::Csv)).unwrap();
+        assert!(table.find_record(&condition(&[("host", "web-3")])).is_err());
+
+        // Make sure the modification time moves, even on coarse filesystems.
+        std::thread::sleep(Duration::from_millis(1100));
+        fs::write(&path, "host,team\nweb-3,backend\n").unwrap();
+
+        // Lookups don't check the file themselves.
+        assert!(table.find_record(&condition(&[("host", "web-3")])).is_err());
+
+        table.reload_if_modified().await;
+        let record = table.find_record(&condition(&[("host", "web-3")])).unwrap();
+        assert_eq!(record["team"], Value::from("backend"));
+    }
+
+    #[tokio::test]
+    async fn swaps_changed_tables_on_commit() {
+        let mut configs = IndexMap::new();
+        configs.insert("owners".to_owned(), config(owners_csv(), Format::Csv));
+        let current = EnrichmentTables::load(&configs, &EnrichmentTables::default())
+            .unwrap()
+            .commit();
+        let table = current.get("owners").unwrap();
+
+        let path = temp_file();
+        fs::write(&path, "host,team\nweb-3,backend\n").unwrap();
+        configs.insert("owners".to_owned(), config(path, Format::Csv));
+        configs.insert("missing".to_owned(), config(temp_file(), Format::Csv));
+
+        // A failed load leaves the running tables alone.
+        assert!(EnrichmentTables::load(&configs, &current).is_err());
+        configs.remove("missing");
+
+        let loaded = EnrichmentTables::load(&configs, &current).unwrap();
+        assert!(Arc::ptr_eq(&loaded.tables().get("owners").unwrap(), &table));
+        assert!(table.find_record(&condition(&[("host", "web-3")])).is_err());
+
+        loaded.commit();
+        let record = table.find_record(&condition(&[("host", "web-3")])).unwrap();
+        assert_eq!(record["team"], Value::from("backend"));
+    }
+}
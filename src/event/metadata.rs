@@ -0,0 +1,10 @@
+use std::time::Instant;
+
+/// Information about an event that isn't part of its data. It's neither
+/// compared, nor shown, nor encoded, so it's lost when the event is written
+/// to a disk buffer or sent to another Vector.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct EventMetadata {
+    /// When the event was received by its source.
+    pub ingress: Option<Instant>,
+}
@@ -81,6 +81,41 @@ pub enum StatisticKind {
 }
 
 impl Metric {
+    /// Approximate size of the metric in memory, including the data it owns.
+    pub fn size_of(&self) -> usize {
+        use std::mem::size_of;
+
+        let value = match &self.value {
+            MetricValue::Counter { .. } | MetricValue::Gauge { .. } => 0,
+            MetricValue::Set { values } => values
+                .iter()
+                .map(|value| size_of::<String>() + value.len())
+                .sum(),
+            MetricValue::Distribution {
+                values,
+                sample_rates,
+                ..
+            } => values.len() * size_of::<f64>() + sample_rates.len() * size_of::<u32>(),
+            MetricValue::AggregatedHistogram {
+                buckets, counts, ..
+            } => buckets.len() * size_of::<f64>() + counts.len() * size_of::<u32>(),
+            MetricValue::AggregatedSummary {
+                quantiles, values, ..
+            } => (quantiles.len() + values.len()) * size_of::<f64>(),
+        };
+        let tags = self.tags.as_ref().map_or(0, |tags| {
+            tags.iter()
+                .map(|(key, value)| 2 * size_of::<String>() + key.len() + value.len())
+                .sum()
+        });
+
+        size_of::<Self>()
+            + self.name.len()
+            + self.namespace.as_ref().map_or(0, String::len)
+            + tags
+            + value
+    }
+
     /// Create a new Metric from this with all the data but marked as absolute.
     pub fn to_absolute(&self) -> Self {
         Self {
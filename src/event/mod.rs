@@ -2,7 +2,10 @@ use self::proto::{event_wrapper::Event as EventProto, metric::Value as MetricPro
 use crate::config::log_schema;
 use bytes::Bytes;
 use chrono::{DateTime, SecondsFormat, TimeZone, Utc};
-use std::collections::{BTreeMap, HashMap};
+use std::{
+    collections::{BTreeMap, HashMap},
+    time::Instant,
+};
 
 pub mod discriminant;
 pub mod merge;
@@ -12,10 +15,12 @@ pub mod util;
 
 mod log_event;
 mod lookup;
+mod metadata;
 mod value;
 
 pub use log_event::LogEvent;
 pub use lookup::Lookup;
+pub use metadata::EventMetadata;
 pub use metric::{Metric, MetricKind, MetricValue, StatisticKind};
 use std::convert::{TryFrom, TryInto};
 pub(crate) use util::log::PathComponent;
@@ -39,6 +44,14 @@ impl Event {
         Event::Log(LogEvent::default())
     }
 
+    /// Approximate size of the event in memory, including the data it owns.
+    pub fn size_of(&self) -> usize {
+        match self {
+            Event::Log(log) => log.size_of(),
+            Event::Metric(metric) => metric.size_of(),
+        }
+    }
+
     pub fn as_log(&self) -> &LogEvent {
         match self {
             Event::Log(log) => log,
@@ -80,6 +93,39 @@ impl Event {
             _ => panic!("Failed type coercion, {:?} is not a metric", self),
         }
     }
+
+    /// When the event happened, from the timestamp of a log or a metric.
+    pub fn timestamp(&self) -> Option<DateTime<Utc>> {
+        match self {
+            Event::Log(log) => log
+                .get(log_schema().timestamp_key())
+                .and_then(Value::as_timestamp)
+                .copied(),
+            Event::Metric(metric) => metric.timestamp,
+        }
+    }
+
+    /// When the event was received by its source. Only logs are stamped.
+    pub fn ingress(&self) -> Option<Instant> {
+        match self {
+            Event::Log(log) => log.metadata().ingress,
+            Event::Metric(_) => None,
+        }
+    }
+
+    /// Stamps a log as received now, unless it already was.
+    pub fn stamp_ingress(&mut self) {
+        if let Event::Log(log) = self {
+            log.metadata_mut().ingress.get_or_insert_with(Instant::now);
+        }
+    }
+
+    /// Forgets when the event was received by its source.
+    pub fn clear_ingress(&mut self) {
+        if let Event::Log(log) = self {
+            log.metadata_mut().ingress = None;
+        }
+    }
 }
 
 fn timestamp_to_string(timestamp: &DateTime<Utc>) -> String {
@@ -425,29 +471,27 @@ impl From<Metric> for Event {
 
 // TODO(jean): add tests
 impl remap::Object for Event {
-    // TODO(jean): replace this with `Lookup`, once that lands.
     fn insert(&mut self, path: &[Vec<String>], value: remap::Value) -> Result<(), String> {
-        let path_str = path
-            .iter()
-            .map(|c| {
-                c.iter()
-                    .map(|p| p.replace(".", "\\."))
-                    .collect::<Vec<_>>()
-                    .join(".")
-            })
-            .collect::<Vec<_>>()
-            .join(".");
-
-        self.as_mut_log().insert(path_str, value);
+        remap::Object::insert_segments(self, &remap::Segments::new(path.to_owned()), value)
+    }
+
+    fn find(&self, path: &[Vec<String>]) -> Result<Option<remap::Value>, String> {
+        remap::Object::find_segments(self, &remap::Segments::new(path.to_owned()))
+    }
+
+    // TODO(jean): replace this with `Lookup`, once that lands.
+    fn insert_segments(
+        &mut self,
+        path: &remap::Segments,
+        value: remap::Value,
+    ) -> Result<(), String> {
+        self.as_mut_log().insert(path.as_str(), value);
         Ok(())
     }
 
     // TODO(jean): replace this with `Lookup`, once that lands.
-    fn find(&self, path: &[Vec<String>]) -> Result<Option<remap::Value>, String> {
-        let path = path
-            .iter()
-            .map(|c| c.iter().map(|p| p.replace(".", "\\.")).collect::<Vec<_>>())
-            .collect::<Vec<_>>();
+    fn find_segments(&self, path: &remap::Segments) -> Result<Option<remap::Value>, String> {
+        let path = path.escaped();
 
         // Event.as_log returns a LogEvent struct rather than a naked
         // IndexMap<_, Value>, which means specifically for the first item in
@@ -509,6 +553,19 @@ mod test {
     use regex::Regex;
     use std::collections::HashSet;
 
+    #[test]
+    fn ingress_is_stamped_once_and_not_compared() {
+        let mut event = Event::from("raw log line");
+        let unstamped = event.clone();
+        assert_eq!(event.ingress(), None);
+
+        event.stamp_ingress();
+        let ingress = event.ingress().unwrap();
+        event.stamp_ingress();
+        assert_eq!(event.ingress(), Some(ingress));
+        assert_eq!(event, unstamped);
+    }
+
     #[test]
     fn serialization() {
         let mut event = Event::from("raw log line");
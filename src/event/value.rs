@@ -237,6 +237,20 @@ impl From<Value> for remap::Value {
 }
 
 impl Value {
+    /// Approximate size of the value in memory, including the data it owns.
+    pub fn size_of(&self) -> usize {
+        std::mem::size_of::<Self>()
+            + match self {
+                Value::Bytes(bytes) => bytes.len(),
+                Value::Map(map) => map
+                    .iter()
+                    .map(|(key, value)| key.len() + value.size_of())
+                    .sum(),
+                Value::Array(array) => array.iter().map(Value::size_of).sum(),
+                _ => 0,
+            }
+    }
+
     // TODO: return Cow
     pub fn to_string_lossy(&self) -> String {
         match self {
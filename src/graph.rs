@@ -0,0 +1,250 @@
+use crate::config::{self, Config};
+use std::{fmt::Write, path::PathBuf};
+use structopt::StructOpt;
+
+#[derive(StructOpt, Debug)]
+#[structopt(rename_all = "kebab-case")]
+pub struct Opts {
+    /// Format the graph in a diagram language.
+    #[structopt(long, default_value = "dot", possible_values = &["dot", "mermaid"])]
+    format: Format,
+
+    /// Any number of Vector config files to render. If none are specified the
+    /// default config path `/etc/vector/vector.toml` will be targeted.
+    paths: Vec<PathBuf>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Format {
+    Dot,
+    Mermaid,
+}
+
+impl std::str::FromStr for Format {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "dot" => Ok(Format::Dot),
+            "mermaid" => Ok(Format::Mermaid),
+            s => Err(format!(
+                "{} is not a valid option, expected `dot` or `mermaid`",
+                s
+            )),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Kind {
+    Source,
+    Transform,
+    Sink,
+}
+
+#[derive(Debug)]
+struct Node<'a> {
+    name: &'a str,
+    kind: Kind,
+    component_type: &'static str,
+}
+
+/// The components of a config, and the connections from their inputs, in the
+/// order they're configured.
+#[derive(Debug)]
+struct Graph<'a> {
+    nodes: Vec<Node<'a>>,
+    edges: Vec<(&'a str, &'a str)>,
+}
+
+impl<'a> Graph<'a> {
+    fn new(config: &'a Config) -> Self {
+        let mut nodes = Vec::new();
+        let mut edges = Vec::new();
+
+        for (name, source) in config.sources.iter() {
+            nodes.push(Node {
+                name,
+                kind: Kind::Source,
+                component_type: source.source_type(),
+            });
+        }
+        for (name, transform) in config.transforms.iter() {
+            nodes.push(Node {
+                name,
+                kind: Kind::Transform,
+                component_type: transform.inner.transform_type(),
+            });
+            edges.extend(
+                transform
+                    .inputs
+                    .iter()
+                    .map(|input| (input.as_str(), name.as_str())),
+            );
+        }
+        for (name, sink) in config.sinks.iter() {
+            nodes.push(Node {
+                name,
+                kind: Kind::Sink,
+                component_type: sink.inner.sink_type(),
+            });
+            edges.extend(
+                sink.inputs
+                    .iter()
+                    .map(|input| (input.as_str(), name.as_str())),
+            );
+        }
+
+        Self { nodes, edges }
+    }
+
+    fn to_dot(&self) -> String {
+        let escape = |s: &str| s.replace('\\', "\\\\").replace('"', "\\\"");
+
+        let mut dot = String::from("digraph {\n");
+        for node in &self.nodes {
+            let shape = match node.kind {
+                Kind::Source => "trapezium",
+                Kind::Transform => "box",
+                Kind::Sink => "invtrapezium",
+            };
+            writeln!(
+                dot,
+                "  \"{}\" [shape={} label=\"{}\\n({})\"]",
+                escape(node.name),
+                shape,
+                escape(node.name),
+                node.component_type
+            )
+            .expect("Writing to a String can't fail");
+        }
+        for &(from, to) in &self.edges {
+            writeln!(dot, "  \"{}\" -> \"{}\"", escape(from), escape(to))
+                .expect("Writing to a String can't fail");
+        }
+        dot.push_str("}\n");
+        dot
+    }
+
+    fn to_mermaid(&self) -> String {
+        // Component names may contain characters Mermaid doesn't allow in ids
+        let id = |name: &str| {
+            self.nodes
+                .iter()
+                .position(|node| node.name == name)
+                .map(|i| format!("c{}", i))
+                .unwrap_or_else(|| name.to_owned())
+        };
+
+        let mut mermaid = String::from("graph LR\n");
+        for (i, node) in self.nodes.iter().enumerate() {
+            let label = format!("{} ({})", node.name, node.component_type).replace('"', "#quot;");
+            let (open, close) = match node.kind {
+                Kind::Source => ("([\"", "\"])"),
+                Kind::Transform => ("[\"", "\"]"),
+                Kind::Sink => ("[(\"", "\")]"),
+            };
+            writeln!(mermaid, "  c{}{}{}{}", i, open, label, close)
+                .expect("Writing to a String can't fail");
+        }
+        for &(from, to) in &self.edges {
+            writeln!(mermaid, "  {} --> {}", id(from), id(to))
+                .expect("Writing to a String can't fail");
+        }
+        mermaid
+    }
+}
+
+/// Prints the topology of a config as a diagram, without building its components.
+pub fn cmd(opts: &Opts) -> exitcode::ExitCode {
+    let paths = match config::process_paths(&opts.paths) {
+        Some(paths) => paths,
+        None => {
+            eprintln!("No config file paths");
+            return exitcode::CONFIG;
+        }
+    };
+
+    let config = match config::load_from_paths(&paths) {
+        Ok(config) => config,
+        Err(errors) => {
+            eprintln!("Failed to load {:?}", paths);
+            for error in errors {
+                eprintln!("  {}", error);
+            }
+            return exitcode::CONFIG;
+        }
+    };
+
+    let graph = Graph::new(&config);
+    match opts.format {
+        Format::Dot => print!("{}", graph.to_dot()),
+        Format::Mermaid => print!("{}", graph.to_mermaid()),
+    }
+
+    exitcode::OK
+}
+
+#[cfg(all(
+    test,
+    feature = "sources-file",
+    feature = "sinks-console",
+    feature = "transforms-json_parser"
+))]
+mod tests {
+    use super::*;
+    use crate::config::load_from_str;
+
+    fn config() -> Config {
+        load_from_str(
+            r#"
+            [sources.in]
+            type = "file"
+            include = ["/var/log/messages"]
+
+            [transforms.parse]
+            type = "json_parser"
+            inputs = ["in"]
+
+            [sinks.out]
+            type = "console"
+            inputs = ["in", "parse"]
+            encoding = "json"
+            "#,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn renders_dot() {
+        let config = config();
+        assert_eq!(
+            Graph::new(&config).to_dot(),
+            r#"digraph {
+  "in" [shape=trapezium label="in\n(file)"]
+  "parse" [shape=box label="parse\n(json_parser)"]
+  "out" [shape=invtrapezium label="out\n(console)"]
+  "in" -> "parse"
+  "in" -> "out"
+  "parse" -> "out"
+}
+"#
+        );
+    }
+
+    #[test]
+    fn renders_mermaid() {
+        let config = config();
+        assert_eq!(
+            Graph::new(&config).to_mermaid(),
+            r#"graph LR
+  c0(["in (file)"])
+  c1["parse (json_parser)"]
+  c2[("out (console)")]
+  c0 --> c1
+  c0 --> c2
+  c1 --> c2
+"#
+        );
+    }
+}
@@ -1,26 +1,36 @@
+use crate::config::ProxyConfig;
 use crate::dns::Resolver;
 use crate::tls::{tls_connector_builder, MaybeTlsSettings};
 use futures::future::BoxFuture;
-use http::header::HeaderValue;
-use http::Request;
+use http::header::{HeaderMap, HeaderValue};
+use http::{uri::Scheme, Request, Uri};
 use hyper::{
     body::{Body, HttpBody},
-    client::{Client, HttpConnector},
+    client::{
+        connect::{Connected, Connection},
+        Client, HttpConnector,
+    },
 };
 use hyper_openssl::HttpsConnector;
+use hyper_proxy::ProxyConnector;
 use serde::{Deserialize, Serialize};
 use std::{
-    fmt,
+    fmt, io,
+    pin::Pin,
     task::{Context, Poll},
 };
+use tokio::io::{AsyncRead, AsyncWrite};
 use tower::Service;
 use tracing::Span;
 use tracing_futures::Instrument;
 
 pub type HttpClientFuture = <HttpClient as Service<http::Request<Body>>>::Future;
 
+type ProxiedConnector = ProxyConnector<HttpConnector<Resolver>>;
+
 pub struct HttpClient<B = Body> {
-    client: Client<HttpsConnector<HttpConnector<Resolver>>, B>,
+    client: Client<HttpsConnector<TunnelConnector>, B>,
+    proxy_connector: ProxiedConnector,
     span: Span,
     user_agent: HeaderValue,
 }
@@ -31,13 +41,22 @@ where
     B::Data: Send,
     B::Error: Into<crate::Error>,
 {
-    pub fn new(tls_settings: impl Into<MaybeTlsSettings>) -> crate::Result<HttpClient<B>> {
+    pub fn new(
+        tls_settings: impl Into<MaybeTlsSettings>,
+        proxy_config: &ProxyConfig,
+    ) -> crate::Result<HttpClient<B>> {
         let mut http = HttpConnector::new_with_resolver(Resolver);
         http.enforce_http(false);
 
+        // HTTPS connections to a proxy are tunneled with `CONNECT`, and then
+        // encrypted end to end like direct ones, see `TunnelConnector`.
+        let mut proxy_connector = ProxyConnector::unsecured(http);
+        proxy_config.configure(&mut proxy_connector)?;
+
         let settings = tls_settings.into();
         let tls = tls_connector_builder(&settings)?;
-        let mut https = HttpsConnector::with_connector(http, tls)?;
+        let tunnel_connector = TunnelConnector(proxy_connector.clone());
+        let mut https = HttpsConnector::with_connector(tunnel_connector, tls)?;
 
         let settings = settings.tls().cloned();
         https.set_callback(move |c, _uri| {
@@ -58,6 +77,7 @@ where
 
         Ok(HttpClient {
             client,
+            proxy_connector,
             span,
             user_agent,
         })
@@ -91,6 +111,12 @@ where
                 .insert("User-Agent", self.user_agent.clone());
         }
 
+        // Plain HTTP requests are sent to the proxy as is, so they carry the
+        // proxy's credentials themselves.
+        if let Some(headers) = self.proxy_connector.http_headers(request.uri()) {
+            request.headers_mut().extend(headers.clone());
+        }
+
         debug!(message = "Sending request.", uri = %request.uri(), method = %request.method());
 
         let response = self.client.request(request);
@@ -114,6 +140,7 @@ impl<B> Clone for HttpClient<B> {
     fn clone(&self) -> Self {
         Self {
             client: self.client.clone(),
+            proxy_connector: self.proxy_connector.clone(),
             span: self.span.clone(),
             user_agent: self.user_agent.clone(),
         }
@@ -124,12 +151,89 @@ impl<B> fmt::Debug for HttpClient<B> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         f.debug_struct("HttpClient")
             .field("client", &self.client)
+            .field("proxy_connector", &self.proxy_connector)
             .field("user_agent", &self.user_agent)
             .finish()
     }
 }
 
-#[derive(Deserialize, Serialize, Clone, Debug)]
+/// Connects through the proxy, marking HTTPS connections tunneled with
+/// `CONNECT` as direct ones.
+///
+/// The proxy connector marks every proxied connection as such, which would
+/// have requests sent in the absolute-form meant for plain HTTP proxies. Once
+/// tunneled though, requests reach the origin server as over a direct
+/// connection, and so are sent in origin-form.
+#[derive(Clone, Debug)]
+struct TunnelConnector(ProxiedConnector);
+
+type ProxiedStream = <ProxiedConnector as Service<Uri>>::Response;
+
+impl Service<Uri> for TunnelConnector {
+    type Response = TunnelStream<ProxiedStream>;
+    type Error = <ProxiedConnector as Service<Uri>>::Error;
+    type Future = BoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.0.poll_ready(cx)
+    }
+
+    fn call(&mut self, uri: Uri) -> Self::Future {
+        let tunneled = uri.scheme() == Some(&Scheme::HTTPS);
+        let connect = self.0.call(uri);
+
+        Box::pin(async move {
+            let stream = connect.await?;
+            Ok(TunnelStream { stream, tunneled })
+        })
+    }
+}
+
+struct TunnelStream<S> {
+    stream: S,
+    tunneled: bool,
+}
+
+impl<S: Connection> Connection for TunnelStream<S> {
+    fn connected(&self) -> Connected {
+        let connected = self.stream.connected();
+        if self.tunneled {
+            connected.proxy(false)
+        } else {
+            connected
+        }
+    }
+}
+
+impl<S: AsyncRead + Unpin> AsyncRead for TunnelStream<S> {
+    fn poll_read(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut [u8],
+    ) -> Poll<io::Result<usize>> {
+        Pin::new(&mut self.stream).poll_read(cx, buf)
+    }
+}
+
+impl<S: AsyncWrite + Unpin> AsyncWrite for TunnelStream<S> {
+    fn poll_write(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        Pin::new(&mut self.stream).poll_write(cx, buf)
+    }
+
+    fn poll_flush(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.stream).poll_flush(cx)
+    }
+
+    fn poll_shutdown(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.stream).poll_shutdown(cx)
+    }
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug, PartialEq)]
 #[serde(deny_unknown_fields, rename_all = "snake_case", tag = "strategy")]
 pub enum Auth {
     Basic { user: String, password: String },
@@ -151,4 +255,118 @@ impl Auth {
             },
         }
     }
+
+    /// Checks the credentials of a request against these.
+    pub fn verify(&self, headers: &HeaderMap) -> bool {
+        use headers::{
+            authorization::{Basic, Bearer},
+            Authorization, HeaderMapExt,
+        };
+        use subtle::ConstantTimeEq;
+
+        // Compared in constant time, so the time to reject a request doesn't
+        // tell how much of the credentials it got right.
+        let eq = |given: &str, expected: &str| given.as_bytes().ct_eq(expected.as_bytes());
+
+        match &self {
+            Auth::Basic { user, password } => headers
+                .typed_get::<Authorization<Basic>>()
+                .map_or(false, |auth| {
+                    (eq(auth.0.username(), user) & eq(auth.0.password(), password)).into()
+                }),
+            Auth::Bearer { token } => headers
+                .typed_get::<Authorization<Bearer>>()
+                .map_or(false, |auth| eq(auth.0.token(), token).into()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        test_util::next_addr,
+        tls::{TlsConfig, TlsOptions, TlsSettings},
+    };
+    use tokio::{
+        io::{AsyncReadExt, AsyncWriteExt},
+        net::TcpListener,
+    };
+
+    async fn read_head<S: AsyncRead + Unpin>(stream: &mut S) -> String {
+        let mut head = Vec::new();
+        let mut byte = [0u8; 1];
+        while !head.ends_with(b"\r\n\r\n") {
+            stream.read_exact(&mut byte).await.unwrap();
+            head.push(byte[0]);
+        }
+        String::from_utf8(head).unwrap()
+    }
+
+    #[tokio::test]
+    async fn tunneled_requests_use_origin_form() {
+        let addr = next_addr();
+        let mut listener = TcpListener::bind(&addr).await.unwrap();
+
+        // Stands in for the proxy, and for the origin server once tunneled.
+        let server = tokio::spawn(async move {
+            let (mut stream, _) = listener.accept().await.unwrap();
+            let connect = read_head(&mut stream).await;
+            stream
+                .write_all(b"HTTP/1.1 200 Connection established\r\n\r\n")
+                .await
+                .unwrap();
+
+            let config = Some(TlsConfig {
+                enabled: Some(true),
+                options: TlsOptions {
+                    crt_file: Some("tests/data/localhost.crt".into()),
+                    key_file: Some("tests/data/localhost.key".into()),
+                    ..Default::default()
+                },
+            });
+            let settings = MaybeTlsSettings::from_config(&config, true).unwrap();
+            let acceptor = settings.tls().unwrap().acceptor().unwrap();
+            let mut stream = tokio_openssl::accept(&acceptor, stream).await.unwrap();
+            let request = read_head(&mut stream).await;
+            stream
+                .write_all(b"HTTP/1.1 200 OK\r\ncontent-length: 0\r\n\r\n")
+                .await
+                .unwrap();
+
+            (connect, request)
+        });
+
+        let proxy = ProxyConfig {
+            enabled: true,
+            http: None,
+            https: Some(format!("http://{}", addr)),
+            no_proxy: Vec::new(),
+        };
+        let tls = TlsSettings::from_options(&Some(TlsOptions {
+            verify_certificate: Some(false),
+            verify_hostname: Some(false),
+            ..Default::default()
+        }))
+        .unwrap();
+        let mut client = HttpClient::new(tls, &proxy).unwrap();
+
+        let request = Request::get("https://localhost:9000/logs?tag=a")
+            .body(Body::empty())
+            .unwrap();
+        let response = client.send(request).await.unwrap();
+        assert!(response.status().is_success());
+
+        let (connect, request) = server.await.unwrap();
+        assert!(
+            connect.starts_with("CONNECT localhost:9000 HTTP/1.1\r\n"),
+            "{}",
+            connect
+        );
+        assert!(
+            request.starts_with("GET /logs?tag=a HTTP/1.1\r\n"),
+            "{}",
+            request
+        );
+    }
 }
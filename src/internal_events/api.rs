@@ -6,11 +6,18 @@ use std::net::SocketAddr;
 pub struct ApiStarted {
     pub addr: SocketAddr,
     pub playground: bool,
+    pub tls: bool,
 }
 
 impl InternalEvent for ApiStarted {
     fn emit_logs(&self) {
-        let playground = &*format!("http://{}:{}/playground", self.addr.ip(), self.addr.port());
+        let scheme = if self.tls { "https" } else { "http" };
+        let playground = &*format!(
+            "{}://{}:{}/playground",
+            scheme,
+            self.addr.ip(),
+            self.addr.port()
+        );
         info!(
             message="API server running.",
             bind = ?self.addr,
@@ -22,3 +29,18 @@ impl InternalEvent for ApiStarted {
         counter!("api_started_total", 1);
     }
 }
+
+#[derive(Debug)]
+pub struct ApiStartFailed {
+    pub error: crate::Error,
+}
+
+impl InternalEvent for ApiStartFailed {
+    fn emit_logs(&self) {
+        error!(message = "API server failed to start.", error = %self.error);
+    }
+
+    fn emit_metrics(&self) {
+        counter!("api_start_errors_total", 1);
+    }
+}
@@ -1,5 +1,5 @@
 use super::InternalEvent;
-use metrics::histogram;
+use metrics::{counter, gauge, histogram};
 use std::time::Duration;
 
 #[derive(Debug)]
@@ -25,6 +25,7 @@ impl InternalEvent for AutoConcurrencyLimit {
 
     fn emit_metrics(&self) {
         histogram!("auto_concurrency_limit", self.concurrency);
+        gauge!("auto_concurrency_current_limit", self.concurrency as f64);
     }
 }
 
@@ -36,6 +37,7 @@ pub struct AutoConcurrencyInFlight {
 impl InternalEvent for AutoConcurrencyInFlight {
     fn emit_metrics(&self) {
         histogram!("auto_concurrency_in_flight", self.in_flight);
+        gauge!("auto_concurrency_current_in_flight", self.in_flight as f64);
     }
 }
 
@@ -60,3 +62,22 @@ impl InternalEvent for AutoConcurrencyAveragedRtt {
         histogram!("auto_concurrency_averaged_rtt", self.rtt);
     }
 }
+
+#[derive(Debug)]
+pub struct AutoConcurrencyThrottled {
+    pub retry_after: Option<Duration>,
+}
+
+impl InternalEvent for AutoConcurrencyThrottled {
+    fn emit_logs(&self) {
+        debug!(
+            message = "Request was throttled.",
+            retry_after = ?self.retry_after,
+            rate_limit_secs = 30,
+        );
+    }
+
+    fn emit_metrics(&self) {
+        counter!("auto_concurrency_throttled_total", 1);
+    }
+}
@@ -0,0 +1,52 @@
+use super::InternalEvent;
+use crate::sinks::util::service::SinkHealth;
+use metrics::{counter, gauge};
+
+#[derive(Debug)]
+pub struct SinkHealthChanged {
+    pub from: SinkHealth,
+    pub to: SinkHealth,
+}
+
+impl InternalEvent for SinkHealthChanged {
+    fn emit_logs(&self) {
+        match self.to {
+            SinkHealth::Healthy => info!(
+                message = "Sink is healthy again.",
+                from = %self.from.as_str(),
+            ),
+            SinkHealth::Degraded => warn!(
+                message = "Sink is degraded.",
+                from = %self.from.as_str(),
+                rate_limit_secs = 30,
+            ),
+            SinkHealth::Unhealthy => error!(
+                message = "Sink is unhealthy; circuit breaker opened.",
+                from = %self.from.as_str(),
+            ),
+        }
+    }
+
+    fn emit_metrics(&self) {
+        gauge!("sink_health", self.to.as_gauge());
+        if self.to == SinkHealth::Unhealthy {
+            counter!("circuit_breaker_opened_total", 1);
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct CircuitBreakerRejectedRequest;
+
+impl InternalEvent for CircuitBreakerRejectedRequest {
+    fn emit_logs(&self) {
+        warn!(
+            message = "Circuit breaker is open; dropping the request.",
+            rate_limit_secs = 30,
+        );
+    }
+
+    fn emit_metrics(&self) {
+        counter!("circuit_breaker_rejected_requests_total", 1);
+    }
+}
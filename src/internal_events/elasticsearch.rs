@@ -36,3 +36,64 @@ impl<'a> InternalEvent for ElasticSearchMissingKeys<'a> {
         counter!("missing_keys_total", 1);
     }
 }
+
+#[derive(Debug)]
+pub struct ElasticSearchInvalidBulkAction<'a> {
+    pub action: &'a str,
+}
+
+impl<'a> InternalEvent for ElasticSearchInvalidBulkAction<'a> {
+    fn emit_logs(&self) {
+        warn!(
+            message = "Invalid bulk action; dropping event.",
+            action = %self.action,
+            rate_limit_secs = 30,
+        )
+    }
+
+    fn emit_metrics(&self) {
+        counter!("invalid_bulk_action_total", 1);
+    }
+}
+
+#[derive(Debug)]
+pub struct ElasticSearchMissingDocumentId<'a> {
+    pub action: &'a str,
+}
+
+impl<'a> InternalEvent for ElasticSearchMissingDocumentId<'a> {
+    fn emit_logs(&self) {
+        warn!(
+            message = "Bulk action requires a document id, but the event has none; dropping event.",
+            action = %self.action,
+            rate_limit_secs = 30,
+        )
+    }
+
+    fn emit_metrics(&self) {
+        counter!("missing_document_id_total", 1);
+    }
+}
+
+#[derive(Debug)]
+pub struct ElasticSearchDocumentRejected<'a> {
+    pub status: u16,
+    pub error_type: &'a str,
+    pub reason: &'a str,
+}
+
+impl<'a> InternalEvent for ElasticSearchDocumentRejected<'a> {
+    fn emit_logs(&self) {
+        error!(
+            message = "Document rejected; dropping event.",
+            status = %self.status,
+            error_type = %self.error_type,
+            reason = %self.reason,
+            rate_limit_secs = 30,
+        )
+    }
+
+    fn emit_metrics(&self) {
+        counter!("documents_rejected_total", 1);
+    }
+}
@@ -0,0 +1,91 @@
+use super::InternalEvent;
+use metrics::counter;
+
+#[derive(Debug)]
+pub struct EnrichmentTableReloaded<'a> {
+    pub name: &'a str,
+}
+
+impl InternalEvent for EnrichmentTableReloaded<'_> {
+    fn emit_logs(&self) {
+        info!(message = "Reloaded enrichment table.", name = %self.name);
+    }
+
+    fn emit_metrics(&self) {
+        counter!("enrichment_table_reloads_total", 1, "table" => self.name.to_owned());
+    }
+}
+
+#[derive(Debug)]
+pub struct EnrichmentTableReloadFailed<'a> {
+    pub name: &'a str,
+    pub error: crate::enrichment_tables::EnrichmentTableError,
+}
+
+impl InternalEvent for EnrichmentTableReloadFailed<'_> {
+    fn emit_logs(&self) {
+        error!(
+            message = "Failed reloading enrichment table; keeping previous records.",
+            name = %self.name,
+            error = %self.error,
+            rate_limit_secs = 30,
+        );
+    }
+
+    fn emit_metrics(&self) {
+        counter!("enrichment_table_reload_errors_total", 1, "table" => self.name.to_owned());
+    }
+}
+
+#[cfg(feature = "transforms-enrichment_table")]
+#[derive(Debug)]
+pub(crate) struct EnrichmentTableEventProcessed;
+
+#[cfg(feature = "transforms-enrichment_table")]
+impl InternalEvent for EnrichmentTableEventProcessed {
+    fn emit_metrics(&self) {
+        counter!("events_processed_total", 1);
+    }
+}
+
+#[cfg(feature = "transforms-enrichment_table")]
+#[derive(Debug)]
+pub(crate) struct EnrichmentTableFieldMissing<'a> {
+    pub field: &'a str,
+}
+
+#[cfg(feature = "transforms-enrichment_table")]
+impl InternalEvent for EnrichmentTableFieldMissing<'_> {
+    fn emit_logs(&self) {
+        debug!(
+            message = "Field does not exist.",
+            field = %self.field,
+            rate_limit_secs = 30,
+        );
+    }
+
+    fn emit_metrics(&self) {
+        counter!("processing_errors_total", 1, "error_type" => "field_missing");
+    }
+}
+
+#[cfg(feature = "transforms-enrichment_table")]
+#[derive(Debug)]
+pub(crate) struct EnrichmentTableLookupFailed {
+    pub error: crate::enrichment_tables::EnrichmentTableError,
+}
+
+#[cfg(feature = "transforms-enrichment_table")]
+impl InternalEvent for EnrichmentTableLookupFailed {
+    fn emit_logs(&self) {
+        debug!(
+            message = "Enrichment table lookup failed.",
+            error = %self.error,
+            rate_limit_secs = 30,
+        );
+    }
+
+    fn emit_metrics(&self) {
+        counter!("processing_errors_total", 1, "error_type" => "lookup_failed");
+    }
+}
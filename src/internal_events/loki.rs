@@ -0,0 +1,42 @@
+use super::InternalEvent;
+use metrics::counter;
+
+#[derive(Debug)]
+pub struct LokiOutOfOrderEventDropped {
+    pub count: usize,
+}
+
+impl InternalEvent for LokiOutOfOrderEventDropped {
+    fn emit_logs(&self) {
+        warn!(
+            message = "Received out-of-order events; dropping events.",
+            count = %self.count,
+            rate_limit_secs = 30,
+        );
+    }
+
+    fn emit_metrics(&self) {
+        counter!("events_discarded_total", self.count as u64);
+        counter!("out_of_order_events_total", self.count as u64);
+    }
+}
+
+#[derive(Debug)]
+pub struct LokiOutOfOrderEventRewritten {
+    pub count: usize,
+}
+
+impl InternalEvent for LokiOutOfOrderEventRewritten {
+    fn emit_logs(&self) {
+        debug!(
+            message = "Received out-of-order events; rewriting timestamps.",
+            count = %self.count,
+            rate_limit_secs = 30,
+        );
+    }
+
+    fn emit_metrics(&self) {
+        counter!("rewritten_timestamp_events_total", self.count as u64);
+        counter!("out_of_order_events_total", self.count as u64);
+    }
+}
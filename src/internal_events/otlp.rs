@@ -0,0 +1,89 @@
+use super::InternalEvent;
+use metrics::counter;
+
+#[derive(Debug)]
+pub struct OtlpExporterStarted<'a> {
+    pub endpoint: &'a str,
+    pub sample_rate: f64,
+}
+
+impl InternalEvent for OtlpExporterStarted<'_> {
+    fn emit_logs(&self) {
+        info!(
+            message = "Exporting sampled spans as OpenTelemetry traces.",
+            endpoint = %self.endpoint,
+            sample_rate = %self.sample_rate
+        );
+    }
+}
+
+#[derive(Debug)]
+pub struct OtlpExporterStartFailed {
+    pub error: crate::Error,
+}
+
+impl InternalEvent for OtlpExporterStartFailed {
+    fn emit_logs(&self) {
+        error!(message = "Trace exporter failed to start.", error = %self.error);
+    }
+
+    fn emit_metrics(&self) {
+        counter!("otlp_exporter_start_errors_total", 1);
+    }
+}
+
+#[derive(Debug)]
+pub struct OtlpSpansExported {
+    pub count: usize,
+}
+
+impl InternalEvent for OtlpSpansExported {
+    fn emit_logs(&self) {
+        trace!(message = "Spans exported.", count = %self.count);
+    }
+
+    fn emit_metrics(&self) {
+        counter!("otlp_spans_exported_total", self.count as u64);
+    }
+}
+
+#[derive(Debug)]
+pub struct OtlpSpansExportFailed {
+    pub count: usize,
+    pub error: crate::Error,
+}
+
+impl InternalEvent for OtlpSpansExportFailed {
+    fn emit_logs(&self) {
+        warn!(
+            message = "Failed to export spans.",
+            count = %self.count,
+            error = %self.error,
+            rate_limit_secs = 30
+        );
+    }
+
+    fn emit_metrics(&self) {
+        counter!("otlp_export_errors_total", 1);
+        counter!("otlp_spans_dropped_total", self.count as u64);
+    }
+}
+
+#[derive(Debug)]
+pub struct OtlpSpansDropped {
+    pub count: usize,
+}
+
+impl InternalEvent for OtlpSpansDropped {
+    fn emit_logs(&self) {
+        warn!(
+            message = "Spans dropped as too many were waiting to be exported.",
+            count = %self.count,
+            rate_limit_secs = 30
+        );
+    }
+
+    fn emit_metrics(&self) {
+        counter!("otlp_spans_dropped_total", self.count as u64);
+    }
+}
@@ -0,0 +1,51 @@
+use super::InternalEvent;
+use metrics::counter;
+use std::net::SocketAddr;
+
+#[derive(Debug)]
+pub struct TelemetryServerStarted {
+    pub addr: SocketAddr,
+}
+
+impl InternalEvent for TelemetryServerStarted {
+    fn emit_logs(&self) {
+        info!(
+            message = "Serving internal metrics for Prometheus.",
+            endpoint = %format!("http://{}/metrics", self.addr)
+        );
+    }
+
+    fn emit_metrics(&self) {
+        counter!("telemetry_server_started_total", 1);
+    }
+}
+
+#[derive(Debug)]
+pub struct TelemetryServerStartFailed {
+    pub error: crate::Error,
+}
+
+impl InternalEvent for TelemetryServerStartFailed {
+    fn emit_logs(&self) {
+        error!(message = "Telemetry server failed to start.", error = %self.error);
+    }
+
+    fn emit_metrics(&self) {
+        counter!("telemetry_server_start_errors_total", 1);
+    }
+}
+
+#[derive(Debug)]
+pub struct TelemetryServerFailed {
+    pub error: hyper::Error,
+}
+
+impl InternalEvent for TelemetryServerFailed {
+    fn emit_logs(&self) {
+        error!(message = "Telemetry server failed.", error = %self.error);
+    }
+
+    fn emit_metrics(&self) {
+        counter!("telemetry_server_errors_total", 1);
+    }
+}
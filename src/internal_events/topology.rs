@@ -0,0 +1,48 @@
+use super::InternalEvent;
+use metrics::{counter, histogram};
+use std::time::Duration;
+
+/// An event received by a transform or sink from its inputs.
+#[derive(Debug)]
+pub struct EventIn;
+
+impl InternalEvent for EventIn {
+    fn emit_metrics(&self) {
+        counter!("events_in_total", 1);
+    }
+}
+
+/// An event sent by a source or transform to its outputs.
+#[derive(Debug)]
+pub struct EventOut;
+
+impl InternalEvent for EventOut {
+    fn emit_metrics(&self) {
+        counter!("events_out_total", 1);
+    }
+}
+
+/// The time since an event taken in by a sink happened, from its timestamp.
+#[derive(Debug)]
+pub struct EventLag {
+    pub lag: Duration,
+}
+
+impl InternalEvent for EventLag {
+    fn emit_metrics(&self) {
+        histogram!("event_lag_nanoseconds", self.lag);
+    }
+}
+
+/// The time a log spent in Vector, from its source until its sink acked it.
+/// Only recorded by the sinks listed at `telemetry.end_to_end_latency`.
+#[derive(Debug)]
+pub struct EventLatency {
+    pub latency: Duration,
+}
+
+impl InternalEvent for EventLatency {
+    fn emit_metrics(&self) {
+        histogram!("end_to_end_latency_nanoseconds", self.latency);
+    }
+}
@@ -19,7 +19,7 @@
 //!   The reference implementation on preparing the in-cluster config.
 //!
 
-use crate::{http::HttpClient, tls::TlsSettings};
+use crate::{config::ProxyConfig, http::HttpClient, tls::TlsSettings};
 use http::{
     header::{self, HeaderValue},
     uri, Request, Response, Uri,
@@ -57,7 +57,12 @@ impl Client {
         } = config;
 
         let tls_settings = TlsSettings::from_options(&Some(tls_options))?;
-        let inner = HttpClient::new(tls_settings)?;
+        // The API server is expected to be reachable directly.
+        let proxy = ProxyConfig {
+            enabled: false,
+            ..Default::default()
+        };
+        let inner = HttpClient::new(tls_settings, &proxy)?;
 
         let uri::Parts {
             scheme, authority, ..
@@ -26,10 +26,13 @@ pub mod config;
 pub mod buffers;
 pub mod cli;
 pub mod conditions;
+pub mod diagnose;
 pub mod dns;
+pub mod enrichment_tables;
 pub mod event;
 pub mod expiring_hash_map;
 pub mod generate;
+pub mod graph;
 #[cfg(feature = "wasm")]
 pub mod wasm;
 #[macro_use]
@@ -59,6 +62,9 @@ pub mod sink;
 pub mod sinks;
 pub mod sources;
 pub mod stream;
+#[cfg(feature = "api-client")]
+pub mod tap;
+pub mod telemetry;
 pub mod template;
 pub mod test_util;
 pub mod tls;
@@ -72,6 +78,7 @@ pub mod unit_test;
 pub mod validate;
 #[cfg(windows)]
 pub mod vector_windows;
+pub mod vrl;
 
 pub use event::Event;
 pub use pipeline::Pipeline;
@@ -15,8 +15,11 @@ impl Sink for Pipeline {
 
     fn start_send(
         &mut self,
-        item: Self::SinkItem,
+        mut item: Self::SinkItem,
     ) -> Result<AsyncSink<Self::SinkItem>, Self::SinkError> {
+        // Sources send their events through a pipeline, so this is when
+        // they enter Vector
+        item.stamp_ingress();
         self.inner.start_send(item)
     }
 
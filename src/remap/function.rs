@@ -30,22 +30,40 @@ macro_rules! optional {
 
 mod ceil;
 mod contains;
+mod decode_base64;
 mod del;
 mod downcase;
+mod encode_base64;
+mod encode_json;
 mod ends_with;
 mod floor;
 mod format_number;
 mod format_timestamp;
+mod get_enrichment_table_record;
+mod ip_cidr_contains;
+mod ip_subnet;
+mod ip_to_ipv6;
+mod is_private_ip;
+mod log_util;
 mod r#match;
 mod md5;
 mod now;
 mod only_fields;
+mod parse_apache_log;
+mod parse_common_log;
 mod parse_duration;
+#[cfg(feature = "grok")]
+mod parse_grok;
 mod parse_json;
+mod parse_key_value;
+mod parse_nginx_log;
+mod parse_regex;
 mod parse_syslog;
 mod parse_timestamp;
 mod parse_url;
+mod parse_user_agent;
 mod replace;
+mod reverse_dns;
 mod round;
 mod sha1;
 mod sha2;
@@ -71,21 +89,38 @@ pub use self::sha2::Sha2;
 pub use self::sha3::Sha3;
 pub use ceil::Ceil;
 pub use contains::Contains;
+pub use decode_base64::DecodeBase64;
 pub use del::Del;
 pub use downcase::Downcase;
+pub use encode_base64::EncodeBase64;
+pub use encode_json::EncodeJson;
 pub use ends_with::EndsWith;
 pub use floor::Floor;
 pub use format_number::FormatNumber;
 pub use format_timestamp::FormatTimestamp;
+pub use get_enrichment_table_record::GetEnrichmentTableRecord;
+pub use ip_cidr_contains::IpCidrContains;
+pub use ip_subnet::IpSubnet;
+pub use ip_to_ipv6::IpToIpv6;
+pub use is_private_ip::IsPrivateIp;
 pub use now::Now;
 pub use only_fields::OnlyFields;
+pub use parse_apache_log::ParseApacheLog;
+pub use parse_common_log::ParseCommonLog;
 pub use parse_duration::ParseDuration;
+#[cfg(feature = "grok")]
+pub use parse_grok::ParseGrok;
 pub use parse_json::ParseJson;
+pub use parse_key_value::ParseKeyValue;
+pub use parse_nginx_log::ParseNginxLog;
+pub use parse_regex::ParseRegex;
 pub use parse_syslog::ParseSyslog;
 pub use parse_timestamp::ParseTimestamp;
 pub use parse_url::ParseUrl;
+pub use parse_user_agent::ParseUserAgent;
 pub use r#match::Match;
 pub use replace::Replace;
+pub use reverse_dns::ReverseDns;
 pub use round::Round;
 pub use slice::Slice;
 pub use split::Split;
@@ -127,6 +162,24 @@ fn is_scalar_value(value: &Value) -> bool {
     }
 }
 
+/// Builds the base64 configuration for the given character set, defaulting to
+/// the standard alphabet.
+fn base64_config(charset: Option<&str>, padding: bool) -> Result<base64::Config> {
+    let charset = match charset.unwrap_or("standard") {
+        "standard" => base64::CharacterSet::Standard,
+        "url_safe" => base64::CharacterSet::UrlSafe,
+        charset => {
+            return Err(format!(
+                r#"unknown charset "{}", expected "standard" or "url_safe""#,
+                charset
+            )
+            .into())
+        }
+    };
+
+    Ok(base64::Config::new(charset, padding))
+}
+
 /// Rounds the given number to the given precision.
 /// Takes a function parameter so the exact rounding function (ceil, floor or round)
 /// can be specified.
@@ -0,0 +1,108 @@
+use remap::prelude::*;
+
+#[derive(Clone, Copy, Debug)]
+pub struct DecodeBase64;
+
+impl Function for DecodeBase64 {
+    fn identifier(&self) -> &'static str {
+        "decode_base64"
+    }
+
+    fn parameters(&self) -> &'static [Parameter] {
+        &[
+            Parameter {
+                keyword: "value",
+                accepts: |v| matches!(v, Value::String(_)),
+                required: true,
+            },
+            Parameter {
+                keyword: "charset",
+                accepts: |v| matches!(v, Value::String(_)),
+                required: false,
+            },
+        ]
+    }
+
+    fn compile(&self, mut arguments: ArgumentList) -> Result<Box<dyn Expression>> {
+        let value = arguments.required_expr("value")?;
+        let charset = arguments.optional_expr("charset")?;
+
+        Ok(Box::new(DecodeBase64Fn { value, charset }))
+    }
+}
+
+#[derive(Debug, Clone)]
+struct DecodeBase64Fn {
+    value: Box<dyn Expression>,
+    charset: Option<Box<dyn Expression>>,
+}
+
+impl DecodeBase64Fn {
+    #[cfg(test)]
+    fn new(value: Box<dyn Expression>, charset: Option<&str>) -> Self {
+        let charset = charset.map(|v| Box::new(Literal::from(v)) as _);
+
+        Self { value, charset }
+    }
+}
+
+impl Expression for DecodeBase64Fn {
+    fn execute(&self, state: &mut State, object: &mut dyn Object) -> Result<Option<Value>> {
+        let value = required!(state, object, self.value, Value::String(v) => v);
+        let charset = optional!(state, object, self.charset, Value::String(v) => String::from_utf8_lossy(&v).into_owned());
+
+        // Decoding accepts input both with and without padding.
+        let config = super::base64_config(charset.as_deref(), false)?;
+
+        base64::decode_config(&value, config)
+            .map(|bytes| Some(bytes.into()))
+            .map_err(|error| format!("unable to decode value from base64: {}", error).into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::map;
+
+    #[test]
+    fn decode_base64() {
+        let cases = vec![
+            (
+                map!["foo": "c29tZSs9c3RyaW5nL3ZhbHVl"],
+                Ok(Some("some+=string/value".into())),
+                DecodeBase64Fn::new(Box::new(Path::from("foo")), None),
+            ),
+            (
+                map![],
+                Ok(Some("a".into())),
+                DecodeBase64Fn::new(Box::new(Literal::from("YQ==")), None),
+            ),
+            (
+                map![],
+                Ok(Some("a".into())),
+                DecodeBase64Fn::new(Box::new(Literal::from("YQ")), None),
+            ),
+            (
+                map![],
+                Ok(Some("<<??>>".into())),
+                DecodeBase64Fn::new(Box::new(Literal::from("PDw_Pz4-")), Some("url_safe")),
+            ),
+            (
+                map![],
+                Err("function call error: unable to decode value from base64: Invalid byte 95, offset 3.".into()),
+                DecodeBase64Fn::new(Box::new(Literal::from("PDw_Pz4-")), Some("standard")),
+            ),
+        ];
+
+        let mut state = remap::State::default();
+
+        for (mut object, exp, func) in cases {
+            let got = func
+                .execute(&mut state, &mut object)
+                .map_err(|e| format!("{:#}", anyhow::anyhow!(e)));
+
+            assert_eq!(got, exp);
+        }
+    }
+}
@@ -0,0 +1,128 @@
+use remap::prelude::*;
+
+#[derive(Clone, Copy, Debug)]
+pub struct EncodeBase64;
+
+impl Function for EncodeBase64 {
+    fn identifier(&self) -> &'static str {
+        "encode_base64"
+    }
+
+    fn parameters(&self) -> &'static [Parameter] {
+        &[
+            Parameter {
+                keyword: "value",
+                accepts: |v| matches!(v, Value::String(_)),
+                required: true,
+            },
+            Parameter {
+                keyword: "padding",
+                accepts: |v| matches!(v, Value::Boolean(_)),
+                required: false,
+            },
+            Parameter {
+                keyword: "charset",
+                accepts: |v| matches!(v, Value::String(_)),
+                required: false,
+            },
+        ]
+    }
+
+    fn compile(&self, mut arguments: ArgumentList) -> Result<Box<dyn Expression>> {
+        let value = arguments.required_expr("value")?;
+        let padding = arguments.optional_expr("padding")?;
+        let charset = arguments.optional_expr("charset")?;
+
+        Ok(Box::new(EncodeBase64Fn {
+            value,
+            padding,
+            charset,
+        }))
+    }
+}
+
+#[derive(Debug, Clone)]
+struct EncodeBase64Fn {
+    value: Box<dyn Expression>,
+    padding: Option<Box<dyn Expression>>,
+    charset: Option<Box<dyn Expression>>,
+}
+
+impl EncodeBase64Fn {
+    #[cfg(test)]
+    fn new(value: Box<dyn Expression>, padding: Option<bool>, charset: Option<&str>) -> Self {
+        let padding = padding.map(|v| Box::new(Literal::from(v)) as _);
+        let charset = charset.map(|v| Box::new(Literal::from(v)) as _);
+
+        Self {
+            value,
+            padding,
+            charset,
+        }
+    }
+}
+
+impl Expression for EncodeBase64Fn {
+    fn execute(&self, state: &mut State, object: &mut dyn Object) -> Result<Option<Value>> {
+        let value = required!(state, object, self.value, Value::String(v) => v);
+        let padding =
+            optional!(state, object, self.padding, Value::Boolean(v) => v).unwrap_or(true);
+        let charset = optional!(state, object, self.charset, Value::String(v) => String::from_utf8_lossy(&v).into_owned());
+
+        let config = super::base64_config(charset.as_deref(), padding)?;
+
+        Ok(Some(base64::encode_config(&value, config).into()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::map;
+
+    #[test]
+    fn encode_base64() {
+        let cases = vec![
+            (
+                map!["foo": "some+=string/value"],
+                Ok(Some("c29tZSs9c3RyaW5nL3ZhbHVl".into())),
+                EncodeBase64Fn::new(Box::new(Path::from("foo")), None, None),
+            ),
+            (
+                map![],
+                Ok(Some("PDw/Pz4+".into())),
+                EncodeBase64Fn::new(Box::new(Literal::from("<<??>>")), None, Some("standard")),
+            ),
+            (
+                map![],
+                Ok(Some("PDw_Pz4-".into())),
+                EncodeBase64Fn::new(Box::new(Literal::from("<<??>>")), None, Some("url_safe")),
+            ),
+            (
+                map![],
+                Ok(Some("YQ==".into())),
+                EncodeBase64Fn::new(Box::new(Literal::from("a")), Some(true), None),
+            ),
+            (
+                map![],
+                Ok(Some("YQ".into())),
+                EncodeBase64Fn::new(Box::new(Literal::from("a")), Some(false), None),
+            ),
+            (
+                map![],
+                Err(r#"function call error: unknown charset "base32", expected "standard" or "url_safe""#.into()),
+                EncodeBase64Fn::new(Box::new(Literal::from("a")), None, Some("base32")),
+            ),
+        ];
+
+        let mut state = remap::State::default();
+
+        for (mut object, exp, func) in cases {
+            let got = func
+                .execute(&mut state, &mut object)
+                .map_err(|e| format!("{:#}", anyhow::anyhow!(e)));
+
+            assert_eq!(got, exp);
+        }
+    }
+}
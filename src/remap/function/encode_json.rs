@@ -0,0 +1,96 @@
+use remap::prelude::*;
+use std::convert::TryInto;
+
+#[derive(Clone, Copy, Debug)]
+pub struct EncodeJson;
+
+impl Function for EncodeJson {
+    fn identifier(&self) -> &'static str {
+        "encode_json"
+    }
+
+    fn parameters(&self) -> &'static [Parameter] {
+        &[Parameter {
+            keyword: "value",
+            accepts: |_| true,
+            required: true,
+        }]
+    }
+
+    fn compile(&self, mut arguments: ArgumentList) -> Result<Box<dyn Expression>> {
+        let value = arguments.required_expr("value")?;
+
+        Ok(Box::new(EncodeJsonFn { value }))
+    }
+}
+
+#[derive(Debug, Clone)]
+struct EncodeJsonFn {
+    value: Box<dyn Expression>,
+}
+
+impl EncodeJsonFn {
+    #[cfg(test)]
+    fn new(value: Box<dyn Expression>) -> Self {
+        Self { value }
+    }
+}
+
+impl Expression for EncodeJsonFn {
+    fn execute(&self, state: &mut State, object: &mut dyn Object) -> Result<Option<Value>> {
+        let value = match self.value.execute(state, object)? {
+            Some(value) => value,
+            None => return Ok(None),
+        };
+
+        let json: serde_json::Value = crate::event::Value::from(value)
+            .try_into()
+            .map_err(|error| format!("unable to encode value as json: {}", error))?;
+
+        Ok(Some(json.to_string().into()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::map;
+    use chrono::prelude::*;
+
+    #[test]
+    fn encode_json() {
+        let cases = vec![
+            (
+                map!["foo": "bar"],
+                Ok(Some(r#""bar""#.into())),
+                EncodeJsonFn::new(Box::new(Path::from("foo"))),
+            ),
+            (
+                map![],
+                Ok(Some(r#"{"field":"value","list":[1,true,null]}"#.into())),
+                EncodeJsonFn::new(Box::new(Literal::from(Value::from(map![
+                    "field": "value",
+                    "list": vec![Value::from(1), true.into(), Value::Null],
+                ])))),
+            ),
+            (
+                map![],
+                Ok(Some(r#""2020-10-21T16:00:00Z""#.into())),
+                EncodeJsonFn::new(Box::new(Literal::from(Value::from(
+                    Utc.ymd(2020, 10, 21).and_hms(16, 0, 0),
+                )))),
+            ),
+            (map![], Ok(None), EncodeJsonFn::new(Box::new(Noop))),
+        ];
+
+        let mut state = remap::State::default();
+
+        for (mut object, exp, func) in cases {
+            let got = func
+                .execute(&mut state, &mut object)
+                .map_err(|e| format!("{:#}", anyhow::anyhow!(e)));
+
+            assert_eq!(got, exp);
+        }
+    }
+}
@@ -0,0 +1,193 @@
+use crate::enrichment_tables::{EnrichmentTables, Table};
+use remap::prelude::*;
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+/// Looks up records in the enrichment tables of the topology it is built in.
+#[derive(Clone, Debug)]
+pub struct GetEnrichmentTableRecord {
+    tables: EnrichmentTables,
+}
+
+impl GetEnrichmentTableRecord {
+    pub fn new(tables: EnrichmentTables) -> Self {
+        Self { tables }
+    }
+}
+
+impl Function for GetEnrichmentTableRecord {
+    fn identifier(&self) -> &'static str {
+        "get_enrichment_table_record"
+    }
+
+    fn parameters(&self) -> &'static [Parameter] {
+        &[
+            Parameter {
+                keyword: "table",
+                accepts: |v| matches!(v, Value::String(_)),
+                required: true,
+            },
+            Parameter {
+                keyword: "condition",
+                accepts: |v| matches!(v, Value::Map(_)),
+                required: true,
+            },
+        ]
+    }
+
+    fn compile(&self, mut arguments: ArgumentList) -> Result<Box<dyn Expression>> {
+        let table = arguments.required_expr("table")?;
+        let condition = arguments.required_expr("condition")?;
+
+        // Tables named by a literal are resolved once, along with the program,
+        // so that referencing an unknown table fails early.
+        let resolved = match table.as_value() {
+            Some(Value::String(v)) => Some(
+                self.tables
+                    .get(&String::from_utf8_lossy(v))
+                    .map_err(|error| error.to_string())?,
+            ),
+            _ => None,
+        };
+
+        Ok(Box::new(GetEnrichmentTableRecordFn {
+            table,
+            condition,
+            resolved,
+            tables: self.tables.clone(),
+        }))
+    }
+}
+
+#[derive(Debug, Clone)]
+struct GetEnrichmentTableRecordFn {
+    table: Box<dyn Expression>,
+    condition: Box<dyn Expression>,
+
+    /// The table, if it is named by a literal.
+    resolved: Option<Arc<Table>>,
+
+    /// The tables to resolve other names in.
+    tables: EnrichmentTables,
+}
+
+impl GetEnrichmentTableRecordFn {
+    #[cfg(test)]
+    fn new(table: Arc<Table>, condition: Box<dyn Expression>) -> Self {
+        Self {
+            table: Box::new(Literal::from("test")),
+            condition,
+            resolved: Some(table),
+            tables: EnrichmentTables::default(),
+        }
+    }
+}
+
+impl Expression for GetEnrichmentTableRecordFn {
+    fn execute(&self, state: &mut State, object: &mut dyn Object) -> Result<Option<Value>> {
+        let condition = required!(state, object, self.condition, Value::Map(v) => v)
+            .into_iter()
+            .map(|(field, value)| {
+                let value = crate::event::Value::from(value).to_string_lossy();
+                (field, value)
+            })
+            .collect::<BTreeMap<_, _>>();
+
+        let table = match &self.resolved {
+            Some(table) => table.clone(),
+            None => {
+                let name = required!(state, object, self.table, Value::String(v) => String::from_utf8_lossy(&v).into_owned());
+                self.tables.get(&name).map_err(|error| error.to_string())?
+            }
+        };
+
+        let record = table
+            .find_record(&condition)
+            .map_err(|error| format!("unable to find record: {}", error))?;
+
+        Ok(Some(
+            record
+                .into_iter()
+                .map(|(field, value)| (field, value.into()))
+                .collect::<BTreeMap<_, Value>>()
+                .into(),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{enrichment_tables::TableConfig, map, test_util::temp_file};
+
+    fn table() -> Arc<Table> {
+        let path = temp_file();
+        std::fs::write(&path, "id,host,team\n1,web-1,frontend\n2,db-1,storage\n").unwrap();
+
+        let config: TableConfig = toml::from_str(&format!(
+            "path = {:?}\nformat = \"csv\"",
+            path.to_str().unwrap()
+        ))
+        .unwrap();
+
+        Arc::new(Table::load("owners", config).unwrap())
+    }
+
+    fn condition(fields: BTreeMap<String, Value>) -> Box<dyn Expression> {
+        Box::new(Literal::from(fields))
+    }
+
+    #[test]
+    fn get_enrichment_table_record() {
+        let table = table();
+
+        let cases = vec![
+            (
+                map![],
+                Ok(Some(
+                    map!["id": "2", "host": "db-1", "team": "storage"].into(),
+                )),
+                GetEnrichmentTableRecordFn::new(table.clone(), condition(map!["host": "db-1"])),
+            ),
+            (
+                map![],
+                Ok(Some(
+                    map!["id": "1", "host": "web-1", "team": "frontend"].into(),
+                )),
+                GetEnrichmentTableRecordFn::new(table.clone(), condition(map!["id": 1])),
+            ),
+            (
+                map![],
+                Err("function call error: unable to find record: No matching record found".into()),
+                GetEnrichmentTableRecordFn::new(table, condition(map!["host": "web-2"])),
+            ),
+        ];
+
+        let mut state = remap::State::default();
+
+        for (mut object, exp, func) in cases {
+            let got = func
+                .execute(&mut state, &mut object)
+                .map_err(|e| format!("{:#}", anyhow::anyhow!(e)));
+
+            assert_eq!(got, exp);
+        }
+    }
+
+    #[test]
+    fn unknown_literal_table() {
+        let mut arguments = ArgumentList::default();
+        arguments.insert(
+            "table",
+            Argument::Expression(Box::new(Literal::from("not_a_table"))),
+        );
+        arguments.insert(
+            "condition",
+            Argument::Expression(Box::new(Literal::from(BTreeMap::<String, Value>::new()))),
+        );
+
+        assert!(GetEnrichmentTableRecord::new(EnrichmentTables::default())
+            .compile(arguments)
+            .is_err());
+    }
+}
@@ -0,0 +1,146 @@
+use cidr_utils::cidr::IpCidr;
+use remap::prelude::*;
+use std::net::IpAddr;
+use std::str::FromStr;
+
+#[derive(Clone, Copy, Debug)]
+pub struct IpCidrContains;
+
+impl Function for IpCidrContains {
+    fn identifier(&self) -> &'static str {
+        "ip_cidr_contains"
+    }
+
+    fn parameters(&self) -> &'static [Parameter] {
+        &[
+            Parameter {
+                keyword: "cidr",
+                accepts: |v| matches!(v, Value::String(_) | Value::Array(_)),
+                required: true,
+            },
+            Parameter {
+                keyword: "value",
+                accepts: |v| matches!(v, Value::String(_)),
+                required: true,
+            },
+        ]
+    }
+
+    fn compile(&self, mut arguments: ArgumentList) -> Result<Box<dyn Expression>> {
+        let cidr = arguments.required_expr("cidr")?;
+        let value = arguments.required_expr("value")?;
+
+        Ok(Box::new(IpCidrContainsFn { cidr, value }))
+    }
+}
+
+#[derive(Debug, Clone)]
+struct IpCidrContainsFn {
+    cidr: Box<dyn Expression>,
+    value: Box<dyn Expression>,
+}
+
+impl IpCidrContainsFn {
+    #[cfg(test)]
+    fn new(cidr: Box<dyn Expression>, value: Box<dyn Expression>) -> Self {
+        Self { cidr, value }
+    }
+}
+
+impl Expression for IpCidrContainsFn {
+    fn execute(&self, state: &mut State, object: &mut dyn Object) -> Result<Option<Value>> {
+        let value = required!(state, object, self.value, Value::String(v) => String::from_utf8_lossy(&v).into_owned());
+        let cidrs = required!(state, object, self.cidr,
+            Value::String(v) => vec![Value::String(v)],
+            Value::Array(v) => v,
+        );
+
+        let ip = IpAddr::from_str(&value)
+            .map_err(|error| format!("unable to parse IP address: {}", error))?;
+
+        for cidr in cidrs {
+            let cidr = match cidr {
+                Value::String(v) => String::from_utf8_lossy(&v).into_owned(),
+                v => {
+                    return Err(
+                        format!(r#"expected cidr of type "string", got "{}""#, v.kind()).into(),
+                    )
+                }
+            };
+
+            let cidr =
+                IpCidr::from_str(&cidr).map_err(|_| format!("unable to parse CIDR: {}", cidr))?;
+
+            if cidr.contains(ip) {
+                return Ok(Some(true.into()));
+            }
+        }
+
+        Ok(Some(false.into()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::map;
+
+    #[test]
+    fn ip_cidr_contains() {
+        let cases = vec![
+            (
+                map!["foo": "192.168.10.32"],
+                Ok(Some(true.into())),
+                IpCidrContainsFn::new(
+                    Box::new(Literal::from("192.168.0.0/16")),
+                    Box::new(Path::from("foo")),
+                ),
+            ),
+            (
+                map!["foo": "192.168.10.32"],
+                Ok(Some(false.into())),
+                IpCidrContainsFn::new(
+                    Box::new(Literal::from("10.0.0.0/8")),
+                    Box::new(Path::from("foo")),
+                ),
+            ),
+            (
+                map!["foo": "2001:4f8:3:ba:2e0:81ff:fe22:d1f1"],
+                Ok(Some(true.into())),
+                IpCidrContainsFn::new(
+                    Box::new(Literal::from(vec!["10.0.0.0/8", "2001:4f8:3:ba::/64"])),
+                    Box::new(Path::from("foo")),
+                ),
+            ),
+            (
+                map!["foo": "not an ip"],
+                Err(
+                    "function call error: unable to parse IP address: invalid IP address syntax"
+                        .into(),
+                ),
+                IpCidrContainsFn::new(
+                    Box::new(Literal::from("10.0.0.0/8")),
+                    Box::new(Path::from("foo")),
+                ),
+            ),
+            (
+                map!["foo": "10.0.0.1"],
+                Err("function call error: unable to parse CIDR: 10.0.0.0/33".into()),
+                IpCidrContainsFn::new(
+                    Box::new(Literal::from("10.0.0.0/33")),
+                    Box::new(Path::from("foo")),
+                ),
+            ),
+        ];
+
+        let mut state = remap::State::default();
+
+        for (mut object, exp, func) in cases {
+            let got = func
+                .execute(&mut state, &mut object)
+                .map_err(|e| format!("{:#}", anyhow::anyhow!(e)));
+
+            assert_eq!(got, exp);
+        }
+    }
+}
@@ -0,0 +1,151 @@
+use remap::prelude::*;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+use std::str::FromStr;
+
+#[derive(Clone, Copy, Debug)]
+pub struct IpSubnet;
+
+impl Function for IpSubnet {
+    fn identifier(&self) -> &'static str {
+        "ip_subnet"
+    }
+
+    fn parameters(&self) -> &'static [Parameter] {
+        &[
+            Parameter {
+                keyword: "value",
+                accepts: |v| matches!(v, Value::String(_)),
+                required: true,
+            },
+            Parameter {
+                keyword: "subnet",
+                accepts: |v| matches!(v, Value::String(_)),
+                required: true,
+            },
+        ]
+    }
+
+    fn compile(&self, mut arguments: ArgumentList) -> Result<Box<dyn Expression>> {
+        let value = arguments.required_expr("value")?;
+        let subnet = arguments.required_expr("subnet")?;
+
+        Ok(Box::new(IpSubnetFn { value, subnet }))
+    }
+}
+
+#[derive(Debug, Clone)]
+struct IpSubnetFn {
+    value: Box<dyn Expression>,
+    subnet: Box<dyn Expression>,
+}
+
+impl IpSubnetFn {
+    #[cfg(test)]
+    fn new(value: Box<dyn Expression>, subnet: &str) -> Self {
+        let subnet = Box::new(Literal::from(subnet));
+
+        Self { value, subnet }
+    }
+}
+
+impl Expression for IpSubnetFn {
+    fn execute(&self, state: &mut State, object: &mut dyn Object) -> Result<Option<Value>> {
+        let value = required!(state, object, self.value, Value::String(v) => String::from_utf8_lossy(&v).into_owned());
+        let subnet = required!(state, object, self.subnet, Value::String(v) => String::from_utf8_lossy(&v).into_owned());
+
+        let ip = IpAddr::from_str(&value)
+            .map_err(|error| format!("unable to parse IP address: {}", error))?;
+
+        let network = match (ip, parse_mask(&subnet, &ip)?) {
+            (IpAddr::V4(ip), IpAddr::V4(mask)) => {
+                IpAddr::V4(Ipv4Addr::from(u32::from(ip) & u32::from(mask)))
+            }
+            (IpAddr::V6(ip), IpAddr::V6(mask)) => {
+                IpAddr::V6(Ipv6Addr::from(u128::from(ip) & u128::from(mask)))
+            }
+            _ => return Err("subnet mask must be of the same IP version as the address".into()),
+        };
+
+        Ok(Some(network.to_string().into()))
+    }
+}
+
+/// Parses a subnet given either as a prefix length (`/24`) or as a mask
+/// (`255.255.255.0`).
+fn parse_mask(subnet: &str, ip: &IpAddr) -> Result<IpAddr> {
+    if !subnet.starts_with('/') {
+        return IpAddr::from_str(subnet)
+            .map_err(|error| format!("unable to parse subnet mask: {}", error).into());
+    }
+
+    let bits = subnet[1..]
+        .parse::<u32>()
+        .map_err(|error| format!("unable to parse subnet prefix: {}", error))?;
+
+    match ip {
+        IpAddr::V4(_) if bits <= 32 => Ok(IpAddr::V4(Ipv4Addr::from(
+            u32::MAX.checked_shl(32 - bits).unwrap_or(0),
+        ))),
+        IpAddr::V6(_) if bits <= 128 => Ok(IpAddr::V6(Ipv6Addr::from(
+            u128::MAX.checked_shl(128 - bits).unwrap_or(0),
+        ))),
+        _ => Err(format!("subnet prefix /{} is too large for {}", bits, ip).into()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::map;
+
+    #[test]
+    fn ip_subnet() {
+        let cases = vec![
+            (
+                map!["foo": "192.168.10.32"],
+                Ok(Some("192.168.10.0".into())),
+                IpSubnetFn::new(Box::new(Path::from("foo")), "255.255.255.0"),
+            ),
+            (
+                map!["foo": "192.168.10.32"],
+                Ok(Some("192.168.0.0".into())),
+                IpSubnetFn::new(Box::new(Path::from("foo")), "/16"),
+            ),
+            (
+                map!["foo": "192.168.10.32"],
+                Ok(Some("0.0.0.0".into())),
+                IpSubnetFn::new(Box::new(Path::from("foo")), "/0"),
+            ),
+            (
+                map!["foo": "2404:6800:4003:c02::64"],
+                Ok(Some("2400::".into())),
+                IpSubnetFn::new(Box::new(Path::from("foo")), "/6"),
+            ),
+            (
+                map!["foo": "2404:6800:4003:c02::64"],
+                Ok(Some("2404:6800::".into())),
+                IpSubnetFn::new(Box::new(Path::from("foo")), "ffff:ffff::"),
+            ),
+            (
+                map!["foo": "192.168.10.32"],
+                Err("function call error: subnet prefix /33 is too large for 192.168.10.32".into()),
+                IpSubnetFn::new(Box::new(Path::from("foo")), "/33"),
+            ),
+            (
+                map!["foo": "192.168.10.32"],
+                Err("function call error: subnet mask must be of the same IP version as the address".into()),
+                IpSubnetFn::new(Box::new(Path::from("foo")), "ffff::"),
+            ),
+        ];
+
+        let mut state = remap::State::default();
+
+        for (mut object, exp, func) in cases {
+            let got = func
+                .execute(&mut state, &mut object)
+                .map_err(|e| format!("{:#}", anyhow::anyhow!(e)));
+
+            assert_eq!(got, exp);
+        }
+    }
+}
@@ -0,0 +1,93 @@
+use remap::prelude::*;
+use std::net::IpAddr;
+use std::str::FromStr;
+
+#[derive(Clone, Copy, Debug)]
+pub struct IpToIpv6;
+
+impl Function for IpToIpv6 {
+    fn identifier(&self) -> &'static str {
+        "ip_to_ipv6"
+    }
+
+    fn parameters(&self) -> &'static [Parameter] {
+        &[Parameter {
+            keyword: "value",
+            accepts: |v| matches!(v, Value::String(_)),
+            required: true,
+        }]
+    }
+
+    fn compile(&self, mut arguments: ArgumentList) -> Result<Box<dyn Expression>> {
+        let value = arguments.required_expr("value")?;
+
+        Ok(Box::new(IpToIpv6Fn { value }))
+    }
+}
+
+#[derive(Debug, Clone)]
+struct IpToIpv6Fn {
+    value: Box<dyn Expression>,
+}
+
+impl IpToIpv6Fn {
+    #[cfg(test)]
+    fn new(value: Box<dyn Expression>) -> Self {
+        Self { value }
+    }
+}
+
+impl Expression for IpToIpv6Fn {
+    fn execute(&self, state: &mut State, object: &mut dyn Object) -> Result<Option<Value>> {
+        let value = required!(state, object, self.value, Value::String(v) => String::from_utf8_lossy(&v).into_owned());
+
+        let ipv6 = match IpAddr::from_str(&value)
+            .map_err(|error| format!("unable to parse IP address: {}", error))?
+        {
+            IpAddr::V4(ip) => ip.to_ipv6_mapped(),
+            IpAddr::V6(ip) => ip,
+        };
+
+        Ok(Some(ipv6.to_string().into()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::map;
+
+    #[test]
+    fn ip_to_ipv6() {
+        let cases = vec![
+            (
+                map!["foo": "192.168.0.1"],
+                Ok(Some("::ffff:192.168.0.1".into())),
+                IpToIpv6Fn::new(Box::new(Path::from("foo"))),
+            ),
+            (
+                map!["foo": "2404:6800:4003:c02::64"],
+                Ok(Some("2404:6800:4003:c02::64".into())),
+                IpToIpv6Fn::new(Box::new(Path::from("foo"))),
+            ),
+            (
+                map!["foo": "i am not an ip address"],
+                Err(
+                    "function call error: unable to parse IP address: invalid IP address syntax"
+                        .into(),
+                ),
+                IpToIpv6Fn::new(Box::new(Path::from("foo"))),
+            ),
+        ];
+
+        let mut state = remap::State::default();
+
+        for (mut object, exp, func) in cases {
+            let got = func
+                .execute(&mut state, &mut object)
+                .map_err(|e| format!("{:#}", anyhow::anyhow!(e)));
+
+            assert_eq!(got, exp);
+        }
+    }
+}
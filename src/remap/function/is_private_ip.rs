@@ -0,0 +1,119 @@
+use remap::prelude::*;
+use std::net::{IpAddr, Ipv6Addr};
+use std::str::FromStr;
+
+#[derive(Clone, Copy, Debug)]
+pub struct IsPrivateIp;
+
+impl Function for IsPrivateIp {
+    fn identifier(&self) -> &'static str {
+        "is_private_ip"
+    }
+
+    fn parameters(&self) -> &'static [Parameter] {
+        &[Parameter {
+            keyword: "value",
+            accepts: |v| matches!(v, Value::String(_)),
+            required: true,
+        }]
+    }
+
+    fn compile(&self, mut arguments: ArgumentList) -> Result<Box<dyn Expression>> {
+        let value = arguments.required_expr("value")?;
+
+        Ok(Box::new(IsPrivateIpFn { value }))
+    }
+}
+
+#[derive(Debug, Clone)]
+struct IsPrivateIpFn {
+    value: Box<dyn Expression>,
+}
+
+impl IsPrivateIpFn {
+    #[cfg(test)]
+    fn new(value: Box<dyn Expression>) -> Self {
+        Self { value }
+    }
+}
+
+impl Expression for IsPrivateIpFn {
+    fn execute(&self, state: &mut State, object: &mut dyn Object) -> Result<Option<Value>> {
+        let value = required!(state, object, self.value, Value::String(v) => String::from_utf8_lossy(&v).into_owned());
+
+        let ip = IpAddr::from_str(&value)
+            .map_err(|error| format!("unable to parse IP address: {}", error))?;
+
+        Ok(Some(is_private(&ip).into()))
+    }
+}
+
+/// Whether the address is not globally routable: private (RFC 1918), shared
+/// (RFC 6598), loopback, link-local or unique local (RFC 4193).
+fn is_private(ip: &IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(ip) => {
+            let [a, b, ..] = ip.octets();
+
+            ip.is_private()
+                || ip.is_loopback()
+                || ip.is_link_local()
+                || (a == 100 && (b & 0b1100_0000) == 64)
+        }
+        IpAddr::V6(ip) => match ip.to_ipv4() {
+            Some(ipv4) if is_ipv4_mapped(ip) => is_private(&IpAddr::V4(ipv4)),
+            _ => {
+                let first = ip.segments()[0];
+
+                ip.is_loopback() || (first & 0xfe00) == 0xfc00 || (first & 0xffc0) == 0xfe80
+            }
+        },
+    }
+}
+
+fn is_ipv4_mapped(ip: &Ipv6Addr) -> bool {
+    matches!(ip.segments(), [0, 0, 0, 0, 0, 0xffff, _, _])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::map;
+
+    #[test]
+    fn is_private_ip() {
+        let cases =
+            vec![
+            ("10.0.0.1", Ok(Some(true.into()))),
+            ("172.16.5.4", Ok(Some(true.into()))),
+            ("192.168.10.32", Ok(Some(true.into()))),
+            ("100.64.0.1", Ok(Some(true.into()))),
+            ("127.0.0.1", Ok(Some(true.into()))),
+            ("169.254.1.1", Ok(Some(true.into()))),
+            ("8.8.8.8", Ok(Some(false.into()))),
+            ("100.128.0.1", Ok(Some(false.into()))),
+            ("::1", Ok(Some(true.into()))),
+            ("fd12:3456:789a:1::1", Ok(Some(true.into()))),
+            ("fe80::1", Ok(Some(true.into()))),
+            ("::ffff:192.168.0.1", Ok(Some(true.into()))),
+            ("::ffff:8.8.8.8", Ok(Some(false.into()))),
+            ("2404:6800:4003:c02::64", Ok(Some(false.into()))),
+            (
+                "not an ip",
+                Err("function call error: unable to parse IP address: invalid IP address syntax"
+                    .into()),
+            ),
+        ];
+
+        let mut state = remap::State::default();
+
+        for (ip, exp) in cases {
+            let mut object = map!["foo": ip];
+            let got = IsPrivateIpFn::new(Box::new(Path::from("foo")))
+                .execute(&mut state, &mut object)
+                .map_err(|e| format!("{:#}", anyhow::anyhow!(e)));
+
+            assert_eq!(got, exp, "{}", ip);
+        }
+    }
+}
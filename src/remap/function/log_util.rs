@@ -0,0 +1,157 @@
+use chrono::{DateTime, NaiveDateTime, Utc};
+use lazy_static::lazy_static;
+use regex::{Captures, Regex};
+use remap::prelude::*;
+use std::collections::BTreeMap;
+
+lazy_static! {
+    // Information about the common log format taken from the
+    // - W3C specification: https://www.w3.org/Daemon/User/Config/Logging.html#common-logfile-format
+    // - Apache HTTP Server docs: https://httpd.apache.org/docs/1.3/logs.html#common
+    pub static ref REGEX_COMMON_LOG: Regex = Regex::new(
+        r#"(?x)                                 # Ignore whitespace and comments in the regex expression.
+        ^\s*                                    # Start with any number of whitespaces.
+        (-|(?P<host>.*?))\s+                    # Match `-` or any character (non-greedily) and at least one whitespace.
+        (-|(?P<identity>.*?))\s+                # Match `-` or any character (non-greedily) and at least one whitespace.
+        (-|(?P<user>.*?))\s+                    # Match `-` or any character (non-greedily) and at least one whitespace.
+        (-|\[(-|(?P<timestamp>[^\[]*))\])\s+    # Match `-` or `[` followed by `-` or any character except `]`, `]` and at least one whitespace.
+        (-|"(-|(\s*                             # Match `-` or `"` followed by `-` or and any number of whitespaces...
+        (?P<message>(                           # Match a request with...
+        (?P<method>\w+)\s+                      # Match at least one word character and at least one whitespace.
+        (?P<path>[[\\"][^"]]*?)\s+              # Match any character except `"`, but `\"` (non-greedily) and at least one whitespace.
+        (?P<protocol>[[\\"][^"]]*?)\s*          # Match any character except `"`, but `\"` (non-greedily) and any number of whitespaces.
+        |[[\\"][^"]]*?))\s*))"                  # ...Or match any character except `"`, but `\"`, and any amount of whitespaces.
+        )\s+                                    # Match at least one whitespace.
+        (-|(?P<status>\d+))\s+                  # Match `-` or at least one digit and at least one whitespace.
+        (-|(?P<size>\d+))                       # Match `-` or at least one digit.
+        \s*$                                    # Match any number of trailing whitespaces.
+        "#
+    )
+    .expect("failed compiling regex for common log");
+
+    // The combined log format extends the common log format with the referrer
+    // and user agent request headers.
+    pub static ref REGEX_COMBINED_LOG: Regex = Regex::new(
+        r#"(?x)                                 # Ignore whitespace and comments in the regex expression.
+        ^\s*                                    # Start with any number of whitespaces.
+        (-|(?P<host>.*?))\s+                    # Match `-` or any character (non-greedily) and at least one whitespace.
+        (-|(?P<identity>.*?))\s+                # Match `-` or any character (non-greedily) and at least one whitespace.
+        (-|(?P<user>.*?))\s+                    # Match `-` or any character (non-greedily) and at least one whitespace.
+        (-|\[(-|(?P<timestamp>[^\[]*))\])\s+    # Match `-` or `[` followed by `-` or any character except `]`, `]` and at least one whitespace.
+        (-|"(-|(\s*                             # Match `-` or `"` followed by `-` or and any number of whitespaces...
+        (?P<message>(                           # Match a request with...
+        (?P<method>\w+)\s+                      # Match at least one word character and at least one whitespace.
+        (?P<path>[[\\"][^"]]*?)\s+              # Match any character except `"`, but `\"` (non-greedily) and at least one whitespace.
+        (?P<protocol>[[\\"][^"]]*?)\s*          # Match any character except `"`, but `\"` (non-greedily) and any number of whitespaces.
+        |[[\\"][^"]]*?))\s*))"                  # ...Or match any character except `"`, but `\"`, and any amount of whitespaces.
+        )\s+                                    # Match at least one whitespace.
+        (-|(?P<status>\d+))\s+                  # Match `-` or at least one digit and at least one whitespace.
+        (-|(?P<size>\d+))\s+                    # Match `-` or at least one digit and at least one whitespace.
+        (-|"(-|(?P<referrer>[^"]*))")\s+        # Match `-` or a quoted referrer, and at least one whitespace.
+        (-|"(-|(?P<agent>[^"]*))")              # Match `-` or a quoted user agent.
+        \s*$                                    # Match any number of trailing whitespaces.
+        "#
+    )
+    .expect("failed compiling regex for combined log");
+
+    // Apache HTTP Server error log, e.g.
+    // `[Wed Oct 11 14:32:52.123456 2000] [core:error] [pid 35708:tid 4328636416] [client 1.2.3.4:80] message`
+    pub static ref REGEX_APACHE_ERROR_LOG: Regex = Regex::new(
+        r#"(?x)                                 # Ignore whitespace and comments in the regex expression.
+        ^\s*                                    # Start with any number of whitespaces.
+        \[(?P<timestamp>[^\]]+)\]\s+            # Match the bracketed timestamp and at least one whitespace.
+        \[((?P<module>[^:\]]+):)?               # Match an optional module name followed by `:`...
+        (?P<severity>[^\]]+)\]\s+               # ...and the severity, and at least one whitespace.
+        \[pid\s+(?P<pid>\d+)                    # Match the process id...
+        (:tid\s+(?P<thread>\d+))?\]\s+          # ...an optional thread id, and at least one whitespace.
+        (\[client\s+(?P<client>[^\]]+?)         # Match an optional client address...
+        (:(?P<port>\d+))?\]\s+)?                # ...with an optional port, and at least one whitespace.
+        (?P<message>.*?)                        # Match the remaining message.
+        \s*$                                    # Match any number of trailing whitespaces.
+        "#
+    )
+    .expect("failed compiling regex for apache error log");
+
+    // Nginx error log, e.g.
+    // `2019/02/14 12:30:03 [error] 10#10: *1 open() failed, client: 1.2.3.4, server: localhost`
+    pub static ref REGEX_NGINX_ERROR_LOG: Regex = Regex::new(
+        r#"(?x)                                 # Ignore whitespace and comments in the regex expression.
+        ^\s*                                    # Start with any number of whitespaces.
+        (?P<timestamp>\d{4}/\d{2}/\d{2}\s\d{2}:\d{2}:\d{2})\s+  # Match the timestamp and at least one whitespace.
+        \[(?P<severity>\w+)\]\s+                # Match the bracketed severity and at least one whitespace.
+        (?P<pid>\d+)\#(?P<tid>\d+):             # Match the process and thread id.
+        (\s+\*(?P<cid>\d+))?\s+                 # Match an optional connection id, and at least one whitespace.
+        (?P<message>.*?)                        # Match the remaining message.
+        \s*$                                    # Match any number of trailing whitespaces.
+        "#
+    )
+    .expect("failed compiling regex for nginx error log");
+}
+
+/// Timestamp format used by both the common and combined log formats.
+pub const TIMESTAMP_FORMAT_COMMON: &str = "%d/%b/%Y:%T %z";
+
+/// Timestamp format used by the Apache HTTP Server error log.
+pub const TIMESTAMP_FORMAT_APACHE_ERROR: &str = "%a %b %d %T%.f %Y";
+
+/// Timestamp format used by the Nginx error log.
+pub const TIMESTAMP_FORMAT_NGINX_ERROR: &str = "%Y/%m/%d %T";
+
+/// Capture groups that are converted to integers.
+const INTEGER_FIELDS: &[&str] = &["status", "size", "pid", "tid", "thread", "cid", "port"];
+
+/// Parses `value` with the given regex and converts the named captures into a
+/// map, dropping any capture that didn't participate in the match.
+///
+/// The `timestamp` capture is parsed using `timestamp_format`. Formats that
+/// lack a timezone are interpreted as UTC.
+pub fn parse_log(value: &str, regex: &Regex, timestamp_format: &str) -> Result<Value> {
+    let captures = regex
+        .captures(value)
+        .ok_or("failed parsing log line: no match")?;
+
+    captures_to_map(regex, &captures, timestamp_format).map(Into::into)
+}
+
+fn captures_to_map(
+    regex: &Regex,
+    captures: &Captures,
+    timestamp_format: &str,
+) -> Result<BTreeMap<String, Value>> {
+    let mut map = BTreeMap::new();
+
+    for name in regex.capture_names().flatten() {
+        let capture = match captures.name(name) {
+            Some(capture) => capture.as_str(),
+            None => continue,
+        };
+
+        let value = match name {
+            "timestamp" => parse_timestamp(capture, timestamp_format)?.into(),
+            name if INTEGER_FIELDS.contains(&name) => capture
+                .parse::<i64>()
+                .map_err(|error| format!("failed parsing {}: {}", name, error))?
+                .into(),
+            _ => capture.into(),
+        };
+
+        map.insert(name.to_owned(), value);
+    }
+
+    Ok(map)
+}
+
+fn parse_timestamp(value: &str, format: &str) -> Result<DateTime<Utc>> {
+    DateTime::parse_from_str(value, format)
+        .map(Into::into)
+        .or_else(|_| {
+            NaiveDateTime::parse_from_str(value, format).map(|dt| DateTime::from_utc(dt, Utc))
+        })
+        .map_err(|error| {
+            format!(
+                r#"failed parsing timestamp {} using format {}: {}"#,
+                value, format, error
+            )
+            .into()
+        })
+}
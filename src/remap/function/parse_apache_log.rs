@@ -0,0 +1,198 @@
+use super::log_util;
+use remap::prelude::*;
+
+#[derive(Clone, Copy, Debug)]
+pub struct ParseApacheLog;
+
+impl Function for ParseApacheLog {
+    fn identifier(&self) -> &'static str {
+        "parse_apache_log"
+    }
+
+    fn parameters(&self) -> &'static [Parameter] {
+        &[
+            Parameter {
+                keyword: "value",
+                accepts: |v| matches!(v, Value::String(_)),
+                required: true,
+            },
+            Parameter {
+                keyword: "format",
+                accepts: |v| matches!(v, Value::String(_)),
+                required: true,
+            },
+            Parameter {
+                keyword: "timestamp_format",
+                accepts: |v| matches!(v, Value::String(_)),
+                required: false,
+            },
+        ]
+    }
+
+    fn compile(&self, mut arguments: ArgumentList) -> Result<Box<dyn Expression>> {
+        let value = arguments.required_expr("value")?;
+        let format = arguments.required_expr("format")?;
+        let timestamp_format = arguments.optional_expr("timestamp_format")?;
+
+        Ok(Box::new(ParseApacheLogFn {
+            value,
+            format,
+            timestamp_format,
+        }))
+    }
+}
+
+#[derive(Debug, Clone)]
+struct ParseApacheLogFn {
+    value: Box<dyn Expression>,
+    format: Box<dyn Expression>,
+    timestamp_format: Option<Box<dyn Expression>>,
+}
+
+impl ParseApacheLogFn {
+    #[cfg(test)]
+    fn new(value: Box<dyn Expression>, format: &str) -> Self {
+        let format = Box::new(Literal::from(format));
+
+        Self {
+            value,
+            format,
+            timestamp_format: None,
+        }
+    }
+}
+
+impl Expression for ParseApacheLogFn {
+    fn execute(&self, state: &mut State, object: &mut dyn Object) -> Result<Option<Value>> {
+        let message = required!(state, object, self.value, Value::String(v) => String::from_utf8_lossy(&v).into_owned());
+        let format = required!(state, object, self.format, Value::String(v) => String::from_utf8_lossy(&v).into_owned());
+        let timestamp_format = optional!(state, object, self.timestamp_format, Value::String(v) => String::from_utf8_lossy(&v).into_owned());
+
+        let (regex, default_timestamp_format) = match format.as_str() {
+            "common" => (
+                &*log_util::REGEX_COMMON_LOG,
+                log_util::TIMESTAMP_FORMAT_COMMON,
+            ),
+            "combined" => (
+                &*log_util::REGEX_COMBINED_LOG,
+                log_util::TIMESTAMP_FORMAT_COMMON,
+            ),
+            "error" => (
+                &*log_util::REGEX_APACHE_ERROR_LOG,
+                log_util::TIMESTAMP_FORMAT_APACHE_ERROR,
+            ),
+            format => {
+                return Err(format!(
+                    r#"unknown format "{}", expected one of "common", "combined" or "error""#,
+                    format
+                )
+                .into())
+            }
+        };
+
+        let timestamp_format = timestamp_format
+            .as_deref()
+            .unwrap_or(default_timestamp_format);
+
+        log_util::parse_log(&message, regex, timestamp_format).map(Some)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::map;
+    use chrono::prelude::*;
+
+    #[test]
+    fn parse_apache_log() {
+        let cases = vec![
+            (
+                map![],
+                Ok(Some(
+                    map![
+                        "host": "127.0.0.1",
+                        "identity": "bob",
+                        "user": "frank",
+                        "timestamp": Utc.ymd(2000, 10, 10).and_hms(20, 55, 36),
+                        "message": "GET /apache_pb.gif HTTP/1.0",
+                        "method": "GET",
+                        "path": "/apache_pb.gif",
+                        "protocol": "HTTP/1.0",
+                        "status": 200,
+                        "size": 2326,
+                    ]
+                    .into(),
+                )),
+                ParseApacheLogFn::new(
+                    Box::new(Literal::from(
+                        r#"127.0.0.1 bob frank [10/Oct/2000:13:55:36 -0700] "GET /apache_pb.gif HTTP/1.0" 200 2326"#,
+                    )),
+                    "common",
+                ),
+            ),
+            (
+                map![],
+                Ok(Some(
+                    map![
+                        "host": "127.0.0.1",
+                        "user": "frank",
+                        "timestamp": Utc.ymd(2000, 10, 10).and_hms(20, 55, 36),
+                        "message": "GET /apache_pb.gif HTTP/1.0",
+                        "method": "GET",
+                        "path": "/apache_pb.gif",
+                        "protocol": "HTTP/1.0",
+                        "status": 200,
+                        "size": 2326,
+                        "referrer": "http://www.example.com/start.html",
+                        "agent": "Mozilla/4.08 [en] (Win98; I ;Nav)",
+                    ]
+                    .into(),
+                )),
+                ParseApacheLogFn::new(
+                    Box::new(Literal::from(
+                        r#"127.0.0.1 - frank [10/Oct/2000:13:55:36 -0700] "GET /apache_pb.gif HTTP/1.0" 200 2326 "http://www.example.com/start.html" "Mozilla/4.08 [en] (Win98; I ;Nav)""#,
+                    )),
+                    "combined",
+                ),
+            ),
+            (
+                map![],
+                Ok(Some(
+                    map![
+                        "timestamp": Utc.ymd(2000, 10, 11).and_hms_micro(14, 32, 52, 123_456),
+                        "module": "core",
+                        "severity": "error",
+                        "pid": 35708,
+                        "thread": 4328636416_i64,
+                        "client": "1.2.3.4",
+                        "port": 80,
+                        "message": "File does not exist: /usr/local/apache2/htdocs/favicon.ico",
+                    ]
+                    .into(),
+                )),
+                ParseApacheLogFn::new(
+                    Box::new(Literal::from(
+                        r#"[Wed Oct 11 14:32:52.123456 2000] [core:error] [pid 35708:tid 4328636416] [client 1.2.3.4:80] File does not exist: /usr/local/apache2/htdocs/favicon.ico"#,
+                    )),
+                    "error",
+                ),
+            ),
+            (
+                map![],
+                Err(r#"function call error: unknown format "nginx", expected one of "common", "combined" or "error""#.into()),
+                ParseApacheLogFn::new(Box::new(Literal::from("")), "nginx"),
+            ),
+        ];
+
+        let mut state = remap::State::default();
+
+        for (mut object, exp, func) in cases {
+            let got = func
+                .execute(&mut state, &mut object)
+                .map_err(|e| format!("{:#}", anyhow::anyhow!(e)));
+
+            assert_eq!(got, exp);
+        }
+    }
+}
@@ -0,0 +1,139 @@
+use super::log_util;
+use remap::prelude::*;
+
+#[derive(Clone, Copy, Debug)]
+pub struct ParseCommonLog;
+
+impl Function for ParseCommonLog {
+    fn identifier(&self) -> &'static str {
+        "parse_common_log"
+    }
+
+    fn parameters(&self) -> &'static [Parameter] {
+        &[
+            Parameter {
+                keyword: "value",
+                accepts: |v| matches!(v, Value::String(_)),
+                required: true,
+            },
+            Parameter {
+                keyword: "timestamp_format",
+                accepts: |v| matches!(v, Value::String(_)),
+                required: false,
+            },
+        ]
+    }
+
+    fn compile(&self, mut arguments: ArgumentList) -> Result<Box<dyn Expression>> {
+        let value = arguments.required_expr("value")?;
+        let timestamp_format = arguments.optional_expr("timestamp_format")?;
+
+        Ok(Box::new(ParseCommonLogFn {
+            value,
+            timestamp_format,
+        }))
+    }
+}
+
+#[derive(Debug, Clone)]
+struct ParseCommonLogFn {
+    value: Box<dyn Expression>,
+    timestamp_format: Option<Box<dyn Expression>>,
+}
+
+impl ParseCommonLogFn {
+    #[cfg(test)]
+    fn new(value: Box<dyn Expression>, timestamp_format: Option<&str>) -> Self {
+        let timestamp_format = timestamp_format.map(|v| Box::new(Literal::from(v)) as _);
+
+        Self {
+            value,
+            timestamp_format,
+        }
+    }
+}
+
+impl Expression for ParseCommonLogFn {
+    fn execute(&self, state: &mut State, object: &mut dyn Object) -> Result<Option<Value>> {
+        let message = required!(state, object, self.value, Value::String(v) => String::from_utf8_lossy(&v).into_owned());
+        let timestamp_format = optional!(state, object, self.timestamp_format, Value::String(v) => String::from_utf8_lossy(&v).into_owned())
+            .unwrap_or_else(|| log_util::TIMESTAMP_FORMAT_COMMON.to_owned());
+
+        log_util::parse_log(&message, &log_util::REGEX_COMMON_LOG, &timestamp_format).map(Some)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::map;
+    use chrono::prelude::*;
+
+    #[test]
+    fn parse_common_log() {
+        let cases = vec![
+            (
+                map![],
+                Ok(Some(
+                    map![
+                        "host": "127.0.0.1",
+                        "identity": "bob",
+                        "user": "frank",
+                        "timestamp": Utc.ymd(2000, 10, 10).and_hms(20, 55, 36),
+                        "message": "GET /apache_pb.gif HTTP/1.0",
+                        "method": "GET",
+                        "path": "/apache_pb.gif",
+                        "protocol": "HTTP/1.0",
+                        "status": 200,
+                        "size": 2326,
+                    ]
+                    .into(),
+                )),
+                ParseCommonLogFn::new(
+                    Box::new(Literal::from(
+                        r#"127.0.0.1 bob frank [10/Oct/2000:13:55:36 -0700] "GET /apache_pb.gif HTTP/1.0" 200 2326"#,
+                    )),
+                    None,
+                ),
+            ),
+            (
+                map![],
+                Ok(Some(
+                    map![
+                        "timestamp": Utc.ymd(2000, 10, 10).and_hms(20, 55, 36),
+                    ]
+                    .into(),
+                )),
+                ParseCommonLogFn::new(
+                    Box::new(Literal::from(r#"- - - [2000-10-10T20:55:36Z] "-" - -"#)),
+                    Some("%+"),
+                ),
+            ),
+            (
+                map![],
+                Err("function call error: failed parsing log line: no match".into()),
+                ParseCommonLogFn::new(Box::new(Literal::from("not a common log line")), None),
+            ),
+            (
+                map![],
+                Err(r#"function call error: failed parsing timestamp 10/Oct/2000:13:55:36 -0700 using format %+: input contains invalid characters"#.into()),
+                ParseCommonLogFn::new(
+                    Box::new(Literal::from(
+                        r#"127.0.0.1 bob frank [10/Oct/2000:13:55:36 -0700] "GET /apache_pb.gif HTTP/1.0" 200 2326"#,
+                    )),
+                    Some("%+"),
+                ),
+            ),
+        ];
+
+        let mut state = remap::State::default();
+
+        for (mut object, exp, func) in cases {
+            let got = func
+                .execute(&mut state, &mut object)
+                .map_err(|e| format!("{:#}", anyhow::anyhow!(e)));
+
+            assert_eq!(got, exp);
+        }
+    }
+}
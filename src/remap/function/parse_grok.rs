@@ -0,0 +1,290 @@
+use lru::LruCache;
+use remap::prelude::*;
+use std::collections::BTreeMap;
+use std::sync::{Arc, Mutex};
+
+/// The maximum number of distinct patterns kept compiled per function call
+/// site. Patterns are usually literals, so this is only reached when the
+/// pattern is built from event data.
+const PATTERN_CACHE_SIZE: usize = 64;
+
+#[derive(Clone, Copy, Debug)]
+pub struct ParseGrok;
+
+impl Function for ParseGrok {
+    fn identifier(&self) -> &'static str {
+        "parse_grok"
+    }
+
+    fn parameters(&self) -> &'static [Parameter] {
+        &[
+            Parameter {
+                keyword: "value",
+                accepts: |v| matches!(v, Value::String(_)),
+                required: true,
+            },
+            Parameter {
+                keyword: "pattern",
+                accepts: |v| matches!(v, Value::String(_)),
+                required: true,
+            },
+            Parameter {
+                keyword: "remove_empty",
+                accepts: |v| matches!(v, Value::Boolean(_)),
+                required: false,
+            },
+        ]
+    }
+
+    fn compile(&self, mut arguments: ArgumentList) -> Result<Box<dyn Expression>> {
+        let value = arguments.required_expr("value")?;
+        let pattern = arguments.required_expr("pattern")?;
+        let remove_empty = arguments.optional_expr("remove_empty")?;
+
+        // Literal patterns are compiled once, along with the program.
+        let compiled = match pattern.as_value() {
+            Some(Value::String(v)) => Some(Arc::new(compile_pattern(&String::from_utf8_lossy(v))?)),
+            _ => None,
+        };
+
+        Ok(Box::new(ParseGrokFn {
+            value,
+            pattern,
+            remove_empty,
+            compiled,
+            patterns: pattern_cache(),
+        }))
+    }
+}
+
+#[derive(Derivative)]
+#[derivative(Debug)]
+struct ParseGrokFn {
+    value: Box<dyn Expression>,
+    pattern: Box<dyn Expression>,
+    remove_empty: Option<Box<dyn Expression>>,
+
+    /// The compiled pattern, if it is a literal.
+    #[derivative(Debug = "ignore")]
+    compiled: Option<Arc<grok::Pattern>>,
+
+    /// Compiled patterns, keyed by their source. Every clone of the function
+    /// keeps its own cache, so the lock is only taken by the task running it.
+    #[derivative(Debug = "ignore")]
+    patterns: Mutex<LruCache<String, Arc<grok::Pattern>>>,
+}
+
+fn pattern_cache() -> Mutex<LruCache<String, Arc<grok::Pattern>>> {
+    Mutex::new(LruCache::new(PATTERN_CACHE_SIZE))
+}
+
+impl Clone for ParseGrokFn {
+    fn clone(&self) -> Self {
+        Self {
+            value: self.value.clone(),
+            pattern: self.pattern.clone(),
+            remove_empty: self.remove_empty.clone(),
+            compiled: self.compiled.clone(),
+            patterns: pattern_cache(),
+        }
+    }
+}
+
+impl ParseGrokFn {
+    #[cfg(test)]
+    fn new(value: Box<dyn Expression>, pattern: &str, remove_empty: bool) -> Self {
+        let pattern = Box::new(Literal::from(pattern));
+        let remove_empty = Some(Box::new(Literal::from(remove_empty)) as _);
+
+        Self {
+            value,
+            pattern,
+            remove_empty,
+            compiled: None,
+            patterns: pattern_cache(),
+        }
+    }
+}
+
+impl Expression for ParseGrokFn {
+    fn execute(&self, state: &mut State, object: &mut dyn Object) -> Result<Option<Value>> {
+        let value = required!(state, object, self.value, Value::String(v) => String::from_utf8_lossy(&v).into_owned());
+        let remove_empty =
+            optional!(state, object, self.remove_empty, Value::Boolean(b) => b).unwrap_or(false);
+
+        if let Some(pattern) = &self.compiled {
+            return matches_to_map(pattern, &value, remove_empty).map(|map| Some(map.into()));
+        }
+
+        let pattern = required!(state, object, self.pattern, Value::String(v) => String::from_utf8_lossy(&v).into_owned());
+        let compiled = {
+            let mut patterns = self.patterns.lock().expect("poisoned lock");
+            match patterns.get(&pattern) {
+                Some(compiled) => Arc::clone(compiled),
+                None => {
+                    let compiled = Arc::new(compile_pattern(&pattern)?);
+                    patterns.put(pattern, Arc::clone(&compiled));
+                    compiled
+                }
+            }
+        };
+
+        matches_to_map(&compiled, &value, remove_empty).map(|map| Some(map.into()))
+    }
+}
+
+fn compile_pattern(pattern: &str) -> Result<grok::Pattern> {
+    grok::Grok::with_patterns()
+        .compile(pattern, true)
+        .map_err(|error| format!("unable to compile grok pattern: {}", error).into())
+}
+
+fn matches_to_map(
+    pattern: &grok::Pattern,
+    value: &str,
+    remove_empty: bool,
+) -> Result<BTreeMap<String, Value>> {
+    let matches = pattern
+        .match_against(value)
+        .ok_or("unable to parse input with grok pattern")?;
+
+    Ok(matches
+        .iter()
+        .filter(|(_, value)| !(remove_empty && value.is_empty()))
+        .map(|(name, value)| (name.to_owned(), value.into()))
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::map;
+
+    #[test]
+    fn parse_grok() {
+        let cases = vec![
+            (
+                map!["message": "2020-10-02T23:22:12.223222Z info Hello world"],
+                Ok(Some(
+                    map![
+                        "timestamp": "2020-10-02T23:22:12.223222Z",
+                        "level": "info",
+                        "message": "Hello world",
+                    ]
+                    .into(),
+                )),
+                ParseGrokFn::new(
+                    Box::new(Path::from("message")),
+                    "%{TIMESTAMP_ISO8601:timestamp} %{LOGLEVEL:level} %{GREEDYDATA:message}",
+                    false,
+                ),
+            ),
+            (
+                map!["message": "- info"],
+                Ok(Some(map!["level": "info"].into())),
+                ParseGrokFn::new(
+                    Box::new(Path::from("message")),
+                    "(%{TIMESTAMP_ISO8601:timestamp}|-) %{LOGLEVEL:level}",
+                    true,
+                ),
+            ),
+            (
+                map!["message": "an ungrokkable message"],
+                Err("function call error: unable to parse input with grok pattern".into()),
+                ParseGrokFn::new(
+                    Box::new(Path::from("message")),
+                    "%{TIMESTAMP_ISO8601:timestamp} %{LOGLEVEL:level}",
+                    false,
+                ),
+            ),
+        ];
+
+        let mut state = remap::State::default();
+
+        for (mut object, exp, func) in cases {
+            let got = func
+                .execute(&mut state, &mut object)
+                .map_err(|e| format!("{:#}", anyhow::anyhow!(e)));
+
+            assert_eq!(got, exp);
+        }
+    }
+
+    #[test]
+    fn invalid_pattern() {
+        let func = ParseGrokFn::new(
+            Box::new(Path::from("message")),
+            "%{NOT_A_PATTERN:foo}",
+            false,
+        );
+        let mut state = remap::State::default();
+        let mut object = map!["message": "foo"];
+
+        let error = func.execute(&mut state, &mut object).unwrap_err();
+
+        assert!(format!("{:#}", anyhow::anyhow!(error))
+            .starts_with("function call error: unable to compile grok pattern"));
+    }
+
+    #[test]
+    fn caches_compiled_patterns() {
+        let func = ParseGrokFn::new(Box::new(Path::from("message")), "%{WORD:word}", false);
+        let mut state = remap::State::default();
+
+        for _ in 0..3 {
+            let mut object = map!["message": "hello"];
+            func.execute(&mut state, &mut object).unwrap();
+        }
+
+        assert_eq!(func.patterns.lock().unwrap().len(), 1);
+        assert_eq!(func.clone().patterns.lock().unwrap().len(), 0);
+    }
+
+    #[test]
+    fn evicts_least_recently_used_patterns() {
+        let func = ParseGrokFn {
+            pattern: Box::new(Path::from("pattern")),
+            ..ParseGrokFn::new(Box::new(Path::from("message")), "", false)
+        };
+        let mut state = remap::State::default();
+
+        for i in 0..=PATTERN_CACHE_SIZE {
+            let mut object = map!["message": "hello", "pattern": format!("%{{WORD:word{}}}", i)];
+            func.execute(&mut state, &mut object).unwrap();
+        }
+
+        let mut patterns = func.patterns.lock().unwrap();
+        assert_eq!(patterns.len(), PATTERN_CACHE_SIZE);
+        assert!(patterns.get("%{WORD:word0}").is_none());
+        assert!(patterns.get("%{WORD:word1}").is_some());
+    }
+
+    #[test]
+    fn compiles_literal_patterns() {
+        let arguments = |pattern: &str| {
+            let mut arguments = ArgumentList::default();
+            arguments.insert(
+                "value",
+                Argument::Expression(Box::new(Path::from("message"))),
+            );
+            arguments.insert(
+                "pattern",
+                Argument::Expression(Box::new(Literal::from(pattern))),
+            );
+            arguments
+        };
+
+        assert!(ParseGrok
+            .compile(arguments("%{NOT_A_PATTERN:foo}"))
+            .is_err());
+
+        let func = ParseGrok.compile(arguments("%{WORD:word}")).unwrap();
+        let mut state = remap::State::default();
+        let mut object = map!["message": "hello"];
+
+        assert_eq!(
+            func.execute(&mut state, &mut object),
+            Ok(Some(map!["word": "hello"].into()))
+        );
+    }
+}
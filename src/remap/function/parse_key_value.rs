@@ -0,0 +1,222 @@
+use remap::prelude::*;
+use std::collections::BTreeMap;
+
+#[derive(Clone, Copy, Debug)]
+pub struct ParseKeyValue;
+
+impl Function for ParseKeyValue {
+    fn identifier(&self) -> &'static str {
+        "parse_key_value"
+    }
+
+    fn parameters(&self) -> &'static [Parameter] {
+        &[
+            Parameter {
+                keyword: "value",
+                accepts: |v| matches!(v, Value::String(_)),
+                required: true,
+            },
+            Parameter {
+                keyword: "key_value_delimiter",
+                accepts: |v| matches!(v, Value::String(_)),
+                required: false,
+            },
+            Parameter {
+                keyword: "field_delimiter",
+                accepts: |v| matches!(v, Value::String(_)),
+                required: false,
+            },
+        ]
+    }
+
+    fn compile(&self, mut arguments: ArgumentList) -> Result<Box<dyn Expression>> {
+        let value = arguments.required_expr("value")?;
+        let key_value_delimiter = arguments.optional_expr("key_value_delimiter")?;
+        let field_delimiter = arguments.optional_expr("field_delimiter")?;
+
+        Ok(Box::new(ParseKeyValueFn {
+            value,
+            key_value_delimiter,
+            field_delimiter,
+        }))
+    }
+}
+
+#[derive(Debug, Clone)]
+struct ParseKeyValueFn {
+    value: Box<dyn Expression>,
+    key_value_delimiter: Option<Box<dyn Expression>>,
+    field_delimiter: Option<Box<dyn Expression>>,
+}
+
+impl ParseKeyValueFn {
+    #[cfg(test)]
+    fn new(
+        value: Box<dyn Expression>,
+        key_value_delimiter: Option<&str>,
+        field_delimiter: Option<&str>,
+    ) -> Self {
+        let key_value_delimiter = key_value_delimiter.map(|v| Box::new(Literal::from(v)) as _);
+        let field_delimiter = field_delimiter.map(|v| Box::new(Literal::from(v)) as _);
+
+        Self {
+            value,
+            key_value_delimiter,
+            field_delimiter,
+        }
+    }
+}
+
+impl Expression for ParseKeyValueFn {
+    fn execute(&self, state: &mut State, object: &mut dyn Object) -> Result<Option<Value>> {
+        let value = required!(state, object, self.value, Value::String(v) => String::from_utf8_lossy(&v).into_owned());
+        let key_value_delimiter = optional!(state, object, self.key_value_delimiter, Value::String(v) => String::from_utf8_lossy(&v).into_owned())
+            .unwrap_or_else(|| "=".to_owned());
+        let field_delimiter = optional!(state, object, self.field_delimiter, Value::String(v) => String::from_utf8_lossy(&v).into_owned())
+            .unwrap_or_else(|| " ".to_owned());
+
+        if key_value_delimiter.is_empty() || field_delimiter.is_empty() {
+            return Err("delimiters must not be empty".into());
+        }
+
+        let map = split_quoted(&value, &field_delimiter)
+            .into_iter()
+            .filter(|field| !field.trim().is_empty())
+            .map(
+                |field| match split_quoted(field, &key_value_delimiter).as_slice() {
+                    [key] => (unquote(key), true.into()),
+                    [key, ..] => {
+                        let value = &field[key.len() + key_value_delimiter.len()..];
+                        (unquote(key), unquote(value).into())
+                    }
+                    [] => unreachable!("split always returns at least one part"),
+                },
+            )
+            .collect::<BTreeMap<String, Value>>();
+
+        Ok(Some(map.into()))
+    }
+}
+
+/// Splits `value` on `delimiter`, ignoring any delimiters that appear between
+/// double quotes.
+fn split_quoted<'a>(value: &'a str, delimiter: &str) -> Vec<&'a str> {
+    let mut parts = Vec::new();
+    let mut in_quotes = false;
+    let mut escaped = false;
+    let mut start = 0;
+    let mut chars = value.char_indices();
+
+    while let Some((i, c)) = chars.next() {
+        match c {
+            _ if escaped => escaped = false,
+            '\\' => escaped = true,
+            '"' => in_quotes = !in_quotes,
+            _ if !in_quotes && value[i..].starts_with(delimiter) => {
+                parts.push(&value[start..i]);
+                start = i + delimiter.len();
+
+                // Skip the remainder of a multi-character delimiter.
+                for _ in delimiter.chars().skip(1) {
+                    chars.next();
+                }
+            }
+            _ => {}
+        }
+    }
+
+    parts.push(&value[start..]);
+    parts
+}
+
+/// Trims surrounding whitespace and, if present, the surrounding double quotes
+/// of `value`, unescaping any quotes inside.
+fn unquote(value: &str) -> String {
+    let value = value.trim();
+
+    if value.len() >= 2 && value.starts_with('"') && value.ends_with('"') {
+        value[1..value.len() - 1].replace(r#"\""#, r#"""#)
+    } else {
+        value.to_owned()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::map;
+
+    #[test]
+    fn parse_key_value() {
+        let cases = vec![
+            (
+                map![],
+                Ok(Some(
+                    map![
+                        "at": "info",
+                        "method": "GET",
+                        "path": "/",
+                        "status": "200",
+                        "msg": "hello world",
+                    ]
+                    .into(),
+                )),
+                ParseKeyValueFn::new(
+                    Box::new(Literal::from(
+                        r#"at=info method=GET path=/ status=200 msg="hello world""#,
+                    )),
+                    None,
+                    None,
+                ),
+            ),
+            (
+                map![],
+                Ok(Some(
+                    map![
+                        "user": "bob",
+                        "query": "a=b",
+                        "flagged": true,
+                    ]
+                    .into(),
+                )),
+                ParseKeyValueFn::new(
+                    Box::new(Literal::from(r#"user: bob, query: a=b, flagged"#)),
+                    Some(":"),
+                    Some(","),
+                ),
+            ),
+            (
+                map![],
+                Ok(Some(
+                    map![
+                        "first name": "john",
+                        "note": r#"says "a || b""#,
+                    ]
+                    .into(),
+                )),
+                ParseKeyValueFn::new(
+                    Box::new(Literal::from(
+                        r#""first name"=>john || note=>"says \"a || b\"""#,
+                    )),
+                    Some("=>"),
+                    Some("||"),
+                ),
+            ),
+            (
+                map![],
+                Err("function call error: delimiters must not be empty".into()),
+                ParseKeyValueFn::new(Box::new(Literal::from("foo=bar")), Some(""), None),
+            ),
+        ];
+
+        let mut state = remap::State::default();
+
+        for (mut object, exp, func) in cases {
+            let got = func
+                .execute(&mut state, &mut object)
+                .map_err(|e| format!("{:#}", anyhow::anyhow!(e)));
+
+            assert_eq!(got, exp);
+        }
+    }
+}
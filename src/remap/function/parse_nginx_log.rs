@@ -0,0 +1,167 @@
+use super::log_util;
+use remap::prelude::*;
+
+#[derive(Clone, Copy, Debug)]
+pub struct ParseNginxLog;
+
+impl Function for ParseNginxLog {
+    fn identifier(&self) -> &'static str {
+        "parse_nginx_log"
+    }
+
+    fn parameters(&self) -> &'static [Parameter] {
+        &[
+            Parameter {
+                keyword: "value",
+                accepts: |v| matches!(v, Value::String(_)),
+                required: true,
+            },
+            Parameter {
+                keyword: "format",
+                accepts: |v| matches!(v, Value::String(_)),
+                required: true,
+            },
+            Parameter {
+                keyword: "timestamp_format",
+                accepts: |v| matches!(v, Value::String(_)),
+                required: false,
+            },
+        ]
+    }
+
+    fn compile(&self, mut arguments: ArgumentList) -> Result<Box<dyn Expression>> {
+        let value = arguments.required_expr("value")?;
+        let format = arguments.required_expr("format")?;
+        let timestamp_format = arguments.optional_expr("timestamp_format")?;
+
+        Ok(Box::new(ParseNginxLogFn {
+            value,
+            format,
+            timestamp_format,
+        }))
+    }
+}
+
+#[derive(Debug, Clone)]
+struct ParseNginxLogFn {
+    value: Box<dyn Expression>,
+    format: Box<dyn Expression>,
+    timestamp_format: Option<Box<dyn Expression>>,
+}
+
+impl ParseNginxLogFn {
+    #[cfg(test)]
+    fn new(value: Box<dyn Expression>, format: &str) -> Self {
+        let format = Box::new(Literal::from(format));
+
+        Self {
+            value,
+            format,
+            timestamp_format: None,
+        }
+    }
+}
+
+impl Expression for ParseNginxLogFn {
+    fn execute(&self, state: &mut State, object: &mut dyn Object) -> Result<Option<Value>> {
+        let message = required!(state, object, self.value, Value::String(v) => String::from_utf8_lossy(&v).into_owned());
+        let format = required!(state, object, self.format, Value::String(v) => String::from_utf8_lossy(&v).into_owned());
+        let timestamp_format = optional!(state, object, self.timestamp_format, Value::String(v) => String::from_utf8_lossy(&v).into_owned());
+
+        let (regex, default_timestamp_format) = match format.as_str() {
+            "combined" => (
+                &*log_util::REGEX_COMBINED_LOG,
+                log_util::TIMESTAMP_FORMAT_COMMON,
+            ),
+            "error" => (
+                &*log_util::REGEX_NGINX_ERROR_LOG,
+                log_util::TIMESTAMP_FORMAT_NGINX_ERROR,
+            ),
+            format => {
+                return Err(format!(
+                    r#"unknown format "{}", expected one of "combined" or "error""#,
+                    format
+                )
+                .into())
+            }
+        };
+
+        let timestamp_format = timestamp_format
+            .as_deref()
+            .unwrap_or(default_timestamp_format);
+
+        log_util::parse_log(&message, regex, timestamp_format).map(Some)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::map;
+    use chrono::prelude::*;
+
+    #[test]
+    fn parse_nginx_log() {
+        let cases = vec![
+            (
+                map![],
+                Ok(Some(
+                    map![
+                        "host": "172.17.0.1",
+                        "timestamp": Utc.ymd(2019, 2, 14).and_hms(12, 30, 3),
+                        "message": "GET /favicon.ico HTTP/1.1",
+                        "method": "GET",
+                        "path": "/favicon.ico",
+                        "protocol": "HTTP/1.1",
+                        "status": 404,
+                        "size": 153,
+                        "referrer": "http://localhost/",
+                        "agent": "Mozilla/5.0 (X11; Linux x86_64) Chrome/72.0.3626.96",
+                    ]
+                    .into(),
+                )),
+                ParseNginxLogFn::new(
+                    Box::new(Literal::from(
+                        r#"172.17.0.1 - - [14/Feb/2019:12:30:03 +0000] "GET /favicon.ico HTTP/1.1" 404 153 "http://localhost/" "Mozilla/5.0 (X11; Linux x86_64) Chrome/72.0.3626.96""#,
+                    )),
+                    "combined",
+                ),
+            ),
+            (
+                map![],
+                Ok(Some(
+                    map![
+                        "timestamp": Utc.ymd(2019, 2, 14).and_hms(12, 30, 3),
+                        "severity": "error",
+                        "pid": 10,
+                        "tid": 10,
+                        "cid": 1,
+                        "message": r#"open() "/usr/share/nginx/html/favicon.ico" failed (2: No such file or directory), client: 172.17.0.1, server: localhost"#,
+                    ]
+                    .into(),
+                )),
+                ParseNginxLogFn::new(
+                    Box::new(Literal::from(
+                        r#"2019/02/14 12:30:03 [error] 10#10: *1 open() "/usr/share/nginx/html/favicon.ico" failed (2: No such file or directory), client: 172.17.0.1, server: localhost"#,
+                    )),
+                    "error",
+                ),
+            ),
+            (
+                map![],
+                Err("function call error: failed parsing log line: no match".into()),
+                ParseNginxLogFn::new(Box::new(Literal::from("not an nginx log")), "error"),
+            ),
+        ];
+
+        let mut state = remap::State::default();
+
+        for (mut object, exp, func) in cases {
+            let got = func
+                .execute(&mut state, &mut object)
+                .map_err(|e| format!("{:#}", anyhow::anyhow!(e)));
+
+            assert_eq!(got, exp);
+        }
+    }
+}
@@ -0,0 +1,126 @@
+use regex::Regex;
+use remap::prelude::*;
+use std::collections::BTreeMap;
+
+#[derive(Clone, Copy, Debug)]
+pub struct ParseRegex;
+
+impl Function for ParseRegex {
+    fn identifier(&self) -> &'static str {
+        "parse_regex"
+    }
+
+    fn parameters(&self) -> &'static [Parameter] {
+        &[
+            Parameter {
+                keyword: "value",
+                accepts: |v| matches!(v, Value::String(_)),
+                required: true,
+            },
+            Parameter {
+                keyword: "pattern",
+                accepts: |_| true,
+                required: true,
+            },
+        ]
+    }
+
+    fn compile(&self, mut arguments: ArgumentList) -> Result<Box<dyn Expression>> {
+        let value = arguments.required_expr("value")?;
+        let pattern = arguments.required_regex("pattern")?;
+
+        Ok(Box::new(ParseRegexFn { value, pattern }))
+    }
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct ParseRegexFn {
+    value: Box<dyn Expression>,
+    pattern: Regex,
+}
+
+impl ParseRegexFn {
+    #[cfg(test)]
+    fn new(value: Box<dyn Expression>, pattern: Regex) -> Self {
+        Self { value, pattern }
+    }
+}
+
+impl Expression for ParseRegexFn {
+    fn execute(&self, state: &mut State, object: &mut dyn Object) -> Result<Option<Value>> {
+        let value = required!(state, object, self.value, Value::String(b) => String::from_utf8_lossy(&b).into_owned());
+
+        let captures = self
+            .pattern
+            .captures(&value)
+            .ok_or("could not find any pattern matches")?;
+
+        let map = self
+            .pattern
+            .capture_names()
+            .flatten()
+            .filter_map(|name| {
+                captures
+                    .name(name)
+                    .map(|capture| (name.to_owned(), capture.as_str().into()))
+            })
+            .collect::<BTreeMap<String, Value>>();
+
+        Ok(Some(map.into()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::map;
+
+    #[test]
+    fn parse_regex() {
+        let cases = vec![
+            (
+                map!["message": "5.86.210.12 - zieme4647 5667 [19/06/2019:17:20:49 -0400]"],
+                Ok(Some(
+                    map![
+                        "host": "5.86.210.12",
+                        "user": "zieme4647",
+                        "bytes_in": "5667",
+                        "timestamp": "19/06/2019:17:20:49 -0400",
+                    ]
+                    .into(),
+                )),
+                ParseRegexFn::new(
+                    Box::new(Path::from("message")),
+                    Regex::new(r#"^(?P<host>[\w\.]+) - (?P<user>[\w]+) (?P<bytes_in>[\d]+) \[(?P<timestamp>.*)\]$"#)
+                        .unwrap(),
+                ),
+            ),
+            (
+                map!["message": "first group"],
+                Ok(Some(map!["first": "first"].into())),
+                ParseRegexFn::new(
+                    Box::new(Path::from("message")),
+                    Regex::new(r#"^(?P<first>\w+) (?P<missing>\d+)?"#).unwrap(),
+                ),
+            ),
+            (
+                map!["message": "no match"],
+                Err("function call error: could not find any pattern matches".into()),
+                ParseRegexFn::new(
+                    Box::new(Path::from("message")),
+                    Regex::new(r#"^(?P<digits>\d+)$"#).unwrap(),
+                ),
+            ),
+        ];
+
+        let mut state = remap::State::default();
+
+        for (mut object, exp, func) in cases {
+            let got = func
+                .execute(&mut state, &mut object)
+                .map_err(|e| format!("{:#}", anyhow::anyhow!(e)));
+
+            assert_eq!(got, exp);
+        }
+    }
+}
@@ -0,0 +1,141 @@
+use remap::prelude::*;
+use std::collections::BTreeMap;
+use woothee::parser::Parser;
+
+#[derive(Clone, Copy, Debug)]
+pub struct ParseUserAgent;
+
+impl Function for ParseUserAgent {
+    fn identifier(&self) -> &'static str {
+        "parse_user_agent"
+    }
+
+    fn parameters(&self) -> &'static [Parameter] {
+        &[Parameter {
+            keyword: "value",
+            accepts: |v| matches!(v, Value::String(_)),
+            required: true,
+        }]
+    }
+
+    fn compile(&self, mut arguments: ArgumentList) -> Result<Box<dyn Expression>> {
+        let value = arguments.required_expr("value")?;
+
+        Ok(Box::new(ParseUserAgentFn { value }))
+    }
+}
+
+#[derive(Debug, Clone)]
+struct ParseUserAgentFn {
+    value: Box<dyn Expression>,
+}
+
+impl ParseUserAgentFn {
+    #[cfg(test)]
+    fn new(value: Box<dyn Expression>) -> Self {
+        Self { value }
+    }
+}
+
+impl Expression for ParseUserAgentFn {
+    fn execute(&self, state: &mut State, object: &mut dyn Object) -> Result<Option<Value>> {
+        let value = required!(state, object, self.value, Value::String(v) => String::from_utf8_lossy(&v).into_owned());
+
+        let known = |value: &str| match value {
+            "" | woothee::woothee::VALUE_UNKNOWN => Value::Null,
+            value => value.into(),
+        };
+
+        let mut browser = BTreeMap::new();
+        let mut os = BTreeMap::new();
+        let mut device = BTreeMap::new();
+
+        match Parser::new().parse(&value) {
+            Some(result) => {
+                browser.insert("family".to_owned(), known(result.name));
+                browser.insert("version".to_owned(), known(result.version));
+                browser.insert("vendor".to_owned(), known(result.vendor));
+                os.insert("family".to_owned(), known(result.os));
+                os.insert("version".to_owned(), known(&result.os_version));
+                device.insert("category".to_owned(), known(result.category));
+            }
+            None => {
+                browser.insert("family".to_owned(), Value::Null);
+                browser.insert("version".to_owned(), Value::Null);
+                browser.insert("vendor".to_owned(), Value::Null);
+                os.insert("family".to_owned(), Value::Null);
+                os.insert("version".to_owned(), Value::Null);
+                device.insert("category".to_owned(), Value::Null);
+            }
+        }
+
+        let mut map = BTreeMap::new();
+        map.insert("browser".to_owned(), browser.into());
+        map.insert("os".to_owned(), os.into());
+        map.insert("device".to_owned(), device.into());
+
+        Ok(Some(map.into()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::map;
+
+    #[test]
+    fn parse_user_agent() {
+        let cases = vec![
+            (
+                map!["foo": "Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/86.0.4240.75 Safari/537.36"],
+                Ok(Some(
+                    map![
+                        "browser":
+                            map![
+                                "family": "Chrome",
+                                "version": "86.0.4240.75",
+                                "vendor": "Google",
+                            ],
+                        "os": map![
+                            "family": "Windows 10",
+                            "version": "NT 10.0",
+                        ],
+                        "device":
+                            map![
+                                "category": "pc",
+                            ],
+                    ]
+                    .into(),
+                )),
+                ParseUserAgentFn::new(Box::new(Path::from("foo"))),
+            ),
+            (
+                map!["foo": "not a user agent"],
+                Ok(Some(
+                    map![
+                        "browser":
+                            map![
+                                "family": Value::Null,
+                                "version": Value::Null,
+                                "vendor": Value::Null,
+                            ],
+                        "os": map!["family": Value::Null, "version": Value::Null,],
+                        "device": map!["category": Value::Null,],
+                    ]
+                    .into(),
+                )),
+                ParseUserAgentFn::new(Box::new(Path::from("foo"))),
+            ),
+        ];
+
+        let mut state = remap::State::default();
+
+        for (mut object, exp, func) in cases {
+            let got = func
+                .execute(&mut state, &mut object)
+                .map_err(|e| format!("{:#}", anyhow::anyhow!(e)));
+
+            assert_eq!(got, exp);
+        }
+    }
+}
@@ -0,0 +1,182 @@
+use crate::dns::{self, DnsError, Resolver};
+use lru::LruCache;
+use remap::prelude::*;
+use std::net::IpAddr;
+use std::str::FromStr;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// The maximum number of addresses kept resolved per function call site.
+const CACHE_SIZE: usize = 1024;
+
+/// How long a lookup, successful or not, is reused for.
+const CACHE_TTL: Duration = Duration::from_secs(60);
+
+type Lookup = fn(IpAddr) -> std::result::Result<String, DnsError>;
+
+#[derive(Clone, Copy, Debug)]
+pub struct ReverseDns;
+
+impl Function for ReverseDns {
+    fn identifier(&self) -> &'static str {
+        "reverse_dns"
+    }
+
+    fn parameters(&self) -> &'static [Parameter] {
+        &[Parameter {
+            keyword: "value",
+            accepts: |v| matches!(v, Value::String(_)),
+            required: true,
+        }]
+    }
+
+    fn compile(&self, mut arguments: ArgumentList) -> Result<Box<dyn Expression>> {
+        let value = arguments.required_expr("value")?;
+
+        Ok(Box::new(ReverseDnsFn::new(value, |ip| {
+            Resolver.reverse_lookup(ip)
+        })))
+    }
+}
+
+#[derive(Derivative)]
+#[derivative(Debug)]
+struct ReverseDnsFn {
+    value: Box<dyn Expression>,
+
+    #[derivative(Debug = "ignore")]
+    lookup: Lookup,
+
+    /// Host names, or the reason there are none, keyed by address. Every
+    /// clone of the function keeps its own cache.
+    #[derivative(Debug = "ignore")]
+    cache: Mutex<LruCache<IpAddr, (Instant, std::result::Result<String, String>)>>,
+}
+
+impl ReverseDnsFn {
+    fn new(value: Box<dyn Expression>, lookup: Lookup) -> Self {
+        Self {
+            value,
+            lookup,
+            cache: Mutex::new(LruCache::new(CACHE_SIZE)),
+        }
+    }
+
+    /// Looks the address up unless it was looked up recently.
+    ///
+    /// The lookup blocks, so on the multi-threaded runtime the worker running
+    /// this task hands its other tasks over to another thread until it
+    /// returns.
+    fn reverse_lookup(&self, ip: IpAddr) -> std::result::Result<String, String> {
+        if let Some((resolved_at, host)) = self.cache.lock().expect("poisoned lock").get(&ip) {
+            if resolved_at.elapsed() < CACHE_TTL {
+                return host.clone();
+            }
+        }
+
+        let lookup = self.lookup;
+        let host = dns::block_in_place(|| lookup(ip)).map_err(|error| error.to_string());
+        self.cache
+            .lock()
+            .expect("poisoned lock")
+            .put(ip, (Instant::now(), host.clone()));
+        host
+    }
+}
+
+impl Clone for ReverseDnsFn {
+    fn clone(&self) -> Self {
+        Self::new(self.value.clone(), self.lookup)
+    }
+}
+
+impl Expression for ReverseDnsFn {
+    fn execute(&self, state: &mut State, object: &mut dyn Object) -> Result<Option<Value>> {
+        let value = required!(state, object, self.value, Value::String(v) => String::from_utf8_lossy(&v).into_owned());
+
+        let ip = IpAddr::from_str(&value)
+            .map_err(|error| format!("unable to parse IP address: {}", error))?;
+
+        self.reverse_lookup(ip)
+            .map(|host| Some(host.into()))
+            .map_err(Into::into)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::map;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    fn lookup(ip: IpAddr) -> std::result::Result<String, DnsError> {
+        match ip {
+            IpAddr::V4(v4) if v4.is_loopback() => Ok("localhost".to_owned()),
+            ip => Err(DnsError::NoHostname { ip }),
+        }
+    }
+
+    #[test]
+    fn reverse_dns() {
+        let cases = vec![
+            (
+                map!["foo": "127.0.0.1"],
+                Ok(Some("localhost".into())),
+                ReverseDnsFn::new(Box::new(Path::from("foo")), lookup),
+            ),
+            (
+                map!["foo": "192.0.2.1"],
+                Err("function call error: No host name found for address 192.0.2.1".to_owned()),
+                ReverseDnsFn::new(Box::new(Path::from("foo")), lookup),
+            ),
+            (
+                map!["foo": "not an ip"],
+                Err(
+                    "function call error: unable to parse IP address: invalid IP address syntax"
+                        .to_owned(),
+                ),
+                ReverseDnsFn::new(Box::new(Path::from("foo")), lookup),
+            ),
+        ];
+
+        let mut state = remap::State::default();
+
+        for (mut object, exp, func) in cases {
+            let got = func
+                .execute(&mut state, &mut object)
+                .map_err(|e| format!("{:#}", anyhow::anyhow!(e)));
+
+            assert_eq!(got, exp);
+        }
+    }
+
+    #[test]
+    fn caches_lookups() {
+        static LOOKUPS: AtomicUsize = AtomicUsize::new(0);
+
+        let func = ReverseDnsFn::new(Box::new(Path::from("foo")), |ip| {
+            LOOKUPS.fetch_add(1, Ordering::SeqCst);
+            lookup(ip)
+        });
+        let mut state = remap::State::default();
+
+        for _ in 0..3 {
+            let mut object = map!["foo": "127.0.0.1"];
+            func.execute(&mut state, &mut object).unwrap();
+        }
+
+        assert_eq!(LOOKUPS.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn reverse_dns_on_basic_scheduler() {
+        let func = ReverseDnsFn::new(Box::new(Path::from("foo")), lookup);
+        let mut state = remap::State::default();
+        let mut object = map!["foo": "127.0.0.1"];
+
+        assert_eq!(
+            func.execute(&mut state, &mut object).unwrap(),
+            Some("localhost".into())
+        );
+    }
+}
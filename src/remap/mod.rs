@@ -1,46 +1,69 @@
 pub(crate) mod function;
 
+use crate::enrichment_tables::EnrichmentTables;
 pub use function::*;
 use lazy_static::lazy_static;
 
 lazy_static! {
     // List of immutable functions that can be loaded into a remap-lang program.
-    pub(crate) static ref FUNCTIONS: Vec<Box<dyn remap::Function>> = vec![
-        Box::new(Split),
-        Box::new(ToString),
-        Box::new(ToInt),
-        Box::new(ToFloat),
-        Box::new(ToBool),
-        Box::new(ToTimestamp),
-        Box::new(Upcase),
-        Box::new(Downcase),
-        Box::new(UuidV4),
-        Box::new(Sha1),
-        Box::new(Md5),
-        Box::new(Now),
-        Box::new(FormatTimestamp),
-        Box::new(Contains),
-        Box::new(StartsWith),
-        Box::new(EndsWith),
-        Box::new(Slice),
-        Box::new(Tokenize),
-        Box::new(Sha2),
-        Box::new(Sha3),
-        Box::new(ParseDuration),
-        Box::new(FormatNumber),
-        Box::new(ParseUrl),
-        Box::new(Ceil),
-        Box::new(Floor),
-        Box::new(Round),
-        Box::new(ParseSyslog),
-        Box::new(ParseTimestamp),
-        Box::new(ParseJson),
-        Box::new(Truncate),
-        Box::new(StripWhitespace),
-        Box::new(StripAnsiEscapeCodes),
-        Box::new(Match),
-        Box::new(Replace),
-    ];
+    pub(crate) static ref FUNCTIONS: Vec<Box<dyn remap::Function>> = {
+        let mut vec: Vec<Box<dyn remap::Function>> = vec![
+            Box::new(Split),
+            Box::new(ToString),
+            Box::new(ToInt),
+            Box::new(ToFloat),
+            Box::new(ToBool),
+            Box::new(ToTimestamp),
+            Box::new(Upcase),
+            Box::new(Downcase),
+            Box::new(UuidV4),
+            Box::new(Sha1),
+            Box::new(Md5),
+            Box::new(Now),
+            Box::new(FormatTimestamp),
+            Box::new(Contains),
+            Box::new(StartsWith),
+            Box::new(EndsWith),
+            Box::new(Slice),
+            Box::new(Tokenize),
+            Box::new(Sha2),
+            Box::new(Sha3),
+            Box::new(ParseDuration),
+            Box::new(FormatNumber),
+            Box::new(ParseUrl),
+            Box::new(Ceil),
+            Box::new(Floor),
+            Box::new(Round),
+            Box::new(ParseSyslog),
+            Box::new(ParseTimestamp),
+            Box::new(ParseJson),
+            Box::new(Truncate),
+            Box::new(StripWhitespace),
+            Box::new(StripAnsiEscapeCodes),
+            Box::new(Match),
+            Box::new(Replace),
+            Box::new(ParseKeyValue),
+            Box::new(ParseRegex),
+            Box::new(ParseCommonLog),
+            Box::new(ParseApacheLog),
+            Box::new(ParseNginxLog),
+            Box::new(EncodeBase64),
+            Box::new(DecodeBase64),
+            Box::new(EncodeJson),
+            Box::new(IpCidrContains),
+            Box::new(IpSubnet),
+            Box::new(IpToIpv6),
+            Box::new(IsPrivateIp),
+            Box::new(ReverseDns),
+            Box::new(ParseUserAgent),
+        ];
+
+        // Grok patterns are only available when built with the `grok` feature.
+        #[cfg(feature = "grok")]
+        vec.push(Box::new(ParseGrok));
+
+        vec
+    };
 
     // List of both mutable, and immutable functions that can be loaded into a
     // remap-lang program.
@@ -51,3 +74,11 @@ lazy_static! {
         vec
     };
 }
+
+/// The mutable and immutable functions, along with lookups in the given
+/// enrichment tables.
+pub(crate) fn functions_with_tables(tables: &EnrichmentTables) -> Vec<Box<dyn remap::Function>> {
+    let mut functions = FUNCTIONS_MUT.clone();
+    functions.push(Box::new(GetEnrichmentTableRecord::new(tables.clone())));
+    functions
+}
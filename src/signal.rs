@@ -1,15 +1,28 @@
+use crate::config::Config;
 use futures::Stream;
+use tokio::sync::oneshot;
 
-#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+#[derive(Debug)]
 pub enum SignalTo {
     /// Signal to reload config.
     Reload,
+    /// Reload config as requested through the API.
+    ReloadFromApi(ReloadRequest),
     /// Signal to shutdown process.
     Shutdown,
     /// Shutdown process immediately.
     Quit,
 }
 
+/// A request to reload the config, answered with the errors that prevented
+/// it from being applied.
+#[derive(Debug)]
+pub struct ReloadRequest {
+    /// The config to apply, or `None` to load it from the config paths again.
+    pub config: Option<Config>,
+    pub reply: oneshot::Sender<Result<(), Vec<String>>>,
+}
+
 /// Signals from OS/user.
 #[cfg(unix)]
 pub fn signals() -> impl Stream<Item = SignalTo> {
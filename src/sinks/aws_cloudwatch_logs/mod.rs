@@ -1,13 +1,15 @@
 mod request;
 
 use crate::{
-    config::{log_schema, DataType, GenerateConfig, SinkConfig, SinkContext, SinkDescription},
+    config::{
+        log_schema, DataType, GenerateConfig, ProxyConfig, SinkConfig, SinkContext, SinkDescription,
+    },
     event::{Event, LogEvent, Value},
     rusoto::{self, RegionOrEndpoint},
     sinks::util::{
         encoding::{EncodingConfig, EncodingConfiguration},
         retries::{FixedRetryPolicy, RetryLogic},
-        BatchConfig, BatchSettings, Compression, EncodedLength, PartitionBatchSink,
+        BatchConfig, BatchSettings, Compression, EncodedLength, EncodedSink, PartitionBatchSink,
         PartitionBuffer, PartitionInnerBuffer, TowerRequestConfig, TowerRequestSettings, VecBuffer,
     },
     template::Template,
@@ -17,9 +19,9 @@ use futures::{
     future::{BoxFuture, FutureExt, TryFutureExt},
     ready,
 };
-use futures01::{stream::iter_ok, Sink};
+use futures01::Sink;
 use lazy_static::lazy_static;
-use rusoto_core::{request::BufferedHttpResponse, RusotoError};
+use rusoto_core::{encoding::ContentEncoding, request::BufferedHttpResponse, RusotoError};
 use rusoto_logs::{
     CloudWatchLogs, CloudWatchLogsClient, CreateLogGroupError, CreateLogStreamError,
     DescribeLogGroupsRequest, DescribeLogStreamsError, InputLogEvent, PutLogEventsError,
@@ -28,7 +30,7 @@ use serde::{Deserialize, Serialize};
 use snafu::Snafu;
 use std::{
     collections::HashMap,
-    convert::TryInto,
+    convert::{TryFrom, TryInto},
     fmt,
     task::{Context, Poll},
 };
@@ -158,13 +160,14 @@ pub enum CloudwatchError {
 }
 
 impl CloudwatchLogsSinkConfig {
-    fn create_client(&self) -> crate::Result<CloudWatchLogsClient> {
+    fn create_client(&self, proxy: &ProxyConfig) -> crate::Result<CloudWatchLogsClient> {
         let region = (&self.region).try_into()?;
 
-        let client = rusoto::client()?;
+        let client = rusoto::client(proxy)?;
         let creds = rusoto::AwsCredentialsProvider::new(&region, self.assume_role.clone())?;
 
-        let client = rusoto_core::Client::new_with_encoding(creds, client, self.compression.into());
+        let encoding = ContentEncoding::try_from(self.compression)?;
+        let client = rusoto_core::Client::new_with_encoding(creds, client, encoding);
         Ok(CloudWatchLogsClient::new_with_client(client, region))
     }
 }
@@ -186,7 +189,7 @@ impl SinkConfig for CloudwatchLogsSinkConfig {
         let log_group = self.group_name.clone();
         let log_stream = self.stream_name.clone();
 
-        let client = self.create_client()?;
+        let client = self.create_client(cx.proxy())?;
         let svc = ServiceBuilder::new()
             .concurrency_limit(request.in_flight_limit.unwrap())
             .service(CloudwatchLogsPartitionSvc::new(
@@ -197,11 +200,14 @@ impl SinkConfig for CloudwatchLogsSinkConfig {
         let encoding = self.encoding.clone();
         let sink = {
             let buffer = PartitionBuffer::new(VecBuffer::new(batch.size));
-            let svc_sink = PartitionBatchSink::new(svc, buffer, batch.timeout, cx.acker())
-                .sink_map_err(|error| error!(message = "Fatal cloudwatchlogs sink error.", %error))
-                .with_flat_map(move |event| {
-                    iter_ok(partition_encode(event, &encoding, &log_group, &log_stream))
-                });
+            let svc_sink =
+                PartitionBatchSink::new(svc, buffer, batch.timeout, cx.acker(), cx.flush_signal())
+                    .encode_events(move |event| {
+                        partition_encode(event, &encoding, &log_group, &log_stream)
+                    })
+                    .sink_map_err(
+                        |error| error!(message = "Fatal cloudwatchlogs sink error.", %error),
+                    );
             Box::new(svc_sink)
         };
 
@@ -686,6 +692,26 @@ mod tests {
         crate::test_util::test_generate_config::<CloudwatchLogsSinkConfig>();
     }
 
+    #[tokio::test]
+    async fn rejects_unsupported_compression() {
+        let config: CloudwatchLogsSinkConfig = toml::from_str(
+            r#"
+            group_name = "vector"
+            stream_name = "test"
+            endpoint = "http://localhost:4566"
+            encoding = "json"
+            compression = "zstd"
+            "#,
+        )
+        .unwrap();
+
+        let error = config.build(SinkContext::new_test()).await.err().unwrap();
+        assert_eq!(
+            error.to_string(),
+            "Rusoto can't compress requests with zstd."
+        );
+    }
+
     #[test]
     fn partition_static() {
         let event = Event::from("hello world");
@@ -793,7 +819,7 @@ mod tests {
             stream: "stream".into(),
             group: "group".into(),
         };
-        let client = config.create_client().unwrap();
+        let client = config.create_client(&ProxyConfig::default()).unwrap();
         CloudwatchLogsSvc::new(&config, &key, client)
     }
 
@@ -1247,7 +1273,7 @@ mod integration_tests {
             assume_role: None,
         };
 
-        let client = config.create_client().unwrap();
+        let client = config.create_client(&ProxyConfig::default()).unwrap();
         healthcheck(config, client).await.unwrap();
     }
 
@@ -1257,7 +1283,7 @@ mod integration_tests {
             endpoint: "http://localhost:6000".into(),
         };
 
-        let client = rusoto::client().unwrap();
+        let client = rusoto::client(&ProxyConfig::default()).unwrap();
         let creds = rusoto::AwsCredentialsProvider::new(&region, None).unwrap();
         CloudWatchLogsClient::new_with(client, creds, region)
     }
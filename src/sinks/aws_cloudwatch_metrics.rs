@@ -1,5 +1,5 @@
 use crate::{
-    config::{DataType, SinkConfig, SinkContext, SinkDescription},
+    config::{DataType, ProxyConfig, SinkConfig, SinkContext, SinkDescription},
     event::{
         metric::{Metric, MetricKind, MetricValue},
         Event,
@@ -17,10 +17,10 @@ use lazy_static::lazy_static;
 use rusoto_cloudwatch::{
     CloudWatch, CloudWatchClient, Dimension, MetricDatum, PutMetricDataError, PutMetricDataInput,
 };
-use rusoto_core::{Region, RusotoError};
+use rusoto_core::{encoding::ContentEncoding, Region, RusotoError};
 use serde::{Deserialize, Serialize};
 use std::collections::BTreeMap;
-use std::convert::TryInto;
+use std::convert::{TryFrom, TryInto};
 use std::task::{Context, Poll};
 use tower::Service;
 
@@ -66,7 +66,7 @@ impl SinkConfig for CloudWatchMetricsSinkConfig {
         &self,
         cx: SinkContext,
     ) -> crate::Result<(super::VectorSink, super::Healthcheck)> {
-        let client = self.create_client()?;
+        let client = self.create_client(cx.proxy())?;
         let healthcheck = self.clone().healthcheck(client.clone()).boxed();
         let sink = CloudWatchMetricsSvc::new(self.clone(), client, cx)?;
         Ok((sink, healthcheck))
@@ -96,7 +96,7 @@ impl CloudWatchMetricsSinkConfig {
         client.put_metric_data(request).await.map_err(Into::into)
     }
 
-    fn create_client(&self) -> crate::Result<CloudWatchClient> {
+    fn create_client(&self, proxy: &ProxyConfig) -> crate::Result<CloudWatchClient> {
         let region = (&self.region).try_into()?;
         let region = if cfg!(test) {
             // Moto (used for mocking AWS) doesn't recognize 'custom' as valid region name
@@ -111,10 +111,11 @@ impl CloudWatchMetricsSinkConfig {
             region
         };
 
-        let client = rusoto::client()?;
+        let client = rusoto::client(proxy)?;
         let creds = rusoto::AwsCredentialsProvider::new(&region, self.assume_role.clone())?;
 
-        let client = rusoto_core::Client::new_with_encoding(creds, client, self.compression.into());
+        let encoding = ContentEncoding::try_from(self.compression)?;
+        let client = rusoto_core::Client::new_with_encoding(creds, client, encoding);
         Ok(CloudWatchClient::new_with_client(client, region))
     }
 }
@@ -130,7 +131,10 @@ impl CloudWatchMetricsSvc {
             .events(20)
             .timeout(1)
             .parse_config(config.batch)?;
-        let request = config.request.unwrap_with(&REQUEST_DEFAULTS);
+        let request = config
+            .request
+            .unwrap_with(&REQUEST_DEFAULTS)
+            .when_full(cx.when_full());
 
         let cloudwatch_metrics = CloudWatchMetricsSvc { client, config };
 
@@ -138,16 +142,19 @@ impl CloudWatchMetricsSvc {
 
         let buffer = PartitionBuffer::new(MetricBuffer::new(batch.size));
 
-        let sink = PartitionBatchSink::new(svc, buffer, batch.timeout, cx.acker())
-            .sink_map_err(|error| error!(message = "Fatal CloudwatchMetrics sink error.", %error))
-            .with_flat_map(move |mut event: Event| {
-                let namespace = event
-                    .as_mut_metric()
-                    .namespace
-                    .take()
-                    .unwrap_or_else(|| default_namespace.clone());
-                iter_ok(Some(PartitionInnerBuffer::new(event, namespace)))
-            });
+        let sink =
+            PartitionBatchSink::new(svc, buffer, batch.timeout, cx.acker(), cx.flush_signal())
+                .sink_map_err(
+                    |error| error!(message = "Fatal CloudwatchMetrics sink error.", %error),
+                )
+                .with_flat_map(move |mut event: Event| {
+                    let namespace = event
+                        .as_mut_metric()
+                        .namespace
+                        .take()
+                        .unwrap_or_else(|| default_namespace.clone());
+                    iter_ok(Some(PartitionInnerBuffer::new(event, namespace)))
+                });
 
         Ok(super::VectorSink::Futures01Sink(Box::new(sink)))
     }
@@ -281,6 +288,24 @@ mod tests {
         crate::test_util::test_generate_config::<CloudWatchMetricsSinkConfig>();
     }
 
+    #[tokio::test]
+    async fn rejects_unsupported_compression() {
+        let config: CloudWatchMetricsSinkConfig = toml::from_str(
+            r#"
+            default_namespace = "vector"
+            endpoint = "http://localhost:4566"
+            compression = "zstd"
+            "#,
+        )
+        .unwrap();
+
+        let error = config.build(SinkContext::new_test()).await.err().unwrap();
+        assert_eq!(
+            error.to_string(),
+            "Rusoto can't compress requests with zstd."
+        );
+    }
+
     fn config() -> CloudWatchMetricsSinkConfig {
         CloudWatchMetricsSinkConfig {
             default_namespace: "vector".into(),
@@ -291,7 +316,7 @@ mod tests {
 
     fn svc() -> CloudWatchMetricsSvc {
         let config = config();
-        let client = config.create_client().unwrap();
+        let client = config.create_client(&ProxyConfig::default()).unwrap();
         CloudWatchMetricsSvc { client, config }
     }
 
@@ -452,7 +477,7 @@ mod integration_tests {
     #[tokio::test]
     async fn cloudwatch_metrics_healthchecks() {
         let config = config();
-        let client = config.create_client().unwrap();
+        let client = config.create_client(&ProxyConfig::default()).unwrap();
         config.healthcheck(client).await.unwrap();
     }
 
@@ -460,7 +485,7 @@ mod integration_tests {
     async fn cloudwatch_metrics_put_data() {
         let cx = SinkContext::new_test();
         let config = config();
-        let client = config.create_client().unwrap();
+        let client = config.create_client(&ProxyConfig::default()).unwrap();
         let sink = CloudWatchMetricsSvc::new(config, client, cx).unwrap();
 
         let mut events = Vec::new();
@@ -523,7 +548,7 @@ mod integration_tests {
     async fn cloudwatch_metrics_namespace_partitioning() {
         let cx = SinkContext::new_test();
         let config = config();
-        let client = config.create_client().unwrap();
+        let client = config.create_client(&ProxyConfig::default()).unwrap();
         let sink = CloudWatchMetricsSvc::new(config, client, cx).unwrap();
 
         let mut events = Vec::new();
@@ -1,19 +1,20 @@
 use crate::{
-    config::{DataType, GenerateConfig, SinkConfig, SinkContext, SinkDescription},
+    config::{DataType, GenerateConfig, ProxyConfig, SinkConfig, SinkContext, SinkDescription},
     event::Event,
     rusoto::{self, RegionOrEndpoint},
     sinks::util::{
         encoding::{EncodingConfig, EncodingConfiguration},
         retries::RetryLogic,
         sink::Response,
-        BatchConfig, BatchSettings, Compression, EncodedLength, TowerRequestConfig, VecBuffer,
+        BatchConfig, BatchSettings, Compression, EncodedLength, EncodedSink, TowerRequestConfig,
+        VecBuffer,
     },
 };
 use bytes::Bytes;
 use futures::{future::BoxFuture, FutureExt};
-use futures01::{stream::iter_ok, Sink};
+use futures01::Sink;
 use lazy_static::lazy_static;
-use rusoto_core::RusotoError;
+use rusoto_core::{encoding::ContentEncoding, RusotoError};
 use rusoto_firehose::{
     DescribeDeliveryStreamError, DescribeDeliveryStreamInput, KinesisFirehose,
     KinesisFirehoseClient, PutRecordBatchError, PutRecordBatchInput, PutRecordBatchOutput, Record,
@@ -21,7 +22,7 @@ use rusoto_firehose::{
 use serde::{Deserialize, Serialize};
 use snafu::Snafu;
 use std::{
-    convert::TryInto,
+    convert::{TryFrom, TryInto},
     fmt,
     task::{Context, Poll},
 };
@@ -87,7 +88,7 @@ impl SinkConfig for KinesisFirehoseSinkConfig {
         &self,
         cx: SinkContext,
     ) -> crate::Result<(super::VectorSink, super::Healthcheck)> {
-        let client = self.create_client()?;
+        let client = self.create_client(cx.proxy())?;
         let healthcheck = self.clone().healthcheck(client.clone()).boxed();
         let sink = KinesisFirehoseService::new(self.clone(), client, cx)?;
         Ok((
@@ -128,13 +129,14 @@ impl KinesisFirehoseSinkConfig {
         }
     }
 
-    fn create_client(&self) -> crate::Result<KinesisFirehoseClient> {
+    fn create_client(&self, proxy: &ProxyConfig) -> crate::Result<KinesisFirehoseClient> {
         let region = (&self.region).try_into()?;
 
-        let client = rusoto::client()?;
+        let client = rusoto::client(proxy)?;
         let creds = rusoto::AwsCredentialsProvider::new(&region, self.assume_role.clone())?;
 
-        let client = rusoto_core::Client::new_with_encoding(creds, client, self.compression.into());
+        let encoding = ContentEncoding::try_from(self.compression)?;
+        let client = rusoto_core::Client::new_with_encoding(creds, client, encoding);
         Ok(KinesisFirehoseClient::new_with_client(client, region))
     }
 }
@@ -150,7 +152,10 @@ impl KinesisFirehoseService {
             .events(500)
             .timeout(1)
             .parse_config(config.batch)?;
-        let request = config.request.unwrap_with(&REQUEST_DEFAULTS);
+        let request = config
+            .request
+            .unwrap_with(&REQUEST_DEFAULTS)
+            .when_full(cx.when_full());
         let encoding = config.encoding.clone();
 
         let kinesis = KinesisFirehoseService { client, config };
@@ -162,9 +167,10 @@ impl KinesisFirehoseService {
                 VecBuffer::new(batch.size),
                 batch.timeout,
                 cx.acker(),
+                cx.flush_signal(),
             )
-            .sink_map_err(|error| error!(message = "Fatal kinesis firehose sink error.", %error))
-            .with_flat_map(move |e| iter_ok(encode_event(e, &encoding)));
+            .encode_events(move |e| encode_event(e, &encoding))
+            .sink_map_err(|error| error!(message = "Fatal kinesis firehose sink error.", %error));
 
         Ok(sink)
     }
@@ -272,6 +278,25 @@ mod tests {
         crate::test_util::test_generate_config::<KinesisFirehoseSinkConfig>();
     }
 
+    #[tokio::test]
+    async fn rejects_unsupported_compression() {
+        let config: KinesisFirehoseSinkConfig = toml::from_str(
+            r#"
+            stream_name = "test"
+            endpoint = "http://localhost:4566"
+            encoding = "json"
+            compression = "zstd"
+            "#,
+        )
+        .unwrap();
+
+        let error = config.build(SinkContext::new_test()).await.err().unwrap();
+        assert_eq!(
+            error.to_string(),
+            "Rusoto can't compress requests with zstd."
+        );
+    }
+
     #[test]
     fn firehose_encode_event_text() {
         let message = "hello world".to_string();
@@ -353,7 +378,7 @@ mod integration_tests {
 
         let cx = SinkContext::new_test();
 
-        let client = config.create_client().unwrap();
+        let client = config.create_client(&ProxyConfig::default()).unwrap();
         let sink = KinesisFirehoseService::new(config, client, cx).unwrap();
 
         let (input, events) = random_events_with_stream(100, 100);
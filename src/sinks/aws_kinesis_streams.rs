@@ -1,5 +1,7 @@
 use crate::{
-    config::{log_schema, DataType, GenerateConfig, SinkConfig, SinkContext, SinkDescription},
+    config::{
+        log_schema, DataType, GenerateConfig, ProxyConfig, SinkConfig, SinkContext, SinkDescription,
+    },
     event::Event,
     internal_events::AwsKinesisStreamsEventSent,
     rusoto::{self, RegionOrEndpoint},
@@ -7,15 +9,16 @@ use crate::{
         encoding::{EncodingConfig, EncodingConfiguration},
         retries::RetryLogic,
         sink::Response,
-        BatchConfig, BatchSettings, Compression, EncodedLength, TowerRequestConfig, VecBuffer,
+        BatchConfig, BatchSettings, Compression, EncodedLength, EncodedSink, TowerRequestConfig,
+        VecBuffer,
     },
 };
 use bytes::Bytes;
 use futures::{future::BoxFuture, FutureExt, TryFutureExt};
-use futures01::{stream::iter_ok, Sink};
+use futures01::Sink;
 use lazy_static::lazy_static;
 use rand::random;
-use rusoto_core::RusotoError;
+use rusoto_core::{encoding::ContentEncoding, RusotoError};
 use rusoto_kinesis::{
     DescribeStreamInput, Kinesis, KinesisClient, PutRecordsError, PutRecordsInput,
     PutRecordsOutput, PutRecordsRequestEntry,
@@ -23,7 +26,7 @@ use rusoto_kinesis::{
 use serde::{Deserialize, Serialize};
 use snafu::Snafu;
 use std::{
-    convert::TryInto,
+    convert::{TryFrom, TryInto},
     fmt,
     task::{Context, Poll},
 };
@@ -90,7 +93,7 @@ impl SinkConfig for KinesisSinkConfig {
         &self,
         cx: SinkContext,
     ) -> crate::Result<(super::VectorSink, super::Healthcheck)> {
-        let client = self.create_client()?;
+        let client = self.create_client(cx.proxy())?;
         let healthcheck = self.clone().healthcheck(client.clone()).boxed();
         let sink = KinesisService::new(self.clone(), client, cx)?;
         Ok((
@@ -131,13 +134,14 @@ impl KinesisSinkConfig {
         }
     }
 
-    fn create_client(&self) -> crate::Result<KinesisClient> {
+    fn create_client(&self, proxy: &ProxyConfig) -> crate::Result<KinesisClient> {
         let region = (&self.region).try_into()?;
 
-        let client = rusoto::client()?;
+        let client = rusoto::client(proxy)?;
         let creds = rusoto::AwsCredentialsProvider::new(&region, self.assume_role.clone())?;
 
-        let client = rusoto_core::Client::new_with_encoding(creds, client, self.compression.into());
+        let encoding = ContentEncoding::try_from(self.compression)?;
+        let client = rusoto_core::Client::new_with_encoding(creds, client, encoding);
         Ok(KinesisClient::new_with_client(client, region))
     }
 }
@@ -153,7 +157,10 @@ impl KinesisService {
             .events(500)
             .timeout(1)
             .parse_config(config.batch)?;
-        let request = config.request.unwrap_with(&REQUEST_DEFAULTS);
+        let request = config
+            .request
+            .unwrap_with(&REQUEST_DEFAULTS)
+            .when_full(cx.when_full());
         let encoding = config.encoding.clone();
         let partition_key_field = config.partition_key_field.clone();
 
@@ -166,9 +173,10 @@ impl KinesisService {
                 VecBuffer::new(batch.size),
                 batch.timeout,
                 cx.acker(),
+                cx.flush_signal(),
             )
-            .sink_map_err(|error| error!(message = "Fatal kinesis streams sink error.", %error))
-            .with_flat_map(move |e| iter_ok(encode_event(e, &partition_key_field, &encoding)));
+            .encode_events(move |e| encode_event(e, &partition_key_field, &encoding))
+            .sink_map_err(|error| error!(message = "Fatal kinesis streams sink error.", %error));
 
         Ok(sink)
     }
@@ -331,6 +339,25 @@ mod tests {
         crate::test_util::test_generate_config::<KinesisSinkConfig>();
     }
 
+    #[tokio::test]
+    async fn rejects_unsupported_compression() {
+        let config: KinesisSinkConfig = toml::from_str(
+            r#"
+            stream_name = "test"
+            endpoint = "http://localhost:4566"
+            encoding = "json"
+            compression = "zstd"
+            "#,
+        )
+        .unwrap();
+
+        let error = config.build(SinkContext::new_test()).await.err().unwrap();
+        assert_eq!(
+            error.to_string(),
+            "Rusoto can't compress requests with zstd."
+        );
+    }
+
     #[test]
     fn kinesis_encode_event_text() {
         let message = "hello world".to_string();
@@ -430,7 +457,7 @@ mod integration_tests {
 
         let cx = SinkContext::new_test();
 
-        let client = config.create_client().unwrap();
+        let client = config.create_client(&ProxyConfig::default()).unwrap();
         let sink = KinesisService::new(config, client, cx).unwrap();
 
         let timestamp = chrono::Utc::now().timestamp_millis();
@@ -1,21 +1,25 @@
+#[cfg(feature = "parquet")]
+use crate::sinks::util::buffer::parquet::ParquetBuffer;
 use crate::{
-    config::{log_schema, DataType, SinkConfig, SinkContext, SinkDescription},
+    config::{DataType, ProxyConfig, SinkConfig, SinkContext, SinkDescription},
     event::Event,
     rusoto::{self, RegionOrEndpoint},
     serde::to_string,
     sinks::util::{
-        encoding::{EncodingConfigWithDefault, EncodingConfiguration},
+        buffer::parquet::ParquetConfig,
+        encoding::{Encoder, EncodingConfigWithDefault, EncodingConfiguration, StandardEncodings},
         retries::RetryLogic,
         sink::Response,
-        BatchConfig, BatchSettings, Buffer, Compression, InFlightLimit, PartitionBatchSink,
-        PartitionBuffer, PartitionInnerBuffer, ServiceBuilderExt, TowerRequestConfig,
+        BatchConfig, BatchSettings, Buffer, Compression, EncodedSink, InFlightLimit,
+        PartitionBatchSink, PartitionBuffer, PartitionInnerBuffer, ServiceBuilderExt,
+        TowerRequestConfig,
     },
     template::Template,
 };
 use bytes::Bytes;
 use chrono::Utc;
 use futures::{future::BoxFuture, FutureExt};
-use futures01::{stream::iter_ok, Sink};
+use futures01::Sink;
 use http::StatusCode;
 use lazy_static::lazy_static;
 use rusoto_core::RusotoError;
@@ -26,6 +30,7 @@ use serde::{Deserialize, Serialize};
 use snafu::Snafu;
 use std::collections::BTreeMap;
 use std::convert::{TryFrom, TryInto};
+use std::sync::Arc;
 use std::task::Context;
 use std::task::Poll;
 
@@ -54,7 +59,9 @@ pub struct S3SinkConfig {
         skip_serializing_if = "crate::serde::skip_serializing_if_default",
         default
     )]
-    pub encoding: EncodingConfigWithDefault<Encoding>,
+    pub encoding: EncodingConfigWithDefault<StandardEncodings>,
+    /// Writes objects as Parquet files, instead of encoding each event.
+    pub parquet: Option<ParquetConfig>,
     #[serde(default = "Compression::gzip_default")]
     pub compression: Compression,
     #[serde(default)]
@@ -124,15 +131,6 @@ lazy_static! {
     };
 }
 
-#[derive(Deserialize, Serialize, Debug, Eq, PartialEq, Clone, Derivative)]
-#[serde(rename_all = "snake_case")]
-#[derivative(Default)]
-pub enum Encoding {
-    #[derivative(Default)]
-    Text,
-    Ndjson,
-}
-
 inventory::submit! {
     SinkDescription::new::<S3SinkConfig>("aws_s3")
 }
@@ -146,7 +144,7 @@ impl SinkConfig for S3SinkConfig {
         &self,
         cx: SinkContext,
     ) -> crate::Result<(super::VectorSink, super::Healthcheck)> {
-        let client = self.create_client()?;
+        let client = self.create_client(cx.proxy())?;
         let healthcheck = self.clone().healthcheck(client.clone()).boxed();
         let sink = self.new(client, cx)?;
         Ok((sink, healthcheck))
@@ -173,28 +171,46 @@ enum HealthcheckError {
 
 impl S3SinkConfig {
     pub fn new(&self, client: S3Client, cx: SinkContext) -> crate::Result<super::VectorSink> {
-        let request = self.request.unwrap_with(&REQUEST_DEFAULTS);
-        let encoding = self.encoding.clone();
-
-        let compression = self.compression;
+        let request = self
+            .request
+            .unwrap_with(&REQUEST_DEFAULTS)
+            .when_full(cx.when_full());
+        let encoding = self.encoding.loaded()?;
+
+        let parquet = self
+            .parquet
+            .as_ref()
+            .map(ParquetConfig::build)
+            .transpose()?
+            .map(Arc::new);
+
+        // Parquet files compress their columns themselves.
+        let compression = match parquet {
+            Some(_) => Compression::None,
+            None => self.compression,
+        };
         let filename_time_format = self
             .filename_time_format
             .clone()
             .unwrap_or_else(|| "%s".into());
         let filename_append_uuid = self.filename_append_uuid.unwrap_or(true);
-        let batch = BatchSettings::default()
-            .bytes(10_000_000)
-            .timeout(300)
-            .parse_config(self.batch)?;
 
         let key_prefix = self.key_prefix.as_deref().unwrap_or("date=%F/");
         let key_prefix = Template::try_from(key_prefix)?;
 
         let s3 = S3Sink { client };
 
-        let filename_extension = self.filename_extension.clone();
+        let filename_extension = self
+            .filename_extension
+            .clone()
+            .or_else(|| parquet.as_ref().map(|_| "parquet".into()));
         let bucket = self.bucket.clone();
-        let options = self.options.clone();
+        let mut options = self.options.clone();
+        if parquet.is_some() {
+            options
+                .content_type
+                .get_or_insert_with(|| "application/octet-stream".into());
+        }
 
         let svc = ServiceBuilder::new()
             .map(move |req| {
@@ -211,13 +227,51 @@ impl S3SinkConfig {
             .settings(request, S3RetryLogic)
             .service(s3);
 
-        let buffer = PartitionBuffer::new(Buffer::new(batch.size, self.compression));
-
-        let sink = PartitionBatchSink::new(svc, buffer, batch.timeout, cx.acker())
-            .with_flat_map(move |e| iter_ok(encode_event(e, &key_prefix, &encoding)))
-            .sink_map_err(|error| error!(message = "Sink failed to flush.", %error));
+        let sink: Box<dyn Sink<SinkItem = Event, SinkError = ()> + Send> = match parquet {
+            #[cfg(feature = "parquet")]
+            Some(schema) => {
+                let batch = BatchSettings::default()
+                    .bytes(10_000_000)
+                    .timeout(300)
+                    .parse_config(self.batch)?;
+                let buffer = PartitionBuffer::new(ParquetBuffer::new(batch.size, schema));
+
+                Box::new(
+                    PartitionBatchSink::new(
+                        svc,
+                        buffer,
+                        batch.timeout,
+                        cx.acker(),
+                        cx.flush_signal(),
+                    )
+                    .encode_events(move |e| partition_event(e, &key_prefix, &encoding))
+                    .sink_map_err(|error| error!(message = "Sink failed to flush.", %error)),
+                )
+            }
+            #[cfg(not(feature = "parquet"))]
+            Some(_) => unreachable!("Parquet configs fail to build without the `parquet` feature"),
+            None => {
+                let batch = BatchSettings::default()
+                    .bytes(10_000_000)
+                    .timeout(300)
+                    .parse_config(self.batch)?;
+                let buffer = PartitionBuffer::new(Buffer::new(batch.size, compression));
+
+                Box::new(
+                    PartitionBatchSink::new(
+                        svc,
+                        buffer,
+                        batch.timeout,
+                        cx.acker(),
+                        cx.flush_signal(),
+                    )
+                    .encode_events(move |e| encode_event(e, &key_prefix, &encoding))
+                    .sink_map_err(|error| error!(message = "Sink failed to flush.", %error)),
+                )
+            }
+        };
 
-        Ok(super::VectorSink::Futures01Sink(Box::new(sink)))
+        Ok(super::VectorSink::Futures01Sink(sink))
     }
 
     pub async fn healthcheck(self, client: S3Client) -> crate::Result<()> {
@@ -241,9 +295,9 @@ impl S3SinkConfig {
         }
     }
 
-    pub fn create_client(&self) -> crate::Result<S3Client> {
+    pub fn create_client(&self, proxy: &ProxyConfig) -> crate::Result<S3Client> {
         let region = (&self.region).try_into()?;
-        let client = rusoto::client()?;
+        let client = rusoto::client(proxy)?;
 
         let creds = rusoto::AwsCredentialsProvider::new(&region, self.assume_role.clone())?;
 
@@ -377,13 +431,9 @@ impl RetryLogic for S3RetryLogic {
     }
 }
 
-fn encode_event(
-    mut event: Event,
-    key_prefix: &Template,
-    encoding: &EncodingConfigWithDefault<Encoding>,
-) -> Option<PartitionInnerBuffer<Vec<u8>, Bytes>> {
-    let key = key_prefix
-        .render_string(&event)
+fn render_key(event: &Event, key_prefix: &Template) -> Option<Bytes> {
+    key_prefix
+        .render(event)
         .map_err(|missing_keys| {
             warn!(
                 message = "Keys do not exist on the event; dropping event.",
@@ -391,35 +441,51 @@ fn encode_event(
                 rate_limit_secs = 30,
             );
         })
+        .ok()
+}
+
+fn encode_event(
+    event: Event,
+    key_prefix: &Template,
+    encoding: &EncodingConfigWithDefault<StandardEncodings>,
+) -> Option<PartitionInnerBuffer<Vec<u8>, Bytes>> {
+    let key = render_key(&event, key_prefix)?;
+
+    let bytes = encoding
+        .encode(event)
+        .map_err(|error| {
+            warn!(
+                message = "Failed to encode event; dropping event.",
+                %error,
+                rate_limit_secs = 30,
+            );
+        })
         .ok()?;
 
-    encoding.apply_rules(&mut event);
+    Some(PartitionInnerBuffer::new(
+        encoding.codec().frame(bytes),
+        key,
+    ))
+}
 
-    let log = event.into_log();
-    let bytes = match encoding.codec() {
-        Encoding::Ndjson => serde_json::to_vec(&log)
-            .map(|mut b| {
-                b.push(b'\n');
-                b
-            })
-            .expect("Failed to encode event as json, this is a bug!"),
-        Encoding::Text => {
-            let mut bytes = log
-                .get(log_schema().message_key())
-                .map(|v| v.as_bytes().to_vec())
-                .unwrap_or_default();
-            bytes.push(b'\n');
-            bytes
-        }
-    };
+/// Partitions an event that is written to a Parquet file, which encodes it
+/// along with the rest of its batch.
+#[cfg(feature = "parquet")]
+fn partition_event(
+    mut event: Event,
+    key_prefix: &Template,
+    encoding: &EncodingConfigWithDefault<StandardEncodings>,
+) -> Option<PartitionInnerBuffer<Event, Bytes>> {
+    let key = render_key(&event, key_prefix)?;
+    encoding.apply_rules(&mut event);
 
-    Some(PartitionInnerBuffer::new(bytes, key.into()))
+    Some(PartitionInnerBuffer::new(event, key))
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::event::Event;
+    use crate::{config::log_schema, event::Event};
 
     use std::collections::BTreeMap;
 
@@ -435,7 +501,7 @@ mod tests {
         let bytes = encode_event(
             message.clone().into(),
             &batch_time_format,
-            &Encoding::Text.into(),
+            &StandardEncodings::Text.into(),
         )
         .unwrap();
 
@@ -451,7 +517,8 @@ mod tests {
         event.as_mut_log().insert("key", "value");
 
         let batch_time_format = Template::try_from("date=%F").unwrap();
-        let bytes = encode_event(event, &batch_time_format, &Encoding::Ndjson.into()).unwrap();
+        let bytes =
+            encode_event(event, &batch_time_format, &StandardEncodings::Ndjson.into()).unwrap();
 
         let (bytes, _) = bytes.into_parts();
         let map: BTreeMap<String, String> = serde_json::from_slice(&bytes[..]).unwrap();
@@ -469,7 +536,7 @@ mod tests {
         let key_prefix = Template::try_from("{{ key }}").unwrap();
 
         let encoding_config = EncodingConfigWithDefault {
-            codec: Encoding::Ndjson,
+            codec: StandardEncodings::Ndjson,
             except_fields: Some(vec!["key".into()]),
             ..Default::default()
         };
@@ -483,6 +550,27 @@ mod tests {
         // assert_eq!(map["key"], "value".to_string());
     }
 
+    #[cfg(feature = "parquet")]
+    #[test]
+    fn s3_partition_event_for_parquet() {
+        let mut event = Event::from("hello world");
+        event.as_mut_log().insert("key", "value");
+
+        let key_prefix = Template::try_from("{{ key }}/").unwrap();
+
+        let encoding_config = EncodingConfigWithDefault {
+            except_fields: Some(vec!["key".into()]),
+            ..Default::default()
+        };
+
+        let (event, key) = partition_event(event, &key_prefix, &encoding_config)
+            .unwrap()
+            .into_parts();
+
+        assert_eq!(key, "value/");
+        assert!(event.as_log().get("key").is_none());
+    }
+
     #[test]
     fn s3_build_request() {
         let buf = PartitionInnerBuffer::new(vec![0u8; 10], Bytes::from("key/"));
@@ -560,7 +648,7 @@ mod integration_tests {
 
         let config = config(1000000).await;
         let prefix = config.key_prefix.clone();
-        let client = config.create_client().unwrap();
+        let client = config.create_client(&ProxyConfig::default()).unwrap();
         let sink = config.new(client, cx).unwrap();
 
         let (lines, events) = random_lines_with_stream(100, 10);
@@ -590,7 +678,7 @@ mod integration_tests {
             ..config(1010).await
         };
         let prefix = config.key_prefix.clone();
-        let client = config.create_client().unwrap();
+        let client = config.create_client(&ProxyConfig::default()).unwrap();
         let sink = config.new(client, cx).unwrap();
 
         let (lines, _events) = random_lines_with_stream(100, 30);
@@ -635,7 +723,7 @@ mod integration_tests {
         };
 
         let prefix = config.key_prefix.clone();
-        let client = config.create_client().unwrap();
+        let client = config.create_client(&ProxyConfig::default()).unwrap();
         let sink = config.new(client, cx).unwrap();
 
         let (lines, events) = random_lines_with_stream(100, 500);
@@ -660,7 +748,7 @@ mod integration_tests {
     #[tokio::test]
     async fn s3_healthchecks() {
         let config = config(1).await;
-        let client = config.create_client().unwrap();
+        let client = config.create_client(&ProxyConfig::default()).unwrap();
         config.healthcheck(client).await.unwrap();
     }
 
@@ -670,7 +758,7 @@ mod integration_tests {
             bucket: "asdflkjadskdaadsfadf".to_string(),
             ..config(1).await
         };
-        let client = config.create_client().unwrap();
+        let client = config.create_client(&ProxyConfig::default()).unwrap();
         assert_downcast_matches!(
             config.healthcheck(client).await.unwrap_err(),
             HealthcheckError,
@@ -111,10 +111,13 @@ impl SinkConfig for AzureMonitorLogsConfig {
         }
 
         let tls_settings = TlsSettings::from_options(&self.tls)?;
-        let client = HttpClient::new(Some(tls_settings))?;
+        let client = HttpClient::new(Some(tls_settings), cx.proxy())?;
 
         let sink = AzureMonitorLogsSink::new(self)?;
-        let request_settings = self.request.unwrap_with(&REQUEST_DEFAULTS);
+        let request_settings = self
+            .request
+            .unwrap_with(&REQUEST_DEFAULTS)
+            .when_full(cx.when_full());
 
         let healthcheck = healthcheck(sink.clone(), client.clone()).boxed();
 
@@ -125,6 +128,7 @@ impl SinkConfig for AzureMonitorLogsConfig {
             batch_settings.timeout,
             client,
             cx.acker(),
+            cx.flush_signal(),
         )
         .sink_map_err(|error| error!(message = "Fatal azure_monitor_logs sink error.", %error));
 
@@ -77,6 +77,7 @@ impl BlackholeSink {
 impl StreamSink for BlackholeSink {
     async fn run(&mut self, mut input: BoxStream<'_, Event>) -> Result<(), ()> {
         while let Some(event) = input.next().await {
+            let ingress = event.ingress();
             let message_len = match event {
                 Event::Log(log) => serde_json::to_string(&log),
                 Event::Metric(metric) => serde_json::to_string(&metric),
@@ -98,7 +99,7 @@ impl StreamSink for BlackholeSink {
                 }, "Total events collected");
             }
 
-            self.acker.ack(1);
+            self.acker.ack_with_ingress(1, ingress);
         }
         Ok(())
     }
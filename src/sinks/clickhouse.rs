@@ -73,9 +73,12 @@ impl SinkConfig for ClickhouseConfig {
             .bytes(bytesize::mib(10u64))
             .timeout(1)
             .parse_config(self.batch)?;
-        let request = self.request.unwrap_with(&REQUEST_DEFAULTS);
+        let request = self
+            .request
+            .unwrap_with(&REQUEST_DEFAULTS)
+            .when_full(cx.when_full());
         let tls_settings = TlsSettings::from_options(&self.tls)?;
-        let client = HttpClient::new(tls_settings)?;
+        let client = HttpClient::new(tls_settings, cx.proxy())?;
 
         let sink = BatchedHttpSink::with_retry_logic(
             self.clone(),
@@ -85,6 +88,7 @@ impl SinkConfig for ClickhouseConfig {
             batch.timeout,
             client.clone(),
             cx.acker(),
+            cx.flush_signal(),
         )
         .sink_map_err(|error| error!(message = "Fatal clickhouse sink error.", %error));
 
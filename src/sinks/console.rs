@@ -4,7 +4,7 @@ use crate::{
     event::Event,
     internal_events::{ConsoleEventProcessed, ConsoleFieldNotFound},
     sinks::util::{
-        encoding::{EncodingConfig, EncodingConfiguration},
+        encoding::{Encoder, EncodingConfig, EncodingConfiguration, StandardEncodings},
         StreamSink,
     },
 };
@@ -32,14 +32,7 @@ pub enum Target {
 pub struct ConsoleSinkConfig {
     #[serde(default)]
     pub target: Target,
-    pub encoding: EncodingConfig<Encoding>,
-}
-
-#[derive(Deserialize, Serialize, Debug, Eq, PartialEq, Clone)]
-#[serde(rename_all = "snake_case")]
-pub enum Encoding {
-    Text,
-    Json,
+    pub encoding: EncodingConfig<StandardEncodings>,
 }
 
 inventory::submit! {
@@ -50,7 +43,7 @@ impl GenerateConfig for ConsoleSinkConfig {
     fn generate_config() -> toml::Value {
         toml::Value::try_from(Self {
             target: Target::Stdout,
-            encoding: Encoding::Json.into(),
+            encoding: StandardEncodings::Json.into(),
         })
         .unwrap()
     }
@@ -63,7 +56,7 @@ impl SinkConfig for ConsoleSinkConfig {
         &self,
         cx: SinkContext,
     ) -> crate::Result<(super::VectorSink, super::Healthcheck)> {
-        let encoding = self.encoding.clone();
+        let encoding = self.encoding.loaded()?;
 
         let output: Box<dyn io::AsyncWrite + Send + Sync + Unpin> = match self.target {
             Target::Stdout => Box::new(io::stdout()),
@@ -91,53 +84,37 @@ impl SinkConfig for ConsoleSinkConfig {
     }
 }
 
-fn encode_event(mut event: Event, encoding: &EncodingConfig<Encoding>) -> Option<String> {
-    encoding.apply_rules(&mut event);
-    match event {
-        Event::Log(log) => match encoding.codec() {
-            Encoding::Json => serde_json::to_string(&log)
-                .map_err(|error| {
-                    error!(message = "Error encoding json.", %error);
-                })
-                .ok(),
-            Encoding::Text => {
-                let field = crate::config::log_schema().message_key();
-                match log.get(field) {
-                    Some(v) => Some(v.to_string_lossy()),
-                    None => {
-                        emit!(ConsoleFieldNotFound {
-                            missing_field: field,
-                        });
-                        None
-                    }
-                }
-            }
-        },
-        Event::Metric(metric) => match encoding.codec() {
-            Encoding::Json => serde_json::to_string(&metric)
-                .map_err(|error| {
-                    error!(message = "Error encoding json.", %error);
-                })
-                .ok(),
-            Encoding::Text => Some(format!("{}", metric)),
-        },
+fn encode_event(event: Event, encoding: &EncodingConfig<StandardEncodings>) -> Option<Vec<u8>> {
+    if let (Event::Log(log), StandardEncodings::Text) = (&event, encoding.codec()) {
+        let field = crate::config::log_schema().message_key();
+        if !log.contains(field) {
+            emit!(ConsoleFieldNotFound {
+                missing_field: field,
+            });
+            return None;
+        }
     }
+
+    encoding
+        .encode(event)
+        .map_err(|error| error!(message = "Error encoding event.", %error))
+        .ok()
 }
 
 struct WriterSink {
     acker: Acker,
     output: Box<dyn io::AsyncWrite + Send + Sync + Unpin>,
-    encoding: EncodingConfig<Encoding>,
+    encoding: EncodingConfig<StandardEncodings>,
 }
 
 #[async_trait]
 impl StreamSink for WriterSink {
     async fn run(&mut self, mut input: BoxStream<'_, Event>) -> Result<(), ()> {
         while let Some(event) = input.next().await {
-            self.acker.ack(1);
-            if let Some(mut buf) = encode_event(event, &self.encoding) {
-                buf.push('\n');
-                if let Err(error) = self.output.write_all(buf.as_bytes()).await {
+            self.acker.ack_with_ingress(1, event.ingress());
+            if let Some(buf) = encode_event(event, &self.encoding) {
+                let buf = self.encoding.codec().frame(buf);
+                if let Err(error) = self.output.write_all(&buf).await {
                     // Error when writing to stdout/stderr is likely irrecoverable,
                     // so stop the sink.
                     error!(message = "Error writing to output. Stopping sink.", %error);
@@ -155,7 +132,7 @@ impl StreamSink for WriterSink {
 
 #[cfg(test)]
 mod test {
-    use super::{encode_event, ConsoleSinkConfig, Encoding, EncodingConfig};
+    use super::{ConsoleSinkConfig, EncodingConfig, StandardEncodings};
     use crate::event::metric::{Metric, MetricKind, MetricValue, StatisticKind};
     use crate::event::{Event, Value};
     use chrono::{offset::TimeZone, Utc};
@@ -165,12 +142,16 @@ mod test {
         crate::test_util::test_generate_config::<ConsoleSinkConfig>();
     }
 
+    fn encode_event(event: Event, encoding: &EncodingConfig<StandardEncodings>) -> Option<String> {
+        super::encode_event(event, encoding).map(|bytes| String::from_utf8(bytes).unwrap())
+    }
+
     #[test]
     fn encodes_raw_logs() {
         let event = Event::from("foo");
         assert_eq!(
             "foo",
-            encode_event(event, &EncodingConfig::from(Encoding::Text)).unwrap()
+            encode_event(event, &EncodingConfig::from(StandardEncodings::Text)).unwrap()
         );
     }
 
@@ -182,7 +163,7 @@ mod test {
         log.insert("z", Value::from(25));
         log.insert("a", Value::from("0"));
 
-        let encoded = encode_event(event, &EncodingConfig::from(Encoding::Json));
+        let encoded = encode_event(event, &EncodingConfig::from(StandardEncodings::Json));
         let expected = r#"{"a":"0","x":"23","z":25}"#;
         assert_eq!(encoded.unwrap(), expected);
     }
@@ -207,7 +188,7 @@ mod test {
         });
         assert_eq!(
             r#"{"name":"foos","namespace":"vector","timestamp":"2018-11-14T08:09:10.000000011Z","tags":{"Key3":"Value3","key1":"value1","key2":"value2"},"kind":"incremental","counter":{"value":100.0}}"#,
-            encode_event(event, &EncodingConfig::from(Encoding::Json)).unwrap()
+            encode_event(event, &EncodingConfig::from(StandardEncodings::Json)).unwrap()
         );
     }
 
@@ -225,7 +206,7 @@ mod test {
         });
         assert_eq!(
             r#"{"name":"users","kind":"incremental","set":{"values":["bob"]}}"#,
-            encode_event(event, &EncodingConfig::from(Encoding::Json)).unwrap()
+            encode_event(event, &EncodingConfig::from(StandardEncodings::Json)).unwrap()
         );
     }
 
@@ -245,7 +226,7 @@ mod test {
         });
         assert_eq!(
             r#"{"name":"glork","kind":"incremental","distribution":{"values":[10.0],"sample_rates":[1],"statistic":"histogram"}}"#,
-            encode_event(event, &EncodingConfig::from(Encoding::Json)).unwrap()
+            encode_event(event, &EncodingConfig::from(StandardEncodings::Json)).unwrap()
         );
     }
 
@@ -263,7 +244,7 @@ mod test {
         });
         assert_eq!(
             "users{} + bob",
-            encode_event(event, &EncodingConfig::from(Encoding::Text)).unwrap()
+            encode_event(event, &EncodingConfig::from(StandardEncodings::Text)).unwrap()
         );
     }
 }
@@ -103,14 +103,17 @@ impl DatadogLogsConfig {
         B::Input: std::marker::Send,
         T: HttpSink<Input = B::Input, Output = B::Output> + Clone,
     {
-        let request_settings = self.request.unwrap_with(&TowerRequestConfig::default());
+        let request_settings = self
+            .request
+            .unwrap_with(&TowerRequestConfig::default())
+            .when_full(cx.when_full());
 
         let tls_settings = MaybeTlsSettings::from_config(
             &Some(self.tls.clone().unwrap_or_else(TlsConfig::enabled)),
             false,
         )?;
 
-        let client = HttpClient::new(tls_settings)?;
+        let client = HttpClient::new(tls_settings, cx.proxy())?;
         let healthcheck = healthcheck(service.clone(), client.clone()).boxed();
         let sink = BatchedHttpSink::new(
             service,
@@ -119,6 +122,7 @@ impl DatadogLogsConfig {
             timeout,
             client,
             cx.acker(),
+            cx.flush_signal(),
         )
         .sink_map_err(|error| error!(message = "Fatal datadog_logs text sink error.", %error));
 
@@ -153,6 +157,7 @@ impl DatadogLogsConfig {
                     encoder.finish()?,
                 )
             }
+            _ => unreachable!("Unsupported compression is rejected when the sink is built"),
         };
 
         request
@@ -166,6 +171,10 @@ impl DatadogLogsConfig {
 #[typetag::serde(name = "datadog_logs")]
 impl SinkConfig for DatadogLogsConfig {
     async fn build(&self, cx: SinkContext) -> crate::Result<(VectorSink, Healthcheck)> {
+        self.compression
+            .unwrap_or_default()
+            .check_supported("Datadog", &["gzip"])?;
+
         // Create a different sink depending on which encoding we have chosen.
         // Json and Text have different batching strategies and so each needs to be
         // handled differently.
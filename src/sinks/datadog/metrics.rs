@@ -160,14 +160,17 @@ impl_generate_config_from_default!(DatadogConfig);
 #[typetag::serde(name = "datadog_metrics")]
 impl SinkConfig for DatadogConfig {
     async fn build(&self, cx: SinkContext) -> crate::Result<(VectorSink, Healthcheck)> {
-        let client = HttpClient::new(None)?;
+        let client = HttpClient::new(None, cx.proxy())?;
         let healthcheck = healthcheck(self.clone(), client.clone()).boxed();
 
         let batch = BatchSettings::default()
             .events(20)
             .timeout(1)
             .parse_config(self.batch)?;
-        let request = self.request.unwrap_with(&REQUEST_DEFAULTS);
+        let request = self
+            .request
+            .unwrap_with(&REQUEST_DEFAULTS)
+            .when_full(cx.when_full());
 
         let uri = DatadogEndpoint::build_uri(&self.get_endpoint())?;
         let timestamp = Utc::now().timestamp();
@@ -189,12 +192,13 @@ impl SinkConfig for DatadogConfig {
 
         let buffer = PartitionBuffer::new(MetricBuffer::new(batch.size));
 
-        let svc_sink = PartitionBatchSink::new(svc, buffer, batch.timeout, cx.acker())
-            .sink_map_err(|error| error!(message = "Fatal datadog metric sink error.", %error))
-            .with_flat_map(move |event: Event| {
-                let ep = DatadogEndpoint::from_metric(&event);
-                iter_ok(Some(PartitionInnerBuffer::new(event, ep)))
-            });
+        let svc_sink =
+            PartitionBatchSink::new(svc, buffer, batch.timeout, cx.acker(), cx.flush_signal())
+                .sink_map_err(|error| error!(message = "Fatal datadog metric sink error.", %error))
+                .with_flat_map(move |event: Event| {
+                    let ep = DatadogEndpoint::from_metric(&event);
+                    iter_ok(Some(PartitionInnerBuffer::new(event, ep)))
+                });
 
         Ok((VectorSink::Futures01Sink(Box::new(svc_sink)), healthcheck))
     }
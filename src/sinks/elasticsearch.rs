@@ -1,28 +1,32 @@
 use crate::{
-    config::{DataType, SinkConfig, SinkContext, SinkDescription},
+    config::{log_schema, DataType, SinkConfig, SinkContext, SinkDescription},
     emit,
     event::Event,
     http::HttpClient,
-    internal_events::{ElasticSearchEventReceived, ElasticSearchMissingKeys},
+    internal_events::{
+        ElasticSearchDocumentRejected, ElasticSearchEventReceived, ElasticSearchInvalidBulkAction,
+        ElasticSearchMissingDocumentId, ElasticSearchMissingKeys,
+    },
     rusoto::{self, region_from_endpoint, RegionOrEndpoint},
     sinks::util::{
         encoding::{EncodingConfigWithDefault, EncodingConfiguration},
-        http::{BatchedHttpSink, HttpSink},
+        http::retry_after,
         retries::{RetryAction, RetryLogic},
-        BatchConfig, BatchSettings, Buffer, Compression, TowerRequestConfig,
+        BatchConfig, BatchSettings, Compression, EncodedLength, EncodedSink, TowerRequestConfig,
+        VecBuffer,
     },
     template::{Template, TemplateError},
     tls::{TlsOptions, TlsSettings},
 };
-use bytes::Bytes;
-use futures::FutureExt;
+use bytes::{Buf, Bytes};
+use futures::{future::BoxFuture, FutureExt};
 use futures01::Sink;
 use http::{
     header::{HeaderName, HeaderValue},
     uri::InvalidUri,
     Request, StatusCode, Uri,
 };
-use hyper::Body;
+use hyper::{body, Body};
 use lazy_static::lazy_static;
 use rusoto_core::Region;
 use rusoto_credential::{CredentialsError, ProvideAwsCredentials};
@@ -30,8 +34,17 @@ use rusoto_signature::{SignedRequest, SignedRequestPayload};
 use serde::{Deserialize, Serialize};
 use serde_json::json;
 use snafu::{ResultExt, Snafu};
-use std::collections::HashMap;
-use std::convert::TryFrom;
+use std::{
+    collections::HashMap,
+    convert::TryFrom,
+    str::FromStr,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    task::{Context, Poll},
+};
+use tower::Service;
 
 #[derive(Deserialize, Serialize, Debug, Clone, Default)]
 #[serde(deny_unknown_fields)]
@@ -43,6 +56,10 @@ pub struct ElasticSearchConfig {
     pub doc_type: Option<String>,
     pub id_key: Option<String>,
     pub pipeline: Option<String>,
+    #[serde(default)]
+    pub mode: ElasticSearchMode,
+    pub bulk_action: Option<String>,
+    pub data_stream: Option<DataStreamConfig>,
 
     #[serde(default)]
     pub compression: Compression,
@@ -78,6 +95,122 @@ pub enum Encoding {
     Default,
 }
 
+#[derive(Deserialize, Serialize, Debug, Eq, PartialEq, Clone, Copy, Derivative)]
+#[serde(rename_all = "snake_case")]
+#[derivative(Default)]
+pub enum ElasticSearchMode {
+    /// Events are written to `index` with `bulk_action`.
+    #[derivative(Default)]
+    Normal,
+    /// Events are appended to the data stream named by `data_stream`.
+    DataStream,
+}
+
+/// The parts of a data stream's name, `<type>-<dataset>-<namespace>`.
+#[derive(Deserialize, Serialize, Debug, Clone, Derivative)]
+#[serde(deny_unknown_fields)]
+#[derivative(Default)]
+pub struct DataStreamConfig {
+    #[serde(rename = "type", default = "default_data_stream_type")]
+    #[derivative(Default(value = "default_data_stream_type()"))]
+    pub dtype: String,
+    #[serde(default = "default_data_stream_dataset")]
+    #[derivative(Default(value = "default_data_stream_dataset()"))]
+    pub dataset: String,
+    #[serde(default = "default_data_stream_namespace")]
+    #[derivative(Default(value = "default_data_stream_namespace()"))]
+    pub namespace: String,
+}
+
+fn default_data_stream_type() -> String {
+    "logs".into()
+}
+
+fn default_data_stream_dataset() -> String {
+    "generic".into()
+}
+
+fn default_data_stream_namespace() -> String {
+    "default".into()
+}
+
+#[derive(Debug)]
+struct DataStreamTemplates {
+    dtype: Template,
+    dataset: Template,
+    namespace: Template,
+}
+
+impl DataStreamTemplates {
+    fn new(config: &DataStreamConfig) -> Result<Self, TemplateError> {
+        Ok(Self {
+            dtype: Template::try_from(config.dtype.as_str())?,
+            dataset: Template::try_from(config.dataset.as_str())?,
+            namespace: Template::try_from(config.namespace.as_str())?,
+        })
+    }
+
+    /// Renders the type, dataset and namespace for `event`.
+    fn render(&self, event: &Event) -> Result<(String, String, String), Vec<String>> {
+        let mut missing_keys = Vec::new();
+        let mut render = |template: &Template| {
+            template.render_string(event).unwrap_or_else(|keys| {
+                missing_keys.extend(keys);
+                String::new()
+            })
+        };
+        let parts = (
+            render(&self.dtype),
+            render(&self.dataset),
+            render(&self.namespace),
+        );
+
+        if missing_keys.is_empty() {
+            Ok(parts)
+        } else {
+            Err(missing_keys)
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+enum BulkAction {
+    Index,
+    Create,
+    Update,
+    Delete,
+}
+
+impl BulkAction {
+    fn as_str(self) -> &'static str {
+        match self {
+            Self::Index => "index",
+            Self::Create => "create",
+            Self::Update => "update",
+            Self::Delete => "delete",
+        }
+    }
+
+    /// Whether the action targets an existing document, by its `_id`.
+    fn requires_id(self) -> bool {
+        matches!(self, Self::Update | Self::Delete)
+    }
+}
+
+impl FromStr for BulkAction {
+    type Err = String;
+
+    fn from_str(action: &str) -> Result<Self, Self::Err> {
+        match action {
+            "index" => Ok(Self::Index),
+            "create" => Ok(Self::Create),
+            "update" => Ok(Self::Update),
+            "delete" => Ok(Self::Delete),
+            _ => Err(format!("Invalid bulk action {:?}", action)),
+        }
+    }
+}
+
 #[derive(Deserialize, Serialize, Clone, Debug)]
 #[serde(deny_unknown_fields, rename_all = "snake_case", tag = "strategy")]
 pub enum ElasticSearchAuth {
@@ -109,28 +242,35 @@ impl SinkConfig for ElasticSearchConfig {
         cx: SinkContext,
     ) -> crate::Result<(super::VectorSink, super::Healthcheck)> {
         let common = ElasticSearchCommon::parse_config(&self)?;
-        let client = HttpClient::new(common.tls_settings.clone())?;
+        let client = HttpClient::new(common.tls_settings.clone(), cx.proxy())?;
 
         let healthcheck = healthcheck(client.clone(), common).boxed();
 
-        let common = ElasticSearchCommon::parse_config(&self)?;
-        let compression = common.compression;
+        let common = Arc::new(ElasticSearchCommon::parse_config(&self)?);
         let batch = BatchSettings::default()
             .bytes(bytesize::mib(10u64))
             .timeout(1)
-            .parse_config(self.batch)?;
-        let request = self.request.unwrap_with(&REQUEST_DEFAULTS);
-
-        let sink = BatchedHttpSink::with_retry_logic(
-            common,
-            Buffer::new(batch.size, compression),
-            ElasticSearchRetryLogic,
-            request,
-            batch.timeout,
+            .parse_config(self.batch.use_size_as_bytes()?)?;
+        let request = self
+            .request
+            .unwrap_with(&REQUEST_DEFAULTS)
+            .when_full(cx.when_full());
+
+        let service = ElasticSearchService {
             client,
-            cx.acker(),
-        )
-        .sink_map_err(|error| error!(message = "Fatal elasticsearch sink error.", %error));
+            common: Arc::clone(&common),
+        };
+        let sink = request
+            .batch_sink(
+                ElasticSearchRetryLogic,
+                service,
+                VecBuffer::new(batch.size),
+                batch.timeout,
+                cx.acker(),
+                cx.flush_signal(),
+            )
+            .encode_events(move |event| common.encode_event(event))
+            .sink_map_err(|error| error!(message = "Fatal elasticsearch sink error.", %error));
 
         Ok((
             super::VectorSink::Futures01Sink(Box::new(sink)),
@@ -154,6 +294,8 @@ pub struct ElasticSearchCommon {
     authorization: Option<String>,
     credentials: Option<rusoto::AwsCredentialsProvider>,
     index: Template,
+    bulk_action: Template,
+    data_stream: Option<DataStreamTemplates>,
     doc_type: String,
     tls_settings: TlsSettings,
     config: ElasticSearchConfig,
@@ -172,16 +314,76 @@ enum ParseError {
     AWSCredentialsGenerateFailed { source: CredentialsError },
     #[snafu(display("Index template parse error: {}", source))]
     IndexTemplate { source: TemplateError },
+    #[snafu(display("Bulk action template parse error: {}", source))]
+    BulkActionTemplate { source: TemplateError },
+    #[snafu(display("{}", message))]
+    InvalidBulkAction { message: String },
+    #[snafu(display("The {:?} bulk action requires `id_key` to be set", action))]
+    BulkActionRequiresId { action: &'static str },
+    #[snafu(display("Data stream template parse error: {}", source))]
+    DataStreamTemplate { source: TemplateError },
+    #[snafu(display("Data streams only accept the `create` bulk action"))]
+    DataStreamBulkAction,
+    #[snafu(display("Data streams are named by `data_stream`, not `index`"))]
+    DataStreamIndex,
 }
 
-#[async_trait::async_trait]
-impl HttpSink for ElasticSearchCommon {
-    type Input = Vec<u8>;
-    type Output = Vec<u8>;
+/// The bulk action and source of a single document, as NDJSON lines.
+///
+/// Clones of an item share whether it's settled, so that a request retried
+/// after a partial failure sends only the documents that can be retried.
+#[derive(Clone, Debug)]
+pub struct BulkItem {
+    body: Vec<u8>,
+    settled: Arc<AtomicBool>,
+}
+
+impl BulkItem {
+    fn new(body: Vec<u8>) -> Self {
+        Self {
+            body,
+            settled: Arc::new(AtomicBool::new(false)),
+        }
+    }
+
+    fn is_settled(&self) -> bool {
+        self.settled.load(Ordering::Acquire)
+    }
+
+    fn settle(&self) {
+        self.settled.store(true, Ordering::Release)
+    }
+}
+
+impl EncodedLength for BulkItem {
+    fn encoded_length(&self) -> usize {
+        self.body.len()
+    }
+}
+
+impl ElasticSearchCommon {
+    pub fn encode_event(&self, mut event: Event) -> Option<BulkItem> {
+        let index = match &self.data_stream {
+            None => self.index.render_string(&event),
+            Some(data_stream) => data_stream
+                .render(&event)
+                .map(|(dtype, dataset, namespace)| {
+                    let log = event.as_mut_log();
+                    log.insert("data_stream.type", dtype.clone());
+                    log.insert("data_stream.dataset", dataset.clone());
+                    log.insert("data_stream.namespace", namespace.clone());
+                    format!("{}-{}-{}", dtype, dataset, namespace)
+                }),
+        }
+        .map_err(|missing_keys| {
+            emit!(ElasticSearchMissingKeys {
+                keys: &missing_keys
+            });
+        })
+        .ok()?;
 
-    fn encode_event(&self, mut event: Event) -> Option<Self::Input> {
-        let index = self
-            .index
+        let action = self
+            .bulk_action
             .render_string(&event)
             .map_err(|missing_keys| {
                 emit!(ElasticSearchMissingKeys {
@@ -189,36 +391,59 @@ impl HttpSink for ElasticSearchCommon {
                 });
             })
             .ok()?;
-
-        let mut action = json!({
-            "index": {
-                "_index": index,
-                "_type": self.doc_type,
+        let action = match action.parse::<BulkAction>() {
+            Ok(action) => action,
+            Err(_) => {
+                emit!(ElasticSearchInvalidBulkAction { action: &action });
+                return None;
             }
+        };
+
+        let mut metadata = json!({
+            "_index": index,
+            "_type": self.doc_type,
         });
-        maybe_set_id(
-            self.config.id_key.as_ref(),
-            action.pointer_mut("/index").unwrap(),
-            &mut event,
-        );
+        maybe_set_id(self.config.id_key.as_ref(), &mut metadata, &mut event);
+        if action.requires_id() && metadata.get("_id").is_none() {
+            emit!(ElasticSearchMissingDocumentId {
+                action: action.as_str()
+            });
+            return None;
+        }
 
-        let mut body = serde_json::to_vec(&action).unwrap();
+        let mut body = serde_json::to_vec(&json!({ action.as_str(): metadata })).unwrap();
         body.push(b'\n');
 
-        self.config.encoding.apply_rules(&mut event);
+        if action != BulkAction::Delete {
+            self.config.encoding.apply_rules(&mut event);
 
-        serde_json::to_writer(&mut body, &event.into_log()).unwrap();
-        body.push(b'\n');
+            let mut log = event.into_log();
+            if self.data_stream.is_some() && !log.contains("@timestamp") {
+                // Data streams require their documents to have an `@timestamp`.
+                if let Some(timestamp) = log.remove(log_schema().timestamp_key()) {
+                    log.insert("@timestamp", timestamp);
+                }
+            }
+
+            if action == BulkAction::Update {
+                serde_json::to_writer(&mut body, &json!({ "doc": log, "doc_as_upsert": true }))
+                    .unwrap();
+            } else {
+                serde_json::to_writer(&mut body, &log).unwrap();
+            }
+            body.push(b'\n');
+        }
 
         emit!(ElasticSearchEventReceived {
             byte_size: body.len(),
             index
         });
 
-        Some(body)
+        Some(BulkItem::new(body))
     }
 
-    async fn build_request(&self, events: Self::Output) -> crate::Result<http::Request<Vec<u8>>> {
+    async fn build_request(&self, events: Vec<u8>) -> crate::Result<http::Request<Vec<u8>>> {
+        let events = self.compression.compress(&events)?;
         let mut builder = Request::post(&self.bulk_uri);
 
         if let Some(credentials_provider) = &self.credentials {
@@ -226,6 +451,10 @@ impl HttpSink for ElasticSearchCommon {
 
             request.add_header("Content-Type", "application/x-ndjson");
 
+            if let Some(ce) = self.compression.content_encoding() {
+                request.add_header("Content-Encoding", ce);
+            }
+
             if let Some(headers) = &self.config.headers {
                 for (header, value) in headers {
                     request.add_header(header, value);
@@ -268,21 +497,124 @@ impl HttpSink for ElasticSearchCommon {
     }
 }
 
+/// Sends bulk requests, and settles the documents that don't need to be
+/// sent again once Elasticsearch has answered for each of them.
+#[derive(Clone)]
+struct ElasticSearchService {
+    client: HttpClient,
+    common: Arc<ElasticSearchCommon>,
+}
+
+impl Service<Vec<BulkItem>> for ElasticSearchService {
+    type Response = http::Response<Bytes>;
+    type Error = crate::Error;
+    type Future = BoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, items: Vec<BulkItem>) -> Self::Future {
+        let common = Arc::clone(&self.common);
+        let mut client = self.client.clone();
+
+        Box::pin(async move {
+            let pending = items
+                .into_iter()
+                .filter(|item| !item.is_settled())
+                .collect::<Vec<_>>();
+            let mut body = Vec::with_capacity(pending.iter().map(BulkItem::encoded_length).sum());
+            for item in &pending {
+                body.extend_from_slice(&item.body);
+            }
+
+            let request = common.build_request(body).await?.map(Body::from);
+            let response = client.call(request).await?;
+            let (parts, body) = response.into_parts();
+            let mut body = body::aggregate(body).await?;
+            let response = hyper::Response::from_parts(parts, body.to_bytes());
+
+            if response.status().is_success() {
+                settle_items(&pending, response.body());
+            }
+
+            Ok(response)
+        })
+    }
+}
+
+/// Settles the items of a successful bulk request that either succeeded or
+/// can't succeed, reporting the rejected ones. The rest are left to be
+/// retried.
+fn settle_items(items: &[BulkItem], body: &[u8]) {
+    let response = match serde_json::from_slice::<ESResultResponse>(body) {
+        Ok(response) => response,
+        // The retry logic reports responses that can't be parsed.
+        Err(_) => return,
+    };
+
+    for (item, result) in items.iter().zip(response.items) {
+        let result = result.into_result();
+        if result.is_retriable() {
+            continue;
+        }
+        if let Some(error) = &result.error {
+            emit!(ElasticSearchDocumentRejected {
+                status: result.status,
+                error_type: &error.err_type,
+                reason: &error.reason,
+            });
+        }
+        item.settle();
+    }
+}
+
 #[derive(Clone)]
 struct ElasticSearchRetryLogic;
 
 #[derive(Deserialize, Debug)]
 struct ESResultResponse {
+    #[serde(default)]
+    errors: bool,
     items: Vec<ESResultItem>,
 }
+
+/// The result of one document, keyed by its bulk action.
 #[derive(Deserialize, Debug)]
-struct ESResultItem {
-    index: ESIndexResult,
+#[serde(rename_all = "snake_case")]
+enum ESResultItem {
+    Index(ESItemResult),
+    Create(ESItemResult),
+    Update(ESItemResult),
+    Delete(ESItemResult),
 }
+
+impl ESResultItem {
+    fn into_result(self) -> ESItemResult {
+        match self {
+            Self::Index(result)
+            | Self::Create(result)
+            | Self::Update(result)
+            | Self::Delete(result) => result,
+        }
+    }
+}
+
 #[derive(Deserialize, Debug)]
-struct ESIndexResult {
+struct ESItemResult {
+    #[serde(default)]
+    status: u16,
     error: Option<ESErrorDetails>,
 }
+
+impl ESItemResult {
+    /// Documents rejected because the cluster is overloaded, or failing
+    /// for reasons on its side, can be sent again.
+    fn is_retriable(&self) -> bool {
+        self.error.is_some() && (self.status == 429 || self.status >= 500)
+    }
+}
+
 #[derive(Deserialize, Debug)]
 struct ESErrorDetails {
     reason: String,
@@ -302,7 +634,9 @@ impl RetryLogic for ElasticSearchRetryLogic {
         let status = response.status();
 
         match status {
-            StatusCode::TOO_MANY_REQUESTS => RetryAction::Retry("too many requests".into()),
+            StatusCode::TOO_MANY_REQUESTS => {
+                RetryAction::Throttled("too many requests".into(), retry_after(response))
+            }
             StatusCode::NOT_IMPLEMENTED => {
                 RetryAction::DontRetry("endpoint not implemented".into())
             }
@@ -315,30 +649,51 @@ impl RetryLogic for ElasticSearchRetryLogic {
                 let body = String::from_utf8_lossy(response.body());
                 RetryAction::DontRetry(format!("client-side error, {}: {}", status, body))
             }
-            _ if status.is_success() => {
-                let body = String::from_utf8_lossy(response.body());
-
-                if body.contains("\"errors\":true") {
-                    RetryAction::DontRetry(get_error_reason(&body))
-                } else {
-                    RetryAction::Successful
-                }
-            }
+            _ if status.is_success() => item_retry_action(response.body()),
             _ => RetryAction::DontRetry(format!("response status: {}", status)),
         }
     }
 }
 
-fn get_error_reason(body: &str) -> String {
-    match serde_json::from_str::<ESResultResponse>(&body) {
-        Err(json_error) => format!(
-            "some messages failed, could not parse response, error: {}",
-            json_error
-        ),
-        Ok(resp) => match resp.items.into_iter().find_map(|item| item.index.error) {
-            Some(error) => format!("error type: {}, reason: {}", error.err_type, error.reason),
-            None => format!("error response: {}", body),
-        },
+/// Decides what to do with a successful bulk request from the results of its
+/// documents. Rejected documents are reported when they are settled, so only
+/// the ones that can be retried matter here.
+fn item_retry_action(body: &[u8]) -> RetryAction {
+    let response = match serde_json::from_slice::<ESResultResponse>(body) {
+        Ok(response) => response,
+        Err(error) => {
+            return RetryAction::DontRetry(format!(
+                "some messages failed, could not parse response, error: {}",
+                error
+            ))
+        }
+    };
+    if !response.errors {
+        return RetryAction::Successful;
+    }
+
+    let retriable = response
+        .items
+        .into_iter()
+        .map(ESResultItem::into_result)
+        .filter(ESItemResult::is_retriable)
+        .collect::<Vec<_>>();
+    match retriable.first() {
+        None => RetryAction::Successful,
+        Some(first) => {
+            let error = first.error.as_ref().expect("retriable items have errors");
+            let reason = format!(
+                "{} documents failed, first error type: {}, reason: {}",
+                retriable.len(),
+                error.err_type,
+                error.reason
+            );
+            if first.status == 429 {
+                RetryAction::Throttled(reason, None)
+            } else {
+                RetryAction::Retry(reason)
+            }
+        }
     }
 }
 
@@ -378,9 +733,47 @@ impl ElasticSearchCommon {
         };
 
         let compression = config.compression;
+        compression.check_supported("Elasticsearch", &["gzip", "deflate"])?;
         let index = config.index.as_deref().unwrap_or("vector-%Y.%m.%d");
         let index = Template::try_from(index).context(IndexTemplate)?;
 
+        let data_stream = match config.mode {
+            ElasticSearchMode::Normal => None,
+            ElasticSearchMode::DataStream => {
+                if config.index.is_some() {
+                    return Err(ParseError::DataStreamIndex.into());
+                }
+                if config
+                    .bulk_action
+                    .as_deref()
+                    .map_or(false, |action| action != "create")
+                {
+                    return Err(ParseError::DataStreamBulkAction.into());
+                }
+                let data_stream = config.data_stream.clone().unwrap_or_default();
+                Some(DataStreamTemplates::new(&data_stream).context(DataStreamTemplate)?)
+            }
+        };
+
+        let bulk_action = match (config.mode, &config.bulk_action) {
+            (_, Some(action)) => action.as_str(),
+            (ElasticSearchMode::Normal, None) => "index",
+            (ElasticSearchMode::DataStream, None) => "create",
+        };
+        let bulk_action = Template::try_from(bulk_action).context(BulkActionTemplate)?;
+        if !bulk_action.is_dynamic() {
+            let action = bulk_action
+                .get_ref()
+                .parse::<BulkAction>()
+                .map_err(|message| ParseError::InvalidBulkAction { message })?;
+            if action.requires_id() && config.id_key.is_none() {
+                return Err(ParseError::BulkActionRequiresId {
+                    action: action.as_str(),
+                }
+                .into());
+            }
+        }
+
         let doc_type = config.doc_type.clone().unwrap_or_else(|| "_doc".into());
 
         let request = config.request.unwrap_with(&REQUEST_DEFAULTS);
@@ -408,6 +801,8 @@ impl ElasticSearchCommon {
             authorization,
             credentials,
             index,
+            bulk_action,
+            data_stream,
             doc_type,
             tls_settings,
             config,
@@ -490,10 +885,22 @@ fn maybe_set_id(key: Option<impl AsRef<str>>, doc: &mut serde_json::Value, event
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::{sinks::util::retries::RetryAction, Event};
+    use crate::{
+        config::SinkContext,
+        sinks::util::retries::RetryAction,
+        test_util::{next_addr, trace_init},
+        Event,
+    };
+    use futures::stream;
     use http::{Response, StatusCode};
+    use hyper::{
+        service::{make_service_fn, service_fn},
+        Server,
+    };
     use pretty_assertions::assert_eq;
     use serde_json::json;
+    use std::sync::atomic::AtomicUsize;
+    use tokio::sync::mpsc;
 
     #[test]
     fn generate_config() {
@@ -537,18 +944,51 @@ mod tests {
         assert_eq!(json!({}), action);
     }
 
-    #[test]
-    fn handles_error_response() {
-        let json = "{\"took\":185,\"errors\":true,\"items\":[{\"index\":{\"_index\":\"test-hgw28jv10u\",\"_type\":\"log_lines\",\"_id\":\"3GhQLXEBE62DvOOUKdFH\",\"status\":400,\"error\":{\"type\":\"illegal_argument_exception\",\"reason\":\"mapper [message] of different type, current_type [long], merged_type [text]\"}}}]}";
-        let response = Response::builder()
+    fn bulk_response(items: serde_json::Value) -> Response<Bytes> {
+        let errors = items
+            .as_array()
+            .unwrap()
+            .iter()
+            .any(|item| item.pointer("/index/error").is_some());
+        let body = json!({ "took": 1, "errors": errors, "items": items });
+        Response::builder()
             .status(StatusCode::OK)
-            .body(Bytes::from(json))
-            .unwrap();
+            .body(Bytes::from(body.to_string()))
+            .unwrap()
+    }
+
+    #[test]
+    fn does_not_retry_rejected_documents() {
+        let response = bulk_response(json!([
+            {"index": {"status": 201}},
+            {"index": {"status": 400, "error": {"type": "illegal_argument_exception", "reason": "mapper [message] of different type, current_type [long], merged_type [text]"}}},
+        ]));
+        let logic = ElasticSearchRetryLogic;
+        assert!(matches!(
+            logic.should_retry_response(&response),
+            RetryAction::Successful
+        ));
+    }
+
+    #[test]
+    fn retries_throttled_documents() {
+        let response = bulk_response(json!([
+            {"index": {"status": 429, "error": {"type": "es_rejected_execution_exception", "reason": "rejected execution"}}},
+            {"index": {"status": 400, "error": {"type": "mapper_parsing_exception", "reason": "failed to parse"}}},
+        ]));
         let logic = ElasticSearchRetryLogic;
         assert!(matches!(
             logic.should_retry_response(&response),
-            RetryAction::DontRetry(_)
+            RetryAction::Throttled(_, None)
         ));
+
+        let items = vec![
+            BulkItem::new(b"throttled".to_vec()),
+            BulkItem::new(b"rejected".to_vec()),
+        ];
+        settle_items(&items, response.body());
+        assert!(!items[0].is_settled());
+        assert!(items[1].is_settled());
     }
 
     #[test]
@@ -573,7 +1013,166 @@ mod tests {
         let expected = r#"{"index":{"_index":"purple","_type":"_doc"}}
 {"foo":"bar","message":"hello there"}
 "#;
-        assert_eq!(std::str::from_utf8(&encoded).unwrap(), &expected[..]);
+        assert_eq!(std::str::from_utf8(&encoded.body).unwrap(), &expected[..]);
+    }
+
+    #[test]
+    fn encodes_templated_bulk_actions() {
+        let config = ElasticSearchConfig {
+            index: Some(String::from("vector")),
+            id_key: Some(String::from("id")),
+            bulk_action: Some(String::from("{{ action }}")),
+            endpoint: String::from("https://example.com"),
+            ..Default::default()
+        };
+        let es = ElasticSearchCommon::parse_config(&config).unwrap();
+
+        let event = |action: &str| {
+            let mut event = Event::new_empty_log();
+            event.as_mut_log().insert("message", "hello");
+            event.as_mut_log().insert("id", "42");
+            event.as_mut_log().insert("action", action);
+            event
+        };
+        let encode = |event| String::from_utf8(es.encode_event(event).unwrap().body).unwrap();
+
+        assert_eq!(
+            encode(event("update")),
+            r#"{"update":{"_id":"42","_index":"vector","_type":"_doc"}}
+{"doc":{"action":"update","message":"hello"},"doc_as_upsert":true}
+"#
+        );
+        assert_eq!(
+            encode(event("delete")),
+            r#"{"delete":{"_id":"42","_index":"vector","_type":"_doc"}}
+"#
+        );
+        assert!(es.encode_event(event("upsert")).is_none());
+
+        let mut without_id = event("delete");
+        without_id.as_mut_log().remove("id");
+        assert!(es.encode_event(without_id).is_none());
+    }
+
+    #[test]
+    fn rejects_invalid_bulk_actions() {
+        let config = ElasticSearchConfig {
+            bulk_action: Some(String::from("upsert")),
+            endpoint: String::from("https://example.com"),
+            ..Default::default()
+        };
+        assert!(ElasticSearchCommon::parse_config(&config).is_err());
+
+        let config = ElasticSearchConfig {
+            mode: ElasticSearchMode::DataStream,
+            bulk_action: Some(String::from("index")),
+            endpoint: String::from("https://example.com"),
+            ..Default::default()
+        };
+        assert!(ElasticSearchCommon::parse_config(&config).is_err());
+
+        let config = ElasticSearchConfig {
+            bulk_action: Some(String::from("delete")),
+            endpoint: String::from("https://example.com"),
+            ..Default::default()
+        };
+        assert!(ElasticSearchCommon::parse_config(&config).is_err());
+    }
+
+    #[test]
+    fn encodes_data_stream_documents() {
+        let config = ElasticSearchConfig {
+            mode: ElasticSearchMode::DataStream,
+            data_stream: Some(DataStreamConfig {
+                dataset: String::from("{{ service }}"),
+                ..Default::default()
+            }),
+            endpoint: String::from("https://example.com"),
+            ..Default::default()
+        };
+        let es = ElasticSearchCommon::parse_config(&config).unwrap();
+
+        let mut event = Event::new_empty_log();
+        event.as_mut_log().insert("message", "hello");
+        event.as_mut_log().insert("service", "nginx");
+        event
+            .as_mut_log()
+            .insert(log_schema().timestamp_key(), "2020-10-18T12:00:00Z");
+
+        let encoded = es.encode_event(event).unwrap();
+        let expected = r#"{"create":{"_index":"logs-nginx-default","_type":"_doc"}}
+{"@timestamp":"2020-10-18T12:00:00Z","data_stream":{"dataset":"nginx","namespace":"default","type":"logs"},"message":"hello","service":"nginx"}
+"#;
+        assert_eq!(std::str::from_utf8(&encoded.body).unwrap(), &expected[..]);
+    }
+
+    #[tokio::test]
+    async fn resends_only_retriable_documents() {
+        trace_init();
+
+        let addr = next_addr();
+        let (tx, mut rx) = mpsc::channel(10);
+        let attempts = Arc::new(AtomicUsize::new(0));
+        let service = make_service_fn(move |_| {
+            let tx = tx.clone();
+            let attempts = Arc::clone(&attempts);
+            async move {
+                Ok::<_, crate::Error>(service_fn(move |request: Request<Body>| {
+                    let mut tx = tx.clone();
+                    let attempt = attempts.fetch_add(1, Ordering::SeqCst);
+                    async move {
+                        let body = hyper::body::to_bytes(request.into_body()).await.unwrap();
+                        tx.send(body).await.unwrap();
+
+                        let items = if attempt == 0 {
+                            json!([
+                                {"index": {"status": 201}},
+                                {"index": {"status": 429, "error": {"type": "es_rejected_execution_exception", "reason": "rejected execution"}}},
+                                {"index": {"status": 400, "error": {"type": "mapper_parsing_exception", "reason": "failed to parse"}}},
+                            ])
+                        } else {
+                            json!([{"index": {"status": 201}}])
+                        };
+                        Ok::<_, crate::Error>(bulk_response(items).map(Body::from))
+                    }
+                }))
+            }
+        });
+        tokio::spawn(Server::bind(&addr).serve(service));
+
+        let config = ElasticSearchConfig {
+            endpoint: format!("http://{}", addr),
+            index: Some(String::from("vector")),
+            batch: BatchConfig {
+                max_events: Some(3),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let (sink, _healthcheck) = config.build(SinkContext::new_test()).await.unwrap();
+
+        let events = vec!["accepted", "throttled", "rejected"]
+            .into_iter()
+            .map(Event::from);
+        sink.run(stream::iter(events)).await.unwrap();
+
+        let documents = |body: Bytes| {
+            String::from_utf8(body.to_vec())
+                .unwrap()
+                .lines()
+                .skip(1)
+                .step_by(2)
+                .map(|line| {
+                    serde_json::from_str::<serde_json::Value>(line).unwrap()["message"].clone()
+                })
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            documents(rx.recv().await.unwrap()),
+            vec!["accepted", "throttled", "rejected"]
+        );
+        assert_eq!(documents(rx.recv().await.unwrap()), vec!["throttled"]);
+        assert!(rx.try_recv().is_err());
     }
 }
 
@@ -582,7 +1181,7 @@ mod tests {
 mod integration_tests {
     use super::*;
     use crate::{
-        config::{SinkConfig, SinkContext},
+        config::{ProxyConfig, SinkConfig, SinkContext},
         http::HttpClient,
         test_util::{random_events_with_stream, random_string, trace_init},
         tls::TlsOptions,
@@ -834,8 +1433,8 @@ mod integration_tests {
         let uri = format!("{}/_flush", common.base_url);
         let request = Request::post(uri).body(Body::empty()).unwrap();
 
-        let mut client =
-            HttpClient::new(common.tls_settings.clone()).expect("Could not build client to flush");
+        let mut client = HttpClient::new(common.tls_settings.clone(), &ProxyConfig::default())
+            .expect("Could not build client to flush");
         let response = client.send(request).await?;
         match response.status() {
             StatusCode::OK => Ok(()),
@@ -1,15 +1,14 @@
 use crate::expiring_hash_map::ExpiringHashMap;
 use crate::{
     buffers::Acker,
-    config::{log_schema, DataType, GenerateConfig, SinkConfig, SinkContext, SinkDescription},
+    config::{DataType, GenerateConfig, SinkConfig, SinkContext, SinkDescription},
     event::Event,
     sinks::util::{
-        encoding::{EncodingConfigWithDefault, EncodingConfiguration},
-        StreamSink,
+        encoding::{Encoder, EncodingConfigWithDefault, EncodingConfiguration, StandardEncodings},
+        Compression, Compressor, StreamSink,
     },
     template::Template,
 };
-use async_compression::tokio_02::write::GzipEncoder;
 use async_trait::async_trait;
 use bytes::Bytes;
 use futures::{
@@ -18,14 +17,21 @@ use futures::{
     FutureExt,
 };
 use serde::{Deserialize, Serialize};
-use std::time::{Duration, Instant};
+use snafu::Snafu;
+use std::{
+    io::{self, Write},
+    sync::{Arc, Mutex},
+    time::{Duration, Instant, SystemTime},
+};
 
 use tokio::{
     fs::{self, File},
     io::AsyncWriteExt,
 };
 mod bytes_path;
+mod rotation;
 use bytes_path::BytesPath;
+pub use rotation::{RetainConfig, RotateConfig};
 use std::convert::TryFrom;
 
 #[derive(Deserialize, Serialize, Debug)]
@@ -37,12 +43,34 @@ pub struct FileSinkConfig {
         default,
         skip_serializing_if = "crate::serde::skip_serializing_if_default"
     )]
-    pub encoding: EncodingConfigWithDefault<Encoding>,
+    pub encoding: EncodingConfigWithDefault<StandardEncodings>,
     #[serde(
         default,
         skip_serializing_if = "crate::serde::skip_serializing_if_default"
     )]
     pub compression: Compression,
+    #[serde(
+        default,
+        skip_serializing_if = "crate::serde::skip_serializing_if_default"
+    )]
+    pub rotate: RotateConfig,
+    #[serde(
+        default,
+        skip_serializing_if = "crate::serde::skip_serializing_if_default"
+    )]
+    pub retain: RetainConfig,
+}
+
+#[derive(Debug, Snafu)]
+enum BuildError {
+    #[snafu(display(
+        "Rotated files can't be compressed when `compression` already compresses the file."
+    ))]
+    DoubleCompression,
+    #[snafu(display(
+        "Retaining or compressing rotated files requires `rotate.max_bytes` or `rotate.interval_secs`."
+    ))]
+    RotationDisabled,
 }
 
 inventory::submit! {
@@ -56,68 +84,108 @@ impl GenerateConfig for FileSinkConfig {
             idle_timeout_secs: None,
             encoding: Default::default(),
             compression: Default::default(),
+            rotate: Default::default(),
+            retain: Default::default(),
         })
         .unwrap()
     }
 }
 
-#[derive(Deserialize, Serialize, Debug, Eq, PartialEq, Clone)]
-#[serde(rename_all = "snake_case")]
-pub enum Encoding {
-    Text,
-    Ndjson,
+enum OutFile {
+    Regular(File),
+    Compressed {
+        file: File,
+        /// Taken when the file is closed, to write the end of the stream.
+        compressor: Option<Compressor<CompressedOutput>>,
+        output: CompressedOutput,
+    },
 }
 
-impl Default for Encoding {
-    fn default() -> Self {
-        Encoding::Text
-    }
-}
+/// The bytes a compressor has produced, but that haven't been written to the
+/// file yet.
+#[derive(Clone, Default)]
+struct CompressedOutput(Arc<Mutex<Vec<u8>>>);
 
-#[derive(Deserialize, Serialize, Debug, Eq, PartialEq, Clone, Copy)]
-#[serde(rename_all = "snake_case")]
-pub enum Compression {
-    Gzip,
-    None,
+impl CompressedOutput {
+    fn take(&self) -> Vec<u8> {
+        std::mem::take(&mut *self.0.lock().expect("Compressed output mutex is poisoned"))
+    }
 }
 
-impl Default for Compression {
-    fn default() -> Self {
-        Compression::None
+impl Write for CompressedOutput {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.0
+            .lock()
+            .expect("Compressed output mutex is poisoned")
+            .extend_from_slice(buf);
+        Ok(buf.len())
     }
-}
 
-enum OutFile {
-    Regular(File),
-    Gzip(GzipEncoder<File>),
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
 }
 
 impl OutFile {
-    fn new(file: File, compression: Compression) -> Self {
-        match compression {
+    fn new(file: File, compression: Compression) -> io::Result<Self> {
+        Ok(match compression {
             Compression::None => OutFile::Regular(file),
-            Compression::Gzip => OutFile::Gzip(GzipEncoder::new(file)),
-        }
+            compression => {
+                let output = CompressedOutput::default();
+                OutFile::Compressed {
+                    file,
+                    compressor: Some(Compressor::new(output.clone(), compression)?),
+                    output,
+                }
+            }
+        })
     }
 
     async fn sync_all(&mut self) -> Result<(), std::io::Error> {
         match self {
             OutFile::Regular(file) => file.sync_all().await,
-            OutFile::Gzip(gzip) => gzip.get_mut().sync_all().await,
+            OutFile::Compressed { file, .. } => file.sync_all().await,
         }
     }
 
     async fn shutdown(&mut self) -> Result<(), std::io::Error> {
         match self {
             OutFile::Regular(file) => file.shutdown().await,
-            OutFile::Gzip(gzip) => gzip.shutdown().await,
+            OutFile::Compressed {
+                file,
+                compressor,
+                output,
+            } => {
+                if let Some(compressor) = compressor.take() {
+                    compressor.finish()?;
+                }
+                file.write_all(&output.take()).await?;
+                file.shutdown().await
+            }
         }
     }
 
-    async fn write_all(&mut self, src: &[u8]) -> Result<(), std::io::Error> {
+    /// Returns the number of bytes written to the file, which differs from
+    /// the length of `src` when compressing.
+    async fn write_all(&mut self, src: &[u8]) -> Result<u64, std::io::Error> {
         match self {
-            OutFile::Regular(file) => file.write_all(src).await,
-            OutFile::Gzip(gzip) => gzip.write_all(src).await,
+            OutFile::Regular(file) => {
+                file.write_all(src).await?;
+                Ok(src.len() as u64)
+            }
+            OutFile::Compressed {
+                file,
+                compressor,
+                output,
+            } => {
+                compressor
+                    .as_mut()
+                    .ok_or_else(|| io::Error::new(io::ErrorKind::Other, "file is closed"))?
+                    .write_all(src)?;
+                let output = output.take();
+                file.write_all(&output).await?;
+                Ok(output.len() as u64)
+            }
         }
     }
 
@@ -129,6 +197,15 @@ impl OutFile {
     }
 }
 
+/// An open file, along with what's needed to decide when to rotate it.
+struct ActiveFile {
+    file: OutFile,
+    /// The size of the file on disk.
+    size: u64,
+    /// When the file is due to be rotated by age.
+    rotate_at: Option<SystemTime>,
+}
+
 #[async_trait::async_trait]
 #[typetag::serde(name = "file")]
 impl SinkConfig for FileSinkConfig {
@@ -136,7 +213,20 @@ impl SinkConfig for FileSinkConfig {
         &self,
         cx: SinkContext,
     ) -> crate::Result<(super::VectorSink, super::Healthcheck)> {
-        let sink = FileSink::new(&self, cx.acker());
+        if self.rotate.compression != Compression::None && self.compression != Compression::None {
+            return Err(Box::new(BuildError::DoubleCompression));
+        }
+        if !self.rotate.is_enabled()
+            && (self.retain.is_enabled() || self.rotate.compression != Compression::None)
+        {
+            return Err(Box::new(BuildError::RotationDisabled));
+        }
+
+        let config = FileSinkConfig {
+            encoding: self.encoding.loaded()?,
+            ..self.clone()
+        };
+        let sink = FileSink::new(&config, cx.acker());
         Ok((
             super::VectorSink::Stream(Box::new(sink)),
             future::ok(()).boxed(),
@@ -156,10 +246,12 @@ impl SinkConfig for FileSinkConfig {
 pub struct FileSink {
     acker: Acker,
     path: Template,
-    encoding: EncodingConfigWithDefault<Encoding>,
+    encoding: EncodingConfigWithDefault<StandardEncodings>,
     idle_timeout: Duration,
-    files: ExpiringHashMap<Bytes, OutFile>,
+    files: ExpiringHashMap<Bytes, ActiveFile>,
     compression: Compression,
+    rotate: RotateConfig,
+    retain: RetainConfig,
 }
 
 impl FileSink {
@@ -171,6 +263,8 @@ impl FileSink {
             idle_timeout: Duration::from_secs(config.idle_timeout_secs.unwrap_or(30)),
             files: ExpiringHashMap::default(),
             compression: config.compression,
+            rotate: config.rotate.clone(),
+            retain: config.retain.clone(),
         }
     }
 
@@ -203,8 +297,9 @@ impl FileSink {
                 event = input.next() => {
                     match event {
                         Some(event) => {
+                            let ingress = event.ingress();
                             self.process_event(event).await;
-                            self.acker.ack(1);
+                            self.acker.ack_with_ingress(1, ingress);
                         },
                         None => {
                             // If we got `None` - terminate the processing.
@@ -212,8 +307,8 @@ impl FileSink {
 
                             // Close all the open files.
                             debug!(message = "Closing all the open files.");
-                            for (path, file) in self.files.iter_mut() {
-                                if let Err(error) = file.close().await {
+                            for (path, active) in self.files.iter_mut() {
+                                if let Err(error) = active.file.close().await {
                                     error!(message = "Failed to close file.", path = ?path, %error);
                                 } else{
                                     trace!(message = "Successfully closed file.", path = ?path);
@@ -232,7 +327,7 @@ impl FileSink {
                         Some(Ok((mut expired_file, path))) => {
                             // We got an expired file. All we really want is to
                             // flush and close it.
-                            if let Err(error) = expired_file.close().await {
+                            if let Err(error) = expired_file.file.close().await {
                                 error!(message = "Failed to close file.", path = ?path, %error);
                             }
                             drop(expired_file); // ignore close error
@@ -263,31 +358,87 @@ impl FileSink {
         let next_deadline = self.deadline_at();
         trace!(message = "Computed next deadline.", next_deadline = ?next_deadline, path = ?path);
 
-        let file = if let Some(file) = self.files.reset_at(&path, next_deadline) {
+        if self.files.reset_at(&path, next_deadline).is_some() {
             trace!(message = "Working with an already opened file.", path = ?path);
-            file
-        } else {
-            trace!(message = "Opening new file.", ?path);
-            let file = match open_file(BytesPath::new(path.clone())).await {
-                Ok(file) => file,
-                Err(error) => {
-                    // We couldn't open the file for this event.
-                    // Maybe other events will work though! Just log
-                    // the error and skip this event.
-                    error!(message = "Unable to open the file.", path = ?path, %error);
-                    return;
-                }
-            };
+        } else if !self.open(&path, next_deadline).await {
+            return;
+        }
+
+        let active = self.files.get(&path).expect("file was just opened");
+        if self
+            .rotate
+            .is_due(active.size, active.rotate_at, SystemTime::now())
+        {
+            self.rotate_file(&path).await;
+            if !self.open(&path, next_deadline).await {
+                return;
+            }
+        }
 
-            let outfile = OutFile::new(file, self.compression);
+        let active = self.files.get_mut(&path).expect("file was just opened");
+        trace!(message = "Writing an event to file.", path = ?path);
+        match write_event_to_file(&mut active.file, event, &self.encoding).await {
+            Ok(written) => active.size += written,
+            Err(error) => error!(message = "Failed to write file.", path = ?path, %error),
+        }
+    }
 
-            self.files.insert_at(path.clone(), outfile, next_deadline);
-            self.files.get_mut(&path).unwrap()
+    /// Opens the file at `path` and tracks it until `deadline`. Returns
+    /// whether that succeeded; failures are logged.
+    async fn open(&mut self, path: &Bytes, deadline: Instant) -> bool {
+        trace!(message = "Opening new file.", ?path);
+        let file = match open_file(BytesPath::new(path.clone())).await {
+            Ok(file) => file,
+            Err(error) => {
+                // We couldn't open the file for this event.
+                // Maybe other events will work though! Just log
+                // the error and skip this event.
+                error!(message = "Unable to open the file.", path = ?path, %error);
+                return false;
+            }
         };
 
-        trace!(message = "Writing an event to file.", path = ?path);
-        if let Err(error) = write_event_to_file(file, event, &self.encoding).await {
-            error!(message = "Failed to write file.", path = ?path, %error);
+        // Appending to an existing file continues its segment.
+        let now = SystemTime::now();
+        let (size, created) = match file.metadata().await {
+            Ok(metadata) => (metadata.len(), metadata.created().unwrap_or(now)),
+            Err(_) => (0, now),
+        };
+
+        let file = match OutFile::new(file, self.compression) {
+            Ok(file) => file,
+            Err(error) => {
+                error!(message = "Unable to start compressing the file.", path = ?path, %error);
+                return false;
+            }
+        };
+
+        let active = ActiveFile {
+            file,
+            size,
+            rotate_at: self.rotate.deadline(created),
+        };
+        self.files.insert_at(path.clone(), active, deadline);
+        true
+    }
+
+    /// Closes the file at `path` and rotates it.
+    async fn rotate_file(&mut self, path: &Bytes) {
+        if let Some((mut active, _)) = self.files.remove(path) {
+            if let Err(error) = active.file.close().await {
+                error!(message = "Failed to close file.", path = ?path, %error);
+            }
+        }
+
+        let file_path = BytesPath::new(path.clone()).as_ref().to_path_buf();
+        match rotation::rotate(file_path, self.rotate.compression, self.retain.clone()).await {
+            Ok(rotated) => {
+                debug!(message = "Rotated file.", path = ?path, rotated_path = ?rotated.path);
+                for removed in rotated.removed {
+                    debug!(message = "Removed rotated file.", path = ?removed);
+                }
+            }
+            Err(error) => error!(message = "Failed to rotate file.", path = ?path, %error),
         }
     }
 }
@@ -308,39 +459,25 @@ async fn open_file(path: impl AsRef<std::path::Path>) -> std::io::Result<File> {
         .await
 }
 
-pub fn encode_event(encoding: &EncodingConfigWithDefault<Encoding>, mut event: Event) -> Vec<u8> {
-    encoding.apply_rules(&mut event);
-    let log = event.into_log();
-    match encoding.codec() {
-        Encoding::Ndjson => serde_json::to_vec(&log).expect("Unable to encode event as JSON."),
-        Encoding::Text => log
-            .get(log_schema().message_key())
-            .map(|v| v.to_string_lossy().into_bytes())
-            .unwrap_or_default(),
-    }
-}
-
 async fn write_event_to_file(
     file: &mut OutFile,
     event: Event,
-    encoding: &EncodingConfigWithDefault<Encoding>,
-) -> Result<(), std::io::Error> {
-    let mut buf = encode_event(encoding, event);
-    buf.push(b'\n');
+    encoding: &EncodingConfigWithDefault<StandardEncodings>,
+) -> Result<u64, std::io::Error> {
+    let buf = match encoding.encode(event) {
+        Ok(buf) => encoding.codec().frame(buf),
+        Err(error) => {
+            error!(message = "Failed to encode event; dropping event.", %error, rate_limit_secs = 30);
+            return Ok(0);
+        }
+    };
     file.write_all(&buf[..]).await
 }
 
-#[async_trait]
-impl StreamSink for FileSink {
-    async fn run(&mut self, input: BoxStream<'_, Event>) -> Result<(), ()> {
-        FileSink::run(self, input).await.expect("file sink error");
-        Ok(())
-    }
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::config::log_schema;
     use crate::test_util::{
         lines_from_file, lines_from_gzip_file, random_events_with_stream, random_lines_with_stream,
         temp_dir, temp_file, trace_init,
@@ -362,8 +499,10 @@ mod tests {
         let config = FileSinkConfig {
             path: template.clone().try_into().unwrap(),
             idle_timeout_secs: None,
-            encoding: Encoding::Text.into(),
+            encoding: StandardEncodings::Text.into(),
             compression: Compression::None,
+            rotate: Default::default(),
+            retain: Default::default(),
         };
 
         let mut sink = FileSink::new(&config, Acker::Null);
@@ -387,8 +526,10 @@ mod tests {
         let config = FileSinkConfig {
             path: template.clone().try_into().unwrap(),
             idle_timeout_secs: None,
-            encoding: Encoding::Text.into(),
-            compression: Compression::Gzip,
+            encoding: StandardEncodings::Text.into(),
+            compression: Compression::gzip_default(),
+            rotate: Default::default(),
+            retain: Default::default(),
         };
 
         let mut sink = FileSink::new(&config, Acker::Null);
@@ -403,6 +544,81 @@ mod tests {
         }
     }
 
+    #[tokio::test]
+    async fn single_partition_zstd() {
+        trace_init();
+
+        let template = temp_file();
+
+        let config = FileSinkConfig {
+            path: template.clone().try_into().unwrap(),
+            idle_timeout_secs: None,
+            encoding: StandardEncodings::Text.into(),
+            compression: Compression::zstd_default(),
+            rotate: Default::default(),
+            retain: Default::default(),
+        };
+
+        let mut sink = FileSink::new(&config, Acker::Null);
+        let (input, _) = random_lines_with_stream(100, 64);
+
+        let events = Box::pin(stream::iter(input.clone().into_iter().map(Event::from)));
+        sink.run(events).await.unwrap();
+
+        let output = zstd::decode_all(std::fs::File::open(template).unwrap()).unwrap();
+        let output = String::from_utf8(output).unwrap();
+        assert_eq!(output.lines().collect::<Vec<_>>(), input);
+    }
+
+    #[tokio::test]
+    async fn rotates_by_size() {
+        trace_init();
+
+        let directory = temp_dir();
+        let path = directory.join("app.log");
+
+        let config = FileSinkConfig {
+            path: path.clone().try_into().unwrap(),
+            idle_timeout_secs: None,
+            encoding: StandardEncodings::Text.into(),
+            compression: Compression::None,
+            rotate: RotateConfig {
+                max_bytes: Some(1000),
+                interval_secs: None,
+                compression: Compression::gzip_default(),
+            },
+            retain: RetainConfig {
+                max_files: Some(2),
+                max_age_secs: None,
+            },
+        };
+
+        let mut sink = FileSink::new(&config, Acker::Null);
+        // Each line takes 65 bytes, so every 16 lines fill a file.
+        let (input, _) = random_lines_with_stream(64, 100);
+
+        let events = Box::pin(stream::iter(input.clone().into_iter().map(Event::from)));
+        sink.run(events).await.unwrap();
+
+        assert_eq!(lines_from_file(&path), &input[96..]);
+
+        let mut rotated = std::fs::read_dir(&directory)
+            .unwrap()
+            .map(|entry| entry.unwrap().path())
+            .filter(|file| file != &path)
+            .collect::<Vec<_>>();
+        // Files rotated within the same millisecond are numbered.
+        rotated.sort_by_key(|file| file.file_stem().unwrap().to_owned());
+        assert_eq!(rotated.len(), 2);
+        assert!(rotated.iter().all(|file| file.extension().unwrap() == "gz"));
+
+        let output = rotated
+            .into_iter()
+            .flat_map(lines_from_gzip_file)
+            .collect::<Vec<_>>();
+        assert_eq!(output, &input[64..96]);
+    }
+
     #[tokio::test]
     async fn many_partitions() {
         trace_init();
@@ -417,8 +633,10 @@ mod tests {
         let config = FileSinkConfig {
             path: template.try_into().unwrap(),
             idle_timeout_secs: None,
-            encoding: Encoding::Text.into(),
+            encoding: StandardEncodings::Text.into(),
             compression: Compression::None,
+            rotate: Default::default(),
+            retain: Default::default(),
         };
 
         let mut sink = FileSink::new(&config, Acker::Null);
@@ -498,8 +716,10 @@ mod tests {
         let config = FileSinkConfig {
             path: template.clone().try_into().unwrap(),
             idle_timeout_secs: Some(1),
-            encoding: Encoding::Text.into(),
+            encoding: StandardEncodings::Text.into(),
             compression: Compression::None,
+            rotate: Default::default(),
+            retain: Default::default(),
         };
 
         let mut sink = FileSink::new(&config, Acker::Null);
@@ -0,0 +1,301 @@
+//! Rotation and retention of the files written by the sink.
+//!
+//! A file is rotated by renaming it to `<path>.<timestamp>`, where the
+//! timestamp is the UTC time of the rotation, so the next event written to
+//! `path` opens a new file. Rotated files can then be compressed, which adds
+//! the usual suffix of the algorithm, and the oldest ones removed.
+
+use crate::sinks::util::{Compression, Compressor};
+use chrono::{DateTime, NaiveDateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::{
+    ffi::OsString,
+    fs::{self, File},
+    io,
+    path::{Path, PathBuf},
+    time::{Duration, SystemTime},
+};
+use tokio::task;
+
+const TIMESTAMP_FORMAT: &str = "%Y%m%dT%H%M%S%.3fZ";
+/// The length of a timestamp in `TIMESTAMP_FORMAT`.
+const TIMESTAMP_LEN: usize = 20;
+
+#[derive(Deserialize, Serialize, Debug, Clone, Default, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct RotateConfig {
+    /// Rotate a file once it holds at least this many bytes.
+    pub max_bytes: Option<u64>,
+    /// Rotate a file once this long has passed since it was created.
+    pub interval_secs: Option<u64>,
+    /// The compression applied to rotated files.
+    #[serde(
+        default,
+        skip_serializing_if = "crate::serde::skip_serializing_if_default"
+    )]
+    pub compression: Compression,
+}
+
+impl RotateConfig {
+    pub fn is_enabled(&self) -> bool {
+        self.max_bytes.is_some() || self.interval_secs.is_some()
+    }
+
+    /// When a file created at `created` is due to be rotated, if it is ever
+    /// rotated by age.
+    pub fn deadline(&self, created: SystemTime) -> Option<SystemTime> {
+        self.interval_secs
+            .map(|secs| created + Duration::from_secs(secs))
+    }
+
+    /// Whether a file holding `size` bytes should be rotated before anything
+    /// else is written to it. Empty files are never rotated.
+    pub fn is_due(&self, size: u64, deadline: Option<SystemTime>, now: SystemTime) -> bool {
+        size > 0
+            && (self.max_bytes.map_or(false, |max_bytes| size >= max_bytes)
+                || deadline.map_or(false, |deadline| now >= deadline))
+    }
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Default, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct RetainConfig {
+    /// The most rotated files kept for each path. The oldest are removed
+    /// first.
+    pub max_files: Option<usize>,
+    /// Rotated files are removed once this long has passed since their
+    /// rotation.
+    pub max_age_secs: Option<u64>,
+}
+
+impl RetainConfig {
+    pub fn is_enabled(&self) -> bool {
+        self.max_files.is_some() || self.max_age_secs.is_some()
+    }
+}
+
+#[derive(Debug)]
+pub struct Rotated {
+    /// Where the rotated file ended up.
+    pub path: PathBuf,
+    /// The older rotated files removed afterwards.
+    pub removed: Vec<PathBuf>,
+}
+
+/// Rotates the file at `path`, which must already be closed, and applies
+/// `retain` to the files rotated from it.
+pub async fn rotate(
+    path: PathBuf,
+    compression: Compression,
+    retain: RetainConfig,
+) -> io::Result<Rotated> {
+    task::spawn_blocking(move || {
+        let rotated = rename(&path, Utc::now(), compression)?;
+        let rotated = match compression {
+            Compression::None => rotated,
+            compression => compress(rotated, compression)?,
+        };
+        let removed = remove_expired(&path, &retain, Utc::now())?;
+
+        Ok(Rotated {
+            path: rotated,
+            removed,
+        })
+    })
+    .await
+    .map_err(|error| io::Error::new(io::ErrorKind::Other, error))?
+}
+
+fn rename(path: &Path, now: DateTime<Utc>, compression: Compression) -> io::Result<PathBuf> {
+    let base = with_suffix(path, &now.format(TIMESTAMP_FORMAT).to_string());
+
+    // Rotating twice within a millisecond would reuse a name, so number any
+    // further files.
+    let taken = |path: &Path| {
+        path.exists()
+            || compression
+                .file_suffix()
+                .map_or(false, |suffix| with_suffix(path, suffix).exists())
+    };
+    let mut rotated = base.clone();
+    let mut n = 0;
+    while taken(&rotated) {
+        n += 1;
+        rotated = with_suffix(&base, &n.to_string());
+    }
+
+    fs::rename(path, &rotated)?;
+    Ok(rotated)
+}
+
+/// Replaces the file at `path` with a compressed copy.
+fn compress(path: PathBuf, compression: Compression) -> io::Result<PathBuf> {
+    let suffix = compression
+        .file_suffix()
+        .expect("Compression must be enabled");
+    let compressed = with_suffix(&path, suffix);
+    // Write elsewhere first, so a partial file never has the final name.
+    let partial = with_suffix(&compressed, "tmp");
+
+    let mut input = File::open(&path)?;
+    let mut compressor = Compressor::new(File::create(&partial)?, compression)?;
+    io::copy(&mut input, &mut compressor)?;
+    compressor.finish()?.sync_all()?;
+
+    fs::rename(&partial, &compressed)?;
+    fs::remove_file(&path)?;
+    Ok(compressed)
+}
+
+/// Removes the files rotated from `path` that `retain` no longer keeps.
+fn remove_expired(
+    path: &Path,
+    retain: &RetainConfig,
+    now: DateTime<Utc>,
+) -> io::Result<Vec<PathBuf>> {
+    if !retain.is_enabled() {
+        return Ok(Vec::new());
+    }
+
+    let mut rotated = list_rotated(path)?;
+    // Newest first.
+    rotated.sort_by(|a, b| b.0.cmp(&a.0));
+
+    let max_age = retain
+        .max_age_secs
+        .map(|secs| chrono::Duration::seconds(secs as i64));
+    let mut removed = Vec::new();
+    for (index, ((rotated_at, _), file)) in rotated.into_iter().enumerate() {
+        let too_many = retain
+            .max_files
+            .map_or(false, |max_files| index >= max_files);
+        let too_old = max_age.map_or(false, |max_age| now - rotated_at > max_age);
+        if !too_many && !too_old {
+            continue;
+        }
+
+        match fs::remove_file(&file) {
+            Ok(()) => removed.push(file),
+            Err(error) if error.kind() == io::ErrorKind::NotFound => (),
+            Err(error) => return Err(error),
+        }
+    }
+
+    Ok(removed)
+}
+
+/// Lists the files rotated from `path`, with the time of their rotation and
+/// their number among the files rotated at that time.
+fn list_rotated(path: &Path) -> io::Result<Vec<((DateTime<Utc>, u32), PathBuf)>> {
+    let name = match path.file_name().and_then(|name| name.to_str()) {
+        Some(name) => format!("{}.", name),
+        None => return Ok(Vec::new()),
+    };
+    let dir = match path.parent() {
+        Some(dir) if dir != Path::new("") => dir,
+        _ => Path::new("."),
+    };
+
+    let mut rotated = Vec::new();
+    for entry in fs::read_dir(dir)? {
+        let entry = entry?;
+        let file_name = entry.file_name();
+        let rotated_at = file_name
+            .to_str()
+            .filter(|file_name| !file_name.ends_with(".tmp"))
+            .and_then(|file_name| file_name.strip_prefix(name.as_str()))
+            .and_then(parse_rotated_suffix);
+        if let Some(rotated_at) = rotated_at {
+            rotated.push((rotated_at, entry.path()));
+        }
+    }
+
+    Ok(rotated)
+}
+
+/// Parses the part of a rotated file's name following the original name:
+/// the timestamp, then the optional number and compression suffix.
+fn parse_rotated_suffix(suffix: &str) -> Option<(DateTime<Utc>, u32)> {
+    let timestamp = suffix.get(..TIMESTAMP_LEN)?;
+    let rest = &suffix[TIMESTAMP_LEN..];
+    if !rest.is_empty() && !rest.starts_with('.') {
+        return None;
+    }
+
+    let timestamp = NaiveDateTime::parse_from_str(timestamp, TIMESTAMP_FORMAT).ok()?;
+    let number = rest
+        .split('.')
+        .nth(1)
+        .and_then(|number| number.parse().ok())
+        .unwrap_or(0);
+    Some((DateTime::from_utc(timestamp, Utc), number))
+}
+
+fn with_suffix(path: &Path, suffix: &str) -> PathBuf {
+    let mut name = OsString::from(path.as_os_str());
+    name.push(".");
+    name.push(suffix);
+    PathBuf::from(name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::temp_dir;
+    use chrono::TimeZone;
+
+    #[test]
+    fn removes_old_and_extra_files() {
+        let dir = temp_dir();
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("app.log");
+
+        let rotated_at = |day| Utc.ymd(2020, 10, day).and_hms_milli(12, 0, 0, 0);
+        let mut files = Vec::new();
+        for day in 1..=5 {
+            fs::write(&path, "line\n").unwrap();
+            files.push(rename(&path, rotated_at(day), Compression::None).unwrap());
+        }
+        // Neither rotated from `app.log`, nor in the rotated format.
+        fs::write(dir.join("app.log.old"), "").unwrap();
+        fs::write(dir.join("app.logs.20201001T120000.000Z"), "").unwrap();
+
+        let retain = RetainConfig {
+            max_files: Some(3),
+            max_age_secs: Some(3 * 86400 + 60),
+        };
+        let removed = remove_expired(&path, &retain, rotated_at(5)).unwrap();
+
+        // The oldest is too old, and the next beyond `max_files`.
+        assert_eq!(removed.len(), 2);
+        assert!(!files[0].exists() && !files[1].exists());
+        assert!(files[2..].iter().all(|file| file.exists()));
+        assert!(dir.join("app.log.old").exists());
+
+        let retain = RetainConfig {
+            max_files: None,
+            max_age_secs: Some(86400),
+        };
+        let removed = remove_expired(&path, &retain, rotated_at(5)).unwrap();
+        assert_eq!(removed, vec![files[2].clone()]);
+    }
+
+    #[test]
+    fn numbers_simultaneous_rotations() {
+        let dir = temp_dir();
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("app.log");
+        let now = Utc::now();
+
+        fs::write(&path, "first\n").unwrap();
+        let first = rename(&path, now, Compression::None).unwrap();
+        fs::write(&path, "second\n").unwrap();
+        let second = rename(&path, now, Compression::None).unwrap();
+
+        assert_eq!(second, with_suffix(&first, "1"));
+        let mut rotated = list_rotated(&path).unwrap();
+        rotated.sort();
+        assert_eq!(rotated[0].1, first);
+        assert_eq!(rotated[1], (((rotated[0].0).0, 1), second));
+    }
+}
@@ -1,4 +1,6 @@
 use super::{healthcheck_response, GcpAuthConfig, GcpCredentials, Scope};
+#[cfg(feature = "parquet")]
+use crate::sinks::util::buffer::parquet::ParquetBuffer;
 use crate::{
     config::{DataType, GenerateConfig, SinkConfig, SinkContext, SinkDescription},
     event::Event,
@@ -6,10 +8,13 @@ use crate::{
     serde::to_string,
     sinks::{
         util::{
+            buffer::parquet::ParquetConfig,
             encoding::{EncodingConfig, EncodingConfiguration},
+            http::retry_after,
             retries::{RetryAction, RetryLogic},
-            BatchConfig, BatchSettings, Buffer, Compression, InFlightLimit, PartitionBatchSink,
-            PartitionBuffer, PartitionInnerBuffer, ServiceBuilderExt, TowerRequestConfig,
+            BatchConfig, BatchSettings, Buffer, Compression, EncodedSink, InFlightLimit,
+            PartitionBatchSink, PartitionBuffer, PartitionInnerBuffer, ServiceBuilderExt,
+            TowerRequestConfig,
         },
         Healthcheck, VectorSink,
     },
@@ -19,7 +24,7 @@ use crate::{
 use bytes::Bytes;
 use chrono::Utc;
 use futures::FutureExt;
-use futures01::{stream::iter_ok, Sink};
+use futures01::Sink;
 use http::{StatusCode, Uri};
 use hyper::{
     header::{HeaderName, HeaderValue},
@@ -30,6 +35,7 @@ use serde::{Deserialize, Serialize};
 use snafu::{ResultExt, Snafu};
 use std::collections::HashMap;
 use std::convert::TryFrom;
+use std::sync::Arc;
 use std::task::Poll;
 
 use tower::{Service, ServiceBuilder};
@@ -65,6 +71,8 @@ pub struct GcsSinkConfig {
     filename_append_uuid: Option<bool>,
     filename_extension: Option<String>,
     encoding: EncodingConfig<Encoding>,
+    /// Writes objects as Parquet files, instead of encoding each event.
+    parquet: Option<ParquetConfig>,
     #[serde(default)]
     compression: Compression,
     #[serde(default)]
@@ -88,6 +96,7 @@ fn default_config(e: Encoding) -> GcsSinkConfig {
         filename_append_uuid: Default::default(),
         filename_extension: Default::default(),
         encoding: e.into(),
+        parquet: None,
         compression: Compression::gzip_default(),
         batch: Default::default(),
         request: Default::default(),
@@ -190,14 +199,14 @@ enum HealthcheckError {
 }
 
 impl GcsSink {
-    async fn new(config: &GcsSinkConfig, _cx: &SinkContext) -> crate::Result<Self> {
+    async fn new(config: &GcsSinkConfig, cx: &SinkContext) -> crate::Result<Self> {
         let creds = config
             .auth
             .make_credentials(Scope::DevStorageReadWrite)
             .await?;
         let settings = RequestSettings::new(config)?;
         let tls = TlsSettings::from_options(&config.tls)?;
-        let client = HttpClient::new(tls)?;
+        let client = HttpClient::new(tls, cx.proxy())?;
         let base_url = format!("{}{}/", BASE_URL, config.bucket);
         let bucket = config.bucket.clone();
         Ok(GcsSink {
@@ -210,17 +219,22 @@ impl GcsSink {
     }
 
     fn service(self, config: &GcsSinkConfig, cx: &SinkContext) -> crate::Result<VectorSink> {
-        let request = config.request.unwrap_with(&REQUEST_DEFAULTS);
+        let request = config
+            .request
+            .unwrap_with(&REQUEST_DEFAULTS)
+            .when_full(cx.when_full());
         let encoding = config.encoding.clone();
 
-        let batch = BatchSettings::default()
-            .bytes(bytesize::mib(10u64))
-            .timeout(300)
-            .parse_config(config.batch)?;
-
         let key_prefix = config.key_prefix.as_deref().unwrap_or("date=%F/");
         let key_prefix = Template::try_from(key_prefix).context(KeyPrefixTemplate)?;
 
+        let parquet = config
+            .parquet
+            .as_ref()
+            .map(ParquetConfig::build)
+            .transpose()?
+            .map(Arc::new);
+
         let settings = self.settings.clone();
 
         let svc = ServiceBuilder::new()
@@ -228,13 +242,55 @@ impl GcsSink {
             .settings(request, GcsRetryLogic)
             .service(self);
 
-        let buffer = PartitionBuffer::new(Buffer::new(batch.size, config.compression));
-
-        let sink = PartitionBatchSink::new(svc, buffer, batch.timeout, cx.acker())
-            .sink_map_err(|error| error!(message = "Fatal gcp_cloud_storage error.", %error))
-            .with_flat_map(move |e| iter_ok(encode_event(e, &key_prefix, &encoding)));
+        let sink: Box<dyn Sink<SinkItem = Event, SinkError = ()> + Send> = match parquet {
+            #[cfg(feature = "parquet")]
+            Some(schema) => {
+                let batch = BatchSettings::default()
+                    .bytes(bytesize::mib(10u64))
+                    .timeout(300)
+                    .parse_config(config.batch)?;
+                let buffer = PartitionBuffer::new(ParquetBuffer::new(batch.size, schema));
+
+                Box::new(
+                    PartitionBatchSink::new(
+                        svc,
+                        buffer,
+                        batch.timeout,
+                        cx.acker(),
+                        cx.flush_signal(),
+                    )
+                    .encode_events(move |e| partition_event(e, &key_prefix, &encoding))
+                    .sink_map_err(
+                        |error| error!(message = "Fatal gcp_cloud_storage error.", %error),
+                    ),
+                )
+            }
+            #[cfg(not(feature = "parquet"))]
+            Some(_) => unreachable!("Parquet configs fail to build without the `parquet` feature"),
+            None => {
+                let batch = BatchSettings::default()
+                    .bytes(bytesize::mib(10u64))
+                    .timeout(300)
+                    .parse_config(config.batch)?;
+                let buffer = PartitionBuffer::new(Buffer::new(batch.size, config.compression));
+
+                Box::new(
+                    PartitionBatchSink::new(
+                        svc,
+                        buffer,
+                        batch.timeout,
+                        cx.acker(),
+                        cx.flush_signal(),
+                    )
+                    .encode_events(move |e| encode_event(e, &key_prefix, &encoding))
+                    .sink_map_err(
+                        |error| error!(message = "Fatal gcp_cloud_storage error.", %error),
+                    ),
+                )
+            }
+        };
 
-        Ok(VectorSink::Futures01Sink(Box::new(sink)))
+        Ok(VectorSink::Futures01Sink(sink))
     }
 
     async fn healthcheck(mut self) -> crate::Result<()> {
@@ -353,9 +409,17 @@ impl RequestSettings {
         let acl = config
             .acl
             .map(|acl| HeaderValue::from_str(&to_string(acl)).unwrap());
-        let content_type = HeaderValue::from_str(config.encoding.codec().content_type()).unwrap();
-        let content_encoding = config
-            .compression
+        // Parquet files compress their columns themselves.
+        let compression = match config.parquet {
+            Some(_) => Compression::None,
+            None => config.compression,
+        };
+        let content_type = match config.parquet {
+            Some(_) => "application/octet-stream",
+            None => config.encoding.codec().content_type(),
+        };
+        let content_type = HeaderValue::from_str(content_type).unwrap();
+        let content_encoding = compression
             .content_encoding()
             .map(|ce| HeaderValue::from_str(&to_string(ce)).unwrap());
         let storage_class = config.storage_class.unwrap_or_default();
@@ -370,10 +434,13 @@ impl RequestSettings {
                     .collect::<Result<Vec<_>, _>>()
             })
             .unwrap_or_else(|| Ok(vec![]))?;
-        let extension = config
-            .filename_extension
-            .clone()
-            .unwrap_or_else(|| config.compression.extension().into());
+        let extension = config.filename_extension.clone().unwrap_or_else(|| {
+            match config.parquet {
+                Some(_) => "parquet",
+                None => compression.extension(),
+            }
+            .into()
+        });
         let time_format = config
             .filename_time_format
             .clone()
@@ -400,13 +467,9 @@ fn make_header((name, value): (&String, &String)) -> crate::Result<(HeaderName,
     ))
 }
 
-fn encode_event(
-    mut event: Event,
-    key_prefix: &Template,
-    encoding: &EncodingConfig<Encoding>,
-) -> Option<PartitionInnerBuffer<Vec<u8>, Bytes>> {
-    let key = key_prefix
-        .render_string(&event)
+fn render_key(event: &Event, key_prefix: &Template) -> Option<Bytes> {
+    key_prefix
+        .render(event)
         .map_err(|missing_keys| {
             warn!(
                 message = "Keys do not exist on the event; dropping event.",
@@ -414,7 +477,15 @@ fn encode_event(
                 rate_limit_secs = 30,
             );
         })
-        .ok()?;
+        .ok()
+}
+
+fn encode_event(
+    mut event: Event,
+    key_prefix: &Template,
+    encoding: &EncodingConfig<Encoding>,
+) -> Option<PartitionInnerBuffer<Vec<u8>, Bytes>> {
+    let key = render_key(&event, key_prefix)?;
     encoding.apply_rules(&mut event);
     let log = event.into_log();
     let bytes = match encoding.codec() {
@@ -434,7 +505,21 @@ fn encode_event(
         }
     };
 
-    Some(PartitionInnerBuffer::new(bytes, key.into()))
+    Some(PartitionInnerBuffer::new(bytes, key))
+}
+
+/// Partitions an event that is written to a Parquet file, which encodes it
+/// along with the rest of its batch.
+#[cfg(feature = "parquet")]
+fn partition_event(
+    mut event: Event,
+    key_prefix: &Template,
+    encoding: &EncodingConfig<Encoding>,
+) -> Option<PartitionInnerBuffer<Event, Bytes>> {
+    let key = render_key(&event, key_prefix)?;
+    encoding.apply_rules(&mut event);
+
+    Some(PartitionInnerBuffer::new(event, key))
 }
 
 #[derive(Clone)]
@@ -453,7 +538,9 @@ impl RetryLogic for GcsRetryLogic {
         let status = response.status();
 
         match status {
-            StatusCode::TOO_MANY_REQUESTS => RetryAction::Retry("too many requests".into()),
+            StatusCode::TOO_MANY_REQUESTS => {
+                RetryAction::Throttled("too many requests".into(), retry_after(response))
+            }
             StatusCode::NOT_IMPLEMENTED => {
                 RetryAction::DontRetry("endpoint not implemented".into())
             }
@@ -570,4 +657,17 @@ mod tests {
         );
         assert_ne!(req.key, "key/date.log.gz".to_string());
     }
+
+    #[test]
+    fn gcs_parquet_request_settings() {
+        let settings = RequestSettings::new(&GcsSinkConfig {
+            parquet: Some(toml::from_str(r#"schema.message = "string""#).unwrap()),
+            ..default_config(Encoding::Ndjson)
+        })
+        .unwrap();
+
+        assert_eq!(settings.extension, "parquet");
+        assert_eq!(settings.content_type, "application/octet-stream");
+        assert!(settings.content_encoding.is_none());
+    }
 }
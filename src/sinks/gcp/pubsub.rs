@@ -5,7 +5,9 @@ use crate::{
     http::HttpClient,
     sinks::{
         util::{
-            encoding::{EncodingConfigWithDefault, EncodingConfiguration},
+            encoding::{
+                Encoder, EncodingConfigWithDefault, EncodingConfiguration, StandardEncodings,
+            },
             http::{BatchedHttpSink, HttpSink},
             BatchConfig, BatchSettings, BoxedRawValue, JsonArrayBuffer, TowerRequestConfig,
         },
@@ -49,12 +51,20 @@ pub struct PubsubConfig {
     pub tls: Option<TlsOptions>,
 }
 
+/// The encoding of each message's `data`, which defaults to JSON.
 #[derive(Deserialize, Serialize, Debug, Eq, PartialEq, Clone, Derivative)]
-#[serde(rename_all = "snake_case")]
+#[serde(transparent)]
 #[derivative(Default)]
-pub enum Encoding {
-    #[derivative(Default)]
-    Default,
+pub struct Encoding(#[derivative(Default(value = "StandardEncodings::Json"))] StandardEncodings);
+
+impl Encoder for Encoding {
+    fn encode_event(&self, event: Event) -> crate::Result<Vec<u8>> {
+        self.0.encode_event(event)
+    }
+
+    fn load(&mut self) -> crate::Result<()> {
+        self.0.load()
+    }
 }
 
 inventory::submit! {
@@ -80,9 +90,12 @@ impl SinkConfig for PubsubConfig {
             .events(1000)
             .timeout(1)
             .parse_config(self.batch)?;
-        let request_settings = self.request.unwrap_with(&Default::default());
+        let request_settings = self
+            .request
+            .unwrap_with(&Default::default())
+            .when_full(cx.when_full());
         let tls_settings = TlsSettings::from_options(&self.tls)?;
-        let client = HttpClient::new(tls_settings)?;
+        let client = HttpClient::new(tls_settings, cx.proxy())?;
 
         let healthcheck = healthcheck(client.clone(), sink.uri("")?, sink.creds.clone()).boxed();
 
@@ -93,6 +106,7 @@ impl SinkConfig for PubsubConfig {
             batch_settings.timeout,
             client,
             cx.acker(),
+            cx.flush_signal(),
         )
         .sink_map_err(|error| error!(message = "Fatal gcp_pubsub sink error.", %error));
 
@@ -134,7 +148,7 @@ impl PubsubSink {
 
         Ok(Self {
             api_key: config.auth.api_key.clone(),
-            encoding: config.encoding.clone(),
+            encoding: config.encoding.loaded()?,
             creds,
             uri_base,
         })
@@ -156,12 +170,17 @@ impl HttpSink for PubsubSink {
     type Input = Value;
     type Output = Vec<BoxedRawValue>;
 
-    fn encode_event(&self, mut event: Event) -> Option<Self::Input> {
-        self.encoding.apply_rules(&mut event);
+    fn encode_event(&self, event: Event) -> Option<Self::Input> {
         // Each event needs to be base64 encoded, and put into a JSON object
         // as the `data` item.
-        let json = serde_json::to_string(&event.into_log()).unwrap();
-        Some(json!({ "data": base64::encode(&json) }))
+        let data = self
+            .encoding
+            .encode(event)
+            .map_err(|error| {
+                error!(message = "Failed to encode event; dropping event.", %error, rate_limit_secs = 30)
+            })
+            .ok()?;
+        Some(json!({ "data": base64::encode(&data) }))
     }
 
     async fn build_request(&self, events: Self::Output) -> crate::Result<Request<Vec<u8>>> {
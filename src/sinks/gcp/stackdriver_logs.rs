@@ -119,9 +119,12 @@ impl SinkConfig for StackdriverConfig {
             .bytes(bytesize::kib(5000u64))
             .timeout(1)
             .parse_config(self.batch)?;
-        let request = self.request.unwrap_with(&REQUEST_DEFAULTS);
+        let request = self
+            .request
+            .unwrap_with(&REQUEST_DEFAULTS)
+            .when_full(cx.when_full());
         let tls_settings = TlsSettings::from_options(&self.tls)?;
-        let client = HttpClient::new(tls_settings)?;
+        let client = HttpClient::new(tls_settings, cx.proxy())?;
 
         let sink = StackdriverSink {
             config: self.clone(),
@@ -138,6 +141,7 @@ impl SinkConfig for StackdriverConfig {
             batch.timeout,
             client,
             cx.acker(),
+            cx.flush_signal(),
         )
         .sink_map_err(|error| error!(message = "Fatal gcp_stackdriver_logs sink error.", %error));
 
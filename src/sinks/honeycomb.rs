@@ -53,13 +53,16 @@ impl SinkConfig for HoneycombConfig {
         &self,
         cx: SinkContext,
     ) -> crate::Result<(super::VectorSink, super::Healthcheck)> {
-        let request_settings = self.request.unwrap_with(&TowerRequestConfig::default());
+        let request_settings = self
+            .request
+            .unwrap_with(&TowerRequestConfig::default())
+            .when_full(cx.when_full());
         let batch_settings = BatchSettings::default()
             .bytes(bytesize::kib(100u64))
             .timeout(1)
             .parse_config(self.batch)?;
 
-        let client = HttpClient::new(None)?;
+        let client = HttpClient::new(None, cx.proxy())?;
 
         let sink = BatchedHttpSink::new(
             self.clone(),
@@ -68,6 +71,7 @@ impl SinkConfig for HoneycombConfig {
             batch_settings.timeout,
             client.clone(),
             cx.acker(),
+            cx.flush_signal(),
         )
         .sink_map_err(|error| error!(message = "Fatal honeycomb sink error.", %error));
 
@@ -3,7 +3,7 @@ use crate::{
     event::Event,
     http::{Auth, HttpClient},
     sinks::util::{
-        encoding::{EncodingConfig, EncodingConfiguration},
+        encoding::{Encoder, EncodingConfig, EncodingConfiguration, StandardEncodings},
         http::{BatchedHttpSink, HttpSink},
         BatchConfig, BatchSettings, Buffer, Compression, InFlightLimit, TowerRequestConfig,
         UriSerde,
@@ -46,7 +46,7 @@ pub struct HttpSinkConfig {
     pub headers: Option<IndexMap<String, String>>,
     #[serde(default)]
     pub compression: Compression,
-    pub encoding: EncodingConfig<Encoding>,
+    pub encoding: EncodingConfig<StandardEncodings>,
     #[serde(default)]
     pub batch: BatchConfig,
     #[serde(default)]
@@ -55,7 +55,7 @@ pub struct HttpSinkConfig {
 }
 
 #[cfg(test)]
-fn default_config(e: Encoding) -> HttpSinkConfig {
+fn default_config(e: StandardEncodings) -> HttpSinkConfig {
     HttpSinkConfig {
         uri: Default::default(),
         method: Default::default(),
@@ -88,14 +88,6 @@ pub enum HttpMethod {
     Put,
 }
 
-#[derive(Deserialize, Serialize, Debug, Eq, PartialEq, Clone)]
-#[serde(rename_all = "snake_case")]
-pub enum Encoding {
-    Text,
-    Ndjson,
-    Json,
-}
-
 inventory::submit! {
     SinkDescription::new::<HttpSinkConfig>("http")
 }
@@ -119,17 +111,21 @@ impl SinkConfig for HttpSinkConfig {
     ) -> crate::Result<(super::VectorSink, super::Healthcheck)> {
         validate_headers(&self.headers, &self.auth)?;
         let tls = TlsSettings::from_options(&self.tls)?;
-        let client = HttpClient::new(tls)?;
+        let client = HttpClient::new(tls, cx.proxy())?;
 
         let mut config = self.clone();
         config.uri = build_uri(config.uri.clone()).into();
+        config.encoding = config.encoding.loaded()?;
 
         let compression = config.compression;
         let batch = BatchSettings::default()
             .bytes(bytesize::mib(10u64))
             .timeout(1)
             .parse_config(config.batch)?;
-        let request = config.request.unwrap_with(&REQUEST_DEFAULTS);
+        let request = config
+            .request
+            .unwrap_with(&REQUEST_DEFAULTS)
+            .when_full(cx.when_full());
 
         let sink = BatchedHttpSink::new(
             config,
@@ -138,6 +134,7 @@ impl SinkConfig for HttpSinkConfig {
             batch.timeout,
             client.clone(),
             cx.acker(),
+            cx.flush_signal(),
         )
         .sink_map_err(|error| error!(message = "Fatal HTTP sink error.", %error));
 
@@ -166,43 +163,42 @@ impl HttpSink for HttpSinkConfig {
     type Input = Vec<u8>;
     type Output = Vec<u8>;
 
-    fn encode_event(&self, mut event: Event) -> Option<Self::Input> {
-        self.encoding.apply_rules(&mut event);
-        let event = event.into_log();
-
-        let body = match &self.encoding.codec() {
-            Encoding::Text => {
-                if let Some(v) = event.get(crate::config::log_schema().message_key()) {
-                    let mut b = v.to_string_lossy().into_bytes();
-                    b.push(b'\n');
-                    b
-                } else {
-                    warn!(
-                        message = "Event missing the message key; dropping event.",
-                        rate_limit_secs = 30,
-                    );
-                    return None;
-                }
-            }
-
-            Encoding::Ndjson => {
-                let mut b = serde_json::to_vec(&event)
-                    .map_err(|error| panic!("Unable to encode into JSON: {}", error))
-                    .ok()?;
-                b.push(b'\n');
-                b
-            }
+    fn encode_event(&self, event: Event) -> Option<Self::Input> {
+        let codec = self.encoding.codec();
+        if *codec == StandardEncodings::Text
+            && event
+                .as_log()
+                .get(crate::config::log_schema().message_key())
+                .is_none()
+        {
+            warn!(
+                message = "Event missing the message key; dropping event.",
+                rate_limit_secs = 30,
+            );
+            return None;
+        }
 
-            Encoding::Json => {
-                let mut b = serde_json::to_vec(&event)
-                    .map_err(|error| panic!("Unable to encode into JSON: {}", error))
-                    .ok()?;
-                b.push(b',');
-                b
+        let mut body = self
+            .encoding
+            .encode(event)
+            .map_err(|error| {
+                warn!(
+                    message = "Failed to encode event; dropping event.",
+                    %error,
+                    rate_limit_secs = 30,
+                )
+            })
+            .ok()?;
+
+        // `json` batches are sent as an array, everything else as a stream
+        // of delimited events.
+        match codec {
+            StandardEncodings::Json => {
+                body.push(b',');
+                Some(body)
             }
-        };
-
-        Some(body)
+            _ => Some(codec.frame(body)),
+        }
     }
 
     async fn build_request(&self, mut body: Self::Output) -> crate::Result<http::Request<Vec<u8>>> {
@@ -212,16 +208,13 @@ impl HttpSink for HttpSinkConfig {
         };
         let uri: Uri = self.uri.clone().into();
 
-        let ct = match self.encoding.codec() {
-            Encoding::Text => "text/plain",
-            Encoding::Ndjson => "application/x-ndjson",
-            Encoding::Json => {
-                body.insert(0, b'[');
-                body.pop(); // remove trailing comma from last record
-                body.push(b']');
-                "application/json"
-            }
-        };
+        let codec = self.encoding.codec();
+        if *codec == StandardEncodings::Json {
+            body.insert(0, b'[');
+            body.pop(); // remove trailing comma from last record
+            body.push(b']');
+        }
+        let ct = codec.content_type();
 
         let mut builder = Request::builder()
             .method(method)
@@ -325,10 +318,10 @@ mod tests {
 
     #[test]
     fn http_encode_event_text() {
-        let encoding = EncodingConfig::from(Encoding::Text);
+        let encoding = EncodingConfig::from(StandardEncodings::Text);
         let event = Event::from("hello world");
 
-        let mut config = default_config(Encoding::Text);
+        let mut config = default_config(StandardEncodings::Text);
         config.encoding = encoding;
         let bytes = config.encode_event(event).unwrap();
 
@@ -337,10 +330,10 @@ mod tests {
 
     #[test]
     fn http_encode_event_json() {
-        let encoding = EncodingConfig::from(Encoding::Ndjson);
+        let encoding = EncodingConfig::from(StandardEncodings::Ndjson);
         let event = Event::from("hello world");
 
-        let mut config = default_config(Encoding::Json);
+        let mut config = default_config(StandardEncodings::Json);
         config.encoding = encoding;
         let bytes = config.encode_event(event).unwrap();
 
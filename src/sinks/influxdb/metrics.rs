@@ -80,7 +80,7 @@ impl_generate_config_from_default!(InfluxDBConfig);
 impl SinkConfig for InfluxDBConfig {
     async fn build(&self, cx: SinkContext) -> crate::Result<(VectorSink, Healthcheck)> {
         let tls_settings = TlsSettings::from_options(&self.tls)?;
-        let client = HttpClient::new(tls_settings)?;
+        let client = HttpClient::new(tls_settings, cx.proxy())?;
         let healthcheck = healthcheck(
             self.clone().endpoint,
             self.clone().influxdb1_settings,
@@ -120,7 +120,10 @@ impl InfluxDBSvc {
             .events(20)
             .timeout(1)
             .parse_config(config.batch)?;
-        let request = config.request.unwrap_with(&REQUEST_DEFAULTS);
+        let request = config
+            .request
+            .unwrap_with(&REQUEST_DEFAULTS)
+            .when_full(cx.when_full());
 
         let uri = settings.write_uri(endpoint)?;
 
@@ -139,6 +142,7 @@ impl InfluxDBSvc {
                 MetricBuffer::new(batch.size),
                 batch.timeout,
                 cx.acker(),
+                cx.flush_signal(),
             )
             .sink_map_err(|error| error!(message = "Fatal influxdb sink error.", %error));
 
@@ -875,7 +879,7 @@ mod tests {
 #[cfg(test)]
 mod integration_tests {
     use crate::{
-        config::{SinkConfig, SinkContext},
+        config::{ProxyConfig, SinkConfig, SinkContext},
         event::metric::{Metric, MetricKind, MetricValue},
         http::HttpClient,
         sinks::influxdb::{
@@ -1001,7 +1005,7 @@ mod integration_tests {
             events.push(event);
         }
 
-        let client = HttpClient::new(None).unwrap();
+        let client = HttpClient::new(None, &ProxyConfig::default()).unwrap();
         let sink = InfluxDBSvc::new(config, cx, client).unwrap();
         sink.run(stream::iter(events)).await.unwrap();
 
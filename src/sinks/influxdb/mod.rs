@@ -755,6 +755,7 @@ mod tests {
 #[cfg(test)]
 mod integration_tests {
     use crate::{
+        config::ProxyConfig,
         http::HttpClient,
         sinks::influxdb::{
             healthcheck,
@@ -774,7 +775,7 @@ mod integration_tests {
             bucket: BUCKET.to_string(),
             token: TOKEN.to_string(),
         });
-        let client = HttpClient::new(None).unwrap();
+        let client = HttpClient::new(None, &ProxyConfig::default()).unwrap();
 
         healthcheck(endpoint, influxdb1_settings, influxdb2_settings, client)
             .unwrap()
@@ -793,7 +794,7 @@ mod integration_tests {
             bucket: BUCKET.to_string(),
             token: TOKEN.to_string(),
         });
-        let client = HttpClient::new(None).unwrap();
+        let client = HttpClient::new(None, &ProxyConfig::default()).unwrap();
 
         healthcheck(endpoint, influxdb1_settings, influxdb2_settings, client)
             .unwrap()
@@ -812,7 +813,7 @@ mod integration_tests {
             password: None,
         });
         let influxdb2_settings = None;
-        let client = HttpClient::new(None).unwrap();
+        let client = HttpClient::new(None, &ProxyConfig::default()).unwrap();
 
         healthcheck(endpoint, influxdb1_settings, influxdb2_settings, client)
             .unwrap()
@@ -831,7 +832,7 @@ mod integration_tests {
             password: None,
         });
         let influxdb2_settings = None;
-        let client = HttpClient::new(None).unwrap();
+        let client = HttpClient::new(None, &ProxyConfig::default()).unwrap();
 
         healthcheck(endpoint, influxdb1_settings, influxdb2_settings, client)
             .unwrap()
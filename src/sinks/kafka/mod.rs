@@ -4,7 +4,9 @@ use crate::{
     event::{Event, Value},
     kafka::{KafkaAuthConfig, KafkaCompression},
     serde::to_string,
-    sinks::util::encoding::{EncodingConfig, EncodingConfigWithDefault, EncodingConfiguration},
+    sinks::util::encoding::{
+        EncodingConfig, EncodingConfigWithDefault, EncodingConfiguration, StandardEncodings,
+    },
     template::{Template, TemplateError},
 };
 use futures::{
@@ -14,6 +16,7 @@ use futures::{
 use rdkafka::{
     consumer::{BaseConsumer, Consumer},
     error::{KafkaError, RDKafkaError},
+    message::OwnedHeaders,
     producer::{DeliveryFuture, FutureProducer, FutureRecord},
     ClientConfig,
 };
@@ -27,6 +30,9 @@ use std::{
     task::{Context, Poll},
 };
 use tokio::time::{delay_for, Duration};
+use transaction::{Transaction, TransactionError};
+
+mod transaction;
 
 // Maximum number of futures blocked by [send_result](https://docs.rs/rdkafka/0.24.0/rdkafka/producer/future_producer/struct.FutureProducer.html#method.send_result)
 const SEND_RESULT_LIMIT: usize = 5;
@@ -37,6 +43,12 @@ enum BuildError {
     KafkaCreateFailed { source: KafkaError },
     #[snafu(display("invalid topic template: {}", source))]
     TopicTemplate { source: TemplateError },
+    #[snafu(display("invalid partition key template: {}", source))]
+    PartitionKeyTemplate { source: TemplateError },
+    #[snafu(display("only one of `key_field` and `partition_key` can be set"))]
+    ConflictingKeys,
+    #[snafu(display("initializing transactions failed: {}", source))]
+    TransactionsInitFailed { source: TransactionError },
 }
 
 #[derive(Clone, Debug, Default, Deserialize, Serialize)]
@@ -44,7 +56,10 @@ pub struct KafkaSinkConfig {
     bootstrap_servers: String,
     topic: String,
     key_field: Option<String>,
-    encoding: EncodingConfigWithDefault<Encoding>,
+    partition_key: Option<String>,
+    #[serde(default)]
+    headers: HashMap<String, String>,
+    encoding: EncodingConfigWithDefault<StandardEncodings>,
     #[serde(default)]
     compression: KafkaCompression,
     #[serde(flatten)]
@@ -53,6 +68,9 @@ pub struct KafkaSinkConfig {
     socket_timeout_ms: u64,
     #[serde(default = "default_message_timeout_ms")]
     message_timeout_ms: u64,
+    transactional_id: Option<String>,
+    #[serde(default = "default_transaction_timeout_ms")]
+    transaction_timeout_ms: u64,
     librdkafka_options: Option<HashMap<String, String>>,
 }
 
@@ -64,20 +82,23 @@ fn default_message_timeout_ms() -> u64 {
     300000 // default in librdkafka
 }
 
-#[derive(Clone, Copy, Debug, Derivative, Deserialize, Serialize, Eq, PartialEq)]
-#[derivative(Default)]
-#[serde(rename_all = "snake_case")]
-pub enum Encoding {
-    #[derivative(Default)]
-    Text,
-    Json,
+fn default_transaction_timeout_ms() -> u64 {
+    60000 // default in librdkafka
+}
+
+/// Where the key of a record comes from.
+enum KafkaKey {
+    Field(String),
+    Template(Template),
 }
 
 pub struct KafkaSink {
     producer: Arc<FutureProducer>,
     topic: Template,
-    key_field: Option<String>,
-    encoding: EncodingConfig<Encoding>,
+    key: Option<KafkaKey>,
+    headers: HashMap<String, String>,
+    encoding: EncodingConfig<StandardEncodings>,
+    transaction: Option<Transaction>,
     delivery_fut: FuturesUnordered<BoxFuture<'static, (usize, Result<DeliveryFuture, KafkaError>)>>,
     in_flight: FuturesUnordered<
         BoxFuture<'static, (usize, Result<Result<(i32, i64), KafkaError>, Canceled>)>,
@@ -112,7 +133,7 @@ impl SinkConfig for KafkaSinkConfig {
         &self,
         cx: SinkContext,
     ) -> crate::Result<(super::VectorSink, super::Healthcheck)> {
-        let sink = KafkaSink::new(self.clone(), cx.acker())?;
+        let sink = KafkaSink::new(self.clone(), cx.acker()).await?;
         let hc = healthcheck(self.clone()).boxed();
         Ok((super::VectorSink::Sink(Box::new(sink)), hc))
     }
@@ -135,6 +156,16 @@ impl KafkaSinkConfig {
             .set("socket.timeout.ms", &self.socket_timeout_ms.to_string())
             .set("message.timeout.ms", &self.message_timeout_ms.to_string());
 
+        if let Some(transactional_id) = &self.transactional_id {
+            client_config
+                .set("transactional.id", transactional_id)
+                .set("enable.idempotence", "true")
+                .set(
+                    "transaction.timeout.ms",
+                    &self.transaction_timeout_ms.to_string(),
+                );
+        }
+
         self.auth.apply(&mut client_config)?;
 
         if let Some(ref librdkafka_options) = self.librdkafka_options {
@@ -148,13 +179,37 @@ impl KafkaSinkConfig {
 }
 
 impl KafkaSink {
-    fn new(config: KafkaSinkConfig, acker: Acker) -> crate::Result<Self> {
-        let producer = config.to_rdkafka()?.create().context(KafkaCreateFailed)?;
+    async fn new(config: KafkaSinkConfig, acker: Acker) -> crate::Result<Self> {
+        let key = match (&config.key_field, &config.partition_key) {
+            (Some(_), Some(_)) => return Err(BuildError::ConflictingKeys.into()),
+            (Some(field), None) => Some(KafkaKey::Field(field.clone())),
+            (None, Some(template)) => Some(KafkaKey::Template(
+                Template::try_from(template.as_str()).context(PartitionKeyTemplate)?,
+            )),
+            (None, None) => None,
+        };
+
+        let producer: Arc<FutureProducer> =
+            Arc::new(config.to_rdkafka()?.create().context(KafkaCreateFailed)?);
+        let transaction = match config.transactional_id {
+            Some(_) => Some(
+                Transaction::init(
+                    Arc::clone(&producer),
+                    Duration::from_millis(config.transaction_timeout_ms),
+                )
+                .await
+                .context(TransactionsInitFailed)?,
+            ),
+            None => None,
+        };
+
         Ok(KafkaSink {
-            producer: Arc::new(producer),
+            producer,
             topic: Template::try_from(config.topic).context(TopicTemplate)?,
-            key_field: config.key_field,
-            encoding: config.encoding.into(),
+            key,
+            headers: config.headers,
+            encoding: config.encoding.loaded()?.into(),
+            transaction,
             delivery_fut: FuturesUnordered::new(),
             in_flight: FuturesUnordered::new(),
             acker,
@@ -189,6 +244,15 @@ impl Sink<Event> for KafkaSink {
     type Error = ();
 
     fn poll_ready(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        // Nothing can be sent until a commit in progress completes.
+        if self
+            .transaction
+            .as_ref()
+            .map_or(false, Transaction::is_committing)
+        {
+            ready!(self.as_mut().poll_flush(cx))?;
+        }
+
         match self.poll_delivery_fut(cx) {
             Poll::Pending if self.delivery_fut.len() >= SEND_RESULT_LIMIT => Poll::Pending,
             _ => Poll::Ready(Ok(())),
@@ -204,14 +268,34 @@ impl Sink<Event> for KafkaSink {
         let topic = self.topic.render_string(&item).map_err(|missing_keys| {
             error!(message = "Missing keys for topic.", missing_keys = ?missing_keys);
         })?;
-        let (key, body) = encode_event(item.clone(), &self.key_field, &self.encoding);
+        let encoded = encode_event(item.clone(), &self.key, &self.headers, &self.encoding)
+            .map_err(|error| {
+                error!(message = "Failed to encode event.", %error);
+            })?;
+
+        if let Some(transaction) = &mut self.transaction {
+            transaction.begin().map_err(|error| {
+                error!(message = "Kafka transaction error.", %error);
+            })?;
+        }
 
         let seqno = self.seq_head;
         self.seq_head += 1;
 
         let producer = Arc::clone(&self.producer);
         self.delivery_fut.push(Box::pin(async move {
-            let mut record = FutureRecord::to(&topic).key(&key).payload(&body[..]);
+            let mut record = FutureRecord::to(&topic)
+                .key(&encoded.key)
+                .payload(&encoded.body[..]);
+            if !encoded.headers.is_empty() {
+                let headers = encoded
+                    .headers
+                    .iter()
+                    .fold(OwnedHeaders::new(), |headers, (name, value)| {
+                        headers.add(name, value)
+                    });
+                record = record.headers(headers);
+            }
             if let Some(Value::Timestamp(timestamp)) =
                 item.as_log().get(log_schema().timestamp_key())
             {
@@ -253,7 +337,15 @@ impl Sink<Event> for KafkaSink {
                         Ok((partition, offset)) => {
                             trace!(message = "Produced message.", ?partition, ?offset)
                         }
-                        Err(error) => error!(message = "Kafka error.", %error),
+                        Err(error) => {
+                            error!(message = "Kafka error.", %error);
+                            // The open transaction is aborted as a whole, so
+                            // nothing delivered in it is counted any more.
+                            if let Some(transaction) = &mut this.transaction {
+                                transaction.fail(error);
+                                continue;
+                            }
+                        }
                     };
 
                     this.pending_acks.insert(seqno);
@@ -263,7 +355,10 @@ impl Sink<Event> for KafkaSink {
                         num_to_ack += 1;
                         this.seq_tail += 1
                     }
-                    this.acker.ack(num_to_ack);
+                    match &mut this.transaction {
+                        Some(transaction) => transaction.add_delivered(num_to_ack),
+                        None => this.acker.ack(num_to_ack),
+                    }
                 }
                 Some((_, Err(Canceled))) => {
                     error!(message = "Request canceled.");
@@ -273,6 +368,16 @@ impl Sink<Event> for KafkaSink {
             }
         }
 
+        if let Some(transaction) = &mut this.transaction {
+            match ready!(transaction.poll_commit(cx)) {
+                Ok(committed) => this.acker.ack(committed),
+                Err(error) => {
+                    error!(message = "Kafka transaction error.", %error);
+                    return Poll::Ready(Err(()));
+                }
+            }
+        }
+
         Poll::Ready(Ok(()))
     }
 
@@ -310,29 +415,45 @@ async fn healthcheck(config: KafkaSinkConfig) -> crate::Result<()> {
     Ok(())
 }
 
+struct EncodedEvent {
+    key: Vec<u8>,
+    headers: Vec<(String, Vec<u8>)>,
+    body: Vec<u8>,
+}
+
 fn encode_event(
-    mut event: Event,
-    key_field: &Option<String>,
-    encoding: &EncodingConfig<Encoding>,
-) -> (Vec<u8>, Vec<u8>) {
-    let key = key_field
-        .as_ref()
-        .and_then(|f| event.as_log().get(f))
-        .map(|v| v.as_bytes().to_vec())
-        .unwrap_or_default();
-
-    encoding.apply_rules(&mut event);
-
-    let body = match encoding.codec() {
-        Encoding::Json => serde_json::to_vec(&event.as_log()).unwrap(),
-        Encoding::Text => event
+    event: Event,
+    key: &Option<KafkaKey>,
+    headers: &HashMap<String, String>,
+    encoding: &EncodingConfig<StandardEncodings>,
+) -> crate::Result<EncodedEvent> {
+    let key = match key {
+        Some(KafkaKey::Field(field)) => event
             .as_log()
-            .get(log_schema().message_key())
+            .get(field)
             .map(|v| v.as_bytes().to_vec())
             .unwrap_or_default(),
+        Some(KafkaKey::Template(template)) => template
+            .render(&event)
+            .map_err(|missing_keys| format!("Missing keys for partition key: {:?}", missing_keys))?
+            .to_vec(),
+        None => Vec::new(),
     };
 
-    (key, body)
+    let mut headers = headers
+        .iter()
+        .filter_map(|(name, field)| {
+            event
+                .as_log()
+                .get(field)
+                .map(|v| (name.clone(), v.as_bytes().to_vec()))
+        })
+        .collect::<Vec<_>>();
+    headers.sort();
+
+    let body = encoding.encode(event)?;
+
+    Ok(EncodedEvent { key, headers, body })
 }
 
 #[cfg(test)]
@@ -350,14 +471,17 @@ mod tests {
     fn kafka_encode_event_text() {
         let key = "";
         let message = "hello world".to_string();
-        let (key_bytes, bytes) = encode_event(
+        let encoded = encode_event(
             message.clone().into(),
             &None,
-            &EncodingConfig::from(Encoding::Text),
-        );
+            &HashMap::new(),
+            &EncodingConfig::from(StandardEncodings::Text),
+        )
+        .unwrap();
 
-        assert_eq!(&key_bytes[..], key.as_bytes());
-        assert_eq!(&bytes[..], message.as_bytes());
+        assert_eq!(&encoded.key[..], key.as_bytes());
+        assert_eq!(&encoded.body[..], message.as_bytes());
+        assert!(encoded.headers.is_empty());
     }
 
     #[test]
@@ -367,15 +491,17 @@ mod tests {
         event.as_mut_log().insert("key", "value");
         event.as_mut_log().insert("foo", "bar");
 
-        let (key, bytes) = encode_event(
+        let encoded = encode_event(
             event,
-            &Some("key".into()),
-            &EncodingConfig::from(Encoding::Json),
-        );
+            &Some(KafkaKey::Field("key".into())),
+            &HashMap::new(),
+            &EncodingConfig::from(StandardEncodings::Json),
+        )
+        .unwrap();
 
-        let map: BTreeMap<String, String> = serde_json::from_slice(&bytes[..]).unwrap();
+        let map: BTreeMap<String, String> = serde_json::from_slice(&encoded.body[..]).unwrap();
 
-        assert_eq!(&key[..], b"value");
+        assert_eq!(&encoded.key[..], b"value");
         assert_eq!(map[&log_schema().message_key().to_string()], message);
         assert_eq!(map["key"], "value".to_string());
         assert_eq!(map["foo"], "bar".to_string());
@@ -386,22 +512,77 @@ mod tests {
         let mut event = Event::from("hello");
         event.as_mut_log().insert("key", "value");
 
-        let (key, bytes) = encode_event(
+        let encoded = encode_event(
             event,
-            &Some("key".into()),
+            &Some(KafkaKey::Field("key".into())),
+            &HashMap::new(),
             &EncodingConfigWithDefault {
-                codec: Encoding::Json,
+                codec: StandardEncodings::Json,
                 except_fields: Some(vec!["key".into()]),
                 ..Default::default()
             }
             .into(),
-        );
+        )
+        .unwrap();
 
-        let map: BTreeMap<String, String> = serde_json::from_slice(&bytes[..]).unwrap();
+        let map: BTreeMap<String, String> = serde_json::from_slice(&encoded.body[..]).unwrap();
 
-        assert_eq!(&key[..], b"value");
+        assert_eq!(&encoded.key[..], b"value");
         assert!(!map.contains_key("key"));
     }
+
+    #[test]
+    fn kafka_encode_event_templated_key_and_headers() {
+        let mut event = Event::from("hello");
+        event.as_mut_log().insert("tenant", "acme");
+        event.as_mut_log().insert("user_id", 42);
+        event.as_mut_log().insert("trace_id", "abc123");
+
+        let headers = vec![
+            ("trace-id".to_string(), "trace_id".to_string()),
+            ("missing".to_string(), "nonexistent".to_string()),
+        ]
+        .into_iter()
+        .collect();
+        let key = Template::try_from("{{ tenant }}-{{ user_id }}").unwrap();
+
+        let encoded = encode_event(
+            event,
+            &Some(KafkaKey::Template(key.clone())),
+            &headers,
+            &EncodingConfig::from(StandardEncodings::Text),
+        )
+        .unwrap();
+
+        assert_eq!(&encoded.key[..], b"acme-42");
+        assert_eq!(
+            encoded.headers,
+            vec![("trace-id".to_string(), b"abc123".to_vec())]
+        );
+        assert_eq!(&encoded.body[..], b"hello");
+
+        // A key that can't be rendered fails the event.
+        assert!(encode_event(
+            Event::from("hello"),
+            &Some(KafkaKey::Template(key)),
+            &headers,
+            &EncodingConfig::from(StandardEncodings::Text),
+        )
+        .is_err());
+    }
+
+    #[tokio::test]
+    async fn kafka_rejects_conflicting_keys() {
+        let config = KafkaSinkConfig {
+            bootstrap_servers: "localhost:9091".into(),
+            topic: "topic".into(),
+            key_field: Some("key".into()),
+            partition_key: Some("{{ key }}".into()),
+            ..Default::default()
+        };
+
+        assert!(KafkaSink::new(config, Acker::Null).await.is_err());
+    }
 }
 
 #[cfg(feature = "kafka-integration-tests")]
@@ -417,6 +598,7 @@ mod integration_test {
     use futures::{future, StreamExt};
     use rdkafka::{
         consumer::{BaseConsumer, Consumer},
+        message::Headers,
         Message, Offset, TopicPartitionList,
     };
     use std::{thread, time::Duration};
@@ -433,7 +615,7 @@ mod integration_test {
             bootstrap_servers: "localhost:9091".into(),
             topic: topic.clone(),
             compression: KafkaCompression::None,
-            encoding: EncodingConfigWithDefault::from(Encoding::Text),
+            encoding: EncodingConfigWithDefault::from(StandardEncodings::Text),
             key_field: None,
             socket_timeout_ms: 60000,
             message_timeout_ms: 300000,
@@ -521,6 +703,68 @@ mod integration_test {
         .await;
     }
 
+    #[tokio::test]
+    async fn kafka_transactions_with_headers() {
+        let topic = format!("test-{}", random_string(10));
+        let config = KafkaSinkConfig {
+            bootstrap_servers: "localhost:9091".into(),
+            topic: topic.clone(),
+            encoding: EncodingConfigWithDefault::from(StandardEncodings::Text),
+            partition_key: Some("{{ message }}".into()),
+            headers: vec![("line".to_string(), "message".to_string())]
+                .into_iter()
+                .collect(),
+            socket_timeout_ms: 60000,
+            message_timeout_ms: 300000,
+            transactional_id: Some(random_string(10)),
+            transaction_timeout_ms: 60000,
+            ..Default::default()
+        };
+        let (acker, ack_counter) = Acker::new_for_testing();
+        let sink = KafkaSink::new(config, acker).await.unwrap();
+
+        let num_events = 100;
+        let (input, events) = random_lines_with_stream(100, num_events);
+        events.map(Ok).forward(sink).await.unwrap();
+
+        // Everything was committed, so only then acknowledged.
+        assert_eq!(
+            ack_counter.load(std::sync::atomic::Ordering::Relaxed),
+            num_events
+        );
+
+        let mut client_config = rdkafka::ClientConfig::new();
+        client_config.set("bootstrap.servers", "localhost:9091");
+        client_config.set("group.id", &random_string(10));
+        client_config.set("isolation.level", "read_committed");
+
+        let mut tpl = TopicPartitionList::new();
+        tpl.add_partition(&topic, 0).set_offset(Offset::Beginning);
+
+        let consumer: BaseConsumer = client_config.create().unwrap();
+        consumer.assign(&tpl).unwrap();
+
+        let mut failures = 0;
+        let mut out = Vec::new();
+        while failures < 100 && out.len() < input.len() {
+            match consumer.poll(Duration::from_secs(3)) {
+                Some(Ok(msg)) => {
+                    let payload: &str = msg.payload_view().unwrap().unwrap();
+                    let headers = msg.headers().unwrap();
+                    assert_eq!(headers.get(0), Some(("line", payload.as_bytes())));
+                    assert_eq!(msg.key(), Some(payload.as_bytes()));
+                    out.push(payload.to_owned());
+                }
+                _ => {
+                    failures += 1;
+                    thread::sleep(Duration::from_millis(50));
+                }
+            }
+        }
+
+        assert_eq!(out, input);
+    }
+
     async fn kafka_happy_path(
         server: &str,
         sasl: Option<KafkaSaslConfig>,
@@ -534,7 +778,7 @@ mod integration_test {
             bootstrap_servers: server.to_string(),
             topic: format!("{}-%Y%m%d", topic),
             compression,
-            encoding: EncodingConfigWithDefault::from(Encoding::Text),
+            encoding: EncodingConfigWithDefault::from(StandardEncodings::Text),
             key_field: None,
             auth: kafka_auth.clone(),
             socket_timeout_ms: 60000,
@@ -543,7 +787,7 @@ mod integration_test {
         };
         let topic = format!("{}-{}", topic, chrono::Utc::now().format("%Y%m%d"));
         let (acker, ack_counter) = Acker::new_for_testing();
-        let sink = KafkaSink::new(config, acker).unwrap();
+        let sink = KafkaSink::new(config, acker).await.unwrap();
 
         let num_events = 1000;
         let (input, events) = random_lines_with_stream(100, num_events);
@@ -0,0 +1,260 @@
+//! Transactions of the transactional producer.
+//!
+//! The version of `rdkafka` in use does not wrap the transactional API of
+//! `librdkafka` yet, although the bundled `librdkafka` provides it, so the
+//! calls go through the bindings directly.
+//!
+//! A transaction is begun by the first event sent after the previous one was
+//! committed, and committed once every event sent in it is delivered. The
+//! events are only acknowledged after the commit, so events of a transaction
+//! that never commits are sent again after a restart, while consumers using
+//! `isolation.level=read_committed` never see the first attempt. A
+//! transaction with an event that failed delivery is aborted instead.
+
+use futures::{future::BoxFuture, ready, FutureExt};
+use rdkafka::{
+    bindings as rdsys,
+    error::KafkaError,
+    producer::{FutureProducer, Producer},
+};
+use snafu::Snafu;
+use std::{
+    ffi::CStr,
+    os::raw::c_int,
+    sync::Arc,
+    task::{Context, Poll},
+    time::Duration,
+};
+use tokio::task;
+
+/// Retries of a commit failing with a retriable error before the transaction
+/// is aborted.
+const COMMIT_RETRIES: usize = 5;
+/// Delay before the first retry of a commit, doubled on each further retry.
+const COMMIT_BACKOFF: Duration = Duration::from_millis(100);
+
+#[derive(Debug, Snafu)]
+pub enum TransactionError {
+    #[snafu(display("{} failed: {}", operation, message))]
+    Failed {
+        operation: &'static str,
+        message: String,
+        retriable: bool,
+        requires_abort: bool,
+        fatal: bool,
+    },
+    #[snafu(display("delivering events failed: {}", source))]
+    Delivery { source: KafkaError },
+    #[snafu(display("{} task failed: {}", operation, source))]
+    Join {
+        operation: &'static str,
+        source: task::JoinError,
+    },
+}
+
+impl TransactionError {
+    fn is_retriable(&self) -> bool {
+        matches!(self, Self::Failed { retriable: true, .. })
+    }
+
+    fn requires_abort(&self) -> bool {
+        matches!(self, Self::Failed { requires_abort: true, .. })
+    }
+
+    fn is_fatal(&self) -> bool {
+        matches!(self, Self::Failed { fatal: true, .. })
+    }
+}
+
+enum State {
+    Idle,
+    Open,
+    /// Committing or aborting the transaction.
+    Committing(BoxFuture<'static, Result<(), TransactionError>>),
+}
+
+pub struct Transaction {
+    producer: Arc<FutureProducer>,
+    timeout: Duration,
+    state: State,
+    /// Events delivered in the open transaction.
+    uncommitted: usize,
+    /// The first failed delivery of the open transaction.
+    failed: Option<KafkaError>,
+}
+
+impl Transaction {
+    /// Registers the producer's `transactional.id` with the cluster, which
+    /// fences off any earlier producer with the same id and aborts its
+    /// pending transaction.
+    pub async fn init(
+        producer: Arc<FutureProducer>,
+        timeout: Duration,
+    ) -> Result<Self, TransactionError> {
+        let init_producer = Arc::clone(&producer);
+        task::spawn_blocking(move || {
+            check("initializing transactions", unsafe {
+                rdsys::rd_kafka_init_transactions(
+                    init_producer.client().native_ptr(),
+                    timeout_ms(timeout),
+                )
+            })
+        })
+        .await
+        .map_err(|source| TransactionError::Join {
+            operation: "initializing transactions",
+            source,
+        })??;
+
+        Ok(Self {
+            producer,
+            timeout,
+            state: State::Idle,
+            uncommitted: 0,
+            failed: None,
+        })
+    }
+
+    /// Begins a transaction, unless one is already open. This must not be
+    /// called while committing.
+    pub fn begin(&mut self) -> Result<(), TransactionError> {
+        match self.state {
+            State::Idle => {
+                check("beginning transaction", unsafe {
+                    rdsys::rd_kafka_begin_transaction(self.producer.client().native_ptr())
+                })?;
+                self.state = State::Open;
+                Ok(())
+            }
+            State::Open => Ok(()),
+            State::Committing(_) => panic!("Expected the transaction to be committed first."),
+        }
+    }
+
+    pub fn add_delivered(&mut self, count: usize) {
+        self.uncommitted += count;
+    }
+
+    /// Records a failed delivery, so the open transaction is aborted rather
+    /// than committed.
+    pub fn fail(&mut self, error: KafkaError) {
+        self.failed.get_or_insert(error);
+    }
+
+    pub fn is_committing(&self) -> bool {
+        matches!(self.state, State::Committing(_))
+    }
+
+    /// Commits the open transaction, if any, and returns the number of events
+    /// it committed. A transaction with a failed delivery, or whose commit
+    /// failed, is aborted, so its events are never visible to
+    /// `read_committed` consumers.
+    pub fn poll_commit(&mut self, cx: &mut Context<'_>) -> Poll<Result<usize, TransactionError>> {
+        if let State::Open = self.state {
+            let producer = Arc::clone(&self.producer);
+            let timeout = self.timeout;
+            let (operation, future) = match self.failed.take() {
+                Some(source) => (
+                    "aborting transaction",
+                    task::spawn_blocking(move || {
+                        warn!(message = "Aborting transaction.", error = %source);
+                        abort(&producer, timeout)?;
+                        Err(TransactionError::Delivery { source })
+                    }),
+                ),
+                None => (
+                    "committing transaction",
+                    task::spawn_blocking(move || commit(&producer, timeout)),
+                ),
+            };
+            self.state = State::Committing(
+                future
+                    .map(move |result| {
+                        result
+                            .map_err(|source| TransactionError::Join { operation, source })
+                            .and_then(|result| result)
+                    })
+                    .boxed(),
+            );
+        }
+
+        let result = match &mut self.state {
+            State::Committing(future) => ready!(future.poll_unpin(cx)),
+            _ => Ok(()),
+        };
+        self.state = State::Idle;
+        let committed = std::mem::replace(&mut self.uncommitted, 0);
+
+        Poll::Ready(result.map(|()| committed))
+    }
+}
+
+/// Commits the open transaction, retrying retriable errors with backoff. A
+/// fatal error leaves the producer unusable, so it is returned as is, while
+/// any other failure aborts the transaction.
+fn commit(producer: &FutureProducer, timeout: Duration) -> Result<(), TransactionError> {
+    let mut retries = 0;
+    let mut backoff = COMMIT_BACKOFF;
+    let result = loop {
+        let result = check("committing transaction", unsafe {
+            rdsys::rd_kafka_commit_transaction(producer.client().native_ptr(), timeout_ms(timeout))
+        });
+        match result {
+            Err(error) if error.is_retriable() && !error.is_fatal() && retries < COMMIT_RETRIES => {
+                debug!(message = "Retrying transaction commit.", %error, ?backoff);
+                std::thread::sleep(backoff);
+                retries += 1;
+                backoff *= 2;
+            }
+            result => break result,
+        }
+    };
+
+    match result {
+        Err(error) if error.is_fatal() => Err(error),
+        Err(error) => {
+            warn!(message = "Aborting transaction.", %error);
+            abort(producer, timeout)?;
+            Err(error)
+        }
+        Ok(()) => Ok(()),
+    }
+}
+
+fn abort(producer: &FutureProducer, timeout: Duration) -> Result<(), TransactionError> {
+    check("aborting transaction", unsafe {
+        rdsys::rd_kafka_abort_transaction(producer.client().native_ptr(), timeout_ms(timeout))
+    })
+}
+
+fn check(
+    operation: &'static str,
+    error: *mut rdsys::rd_kafka_error_t,
+) -> Result<(), TransactionError> {
+    if error.is_null() {
+        return Ok(());
+    }
+
+    // Safe as `error` is non-null, and is destroyed once read.
+    unsafe {
+        let message = CStr::from_ptr(rdsys::rd_kafka_error_string(error))
+            .to_string_lossy()
+            .into_owned();
+        let retriable = rdsys::rd_kafka_error_is_retriable(error) != 0;
+        let requires_abort = rdsys::rd_kafka_error_txn_requires_abort(error) != 0;
+        let fatal = rdsys::rd_kafka_error_is_fatal(error) != 0;
+        rdsys::rd_kafka_error_destroy(error);
+
+        Err(TransactionError::Failed {
+            operation,
+            message,
+            retriable,
+            requires_abort,
+            fatal,
+        })
+    }
+}
+
+fn timeout_ms(timeout: Duration) -> c_int {
+    timeout.as_millis().min(c_int::MAX as u128) as c_int
+}
@@ -80,12 +80,15 @@ impl SinkConfig for LogdnaConfig {
         &self,
         cx: SinkContext,
     ) -> crate::Result<(super::VectorSink, super::Healthcheck)> {
-        let request_settings = self.request.unwrap_with(&TowerRequestConfig::default());
+        let request_settings = self
+            .request
+            .unwrap_with(&TowerRequestConfig::default())
+            .when_full(cx.when_full());
         let batch_settings = BatchSettings::default()
             .bytes(bytesize::mib(10u64))
             .timeout(1)
             .parse_config(self.batch)?;
-        let client = HttpClient::new(None)?;
+        let client = HttpClient::new(None, cx.proxy())?;
 
         let sink = PartitionHttpSink::new(
             self.clone(),
@@ -94,6 +97,7 @@ impl SinkConfig for LogdnaConfig {
             batch_settings.timeout,
             client.clone(),
             cx.acker(),
+            cx.flush_signal(),
         )
         .sink_map_err(|error| error!(message = "Fatal logdna sink error.", %error));
 
@@ -5,9 +5,15 @@
 //!
 //! https://github.com/grafana/loki/blob/master/docs/api.md
 //!
-//! This sink does not use `PartitionBatching` but elects to do
-//! stream multiplexing by organizing the streams in the `build_request`
-//! phase. There must be at least one valid set of labels.
+//! Batches are partitioned by their stream, which is the rendered set of
+//! labels, so that Loki rejecting the events of one stream only fails, and
+//! retries, the request for that stream. There must be at least one valid
+//! set of labels.
+//!
+//! Events are sorted within each stream before they are sent. Events older
+//! than the latest one Loki accepted for their stream are handled according
+//! to `out_of_order_action`. Only one request is in flight by default, so
+//! that each push is checked against every push accepted before it.
 //!
 //! If an event produces no labels, this can happen if the template
 //! does not match, we will add a default label `{agent="vector"}`.
@@ -17,19 +23,30 @@ use crate::{
     event::{self, Event, Value},
     http::{Auth, HttpClient},
     sinks::util::{
-        buffer::loki::{LokiBuffer, LokiEvent, LokiRecord},
+        buffer::loki::{
+            GlobalTimestamps, Labels, LokiBatch, LokiBuffer, LokiEvent, LokiRecord,
+            OutOfOrderAction,
+        },
         encoding::{EncodingConfigWithDefault, EncodingConfiguration},
-        http::{BatchedHttpSink, HttpSink},
-        BatchConfig, BatchSettings, TowerRequestConfig, UriSerde,
+        http::{HttpBatchService, HttpRetryLogic, HttpSink},
+        BatchConfig, BatchSettings, Compression, EncodedSink, InFlightLimit, PartitionBuffer,
+        PartitionInnerBuffer, TowerRequestConfig, UriSerde,
     },
     template::Template,
     tls::{TlsOptions, TlsSettings},
 };
+use bytes::Bytes;
 use derivative::Derivative;
-use futures::FutureExt;
+use futures::{future::BoxFuture, FutureExt};
 use futures01::Sink;
+use lazy_static::lazy_static;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::{
+    collections::HashMap,
+    sync::Arc,
+    task::{Context, Poll},
+};
+use tower::Service;
 
 #[derive(Clone, Debug, Deserialize, Serialize)]
 #[serde(deny_unknown_fields)]
@@ -45,6 +62,8 @@ pub struct LokiConfig {
     remove_label_fields: bool,
     #[serde(default = "crate::serde::default_true")]
     remove_timestamp: bool,
+    #[serde(default)]
+    out_of_order_action: OutOfOrderAction,
 
     auth: Option<Auth>,
 
@@ -54,9 +73,19 @@ pub struct LokiConfig {
     #[serde(default)]
     batch: BatchConfig,
 
+    #[serde(default)]
+    compression: Compression,
+
     tls: Option<TlsOptions>,
 }
 
+lazy_static! {
+    static ref REQUEST_DEFAULTS: TowerRequestConfig = TowerRequestConfig {
+        in_flight_limit: InFlightLimit::Fixed(1),
+        ..Default::default()
+    };
+}
+
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq, Derivative)]
 #[serde(rename_all = "snake_case")]
 #[derivative(Default)]
@@ -90,25 +119,45 @@ impl SinkConfig for LokiConfig {
         if self.labels.is_empty() {
             return Err("`labels` must include at least one label.".into());
         }
+        self.compression.check_supported("Loki", &["gzip"])?;
 
-        let request_settings = self.request.unwrap_with(&TowerRequestConfig::default());
+        let request_settings = self
+            .request
+            .unwrap_with(&REQUEST_DEFAULTS)
+            .when_full(cx.when_full());
         let batch_settings = BatchSettings::default()
             .bytes(102_400)
             .events(100_000)
             .timeout(1)
             .parse_config(self.batch)?;
         let tls = TlsSettings::from_options(&self.tls)?;
-        let client = HttpClient::new(tls)?;
-
-        let sink = BatchedHttpSink::new(
-            self.clone(),
-            LokiBuffer::new(batch_settings.size),
-            request_settings,
-            batch_settings.timeout,
-            client.clone(),
-            cx.acker(),
-        )
-        .sink_map_err(|error| error!(message = "Fatal loki sink error.", %error));
+        let client = HttpClient::new(tls, cx.proxy())?;
+
+        let config = Arc::new(self.clone());
+        let request_builder = {
+            let config = Arc::clone(&config);
+            move |body| -> BoxFuture<'static, crate::Result<http::Request<Vec<u8>>>> {
+                let config = Arc::clone(&config);
+                Box::pin(async move { config.build_request(body).await })
+            }
+        };
+        let service = LokiSvc {
+            inner: HttpBatchService::new(client.clone(), request_builder),
+            global_timestamps: GlobalTimestamps::default(),
+            out_of_order_action: self.out_of_order_action,
+        };
+
+        let sink = request_settings
+            .partition_sink(
+                HttpRetryLogic,
+                service,
+                PartitionBuffer::new(LokiBuffer::new(batch_settings.size)),
+                batch_settings.timeout,
+                cx.acker(),
+                cx.flush_signal(),
+            )
+            .encode_events(move |event| config.encode_event(event))
+            .sink_map_err(|error| error!(message = "Fatal loki sink error.", %error));
 
         let healthcheck = healthcheck(self.clone(), client).boxed();
 
@@ -129,7 +178,7 @@ impl SinkConfig for LokiConfig {
 
 #[async_trait::async_trait]
 impl HttpSink for LokiConfig {
-    type Input = LokiRecord;
+    type Input = PartitionInnerBuffer<LokiRecord, Labels>;
     type Output = serde_json::Value;
 
     fn encode_event(&self, mut event: Event) -> Option<Self::Input> {
@@ -176,18 +225,32 @@ impl HttpSink for LokiConfig {
         if labels.is_empty() {
             labels = vec![("agent".to_string(), "vector".to_string())]
         }
+        // The labels are the partition key, so they must be in a stable
+        // order for equal sets to end up in the same batch.
+        labels.sort();
 
         let event = LokiEvent { timestamp, event };
-        Some(LokiRecord { labels, event })
+        Some(PartitionInnerBuffer::new(
+            LokiRecord {
+                labels: labels.clone(),
+                event,
+            },
+            labels,
+        ))
     }
 
     async fn build_request(&self, json: Self::Output) -> crate::Result<http::Request<Vec<u8>>> {
         let body = serde_json::to_vec(&json).unwrap();
+        let body = self.compression.compress(&body)?;
 
         let uri = format!("{}loki/api/v1/push", self.endpoint);
 
         let mut req = http::Request::post(uri).header("Content-Type", "application/json");
 
+        if let Some(ce) = self.compression.content_encoding() {
+            req = req.header("Content-Encoding", ce);
+        }
+
         if let Some(tenant_id) = &self.tenant_id {
             req = req.header("X-Scope-OrgID", tenant_id);
         }
@@ -202,6 +265,43 @@ impl HttpSink for LokiConfig {
     }
 }
 
+/// Pushes each batch once the ones before it have settled, applying the
+/// `out_of_order_action` against the streams Loki has accepted so far.
+#[derive(Clone)]
+struct LokiSvc {
+    inner: HttpBatchService<
+        BoxFuture<'static, crate::Result<http::Request<Vec<u8>>>>,
+        serde_json::Value,
+    >,
+    global_timestamps: GlobalTimestamps,
+    out_of_order_action: OutOfOrderAction,
+}
+
+impl Service<PartitionInnerBuffer<LokiBatch, Labels>> for LokiSvc {
+    type Response = http::Response<Bytes>;
+    type Error = crate::Error;
+    type Future = BoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, batch: PartitionInnerBuffer<LokiBatch, Labels>) -> Self::Future {
+        let (batch, _labels) = batch.into_parts();
+        let push = batch.into_push(&self.global_timestamps, self.out_of_order_action);
+        let global_timestamps = self.global_timestamps.clone();
+        let response = self.inner.call(push.body);
+
+        Box::pin(async move {
+            let response = response.await?;
+            if response.status().is_success() {
+                global_timestamps.commit(push.latest);
+            }
+            Ok(response)
+        })
+    }
+}
+
 async fn healthcheck(config: LokiConfig, mut client: HttpClient) -> crate::Result<()> {
     let uri = format!("{}ready", config.endpoint);
 
@@ -223,11 +323,12 @@ async fn healthcheck(config: LokiConfig, mut client: HttpClient) -> crate::Resul
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::config::{ProxyConfig, SinkConfig};
     use crate::sinks::util::http::HttpSink;
     use crate::sinks::util::test::{build_test_server, load_sink};
     use crate::test_util;
     use crate::Event;
-    use futures::StreamExt;
+    use futures::{stream, StreamExt};
 
     #[test]
     fn generate_config() {
@@ -250,10 +351,8 @@ mod tests {
 
         e1.as_mut_log().insert("foo", "bar");
 
-        let mut record = config.encode_event(e1).unwrap();
-
-        // HashMap -> Vec doesn't like keeping ordering
-        record.labels.sort();
+        let (record, key) = config.encode_event(e1).unwrap().into_parts();
+        assert_eq!(record.labels, key);
 
         // The final event should have timestamps and labels removed
         let expected_line = serde_json::to_string(&serde_json::json!({
@@ -286,7 +385,7 @@ mod tests {
 
         e1.as_mut_log().insert("foo", "bar");
 
-        let record = config.encode_event(e1).unwrap();
+        let (record, _) = config.encode_event(e1).unwrap().into_parts();
 
         let expected_line = serde_json::to_string(&serde_json::json!({
             "message": "hello world",
@@ -298,6 +397,25 @@ mod tests {
         assert_eq!(record.labels[0], ("bar".to_string(), "bar".to_string()));
     }
 
+    #[tokio::test]
+    async fn rejects_unsupported_compression() {
+        let (config, cx) = load_sink::<LokiConfig>(
+            r#"
+            endpoint = "http://localhost:3100"
+            labels.test_name = "placeholder"
+            encoding = "json"
+            compression = "zstd"
+        "#,
+        )
+        .unwrap();
+
+        let error = config.build(cx).await.err().unwrap();
+        assert_eq!(
+            error.to_string(),
+            "Loki doesn't accept zstd compressed requests."
+        );
+    }
+
     #[tokio::test]
     async fn healthcheck_includes_auth() {
         let (mut config, _cx) = load_sink::<LokiConfig>(
@@ -323,7 +441,8 @@ mod tests {
         tokio::spawn(server);
 
         let tls = TlsSettings::from_options(&config.tls).expect("could not create TLS settings");
-        let client = HttpClient::new(tls).expect("could not cerate HTTP client");
+        let client =
+            HttpClient::new(tls, &ProxyConfig::default()).expect("could not cerate HTTP client");
 
         healthcheck(config.clone(), client)
             .await
@@ -337,6 +456,64 @@ mod tests {
             output[0].0.headers.get("authorization")
         );
     }
+
+    #[tokio::test]
+    async fn sends_each_stream_separately() {
+        let (mut config, cx) = load_sink::<LokiConfig>(
+            r#"
+            endpoint = "http://localhost:3100"
+            labels = {stream = "{{ stream }}"}
+            encoding = "text"
+        "#,
+        )
+        .unwrap();
+
+        let addr = test_util::next_addr();
+        config.endpoint = format!("http://{}", addr)
+            .parse::<http::Uri>()
+            .expect("could not create URI")
+            .into();
+
+        let (sink, _) = config.build(cx).await.unwrap();
+        let (rx, _trigger, server) = build_test_server(addr);
+        tokio::spawn(server);
+
+        let events = (0..10).map(|i| {
+            let mut event = Event::from(format!("line {}", i));
+            event
+                .as_mut_log()
+                .insert("stream", if i % 2 == 0 { "even" } else { "odd" });
+            event
+        });
+        sink.run(stream::iter(events)).await.unwrap();
+
+        let mut bodies = rx
+            .take(2)
+            .map(|(_, body)| serde_json::from_slice::<serde_json::Value>(&body[..]).unwrap())
+            .collect::<Vec<_>>()
+            .await;
+        bodies.sort_by_key(|body| body["streams"][0]["stream"]["stream"].to_string());
+
+        for (body, (stream, first)) in bodies.iter().zip(&[("even", 0), ("odd", 1)]) {
+            let streams = body["streams"].as_array().unwrap();
+            assert_eq!(streams.len(), 1);
+            assert_eq!(
+                streams[0]["stream"],
+                serde_json::json!({ "stream": stream })
+            );
+
+            let lines = streams[0]["values"]
+                .as_array()
+                .unwrap()
+                .iter()
+                .map(|value| value[1].as_str().unwrap().to_string())
+                .collect::<Vec<_>>();
+            let expected = (0..5)
+                .map(|i| format!("line {}", first + i * 2))
+                .collect::<Vec<_>>();
+            assert_eq!(lines, expected);
+        }
+    }
 }
 
 #[cfg(feature = "loki-integration-tests")]
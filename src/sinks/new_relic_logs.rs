@@ -3,7 +3,7 @@ use crate::{
     sinks::{
         http::{HttpMethod, HttpSinkConfig},
         util::{
-            encoding::{EncodingConfigWithDefault, EncodingConfiguration},
+            encoding::{EncodingConfigWithDefault, EncodingConfiguration, StandardEncodings},
             BatchConfig, Compression, InFlightLimit, TowerRequestConfig,
         },
     },
@@ -69,10 +69,10 @@ pub enum Encoding {
     Json,
 }
 
-impl From<Encoding> for crate::sinks::http::Encoding {
-    fn from(v: Encoding) -> crate::sinks::http::Encoding {
+impl From<Encoding> for StandardEncodings {
+    fn from(v: Encoding) -> StandardEncodings {
         match v {
-            Encoding::Json => crate::sinks::http::Encoding::Json,
+            Encoding::Json => StandardEncodings::Json,
         }
     }
 }
@@ -336,6 +336,35 @@ fn encode_metric_datum(
     s
 }
 
+/// Encodes metrics in the Prometheus exposition format. The `# HELP` and
+/// `# TYPE` headers of a metric are written before its first datum, so the
+/// data of each metric should be adjacent.
+pub(crate) fn encode_metrics<'a>(
+    default_namespace: Option<&str>,
+    buckets: &[f64],
+    quantiles: &[f64],
+    expired: bool,
+    metrics: impl IntoIterator<Item = &'a Metric>,
+) -> String {
+    let mut s = String::new();
+
+    // output headers only once
+    let mut processed_headers = HashSet::new();
+
+    for metric in metrics {
+        let frame = encode_metric_datum(default_namespace, buckets, quantiles, expired, metric);
+
+        if processed_headers.insert(&metric.name) {
+            let header = encode_metric_header(default_namespace, metric);
+            s.push_str(&header);
+        };
+
+        s.push_str(&frame);
+    }
+
+    s
+}
+
 fn handle(
     req: Request<Body>,
     default_namespace: Option<&str>,
@@ -348,24 +377,13 @@ fn handle(
 
     match (req.method(), req.uri().path()) {
         (&Method::GET, "/metrics") => {
-            let mut s = String::new();
-
-            // output headers only once
-            let mut processed_headers = HashSet::new();
-
-            for metric in metrics {
-                let name = &metric.0.name;
-                let frame =
-                    encode_metric_datum(default_namespace, &buckets, quantiles, expired, &metric.0);
-
-                if !processed_headers.contains(&name) {
-                    let header = encode_metric_header(default_namespace, &metric.0);
-                    s.push_str(&header);
-                    processed_headers.insert(name);
-                };
-
-                s.push_str(&frame);
-            }
+            let s = encode_metrics(
+                default_namespace,
+                buckets,
+                quantiles,
+                expired,
+                metrics.iter().map(|metric| &metric.0),
+            );
 
             *response.body_mut() = s.into();
 
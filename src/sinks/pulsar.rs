@@ -1,8 +1,10 @@
 use crate::{
     buffers::Acker,
-    config::{log_schema, DataType, GenerateConfig, SinkConfig, SinkContext, SinkDescription},
+    config::{DataType, GenerateConfig, SinkConfig, SinkContext, SinkDescription},
     event::Event,
-    sinks::util::encoding::{EncodingConfig, EncodingConfigWithDefault, EncodingConfiguration},
+    sinks::util::encoding::{
+        EncodingConfig, EncodingConfigWithDefault, EncodingConfiguration, StandardEncodings,
+    },
 };
 use futures::{future::BoxFuture, ready, stream::FuturesUnordered, FutureExt, Sink, Stream};
 use pulsar::{
@@ -29,7 +31,7 @@ pub struct PulsarSinkConfig {
     #[serde(alias = "address")]
     endpoint: String,
     topic: String,
-    encoding: EncodingConfigWithDefault<Encoding>,
+    encoding: EncodingConfigWithDefault<StandardEncodings>,
     auth: Option<AuthConfig>,
 }
 
@@ -39,15 +41,6 @@ pub struct AuthConfig {
     token: String, // <jwt token>
 }
 
-#[derive(Clone, Copy, Debug, Derivative, Deserialize, Serialize, Eq, PartialEq)]
-#[derivative(Default)]
-#[serde(rename_all = "snake_case")]
-pub enum Encoding {
-    #[derivative(Default)]
-    Text,
-    Json,
-}
-
 type PulsarProducer = Producer<TokioExecutor>;
 type BoxedPulsarProducer = Box<PulsarProducer>;
 
@@ -58,7 +51,7 @@ enum PulsarSinkState {
 }
 
 struct PulsarSink {
-    encoding: EncodingConfig<Encoding>,
+    encoding: EncodingConfig<StandardEncodings>,
     state: PulsarSinkState,
     in_flight:
         FuturesUnordered<BoxFuture<'static, (usize, Result<CommandSendReceipt, PulsarError>)>>,
@@ -78,7 +71,7 @@ impl GenerateConfig for PulsarSinkConfig {
         toml::Value::try_from(Self {
             endpoint: "pulsar://127.0.0.1:6650".to_string(),
             topic: "topic-1234".to_string(),
-            encoding: Encoding::Text.into(),
+            encoding: StandardEncodings::Text.into(),
             auth: None,
         })
         .unwrap()
@@ -96,7 +89,7 @@ impl SinkConfig for PulsarSinkConfig {
             .create_pulsar_producer()
             .await
             .context(CreatePulsarSink)?;
-        let sink = PulsarSink::new(producer, self.encoding.clone().into(), cx.acker());
+        let sink = PulsarSink::new(producer, self.encoding.loaded()?.into(), cx.acker());
 
         let producer = self
             .create_pulsar_producer()
@@ -135,7 +128,11 @@ async fn healthcheck(producer: PulsarProducer) -> crate::Result<()> {
 }
 
 impl PulsarSink {
-    fn new(producer: PulsarProducer, encoding: EncodingConfig<Encoding>, acker: Acker) -> Self {
+    fn new(
+        producer: PulsarProducer,
+        encoding: EncodingConfig<StandardEncodings>,
+        acker: Acker,
+    ) -> Self {
         Self {
             encoding,
             state: PulsarSinkState::Ready(Box::new(producer)),
@@ -239,22 +236,17 @@ impl Sink<Event> for PulsarSink {
     }
 }
 
-fn encode_event(mut item: Event, encoding: &EncodingConfig<Encoding>) -> crate::Result<Vec<u8>> {
-    encoding.apply_rules(&mut item);
-    let log = item.into_log();
-
-    Ok(match encoding.codec() {
-        Encoding::Json => serde_json::to_vec(&log)?,
-        Encoding::Text => log
-            .get(log_schema().message_key())
-            .map(|v| v.as_bytes().to_vec())
-            .unwrap_or_default(),
-    })
+fn encode_event(
+    item: Event,
+    encoding: &EncodingConfig<StandardEncodings>,
+) -> crate::Result<Vec<u8>> {
+    encoding.encode(item)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::config::log_schema;
     use std::collections::HashMap;
 
     #[test]
@@ -267,7 +259,7 @@ mod tests {
         let msg = "hello_world".to_owned();
         let mut evt = Event::from(msg.clone());
         evt.as_mut_log().insert("key", "value");
-        let result = encode_event(evt, &EncodingConfig::from(Encoding::Json)).unwrap();
+        let result = encode_event(evt, &EncodingConfig::from(StandardEncodings::Json)).unwrap();
         let map: HashMap<String, String> = serde_json::from_slice(&result[..]).unwrap();
         assert_eq!(msg, map[&log_schema().message_key().to_string()]);
     }
@@ -276,7 +268,7 @@ mod tests {
     fn pulsar_event_text() {
         let msg = "hello_world".to_owned();
         let evt = Event::from(msg.clone());
-        let event = encode_event(evt, &EncodingConfig::from(Encoding::Text)).unwrap();
+        let event = encode_event(evt, &EncodingConfig::from(StandardEncodings::Text)).unwrap();
 
         assert_eq!(&event[..], msg.as_bytes());
     }
@@ -291,7 +283,7 @@ mod tests {
         let event = encode_event(
             evt,
             &EncodingConfigWithDefault {
-                codec: Encoding::Json,
+                codec: StandardEncodings::Json,
                 except_fields: Some(vec!["key".into()]),
                 ..Default::default()
             }
@@ -323,7 +315,7 @@ mod integration_tests {
         let cnf = PulsarSinkConfig {
             endpoint: "pulsar://127.0.0.1:6650".to_owned(),
             topic: topic.clone(),
-            encoding: Encoding::Text.into(),
+            encoding: StandardEncodings::Text.into(),
             auth: None,
         };
 
@@ -3,7 +3,10 @@ use crate::sinks::util::unix::UnixSinkConfig;
 use crate::{
     config::{DataType, GenerateConfig, SinkConfig, SinkContext, SinkDescription},
     sinks::util::{
-        encode_event, encoding::EncodingConfig, tcp::TcpSinkConfig, udp::UdpSinkConfig, Encoding,
+        encode_event,
+        encoding::{EncodingConfig, StandardEncodings},
+        tcp::TcpSinkConfig,
+        udp::UdpSinkConfig,
     },
     tls::TlsConfig,
 };
@@ -15,7 +18,7 @@ use serde::{Deserialize, Serialize};
 pub struct SocketSinkConfig {
     #[serde(flatten)]
     pub mode: Mode,
-    pub encoding: EncodingConfig<Encoding>,
+    pub encoding: EncodingConfig<StandardEncodings>,
 }
 
 #[derive(Deserialize, Serialize, Debug, Clone)]
@@ -45,7 +48,7 @@ impl GenerateConfig for SocketSinkConfig {
 impl SocketSinkConfig {
     pub fn make_tcp_config(
         address: String,
-        encoding: EncodingConfig<Encoding>,
+        encoding: EncodingConfig<StandardEncodings>,
         tls: Option<TlsConfig>,
     ) -> Self {
         SocketSinkConfig {
@@ -55,7 +58,7 @@ impl SocketSinkConfig {
     }
 
     pub fn make_basic_tcp_config(address: String) -> Self {
-        Self::make_tcp_config(address, EncodingConfig::from(Encoding::Text), None)
+        Self::make_tcp_config(address, EncodingConfig::from(StandardEncodings::Text), None)
     }
 }
 
@@ -66,7 +69,7 @@ impl SinkConfig for SocketSinkConfig {
         &self,
         cx: SinkContext,
     ) -> crate::Result<(super::VectorSink, super::Healthcheck)> {
-        let encoding = self.encoding.clone();
+        let encoding = self.encoding.loaded()?;
         let encode_event = move |event| encode_event(event, &encoding);
         match &self.mode {
             Mode::Tcp(config) => config.build(cx, encode_event),
@@ -117,7 +120,7 @@ mod test {
             mode: Mode::Udp(UdpSinkConfig {
                 address: addr.to_string(),
             }),
-            encoding: Encoding::Json.into(),
+            encoding: StandardEncodings::Json.into(),
         };
         let context = SinkContext::new_test();
         let (sink, _healthcheck) = config.build(context).await.unwrap();
@@ -162,7 +165,7 @@ mod test {
                 address: addr.to_string(),
                 tls: None,
             }),
-            encoding: Encoding::Json.into(),
+            encoding: StandardEncodings::Json.into(),
         };
 
         let context = SinkContext::new_test();
@@ -232,7 +235,7 @@ mod test {
                     },
                 }),
             }),
-            encoding: Encoding::Text.into(),
+            encoding: StandardEncodings::Text.into(),
         };
         let context = SinkContext::new_test();
         let (sink, _healthcheck) = config.build(context).await.unwrap();
@@ -349,7 +352,7 @@ mod test {
                 address: addr.to_string(),
                 tls: None,
             }),
-            encoding: Encoding::Text.into(),
+            encoding: StandardEncodings::Text.into(),
         };
 
         let context = SinkContext::new_test();
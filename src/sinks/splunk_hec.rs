@@ -93,14 +93,18 @@ impl SinkConfig for HecSinkConfig {
         cx: SinkContext,
     ) -> crate::Result<(super::VectorSink, super::Healthcheck)> {
         validate_host(&self.endpoint)?;
+        self.compression.check_supported("Splunk HEC", &["gzip"])?;
 
         let batch = BatchSettings::default()
             .bytes(bytesize::mib(1u64))
             .timeout(1)
             .parse_config(self.batch)?;
-        let request = self.request.unwrap_with(&REQUEST_DEFAULTS);
+        let request = self
+            .request
+            .unwrap_with(&REQUEST_DEFAULTS)
+            .when_full(cx.when_full());
         let tls_settings = TlsSettings::from_options(&self.tls)?;
-        let client = HttpClient::new(tls_settings)?;
+        let client = HttpClient::new(tls_settings, cx.proxy())?;
 
         let sink = BatchedHttpSink::new(
             self.clone(),
@@ -109,6 +113,7 @@ impl SinkConfig for HecSinkConfig {
             batch.timeout,
             client.clone(),
             cx.acker(),
+            cx.flush_signal(),
         )
         .sink_map_err(|error| error!(message = "Fatal splunk_hec sink error.", %error));
 
@@ -423,7 +428,7 @@ mod integration_tests {
     use crate::test_util::retry_until;
     use crate::{
         assert_downcast_matches,
-        config::{SinkConfig, SinkContext},
+        config::{ProxyConfig, SinkConfig, SinkContext},
         sinks,
         test_util::{random_lines_with_stream, random_string},
         Event,
@@ -633,7 +638,7 @@ mod integration_tests {
     async fn splunk_healthcheck() {
         let config_to_healthcheck = move |config: HecSinkConfig| {
             let tls_settings = TlsSettings::from_options(&config.tls).unwrap();
-            let client = HttpClient::new(tls_settings).unwrap();
+            let client = HttpClient::new(tls_settings, &ProxyConfig::default()).unwrap();
             sinks::splunk_hec::healthcheck(config, client)
         };
 
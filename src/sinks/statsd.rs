@@ -107,6 +107,7 @@ impl SinkConfig for StatsdSinkConfig {
                     Buffer::new(batch.size, Compression::None),
                     batch.timeout,
                     cx.acker(),
+                    cx.flush_signal(),
                 )
                 .sink_map_err(|error| error!(message = "Fatal statsd sink error.", %error))
                 .with_flat_map(move |event| {
@@ -3,7 +3,7 @@ use super::{instant_now, AutoConcurrencySettings};
 use crate::emit;
 use crate::internal_events::{
     AutoConcurrencyAveragedRtt, AutoConcurrencyInFlight, AutoConcurrencyLimit,
-    AutoConcurrencyObservedRtt,
+    AutoConcurrencyObservedRtt, AutoConcurrencyThrottled,
 };
 use crate::sinks::util::retries::{RetryAction, RetryLogic};
 #[cfg(test)]
@@ -36,6 +36,9 @@ pub(super) struct Inner {
     current_rtt: Mean,
     had_back_pressure: bool,
     reached_limit: bool,
+    /// The limit is not increased before this time, as requested by a
+    /// throttling response.
+    throttled_until: Option<Instant>,
 }
 
 #[cfg(test)]
@@ -71,6 +74,7 @@ impl<L> Controller<L> {
                 current_rtt: Default::default(),
                 had_back_pressure: false,
                 reached_limit: false,
+                throttled_until: None,
             })),
             #[cfg(test)]
             stats: Arc::new(Mutex::new(ControllerStatistics::default())),
@@ -103,10 +107,26 @@ impl<L> Controller<L> {
     /// Adjust the controller to a response, based on type of response
     /// given (backpressure or not) and if it should be used as a valid
     /// RTT measurement.
-    fn adjust_to_response_inner(&self, start: Instant, is_back_pressure: bool, use_rtt: bool) {
+    fn adjust_to_response_inner(
+        &self,
+        start: Instant,
+        is_back_pressure: bool,
+        use_rtt: bool,
+        retry_after: Option<Duration>,
+    ) {
         let now = instant_now();
         let mut inner = self.inner.lock().expect("Controller mutex is poisoned");
 
+        if let Some(retry_after) = retry_after {
+            let until = now + retry_after;
+            if inner
+                .throttled_until
+                .map_or(true, |current| current < until)
+            {
+                inner.throttled_until = Some(until);
+            }
+        }
+
         let rtt = now.saturating_duration_since(start);
         if use_rtt {
             emit!(AutoConcurrencyObservedRtt { rtt });
@@ -165,7 +185,7 @@ impl<L> Controller<L> {
 
                     // Only manage the concurrency if in_flight_limit was set to "auto"
                     if self.in_flight_limit.is_none() {
-                        self.manage_limit(&mut inner, past_rtt, current_rtt);
+                        self.manage_limit(&mut inner, past_rtt, current_rtt, now);
                     }
 
                     // Reset values for next interval
@@ -181,8 +201,20 @@ impl<L> Controller<L> {
         }
     }
 
-    fn manage_limit(&self, inner: &mut MutexGuard<Inner>, past_rtt: f64, current_rtt: Option<f64>) {
+    fn manage_limit(
+        &self,
+        inner: &mut MutexGuard<Inner>,
+        past_rtt: f64,
+        current_rtt: Option<f64>,
+        now: Instant,
+    ) {
         let threshold = past_rtt * self.settings.rtt_threshold_ratio;
+        // The server asked us to back off for a while, so hold the limit
+        // down until that time has passed.
+        let throttled = inner.throttled_until.map_or(false, |until| now < until);
+        if !throttled {
+            inner.throttled_until = None;
+        }
 
         // Normal quick responses trigger an increase in the
         // concurrency limit. Note that we only check this if we had
@@ -191,6 +223,7 @@ impl<L> Controller<L> {
         if inner.current_limit < super::MAX_CONCURRENCY
             && inner.reached_limit
             && !inner.had_back_pressure
+            && !throttled
             && current_rtt.is_some()
             && current_rtt.unwrap() <= past_rtt + threshold / 10.0
         {
@@ -236,7 +269,7 @@ where
             .as_ref()
             .map(|resp| self.logic.should_retry_response(resp));
         let is_back_pressure = match &response_action {
-            Ok(action) => matches!(action, RetryAction::Retry(_)),
+            Ok(action) => action.is_retryable(),
             Err(error) => {
                 if let Some(error) = error.downcast_ref::<L::Error>() {
                     self.logic.is_retriable_error(error)
@@ -247,9 +280,20 @@ where
                 }
             }
         };
+        // Throttling responses are explicit back pressure, and any delay
+        // they request also pauses increases to the limit.
+        let retry_after = match &response_action {
+            Ok(RetryAction::Throttled(_, retry_after)) => {
+                emit!(AutoConcurrencyThrottled {
+                    retry_after: *retry_after
+                });
+                *retry_after
+            }
+            _ => None,
+        };
         // Only adjust to the RTT when the request was successfully processed.
         let use_rtt = matches!(response_action, Ok(RetryAction::Successful));
-        self.adjust_to_response_inner(start, is_back_pressure, use_rtt)
+        self.adjust_to_response_inner(start, is_back_pressure, use_rtt, retry_after)
     }
 }
 
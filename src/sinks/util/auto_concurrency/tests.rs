@@ -156,6 +156,7 @@ impl SinkConfig for TestConfig {
                 VecBuffer::new(batch.size),
                 batch.timeout,
                 cx.acker(),
+                cx.flush_signal(),
             )
             .sink_map_err(|error| panic!("Fatal test sink error: {}", error));
         let healthcheck = future::ok(()).boxed();
@@ -167,6 +168,7 @@ impl SinkConfig for TestConfig {
                 .get_ref()
                 .get_ref()
                 .get_ref()
+                .get_ref()
                 .controller
                 .stats,
         );
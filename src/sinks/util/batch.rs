@@ -2,7 +2,7 @@ use derivative::Derivative;
 use serde::{Deserialize, Serialize};
 use snafu::Snafu;
 use std::marker::PhantomData;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
 #[derive(Debug, Snafu)]
 pub enum BatchError {
@@ -203,6 +203,9 @@ pub trait Batch: Sized {
 pub struct StatefulBatch<B> {
     inner: B,
     was_full: bool,
+    /// When the events the items were encoded from entered Vector, for those
+    /// that were stamped.
+    ingress: Vec<Instant>,
 }
 
 impl<B> From<B> for StatefulBatch<B> {
@@ -210,6 +213,7 @@ impl<B> From<B> for StatefulBatch<B> {
         Self {
             inner,
             was_full: false,
+            ingress: Vec::new(),
         }
     }
 }
@@ -219,8 +223,22 @@ impl<B> StatefulBatch<B> {
         self.was_full
     }
 
-    pub fn into_inner(self) -> B {
-        self.inner
+    /// Takes the ingress times of the batch's events, to be recorded once
+    /// the batch is acked.
+    pub fn take_ingress(&mut self) -> Vec<Instant> {
+        std::mem::take(&mut self.ingress)
+    }
+}
+
+impl<B: Batch> StatefulBatch<B> {
+    /// Pushes an item encoded from an event that entered Vector at
+    /// `ingress`, which is kept with the batch if the item is taken.
+    pub fn push_from(&mut self, item: B::Input, ingress: Option<Instant>) -> PushResult<B::Input> {
+        let result = self.push(item);
+        if let (PushResult::Ok(_), Some(ingress)) = (&result, ingress) {
+            self.ingress.push(ingress);
+        }
+        result
     }
 }
 
@@ -257,6 +275,7 @@ where
         Self {
             inner: self.inner.fresh(),
             was_full: false,
+            ingress: Vec::new(),
         }
     }
 
@@ -1,17 +1,31 @@
+use flate2::write::GzEncoder;
 use serde::{de, ser};
-use std::fmt;
+use snafu::Snafu;
+use std::{
+    fmt,
+    io::{self, Write},
+};
 
 pub const GZIP_NONE: usize = 0;
 pub const GZIP_FAST: usize = 1;
 pub const GZIP_DEFAULT: usize = 6;
 pub const GZIP_BEST: usize = 9;
 
+pub const ZSTD_FAST: i32 = 1;
+pub const ZSTD_DEFAULT: i32 = 3;
+pub const ZSTD_BEST: i32 = 21;
+
 #[derive(Debug, Derivative, Copy, Clone, Eq, PartialEq)]
 #[derivative(Default)]
 pub enum Compression {
     #[derivative(Default)]
     None,
     Gzip(Option<usize>),
+    Zstd(Option<i32>),
+    /// The Snappy framing format.
+    Snappy,
+    /// The LZ4 frame format.
+    Lz4,
 }
 
 impl Compression {
@@ -19,10 +33,27 @@ impl Compression {
         Compression::Gzip(None)
     }
 
+    pub const fn zstd_default() -> Compression {
+        Compression::Zstd(None)
+    }
+
+    pub fn algorithm(&self) -> &'static str {
+        match self {
+            Self::None => "none",
+            Self::Gzip(_) => "gzip",
+            Self::Zstd(_) => "zstd",
+            Self::Snappy => "snappy",
+            Self::Lz4 => "lz4",
+        }
+    }
+
     pub fn content_encoding(&self) -> Option<&'static str> {
         match self {
             Self::None => None,
             Self::Gzip(_) => Some("gzip"),
+            Self::Zstd(_) => Some("zstd"),
+            Self::Snappy => Some("snappy"),
+            Self::Lz4 => Some("lz4"),
         }
     }
 
@@ -30,23 +61,162 @@ impl Compression {
         match self {
             Self::None => "log",
             Self::Gzip(_) => "log.gz",
+            Self::Zstd(_) => "log.zst",
+            Self::Snappy => "log.sz",
+            Self::Lz4 => "log.lz4",
+        }
+    }
+
+    /// The suffix added to the name of a file compressed this way.
+    pub fn file_suffix(&self) -> Option<&'static str> {
+        match self {
+            Self::None => None,
+            Self::Gzip(_) => Some("gz"),
+            Self::Zstd(_) => Some("zst"),
+            Self::Snappy => Some("sz"),
+            Self::Lz4 => Some("lz4"),
+        }
+    }
+
+    /// Compresses a complete payload.
+    pub fn compress(&self, input: &[u8]) -> io::Result<Vec<u8>> {
+        let mut compressor = Compressor::new(Vec::with_capacity(input.len()), *self)?;
+        compressor.write_all(input)?;
+        compressor.finish()
+    }
+
+    /// Checks the compression against the `Content-Encoding`s a service
+    /// decodes, for sinks that can't send all of them.
+    pub fn check_supported(
+        &self,
+        service: &'static str,
+        algorithms: &[&str],
+    ) -> Result<(), UnsupportedCompression> {
+        match self.content_encoding() {
+            Some(encoding) if !algorithms.contains(&encoding) => Err(UnsupportedCompression {
+                service,
+                algorithm: self.algorithm(),
+            }),
+            _ => Ok(()),
         }
     }
 }
 
+#[derive(Debug, Snafu)]
+#[snafu(display("{} doesn't accept {} compressed requests.", service, algorithm))]
+pub struct UnsupportedCompression {
+    service: &'static str,
+    algorithm: &'static str,
+}
+
+#[derive(Debug, Snafu)]
+#[snafu(display("Rusoto can't compress requests with {}.", algorithm))]
+pub struct UnsupportedRusotoCompression {
+    algorithm: &'static str,
+}
+
 #[cfg(feature = "rusoto_core")]
-impl From<Compression> for rusoto_core::encoding::ContentEncoding {
-    fn from(compression: Compression) -> Self {
+impl std::convert::TryFrom<Compression> for rusoto_core::encoding::ContentEncoding {
+    type Error = UnsupportedRusotoCompression;
+
+    fn try_from(compression: Compression) -> Result<Self, Self::Error> {
         match compression {
-            Compression::None => rusoto_core::encoding::ContentEncoding::Identity,
+            Compression::None => Ok(rusoto_core::encoding::ContentEncoding::Identity),
             Compression::Gzip(level) => {
                 let level = level.unwrap_or(GZIP_DEFAULT);
-                rusoto_core::encoding::ContentEncoding::Gzip(None, level as u32)
+                Ok(rusoto_core::encoding::ContentEncoding::Gzip(
+                    None,
+                    level as u32,
+                ))
             }
+            compression => Err(UnsupportedRusotoCompression {
+                algorithm: compression.algorithm(),
+            }),
+        }
+    }
+}
+
+/// Compresses everything written to it into the inner writer.
+pub enum Compressor<W: Write> {
+    Plain(W),
+    Gzip(GzEncoder<W>),
+    Zstd(zstd::stream::write::Encoder<W>),
+    Snappy(snap::write::FrameEncoder<W>),
+    Lz4(lz4::Encoder<W>),
+}
+
+impl<W: Write> Compressor<W> {
+    pub fn new(writer: W, compression: Compression) -> io::Result<Self> {
+        Ok(match compression {
+            Compression::None => Self::Plain(writer),
+            Compression::Gzip(level) => {
+                let level = level.unwrap_or(GZIP_FAST);
+                Self::Gzip(GzEncoder::new(
+                    writer,
+                    flate2::Compression::new(level as u32),
+                ))
+            }
+            Compression::Zstd(level) => Self::Zstd(zstd::stream::write::Encoder::new(
+                writer,
+                level.unwrap_or(ZSTD_DEFAULT),
+            )?),
+            Compression::Snappy => Self::Snappy(snap::write::FrameEncoder::new(writer)),
+            Compression::Lz4 => Self::Lz4(lz4::EncoderBuilder::new().build(writer)?),
+        })
+    }
+
+    /// Writes the end of the compressed stream, and returns the inner writer.
+    pub fn finish(self) -> io::Result<W> {
+        match self {
+            Self::Plain(writer) => Ok(writer),
+            Self::Gzip(encoder) => encoder.finish(),
+            Self::Zstd(encoder) => encoder.finish(),
+            Self::Snappy(encoder) => encoder
+                .into_inner()
+                .map_err(|error| io::Error::new(io::ErrorKind::Other, error.to_string())),
+            Self::Lz4(encoder) => {
+                let (writer, result) = encoder.finish();
+                result.map(|_| writer)
+            }
+        }
+    }
+}
+
+impl<W: Write> Write for Compressor<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        match self {
+            Self::Plain(writer) => writer.write(buf),
+            Self::Gzip(encoder) => encoder.write(buf),
+            Self::Zstd(encoder) => encoder.write(buf),
+            Self::Snappy(encoder) => encoder.write(buf),
+            Self::Lz4(encoder) => encoder.write(buf),
+        }
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        match self {
+            Self::Plain(writer) => writer.flush(),
+            Self::Gzip(encoder) => encoder.flush(),
+            Self::Zstd(encoder) => encoder.flush(),
+            Self::Snappy(encoder) => encoder.flush(),
+            Self::Lz4(encoder) => encoder.flush(),
         }
     }
 }
 
+impl<W: Write> fmt::Debug for Compressor<W> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let algorithm = match self {
+            Self::Plain(_) => "none",
+            Self::Gzip(_) => "gzip",
+            Self::Zstd(_) => "zstd",
+            Self::Snappy(_) => "snappy",
+            Self::Lz4(_) => "lz4",
+        };
+        f.debug_tuple("Compressor").field(&algorithm).finish()
+    }
+}
+
 impl<'de> de::Deserialize<'de> for Compression {
     fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
     where
@@ -68,6 +238,9 @@ impl<'de> de::Deserialize<'de> for Compression {
                 match s {
                     "none" => Ok(Compression::None),
                     "gzip" => Ok(Compression::gzip_default()),
+                    "zstd" => Ok(Compression::zstd_default()),
+                    "snappy" => Ok(Compression::Snappy),
+                    "lz4" => Ok(Compression::Lz4),
                     _ => Err(de::Error::invalid_value(de::Unexpected::Str(s), &self)),
                 }
             }
@@ -101,10 +274,12 @@ impl<'de> de::Deserialize<'de> for Compression {
                 }
 
                 match algorithm.ok_or_else(|| de::Error::missing_field("algorithm"))? {
-                    "none" => match level {
-                        Some(_) => Err(de::Error::unknown_field("level", &["algorithm"])),
-                        None => Ok(Compression::None),
-                    },
+                    "none" | "snappy" | "lz4" if level.is_some() => {
+                        Err(de::Error::unknown_field("level", &["algorithm"]))
+                    }
+                    "none" => Ok(Compression::None),
+                    "snappy" => Ok(Compression::Snappy),
+                    "lz4" => Ok(Compression::Lz4),
                     "gzip" => Ok(Compression::Gzip(match level {
                         Some(level) => Some(match level.as_str() {
                             "none" => GZIP_NONE,
@@ -129,7 +304,33 @@ impl<'de> de::Deserialize<'de> for Compression {
                         }),
                         None => None,
                     })),
-                    algorithm => Err(de::Error::unknown_variant(algorithm, &["none", "gzip"])),
+                    "zstd" => Ok(Compression::Zstd(match level {
+                        Some(level) => Some(match level.as_str() {
+                            "fast" => ZSTD_FAST,
+                            "default" => ZSTD_DEFAULT,
+                            "best" => ZSTD_BEST,
+                            value => match value.parse::<i32>() {
+                                Ok(level) if (ZSTD_FAST..=ZSTD_BEST).contains(&level) => level,
+                                Ok(level) => {
+                                    return Err(de::Error::invalid_value(
+                                        de::Unexpected::Signed(level as i64),
+                                        &self,
+                                    ))
+                                }
+                                Err(_) => {
+                                    return Err(de::Error::invalid_value(
+                                        de::Unexpected::Str(value),
+                                        &self,
+                                    ))
+                                }
+                            },
+                        }),
+                        None => None,
+                    })),
+                    algorithm => Err(de::Error::unknown_variant(
+                        algorithm,
+                        &["none", "gzip", "zstd", "snappy", "lz4"],
+                    )),
                 }
             }
         }
@@ -146,10 +347,10 @@ impl ser::Serialize for Compression {
         use ser::SerializeMap;
 
         let mut map = serializer.serialize_map(None)?;
+        map.serialize_entry("algorithm", self.algorithm())?;
         match self {
-            Compression::None => map.serialize_entry("algorithm", "none")?,
+            Compression::None | Compression::Snappy | Compression::Lz4 => (),
             Compression::Gzip(level) => {
-                map.serialize_entry("algorithm", "gzip")?;
                 match level.unwrap_or(GZIP_DEFAULT) {
                     GZIP_NONE => map.serialize_entry("level", "none")?,
                     GZIP_FAST => map.serialize_entry("level", "fast")?,
@@ -158,7 +359,104 @@ impl ser::Serialize for Compression {
                     level => map.serialize_entry("level", &level)?,
                 };
             }
+            Compression::Zstd(level) => {
+                match level.unwrap_or(ZSTD_DEFAULT) {
+                    ZSTD_FAST => map.serialize_entry("level", "fast")?,
+                    ZSTD_DEFAULT => map.serialize_entry("level", "default")?,
+                    ZSTD_BEST => map.serialize_entry("level", "best")?,
+                    level => map.serialize_entry("level", &level)?,
+                };
+            }
         };
         map.end()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Read;
+
+    fn parse(config: &str) -> Result<Compression, toml::de::Error> {
+        toml::from_str::<toml::Value>(config).unwrap()["compression"]
+            .clone()
+            .try_into()
+    }
+
+    #[test]
+    fn deserializes_algorithms() {
+        let cases = vec![
+            (r#"compression = "zstd""#, Compression::Zstd(None)),
+            (r#"compression = "snappy""#, Compression::Snappy),
+            (r#"compression = "lz4""#, Compression::Lz4),
+            (
+                r#"compression = { algorithm = "zstd", level = 19 }"#,
+                Compression::Zstd(Some(19)),
+            ),
+            (
+                r#"compression = { algorithm = "zstd", level = "best" }"#,
+                Compression::Zstd(Some(ZSTD_BEST)),
+            ),
+        ];
+        for (config, expected) in cases {
+            assert_eq!(parse(config).unwrap(), expected);
+        }
+
+        assert!(parse(r#"compression = { algorithm = "snappy", level = 3 }"#).is_err());
+        assert!(parse(r#"compression = { algorithm = "zstd", level = 30 }"#).is_err());
+    }
+
+    #[test]
+    fn checks_supported_algorithms() {
+        let gzip_only = &["gzip"];
+        assert!(Compression::None.check_supported("Loki", gzip_only).is_ok());
+        assert!(Compression::gzip_default()
+            .check_supported("Loki", gzip_only)
+            .is_ok());
+
+        let error = Compression::zstd_default()
+            .check_supported("Loki", gzip_only)
+            .unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "Loki doesn't accept zstd compressed requests."
+        );
+    }
+
+    #[test]
+    fn roundtrips_serialization() {
+        for compression in &[
+            Compression::None,
+            Compression::Gzip(Some(GZIP_BEST)),
+            Compression::Zstd(Some(7)),
+            Compression::Snappy,
+            Compression::Lz4,
+        ] {
+            let value = toml::Value::try_from(compression).unwrap();
+            assert_eq!(value.try_into::<Compression>().unwrap(), *compression);
+        }
+    }
+
+    #[test]
+    fn compresses() {
+        let input = b"hello world\n".repeat(100);
+
+        let zstd = Compression::zstd_default().compress(&input).unwrap();
+        assert_eq!(zstd::decode_all(&zstd[..]).unwrap(), input);
+
+        let snappy = Compression::Snappy.compress(&input).unwrap();
+        let mut output = Vec::new();
+        snap::read::FrameDecoder::new(&snappy[..])
+            .read_to_end(&mut output)
+            .unwrap();
+        assert_eq!(output, input);
+
+        let lz4 = Compression::Lz4.compress(&input).unwrap();
+        let mut output = Vec::new();
+        lz4::Decoder::new(&lz4[..])
+            .unwrap()
+            .read_to_end(&mut output)
+            .unwrap();
+        assert_eq!(output, input);
+    }
+}
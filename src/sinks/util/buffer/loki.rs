@@ -3,20 +3,73 @@
 //! This buffer handles stream merging -- when a record is inserted into
 //! the buffer, all records having the same stream label set are grouped
 //! together for more efficient output.
+//!
+//! Loki rejects an event older than the latest one it has accepted for the
+//! same stream, so each stream is sorted as the batch is finished and the
+//! latest timestamp accepted for it is remembered across batches. Events
+//! older than that are handled according to the `OutOfOrderAction` when the
+//! batch is pushed, since only then are the pushes before it settled.
 
 use super::{
     err_event_too_large, json::BoxedRawValue, Batch, BatchConfig, BatchError, BatchSettings,
     BatchSize, PushResult,
 };
+use crate::internal_events::{LokiOutOfOrderEventDropped, LokiOutOfOrderEventRewritten};
+use serde::{Deserialize, Serialize};
 use serde_json::{json, value::to_raw_value};
-use std::collections::HashMap;
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+};
 
 const WRAPPER_OVERHEAD: usize = r#"{"streams":[]}"#.len();
 const STREAM_OVERHEAD: usize = r#"{"stream":{},"values":[]}"#.len();
 const LABEL_OVERHEAD: usize = r#""":"""#.len();
+const VALUE_OVERHEAD: usize = r#"["",]"#.len();
 
 pub type Labels = Vec<(String, String)>;
 
+#[derive(Clone, Copy, Debug, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum OutOfOrderAction {
+    /// Drop the event.
+    Drop,
+    /// Send the event with the latest timestamp of its stream.
+    RewriteTimestamp,
+    /// Send the event as is, for Loki versions that accept it.
+    Accept,
+}
+
+impl Default for OutOfOrderAction {
+    fn default() -> Self {
+        Self::Drop
+    }
+}
+
+/// The latest timestamp Loki accepted for each stream, shared by all of a
+/// sink's pushes.
+#[derive(Clone, Debug, Default)]
+pub struct GlobalTimestamps {
+    map: Arc<Mutex<HashMap<Labels, i64>>>,
+}
+
+impl GlobalTimestamps {
+    fn get(&self, labels: &Labels) -> Option<i64> {
+        self.map.lock().unwrap().get(labels).copied()
+    }
+
+    /// Records the streams of a push once Loki has accepted it, so that a
+    /// failed push doesn't cause the events after it to be dropped or
+    /// rewritten.
+    pub fn commit(&self, latest: Vec<(Labels, i64)>) {
+        let mut map = self.map.lock().unwrap();
+        for (labels, timestamp) in latest {
+            let entry = map.entry(labels).or_insert(timestamp);
+            *entry = (*entry).max(timestamp);
+        }
+    }
+}
+
 #[derive(Clone, Debug)]
 pub struct LokiEvent {
     pub timestamp: i64,
@@ -29,20 +82,31 @@ pub struct LokiRecord {
     pub event: LokiEvent,
 }
 
-#[derive(Debug)]
+#[derive(Clone, Debug)]
 struct LokiEncodedEvent {
     pub timestamp: i64,
-    pub encoded: BoxedRawValue,
+    pub line: BoxedRawValue,
 }
 
-impl From<&LokiEvent> for LokiEncodedEvent {
-    // Pre-encode the record to JSON, but keep the timestamp for sorting at the end.
+impl LokiEncodedEvent {
+    fn len(&self) -> usize {
+        self.line.get().len() + self.timestamp.to_string().len() + VALUE_OVERHEAD
+    }
+
     // The final output should be: `[ts, line]'
+    fn encode(self) -> BoxedRawValue {
+        to_raw_value(&json!([format!("{}", self.timestamp), self.line]))
+            .expect("JSON encoding should never fail")
+    }
+}
+
+impl From<&LokiEvent> for LokiEncodedEvent {
+    // Pre-encode the line to JSON, but keep the timestamp apart, as it is
+    // only final once the stream is sorted.
     fn from(event: &LokiEvent) -> Self {
         Self {
             timestamp: event.timestamp,
-            encoded: to_raw_value(&json!([format!("{}", event.timestamp), event.event]))
-                .expect("JSON encoding should never fail"),
+            line: to_raw_value(&event.event).expect("JSON encoding should never fail"),
         }
     }
 }
@@ -66,9 +130,93 @@ impl LokiBuffer {
     }
 }
 
+/// A finished batch, with each of its streams sorted.
+#[derive(Clone, Debug)]
+pub struct LokiBatch {
+    streams: Vec<(Labels, Vec<LokiEncodedEvent>)>,
+}
+
+/// The body of a push request, along with the latest timestamp of each of
+/// its streams to commit once Loki accepts it.
+#[derive(Debug)]
+pub struct LokiPush {
+    pub body: serde_json::Value,
+    pub latest: Vec<(Labels, i64)>,
+}
+
+impl LokiBatch {
+    /// Applies the `OutOfOrderAction` to the events older than the latest
+    /// one accepted for their stream, and encodes the batch.
+    pub fn into_push(
+        self,
+        global_timestamps: &GlobalTimestamps,
+        out_of_order_action: OutOfOrderAction,
+    ) -> LokiPush {
+        let mut latest = Vec::with_capacity(self.streams.len());
+        let streams = self
+            .streams
+            .into_iter()
+            .filter_map(|(labels, mut events)| {
+                if let Some(accepted) = global_timestamps.get(&labels) {
+                    order_stream(accepted, out_of_order_action, &mut events);
+                }
+                let timestamp = events.last()?.timestamp;
+                latest.push((labels.clone(), timestamp));
+
+                let stream = labels.into_iter().collect::<HashMap<_, _>>();
+                let events = events
+                    .into_iter()
+                    .map(LokiEncodedEvent::encode)
+                    .collect::<Vec<_>>();
+
+                Some(json!({
+                    "stream": to_raw_value(&stream).expect("JSON encoding should never fail"),
+                    "values": events,
+                }))
+            })
+            .collect::<Vec<_>>();
+
+        LokiPush {
+            body: json!({ "streams": streams }),
+            latest,
+        }
+    }
+}
+
+/// Applies the `OutOfOrderAction` to the events of a sorted stream that are
+/// older than `accepted`.
+fn order_stream(
+    accepted: i64,
+    out_of_order_action: OutOfOrderAction,
+    events: &mut Vec<LokiEncodedEvent>,
+) {
+    let out_of_order = events.iter().take_while(|e| e.timestamp < accepted).count();
+    if out_of_order == 0 {
+        return;
+    }
+
+    match out_of_order_action {
+        OutOfOrderAction::Drop => {
+            events.drain(..out_of_order);
+            emit!(LokiOutOfOrderEventDropped {
+                count: out_of_order
+            });
+        }
+        OutOfOrderAction::RewriteTimestamp => {
+            for event in &mut events[..out_of_order] {
+                event.timestamp = accepted;
+            }
+            emit!(LokiOutOfOrderEventRewritten {
+                count: out_of_order
+            });
+        }
+        OutOfOrderAction::Accept => (),
+    }
+}
+
 impl Batch for LokiBuffer {
     type Input = LokiRecord;
-    type Output = serde_json::Value;
+    type Output = LokiBatch;
 
     fn get_settings_defaults(
         config: BatchConfig,
@@ -87,7 +235,7 @@ impl Batch for LokiBuffer {
             .sum::<usize>()
             + STREAM_OVERHEAD;
         let event: LokiEncodedEvent = (&item.event).into();
-        let event_len = event.encoded.get().len();
+        let event_len = event.len();
 
         if self.is_empty() && WRAPPER_OVERHEAD + labels_len + event_len > self.settings.bytes {
             err_event_too_large(WRAPPER_OVERHEAD + labels_len + event_len)
@@ -134,44 +282,24 @@ impl Batch for LokiBuffer {
     }
 
     fn finish(self) -> Self::Output {
-        let streams_json = self
+        let streams = self
             .streams
             .into_iter()
-            .map(|(stream, mut events)| {
-                // Sort events by timestamp
+            .map(|(labels, mut events)| {
                 events.sort_by_key(|e| e.timestamp);
-
-                let stream = stream.into_iter().collect::<HashMap<_, _>>();
-                let events = events.into_iter().map(|e| e.encoded).collect::<Vec<_>>();
-
-                (
-                    to_raw_value(&stream).expect("JSON encoding should never fail"),
-                    events,
-                )
+                (labels, events)
             })
             .collect::<Vec<_>>();
 
         // This is just to guarantee stable key ordering for tests
         #[cfg(test)]
-        let streams_json = {
-            let mut streams_json = streams_json;
-            streams_json.sort_unstable_by_key(|s| s.0.to_string());
-            streams_json
+        let streams = {
+            let mut streams = streams;
+            streams.sort_unstable_by(|a, b| a.0.cmp(&b.0));
+            streams
         };
 
-        let streams_json = streams_json
-            .into_iter()
-            .map(|(stream, events)| {
-                json!({
-                    "stream": stream,
-                    "values": events,
-                })
-            })
-            .collect::<Vec<_>>();
-
-        json!({
-            "streams": streams_json,
-        })
+        LokiBatch { streams }
     }
 
     fn num_items(&self) -> usize {
@@ -183,9 +311,57 @@ impl Batch for LokiBuffer {
 mod tests {
     use super::*;
 
+    fn new_buffer() -> LokiBuffer {
+        LokiBuffer::new(BatchSettings::default().size)
+    }
+
+    fn encode(buffer: LokiBuffer, global_timestamps: &GlobalTimestamps) -> String {
+        let push = buffer
+            .finish()
+            .into_push(global_timestamps, OutOfOrderAction::Drop);
+        serde_json::to_string(&push.body).unwrap()
+    }
+
+    fn record(timestamp: i64, event: &str) -> LokiRecord {
+        LokiRecord {
+            labels: vec![("asdf".into(), "value1".into())],
+            event: LokiEvent {
+                timestamp,
+                event: event.into(),
+            },
+        }
+    }
+
+    /// Pushes a batch of events newer than the next ones, then returns the
+    /// JSON of the next batch.
+    fn finish_after_newer(out_of_order_action: OutOfOrderAction) -> String {
+        let global_timestamps = GlobalTimestamps::default();
+        let mut buffer = new_buffer();
+        assert!(matches!(
+            buffer.push(record(20, "newer")),
+            PushResult::Ok(false)
+        ));
+        let mut next = buffer.fresh();
+        let push = buffer
+            .finish()
+            .into_push(&global_timestamps, out_of_order_action);
+        global_timestamps.commit(push.latest);
+
+        for (timestamp, event) in &[(30, "later"), (10, "earlier"), (20, "same")] {
+            assert!(matches!(
+                next.push(record(*timestamp, event)),
+                PushResult::Ok(false)
+            ));
+        }
+        let push = next
+            .finish()
+            .into_push(&global_timestamps, out_of_order_action);
+        serde_json::to_string(&push.body).unwrap()
+    }
+
     fn test_finish(buffer: LokiBuffer, expected_json: &str) {
         let buffer_bytes = buffer.num_bytes;
-        let json = serde_json::to_string(&buffer.finish()).unwrap();
+        let json = encode(buffer, &GlobalTimestamps::default());
         // Does it track the number of bytes exactly before encoding?
         assert!(
             buffer_bytes == expected_json.len(),
@@ -199,7 +375,7 @@ mod tests {
 
     #[test]
     fn insert_single() {
-        let mut buffer = LokiBuffer::new(BatchSettings::default().size);
+        let mut buffer = new_buffer();
         assert!(matches!(
             buffer.push(LokiRecord {
                 labels: vec![("label1".into(), "value1".into())],
@@ -221,7 +397,7 @@ mod tests {
 
     #[test]
     fn insert_multiple_streams() {
-        let mut buffer = LokiBuffer::new(BatchSettings::default().size);
+        let mut buffer = new_buffer();
         for n in 1..4 {
             assert!(matches!(
                 buffer.push(LokiRecord {
@@ -245,7 +421,7 @@ mod tests {
 
     #[test]
     fn insert_multiple_one_stream() {
-        let mut buffer = LokiBuffer::new(BatchSettings::default().size);
+        let mut buffer = new_buffer();
         for n in 1..4 {
             assert!(matches!(
                 buffer.push(LokiRecord {
@@ -266,4 +442,92 @@ mod tests {
             r#"{"streams":[{"stream":{"asdf":"value1"},"values":[["123456781","event #1"],["123456782","event #2"],["123456783","event #3"]]}]}"#,
         );
     }
+
+    #[test]
+    fn sorts_each_stream() {
+        let mut buffer = new_buffer();
+        for n in &[3, 1, 2] {
+            assert!(matches!(
+                buffer.push(record(123456780 + n, &format!("event #{}", n))),
+                PushResult::Ok(false)
+            ));
+        }
+
+        test_finish(
+            buffer,
+            r#"{"streams":[{"stream":{"asdf":"value1"},"values":[["123456781","event #1"],["123456782","event #2"],["123456783","event #3"]]}]}"#,
+        );
+    }
+
+    #[test]
+    fn drops_out_of_order_events() {
+        assert_eq!(
+            finish_after_newer(OutOfOrderAction::Drop),
+            r#"{"streams":[{"stream":{"asdf":"value1"},"values":[["20","same"],["30","later"]]}]}"#,
+        );
+    }
+
+    #[test]
+    fn rewrites_out_of_order_timestamps() {
+        assert_eq!(
+            finish_after_newer(OutOfOrderAction::RewriteTimestamp),
+            r#"{"streams":[{"stream":{"asdf":"value1"},"values":[["20","earlier"],["20","same"],["30","later"]]}]}"#,
+        );
+    }
+
+    #[test]
+    fn accepts_out_of_order_events() {
+        assert_eq!(
+            finish_after_newer(OutOfOrderAction::Accept),
+            r#"{"streams":[{"stream":{"asdf":"value1"},"values":[["10","earlier"],["20","same"],["30","later"]]}]}"#,
+        );
+    }
+
+    #[test]
+    fn tracks_streams_separately() {
+        let global_timestamps = GlobalTimestamps::default();
+        let mut buffer = new_buffer();
+        assert!(matches!(
+            buffer.push(record(20, "newer")),
+            PushResult::Ok(false)
+        ));
+        let push = buffer
+            .finish()
+            .into_push(&global_timestamps, OutOfOrderAction::Drop);
+        global_timestamps.commit(push.latest);
+
+        let mut next = new_buffer();
+        let mut other = record(10, "other stream");
+        other.labels = vec![("asdf".into(), "value2".into())];
+        assert!(matches!(next.push(other), PushResult::Ok(false)));
+
+        assert_eq!(
+            encode(next, &global_timestamps),
+            r#"{"streams":[{"stream":{"asdf":"value2"},"values":[["10","other stream"]]}]}"#,
+        );
+    }
+
+    #[test]
+    fn ignores_uncommitted_pushes() {
+        let global_timestamps = GlobalTimestamps::default();
+        let mut buffer = new_buffer();
+        assert!(matches!(
+            buffer.push(record(20, "failed")),
+            PushResult::Ok(false)
+        ));
+        let push = buffer
+            .finish()
+            .into_push(&global_timestamps, OutOfOrderAction::Drop);
+        assert_eq!(push.latest, vec![(record(0, "").labels, 20)]);
+
+        let mut next = new_buffer();
+        assert!(matches!(
+            next.push(record(10, "earlier")),
+            PushResult::Ok(false)
+        ));
+        assert_eq!(
+            encode(next, &global_timestamps),
+            r#"{"streams":[{"stream":{"asdf":"value1"},"values":[["10","earlier"]]}]}"#,
+        );
+    }
 }
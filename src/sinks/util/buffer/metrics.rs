@@ -286,7 +286,7 @@ fn compress_distribution(values: Vec<f64>, sample_rates: Vec<u32>) -> (Vec<f64>,
 #[cfg(test)]
 mod test {
     use super::*;
-    use crate::sinks::util::BatchSink;
+    use crate::sinks::util::{flush::FlushSignal, BatchSink};
     use crate::{
         buffers::Acker,
         event::metric::{Metric, MetricValue, StatisticKind},
@@ -332,6 +332,7 @@ mod test {
             MetricBuffer::new(batch_size),
             Duration::from_secs(0),
             acker,
+            FlushSignal::default(),
         );
 
         (buffered, sent_requests)
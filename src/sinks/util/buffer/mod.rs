@@ -1,47 +1,34 @@
 use super::batch::{
     err_event_too_large, Batch, BatchConfig, BatchError, BatchSettings, BatchSize, PushResult,
 };
-use flate2::write::GzEncoder;
 use std::io::Write;
 
 pub mod compression;
 pub mod json;
 pub mod loki;
 pub mod metrics;
+#[cfg(any(feature = "sinks-aws_s3", feature = "sinks-gcp"))]
+pub mod parquet;
 pub mod partition;
 pub mod vec;
 
-pub use compression::{Compression, GZIP_FAST};
+pub use compression::{Compression, Compressor, GZIP_FAST};
 pub use partition::{Partition, PartitionBuffer, PartitionInnerBuffer};
 
 #[derive(Debug)]
 pub struct Buffer {
-    inner: InnerBuffer,
+    inner: Compressor<Vec<u8>>,
     num_items: usize,
     num_bytes: usize,
     settings: BatchSize<Self>,
     compression: Compression,
 }
 
-#[derive(Debug)]
-pub enum InnerBuffer {
-    Plain(Vec<u8>),
-    Gzip(GzEncoder<Vec<u8>>),
-}
-
 impl Buffer {
     pub fn new(settings: BatchSize<Self>, compression: Compression) -> Self {
         let buffer = Vec::with_capacity(settings.bytes);
-        let inner = match compression {
-            Compression::None => InnerBuffer::Plain(buffer),
-            Compression::Gzip(level) => {
-                let level = level.unwrap_or(GZIP_FAST);
-                InnerBuffer::Gzip(GzEncoder::new(
-                    buffer,
-                    flate2::Compression::new(level as u32),
-                ))
-            }
-        };
+        let inner = Compressor::new(buffer, compression)
+            .expect("This can't fail because the inner writer is a Vec");
         Self {
             inner,
             num_items: 0,
@@ -53,21 +40,13 @@ impl Buffer {
 
     pub fn push(&mut self, input: &[u8]) {
         self.num_items += 1;
-        match &mut self.inner {
-            InnerBuffer::Plain(inner) => {
-                inner.extend_from_slice(input);
-            }
-            InnerBuffer::Gzip(inner) => {
-                inner.write_all(input).unwrap();
-            }
-        }
+        self.inner.write_all(input).unwrap();
     }
 
     pub fn is_empty(&self) -> bool {
-        match &self.inner {
-            InnerBuffer::Plain(inner) => inner.is_empty(),
-            InnerBuffer::Gzip(inner) => inner.get_ref().is_empty(),
-        }
+        // Some compressors write a header before any input, so the items are
+        // counted rather than the bytes.
+        self.num_items == 0
     }
 }
 
@@ -111,12 +90,9 @@ impl Batch for Buffer {
     }
 
     fn finish(self) -> Self::Output {
-        match self.inner {
-            InnerBuffer::Plain(inner) => inner,
-            InnerBuffer::Gzip(inner) => inner
-                .finish()
-                .expect("This can't fail because the inner writer is a Vec"),
-        }
+        self.inner
+            .finish()
+            .expect("This can't fail because the inner writer is a Vec")
     }
 
     fn num_items(&self) -> usize {
@@ -128,7 +104,7 @@ impl Batch for Buffer {
 mod test {
     use super::{Buffer, Compression};
     use crate::buffers::Acker;
-    use crate::sinks::util::{BatchSettings, BatchSink};
+    use crate::sinks::util::{flush::FlushSignal, BatchSettings, BatchSink};
     use futures::{compat::Future01CompatExt, future};
     use futures01::Sink;
     use std::{
@@ -157,6 +133,7 @@ mod test {
             Buffer::new(batch_size, Compression::gzip_default()),
             timeout,
             acker,
+            FlushSignal::default(),
         );
 
         let input = std::iter::repeat(
@@ -0,0 +1,442 @@
+//! A `Batch` that writes its events as a Parquet file.
+//!
+//! The columns of the file are configured up front. Each event becomes one
+//! row, with the value of each column taken from the event field of the same
+//! name. Fields that are missing, or that don't fit the type of their column,
+//! are written as nulls.
+//!
+//! The configuration is always available, so that configs stay valid, but
+//! the buffer is only built with the `parquet` feature.
+
+#[cfg(feature = "parquet")]
+use super::super::batch::{
+    err_event_too_large, Batch, BatchConfig, BatchError, BatchSettings, BatchSize, PushResult,
+};
+#[cfg(feature = "parquet")]
+use crate::event::{Event, Value};
+use indexmap::IndexMap;
+#[cfg(feature = "parquet")]
+use parquet::{
+    basic::{Compression, LogicalType, Repetition, Type as PhysicalType},
+    column::writer::ColumnWriter,
+    data_type::ByteArray,
+    errors::ParquetError,
+    file::{
+        properties::{WriterProperties, WriterPropertiesPtr},
+        writer::{FileWriter, InMemoryWriteableCursor, RowGroupWriter, SerializedFileWriter},
+    },
+    schema::types::{Type, TypePtr},
+};
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "parquet")]
+use std::sync::Arc;
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct ParquetConfig {
+    /// The columns of the file, in order, keyed by the event field they are
+    /// read from.
+    pub schema: IndexMap<String, ParquetColumnType>,
+    #[serde(default)]
+    pub compression: ParquetCompression,
+    /// The most rows written to a single row group. Each batch is split into
+    /// as many row groups as it needs.
+    #[serde(default = "default_row_group_size")]
+    pub row_group_size: usize,
+}
+
+const fn default_row_group_size() -> usize {
+    10_000
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ParquetColumnType {
+    Boolean,
+    Int64,
+    Double,
+    String,
+    /// Milliseconds since the Unix epoch.
+    Timestamp,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, Derivative, PartialEq, Eq)]
+#[derivative(Default)]
+#[serde(rename_all = "snake_case")]
+pub enum ParquetCompression {
+    None,
+    #[derivative(Default)]
+    Snappy,
+    Zstd,
+}
+
+#[cfg(feature = "parquet")]
+impl From<ParquetCompression> for Compression {
+    fn from(compression: ParquetCompression) -> Self {
+        match compression {
+            ParquetCompression::None => Compression::UNCOMPRESSED,
+            ParquetCompression::Snappy => Compression::SNAPPY,
+            ParquetCompression::Zstd => Compression::ZSTD,
+        }
+    }
+}
+
+impl ParquetConfig {
+    /// Builds the file schema and writer properties shared by every batch.
+    pub fn build(&self) -> crate::Result<ParquetSchema> {
+        if self.schema.is_empty() {
+            return Err("Parquet schema must have at least one column".into());
+        }
+        if self.row_group_size == 0 {
+            return Err("Parquet row_group_size must be greater than zero".into());
+        }
+
+        self.build_schema()
+    }
+
+    #[cfg(not(feature = "parquet"))]
+    fn build_schema(&self) -> crate::Result<ParquetSchema> {
+        Err("Parquet output requires Vector to be built with the `parquet` feature".into())
+    }
+
+    #[cfg(feature = "parquet")]
+    fn build_schema(&self) -> crate::Result<ParquetSchema> {
+        let mut fields = self
+            .schema
+            .iter()
+            .map(|(name, column)| {
+                let (physical, logical) = match column {
+                    ParquetColumnType::Boolean => (PhysicalType::BOOLEAN, LogicalType::NONE),
+                    ParquetColumnType::Int64 => (PhysicalType::INT64, LogicalType::INT_64),
+                    ParquetColumnType::Double => (PhysicalType::DOUBLE, LogicalType::NONE),
+                    ParquetColumnType::String => (PhysicalType::BYTE_ARRAY, LogicalType::UTF8),
+                    ParquetColumnType::Timestamp => {
+                        (PhysicalType::INT64, LogicalType::TIMESTAMP_MILLIS)
+                    }
+                };
+                Type::primitive_type_builder(name, physical)
+                    .with_repetition(Repetition::OPTIONAL)
+                    .with_logical_type(logical)
+                    .build()
+                    .map(Arc::new)
+            })
+            .collect::<Result<Vec<TypePtr>, ParquetError>>()?;
+
+        let schema = Type::group_type_builder("event")
+            .with_fields(&mut fields)
+            .build()?;
+
+        let properties = WriterProperties::builder()
+            .set_compression(self.compression.into())
+            .build();
+
+        Ok(ParquetSchema {
+            columns: self.schema.clone(),
+            schema: Arc::new(schema),
+            properties: Arc::new(properties),
+            row_group_size: self.row_group_size,
+        })
+    }
+}
+
+/// Without the `parquet` feature a schema can't be built.
+#[cfg(not(feature = "parquet"))]
+#[derive(Debug)]
+pub enum ParquetSchema {}
+
+/// The file schema and writer properties shared by every batch.
+#[cfg(feature = "parquet")]
+#[derive(Debug)]
+pub struct ParquetSchema {
+    columns: IndexMap<String, ParquetColumnType>,
+    schema: TypePtr,
+    properties: WriterPropertiesPtr,
+    row_group_size: usize,
+}
+
+#[cfg(feature = "parquet")]
+#[derive(Clone, Debug)]
+enum ColumnValue {
+    Boolean(bool),
+    Int64(i64),
+    Double(f64),
+    ByteArray(ByteArray),
+}
+
+#[cfg(feature = "parquet")]
+impl ColumnValue {
+    fn new(column: ParquetColumnType, value: &Value) -> Option<Self> {
+        match (column, value) {
+            (ParquetColumnType::Boolean, Value::Boolean(value)) => Some(Self::Boolean(*value)),
+            (ParquetColumnType::Int64, Value::Integer(value)) => Some(Self::Int64(*value)),
+            (ParquetColumnType::Double, Value::Float(value)) => Some(Self::Double(*value)),
+            (ParquetColumnType::Double, Value::Integer(value)) => Some(Self::Double(*value as f64)),
+            (ParquetColumnType::String, Value::Null) => None,
+            (ParquetColumnType::String, value) => Some(Self::ByteArray(ByteArray::from(
+                value.to_string_lossy().into_bytes(),
+            ))),
+            (ParquetColumnType::Timestamp, Value::Timestamp(value)) => {
+                Some(Self::Int64(value.timestamp_millis()))
+            }
+            _ => None,
+        }
+    }
+
+    /// The approximate size of the value once written.
+    fn size(&self) -> usize {
+        match self {
+            Self::Boolean(_) => 1,
+            Self::Int64(_) | Self::Double(_) => 8,
+            Self::ByteArray(value) => value.len(),
+        }
+    }
+}
+
+#[cfg(feature = "parquet")]
+type Row = Vec<Option<ColumnValue>>;
+
+#[cfg(feature = "parquet")]
+/// A `batch` implementation that encodes log events as the rows of a
+/// Parquet file.
+#[derive(Debug)]
+pub struct ParquetBuffer {
+    rows: Vec<Row>,
+    num_bytes: usize,
+    settings: BatchSize<Self>,
+    schema: Arc<ParquetSchema>,
+}
+
+#[cfg(feature = "parquet")]
+impl ParquetBuffer {
+    pub fn new(settings: BatchSize<Self>, schema: Arc<ParquetSchema>) -> Self {
+        Self {
+            rows: Vec::new(),
+            num_bytes: 0,
+            settings,
+            schema,
+        }
+    }
+
+    fn row(&self, event: &Event) -> Row {
+        let log = event.as_log();
+        self.schema
+            .columns
+            .iter()
+            .map(|(field, column)| {
+                log.get(field)
+                    .and_then(|value| ColumnValue::new(*column, value))
+            })
+            .collect()
+    }
+
+    fn write(self) -> Result<Vec<u8>, ParquetError> {
+        let cursor = InMemoryWriteableCursor::default();
+        let mut writer = SerializedFileWriter::new(
+            cursor.clone(),
+            Arc::clone(&self.schema.schema),
+            Arc::clone(&self.schema.properties),
+        )?;
+
+        for rows in self.rows.chunks(self.schema.row_group_size) {
+            let mut row_group = writer.next_row_group()?;
+            let mut index = 0;
+            while let Some(mut column) = row_group.next_column()? {
+                write_column(&mut column, rows.iter().map(|row| row[index].as_ref()))?;
+                row_group.close_column(column)?;
+                index += 1;
+            }
+            writer.close_row_group(row_group)?;
+        }
+        writer.close()?;
+
+        Ok(cursor.data())
+    }
+}
+
+#[cfg(feature = "parquet")]
+/// Writes one column of a row group. Columns are optional, so nulls are
+/// written as a definition level of zero and have no value.
+fn write_column<'a>(
+    column: &mut ColumnWriter,
+    values: impl Iterator<Item = Option<&'a ColumnValue>>,
+) -> Result<(), ParquetError> {
+    let values = values.collect::<Vec<_>>();
+    let levels = values
+        .iter()
+        .map(|value| if value.is_some() { 1 } else { 0 })
+        .collect::<Vec<i16>>();
+    let values = values.into_iter().flatten();
+
+    match column {
+        ColumnWriter::BoolColumnWriter(writer) => {
+            let values = values
+                .filter_map(|value| match value {
+                    ColumnValue::Boolean(value) => Some(*value),
+                    _ => None,
+                })
+                .collect::<Vec<_>>();
+            writer.write_batch(&values, Some(&levels), None)?;
+        }
+        ColumnWriter::Int64ColumnWriter(writer) => {
+            let values = values
+                .filter_map(|value| match value {
+                    ColumnValue::Int64(value) => Some(*value),
+                    _ => None,
+                })
+                .collect::<Vec<_>>();
+            writer.write_batch(&values, Some(&levels), None)?;
+        }
+        ColumnWriter::DoubleColumnWriter(writer) => {
+            let values = values
+                .filter_map(|value| match value {
+                    ColumnValue::Double(value) => Some(*value),
+                    _ => None,
+                })
+                .collect::<Vec<_>>();
+            writer.write_batch(&values, Some(&levels), None)?;
+        }
+        ColumnWriter::ByteArrayColumnWriter(writer) => {
+            let values = values
+                .filter_map(|value| match value {
+                    ColumnValue::ByteArray(value) => Some(value.clone()),
+                    _ => None,
+                })
+                .collect::<Vec<_>>();
+            writer.write_batch(&values, Some(&levels), None)?;
+        }
+        _ => unreachable!("The schema only has boolean, int64, double and byte array columns"),
+    }
+
+    Ok(())
+}
+
+#[cfg(feature = "parquet")]
+impl Batch for ParquetBuffer {
+    type Input = Event;
+    type Output = Vec<u8>;
+
+    fn get_settings_defaults(
+        config: BatchConfig,
+        defaults: BatchSettings<Self>,
+    ) -> Result<BatchSettings<Self>, BatchError> {
+        Ok(config
+            .use_size_as_bytes()?
+            .get_settings_or_default(defaults))
+    }
+
+    fn push(&mut self, item: Self::Input) -> PushResult<Self::Input> {
+        // Column compression happens when the file is written, so this
+        // counts the uncompressed size of the values.
+        let row = self.row(&item);
+        let size = row.iter().flatten().map(ColumnValue::size).sum::<usize>();
+        let new_bytes = self.num_bytes + size;
+        if self.is_empty() && size > self.settings.bytes {
+            err_event_too_large(size)
+        } else if self.rows.len() >= self.settings.events || new_bytes > self.settings.bytes {
+            PushResult::Overflow(item)
+        } else {
+            self.rows.push(row);
+            self.num_bytes = new_bytes;
+            PushResult::Ok(
+                self.rows.len() >= self.settings.events || new_bytes >= self.settings.bytes,
+            )
+        }
+    }
+
+    fn is_empty(&self) -> bool {
+        self.rows.is_empty()
+    }
+
+    fn fresh(&self) -> Self {
+        Self::new(self.settings, Arc::clone(&self.schema))
+    }
+
+    fn finish(self) -> Self::Output {
+        self.write()
+            .expect("This can't fail because the values match the schema and the writer is a Vec")
+    }
+
+    fn num_items(&self) -> usize {
+        self.rows.len()
+    }
+}
+
+#[cfg(all(test, feature = "parquet"))]
+mod tests {
+    use super::*;
+    use chrono::{TimeZone, Utc};
+    use parquet::{
+        file::reader::{FileReader, SerializedFileReader},
+        record::RowAccessor,
+        util::cursor::SliceableCursor,
+    };
+
+    fn schema(row_group_size: usize) -> Arc<ParquetSchema> {
+        let config: ParquetConfig = toml::from_str(&format!(
+            r#"
+            schema.message = "string"
+            schema.status = "int64"
+            schema.duration = "double"
+            schema.cached = "boolean"
+            schema.timestamp = "timestamp"
+            compression = "zstd"
+            row_group_size = {}
+            "#,
+            row_group_size
+        ))
+        .unwrap();
+        Arc::new(config.build().unwrap())
+    }
+
+    fn event(status: i64) -> Event {
+        let mut event = Event::new_empty_log();
+        let log = event.as_mut_log();
+        log.insert("message", format!("request {}", status));
+        log.insert("status", status);
+        log.insert("duration", 1.5);
+        log.insert("cached", "not a boolean");
+        log.insert("timestamp", Utc.timestamp_millis(1_600_000_000_123));
+        event
+    }
+
+    #[test]
+    fn writes_rows_in_row_groups() {
+        let batch = BatchSettings::default().bytes(9999).events(10).size;
+        let mut buffer = ParquetBuffer::new(batch, schema(2));
+
+        for status in 0..3 {
+            assert_eq!(buffer.push(event(status)), PushResult::Ok(false));
+        }
+
+        let reader = SerializedFileReader::new(SliceableCursor::new(buffer.finish())).unwrap();
+        assert_eq!(reader.metadata().num_row_groups(), 2);
+
+        let rows = reader.get_row_iter(None).unwrap().collect::<Vec<_>>();
+        assert_eq!(rows.len(), 3);
+
+        let row = &rows[2];
+        assert_eq!(row.get_string(0).unwrap(), "request 2");
+        assert_eq!(row.get_long(1).unwrap(), 2);
+        assert_eq!(row.get_double(2).unwrap(), 1.5);
+        // Values that don't fit their column are written as nulls.
+        assert!(row.get_bool(3).is_err());
+        assert_eq!(row.get_timestamp_millis(4).unwrap(), 1_600_000_000_123);
+    }
+
+    #[test]
+    fn overflows_on_events() {
+        let batch = BatchSettings::default().bytes(9999).events(2).size;
+        let mut buffer = ParquetBuffer::new(batch, schema(10));
+
+        assert_eq!(buffer.push(event(1)), PushResult::Ok(false));
+        assert_eq!(buffer.push(event(2)), PushResult::Ok(true));
+        assert!(matches!(buffer.push(event(3)), PushResult::Overflow(_)));
+        assert_eq!(buffer.num_items(), 2);
+    }
+
+    #[test]
+    fn rejects_empty_schema() {
+        let config: ParquetConfig = toml::from_str("schema = {}").unwrap();
+        assert!(config.build().is_err());
+    }
+}
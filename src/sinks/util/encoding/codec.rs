@@ -0,0 +1,441 @@
+use super::{EncodingConfig, EncodingConfigWithDefault};
+use crate::{
+    config::log_schema,
+    event::{proto, Event, LogEvent, Value},
+    Result,
+};
+use prost::Message;
+use serde::{Deserialize, Serialize};
+use snafu::{ResultExt, Snafu};
+use std::{
+    fmt::{self, Write},
+    path::PathBuf,
+    sync::Arc,
+};
+
+/// Serializes single events into bytes.
+pub trait Encoder {
+    /// Encodes an event the encoding rules have already been applied to.
+    ///
+    /// The result holds the event alone, without any trailing delimiter.
+    fn encode_event(&self, event: Event) -> Result<Vec<u8>>;
+
+    /// Reads the files the encoder is configured with. Sinks call this when
+    /// they are built, so that parsing a config doesn't touch the filesystem.
+    fn load(&mut self) -> Result<()> {
+        Ok(())
+    }
+
+    /// Whether encoded events can hold any bytes, including newlines.
+    fn is_binary(&self) -> bool {
+        false
+    }
+
+    /// Delimits an encoded event, for sinks writing a stream of them. Text is
+    /// followed by a newline, while binary events are prefixed with their
+    /// length as a 32-bit big-endian integer.
+    fn frame(&self, mut bytes: Vec<u8>) -> Vec<u8> {
+        if self.is_binary() {
+            let mut framed = Vec::with_capacity(4 + bytes.len());
+            framed.extend_from_slice(&(bytes.len() as u32).to_be_bytes());
+            framed.extend_from_slice(&bytes);
+            framed
+        } else {
+            bytes.push(b'\n');
+            bytes
+        }
+    }
+}
+
+/// The encodings any sink writing bytes can offer.
+///
+/// Events are encoded one at a time. `json` and `ndjson` encode an event the
+/// same way; sinks sending batches in one payload write `json` batches as
+/// arrays and `ndjson` batches as one event per line.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq, Derivative)]
+#[derivative(Default)]
+#[serde(rename_all = "snake_case")]
+pub enum StandardEncodings {
+    /// The message field.
+    #[derivative(Default)]
+    Text,
+    Json,
+    Ndjson,
+    /// `key=value` pairs for every field, sorted by key.
+    Logfmt,
+    /// The values of the given fields, in order, as one CSV record.
+    Csv {
+        fields: Vec<String>,
+    },
+    /// An Avro datum, following the schema in the given file. Being binary,
+    /// it is length-delimited when written as a stream.
+    Avro(AvroSchema),
+    /// A protobuf `EventWrapper`, as sent by the `vector` sink, and
+    /// length-delimited the same way.
+    Protobuf,
+    /// The unchanged bytes of a single field.
+    Raw {
+        #[serde(default = "default_raw_field")]
+        field: String,
+    },
+}
+
+fn default_raw_field() -> String {
+    log_schema().message_key().to_owned()
+}
+
+#[derive(Debug, Snafu)]
+enum EncoderError {
+    #[snafu(display("Metrics can't be encoded as {}.", codec))]
+    UnsupportedMetric { codec: &'static str },
+    #[snafu(display("Event is missing the {:?} field.", field))]
+    MissingField { field: String },
+    #[snafu(display("Unable to read Avro schema {:?}: {}", path, source))]
+    AvroSchemaRead {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+    #[snafu(display("Invalid Avro schema {:?}: {}", path, source))]
+    AvroSchemaParse {
+        path: PathBuf,
+        source: avro_rs::Error,
+    },
+    #[snafu(display("Avro schema {:?} was never loaded.", path))]
+    AvroSchemaNotLoaded { path: PathBuf },
+}
+
+impl StandardEncodings {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Text => "text",
+            Self::Json => "json",
+            Self::Ndjson => "ndjson",
+            Self::Logfmt => "logfmt",
+            Self::Csv { .. } => "csv",
+            Self::Avro(_) => "avro",
+            Self::Protobuf => "protobuf",
+            Self::Raw { .. } => "raw",
+        }
+    }
+
+    /// The `Content-Type` of a payload of events in this encoding.
+    pub fn content_type(&self) -> &'static str {
+        match self {
+            Self::Text | Self::Logfmt | Self::Raw { .. } => "text/plain",
+            Self::Json => "application/json",
+            Self::Ndjson => "application/x-ndjson",
+            Self::Csv { .. } => "text/csv",
+            Self::Avro(_) => "avro/binary",
+            Self::Protobuf => "application/x-protobuf",
+        }
+    }
+}
+
+impl Encoder for StandardEncodings {
+    fn encode_event(&self, event: Event) -> Result<Vec<u8>> {
+        let log = match event {
+            Event::Log(log) => log,
+            Event::Metric(metric) => {
+                return match self {
+                    Self::Text => Ok(metric.to_string().into_bytes()),
+                    Self::Json | Self::Ndjson => Ok(serde_json::to_vec(&metric)?),
+                    Self::Protobuf => encode_protobuf(Event::Metric(metric)),
+                    _ => Err(EncoderError::UnsupportedMetric { codec: self.name() }.into()),
+                };
+            }
+        };
+
+        match self {
+            Self::Text => Ok(log
+                .get(log_schema().message_key())
+                .map(|value| value.as_bytes().to_vec())
+                .unwrap_or_default()),
+            Self::Json | Self::Ndjson => Ok(serde_json::to_vec(&log)?),
+            Self::Logfmt => Ok(encode_logfmt(&log).into_bytes()),
+            Self::Csv { fields } => encode_csv(&log, fields),
+            Self::Avro(schema) => schema.encode(&log),
+            Self::Protobuf => encode_protobuf(Event::Log(log)),
+            Self::Raw { field } => log
+                .get(field)
+                .map(|value| value.as_bytes().to_vec())
+                .ok_or_else(|| {
+                    EncoderError::MissingField {
+                        field: field.clone(),
+                    }
+                    .into()
+                }),
+        }
+    }
+
+    fn load(&mut self) -> Result<()> {
+        match self {
+            Self::Avro(schema) => schema.load(),
+            _ => Ok(()),
+        }
+    }
+
+    fn is_binary(&self) -> bool {
+        matches!(self, Self::Avro(_) | Self::Protobuf)
+    }
+}
+
+impl<E: Encoder + Clone> EncodingConfig<E> {
+    /// A copy of this config with its codec loaded.
+    pub fn loaded(&self) -> Result<Self> {
+        let mut config = self.clone();
+        config.codec.load()?;
+        Ok(config)
+    }
+}
+
+impl<E: Encoder + Clone + Default + PartialEq> EncodingConfigWithDefault<E> {
+    /// A copy of this config with its codec loaded.
+    pub fn loaded(&self) -> Result<Self> {
+        let mut config = self.clone();
+        config.codec.load()?;
+        Ok(config)
+    }
+}
+
+fn encode_logfmt(log: &LogEvent) -> String {
+    let mut output = String::new();
+    for (key, value) in log.all_fields() {
+        if !output.is_empty() {
+            output.push(' ');
+        }
+        let value = match value {
+            Value::Null => String::new(),
+            value => value.to_string_lossy(),
+        };
+        write!(output, "{}={}", key, LogfmtValue(&value)).expect("writing to a String");
+    }
+    output
+}
+
+/// Quotes values that would otherwise be ambiguous.
+struct LogfmtValue<'a>(&'a str);
+
+impl fmt::Display for LogfmtValue<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let needs_quotes = self.0.is_empty()
+            || self
+                .0
+                .chars()
+                .any(|c| c.is_whitespace() || c == '=' || c == '"' || c == '\\');
+        if !needs_quotes {
+            return f.write_str(self.0);
+        }
+
+        f.write_char('"')?;
+        for c in self.0.chars() {
+            match c {
+                '"' => f.write_str("\\\"")?,
+                '\\' => f.write_str("\\\\")?,
+                '\n' => f.write_str("\\n")?,
+                c => f.write_char(c)?,
+            }
+        }
+        f.write_char('"')
+    }
+}
+
+fn encode_csv(log: &LogEvent, fields: &[String]) -> Result<Vec<u8>> {
+    let mut writer = csv::WriterBuilder::new()
+        .terminator(csv::Terminator::Any(b'\n'))
+        .from_writer(Vec::new());
+    writer.write_record(fields.iter().map(|field| {
+        log.get(field)
+            .map(|value| value.to_string_lossy())
+            .unwrap_or_default()
+    }))?;
+
+    let mut bytes = writer.into_inner().map_err(|error| error.to_string())?;
+    bytes.pop();
+    Ok(bytes)
+}
+
+fn encode_protobuf(event: Event) -> Result<Vec<u8>> {
+    let event = proto::EventWrapper::from(event);
+    let mut bytes = Vec::with_capacity(event.encoded_len());
+    event.encode(&mut bytes)?;
+    Ok(bytes)
+}
+
+/// The Avro schema file to encode events with, which is read when the sink
+/// is built.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct AvroSchema {
+    schema_path: PathBuf,
+    #[serde(skip)]
+    schema: Option<Arc<avro_rs::Schema>>,
+}
+
+impl AvroSchema {
+    fn load(&mut self) -> Result<()> {
+        let path = &self.schema_path;
+        let schema = std::fs::read_to_string(path).context(AvroSchemaRead { path })?;
+        let schema = avro_rs::Schema::parse_str(&schema).context(AvroSchemaParse { path })?;
+        self.schema = Some(Arc::new(schema));
+        Ok(())
+    }
+
+    fn encode(&self, log: &LogEvent) -> Result<Vec<u8>> {
+        let schema = self
+            .schema
+            .as_ref()
+            .ok_or_else(|| EncoderError::AvroSchemaNotLoaded {
+                path: self.schema_path.clone(),
+            })?;
+        let value = avro_rs::to_value(log)?.resolve(schema)?;
+        Ok(avro_rs::to_avro_datum(schema, value)?)
+    }
+}
+
+impl PartialEq for AvroSchema {
+    fn eq(&self, other: &Self) -> bool {
+        self.schema_path == other.schema_path
+    }
+}
+
+impl Eq for AvroSchema {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        event::metric::{Metric, MetricKind, MetricValue},
+        test_util::temp_file,
+    };
+
+    fn event() -> Event {
+        let mut event = Event::new_empty_log();
+        let log = event.as_mut_log();
+        log.insert("message", "hello world");
+        log.insert("status", 200);
+        log.insert("user.name", "a \"quoted\" name");
+        event
+    }
+
+    fn encode(codec: &StandardEncodings, event: Event) -> String {
+        String::from_utf8(codec.encode_event(event).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn encodes_text_and_json() {
+        assert_eq!(encode(&StandardEncodings::Text, event()), "hello world");
+        assert_eq!(
+            encode(&StandardEncodings::Json, event()),
+            r#"{"message":"hello world","status":200,"user":{"name":"a \"quoted\" name"}}"#
+        );
+    }
+
+    #[test]
+    fn encodes_logfmt() {
+        assert_eq!(
+            encode(&StandardEncodings::Logfmt, event()),
+            r#"message="hello world" status=200 user.name="a \"quoted\" name""#
+        );
+    }
+
+    #[test]
+    fn encodes_csv() {
+        let codec = StandardEncodings::Csv {
+            fields: vec!["status".into(), "missing".into(), "user.name".into()],
+        };
+        assert_eq!(encode(&codec, event()), r#"200,,"a ""quoted"" name""#);
+    }
+
+    #[test]
+    fn encodes_raw_field() {
+        let codec: StandardEncodings = toml::from_str::<toml::Value>(r#"raw.field = "status""#)
+            .unwrap()
+            .try_into()
+            .unwrap();
+        assert_eq!(encode(&codec, event()), "200");
+
+        let codec = StandardEncodings::Raw {
+            field: "nope".into(),
+        };
+        assert!(codec.encode_event(event()).is_err());
+    }
+
+    #[test]
+    fn encodes_protobuf() {
+        let bytes = StandardEncodings::Protobuf.encode_event(event()).unwrap();
+        let decoded = Event::from(proto::EventWrapper::decode(&bytes[..]).unwrap());
+        assert_eq!(decoded, event());
+    }
+
+    #[test]
+    fn encodes_avro() {
+        let path = temp_file();
+        std::fs::write(
+            &path,
+            r#"{
+                "type": "record",
+                "name": "log",
+                "fields": [
+                    {"name": "message", "type": "string"},
+                    {"name": "status", "type": "long"}
+                ]
+            }"#,
+        )
+        .unwrap();
+
+        let mut codec: StandardEncodings = toml::from_str::<toml::Value>(&format!(
+            "avro.schema_path = {:?}",
+            path.to_str().unwrap()
+        ))
+        .unwrap()
+        .try_into()
+        .unwrap();
+
+        let mut event = Event::new_empty_log();
+        event.as_mut_log().insert("message", "hi");
+        event.as_mut_log().insert("status", 200);
+
+        // The schema is only read once the sink is built.
+        assert!(codec.encode_event(event.clone()).is_err());
+        codec.load().unwrap();
+
+        let bytes = codec.encode_event(event).unwrap();
+        // Zig-zag encoded string length and bytes, then the zig-zag long.
+        assert_eq!(bytes, vec![4, b'h', b'i', 0x90, 0x03]);
+        assert_eq!(
+            codec.frame(bytes),
+            vec![0, 0, 0, 5, 4, b'h', b'i', 0x90, 0x03]
+        );
+    }
+
+    #[test]
+    fn defers_reading_avro_schema() {
+        let codec: StandardEncodings =
+            toml::from_str::<toml::Value>(r#"avro.schema_path = "/nonexistent/schema.avsc""#)
+                .unwrap()
+                .try_into()
+                .unwrap();
+
+        assert!(EncodingConfig::from(codec).loaded().is_err());
+    }
+
+    #[test]
+    fn frames_text_with_newlines() {
+        assert_eq!(StandardEncodings::Json.frame(b"{}".to_vec()), b"{}\n");
+    }
+
+    #[test]
+    fn rejects_unsupported_metrics() {
+        let metric = Event::Metric(Metric {
+            name: "hits".into(),
+            namespace: None,
+            timestamp: None,
+            tags: None,
+            kind: MetricKind::Incremental,
+            value: MetricValue::Counter { value: 1.0 },
+        });
+
+        assert!(StandardEncodings::Json.encode_event(metric.clone()).is_ok());
+        assert!(StandardEncodings::Logfmt.encode_event(metric).is_err());
+    }
+}
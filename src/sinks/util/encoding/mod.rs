@@ -4,7 +4,8 @@
 //!   * `EncodingConfig<E>`: For sinks without a default `Encoding`.
 //!   * `EncodingConfigWithDefault<E: Default>`: For sinks that have a default `Encoding`.
 //!
-//! Your sink should define some `Encoding` enum that is used as the `E` parameter.
+//! Your sink should define some `Encoding` enum that is used as the `E` parameter, or use
+//! `StandardEncodings` if it writes bytes and can offer any of the shared formats.
 //!
 //! You can use either of these for a sink! They both implement `EncodingConfiguration`, which you
 //! will need to import as well.
@@ -16,6 +17,9 @@
 //!  1. Choose between `EncodingConfig` and `EncodingConfigWithDefault`.
 //!  2. Call `apply_rules(&mut event)` on this config **on each event** just before it gets sent.
 //!
+//! If `E` implements `Encoder`, calling `encode(event)` does both the second step and the
+//! serialization.
+//!
 //! # Implementation notes
 //!
 //! You may wonder why we have both of these types! **Great question.** `serde` works with the
@@ -24,11 +28,9 @@
 //! We don't require `E: Default` we can't always assume that, so we need to create statically
 //! distinct types! Having `EncodingConfigWithDefault` is a relatively straightforward way to
 //! accomplish this without a bunch of magic.
-//!
-// TODO: To avoid users forgetting to apply the rules, the `E` param should require a trait
-//       `Encoder` that defines some `encode` function which this config then calls internally as
-//       part of it's own (yet to be written) `encode() -> Vec<u8>` function.
 
+mod codec;
+pub use codec::{AvroSchema, Encoder, StandardEncodings};
 mod config;
 pub use config::EncodingConfig;
 mod with_default;
@@ -143,6 +145,15 @@ pub trait EncodingConfiguration<E> {
         self.apply_only_fields(event);
         self.apply_timestamp_format(event);
     }
+
+    /// Apply the EncodingConfig rules to the provided event, and encode it with the codec.
+    fn encode(&self, mut event: Event) -> Result<Vec<u8>>
+    where
+        E: Encoder,
+    {
+        self.apply_rules(&mut event);
+        self.codec().encode_event(event)
+    }
 }
 
 #[derive(Serialize, Deserialize, Debug, Copy, Clone, PartialEq, Eq)]
@@ -0,0 +1,83 @@
+//! Flushing the batches of a running sink on request.
+//!
+//! The topology creates a `FlushHandle` for each sink and hands it to the
+//! sink through its `SinkContext`. A `BatchSink` or `PartitionBatchSink` is
+//! built with a `FlushSignal` of that handle, and a `request` through the
+//! handle then wakes the sink, which sends its open batches without waiting
+//! for them to fill up or for their linger timeout.
+
+use futures01::task::AtomicTask;
+use std::sync::{
+    atomic::{AtomicUsize, Ordering},
+    Arc,
+};
+
+#[derive(Debug, Default)]
+struct Signal {
+    requests: AtomicUsize,
+    /// Number of batching sinks attached, which overlap while reloading.
+    attached: AtomicUsize,
+    task: AtomicTask,
+}
+
+/// The requesting end of a sink's flushes, kept by the topology.
+#[derive(Clone, Debug, Default)]
+pub struct FlushHandle {
+    signal: Arc<Signal>,
+}
+
+impl FlushHandle {
+    /// A signal for a batching sink to receive this handle's requests.
+    pub fn signal(&self) -> FlushSignal {
+        self.signal.attached.fetch_add(1, Ordering::AcqRel);
+        FlushSignal {
+            seen: self.signal.requests.load(Ordering::Acquire),
+            signal: Some(Arc::clone(&self.signal)),
+        }
+    }
+
+    /// Requests the sink to flush its batches. Returns `false` if the sink
+    /// doesn't batch events.
+    pub fn request(&self) -> bool {
+        if self.signal.attached.load(Ordering::Acquire) > 0 {
+            self.signal.requests.fetch_add(1, Ordering::AcqRel);
+            self.signal.task.notify();
+            true
+        } else {
+            false
+        }
+    }
+}
+
+/// The receiving end of flush requests, owned by a batching sink. The
+/// default signal never receives any.
+#[derive(Debug, Default)]
+pub struct FlushSignal {
+    signal: Option<Arc<Signal>>,
+    seen: usize,
+}
+
+impl FlushSignal {
+    /// Returns whether a flush was requested since the last call, and
+    /// arranges for the current task to be notified of the next request.
+    pub fn poll(&mut self) -> bool {
+        let signal = match &self.signal {
+            Some(signal) => signal,
+            None => return false,
+        };
+
+        signal.task.register();
+        let requests = signal.requests.load(Ordering::Acquire);
+        let requested = requests != self.seen;
+        self.seen = requests;
+        requested
+    }
+}
+
+impl Drop for FlushSignal {
+    fn drop(&mut self) {
+        if let Some(signal) = &self.signal {
+            signal.attached.fetch_sub(1, Ordering::AcqRel);
+        }
+    }
+}
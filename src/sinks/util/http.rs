@@ -1,12 +1,15 @@
 use super::{
+    flush::FlushSignal,
     retries::{RetryAction, RetryLogic},
-    sink, Batch, Partition, TowerBatchedSink, TowerPartitionSink, TowerRequestSettings,
+    sink::{self, EncodedSink},
+    Batch, Partition, TowerBatchedSink, TowerPartitionSink, TowerRequestSettings,
 };
 use crate::{buffers::Acker, event::Event, http::HttpClient};
 use bytes::{Buf, Bytes};
+use chrono::{DateTime, Utc};
 use futures::future::BoxFuture;
 use futures01::{Async, AsyncSink, Poll as Poll01, Sink, StartSend};
-use http::StatusCode;
+use http::{header::RETRY_AFTER, StatusCode};
 use hyper::body::{self, Body};
 use std::{
     fmt,
@@ -14,7 +17,7 @@ use std::{
     hash::Hash,
     sync::Arc,
     task::{Context, Poll},
-    time::Duration,
+    time::{Duration, Instant},
 };
 use tower::Service;
 
@@ -44,7 +47,7 @@ where
         K,
         B::Output,
     >,
-    slot: Option<B::Input>,
+    slot: Option<(B::Input, Option<Instant>)>,
 }
 
 impl<T, B, K> PartitionHttpSink<T, B, K, HttpRetryLogic>
@@ -62,6 +65,7 @@ where
         batch_timeout: Duration,
         client: HttpClient,
         acker: Acker,
+        flush: FlushSignal,
     ) -> Self {
         Self::with_retry_logic(
             sink,
@@ -71,6 +75,7 @@ where
             batch_timeout,
             client,
             acker,
+            flush,
         )
     }
 }
@@ -92,6 +97,7 @@ where
         batch_timeout: Duration,
         client: HttpClient,
         acker: Acker,
+        flush: FlushSignal,
     ) -> Self {
         let sink = Arc::new(sink);
 
@@ -103,7 +109,7 @@ where
             };
 
         let svc = HttpBatchService::new(client, request_builder);
-        let inner = request_settings.partition_sink(logic, svc, batch, batch_timeout, acker);
+        let inner = request_settings.partition_sink(logic, svc, batch, batch_timeout, acker, flush);
 
         Self {
             sink,
@@ -131,8 +137,9 @@ where
         }
         assert!(self.slot.is_none(), "poll_complete did not clear slot");
 
+        let ingress = item.ingress();
         if let Some(item) = self.sink.encode_event(item) {
-            self.slot = Some(item);
+            self.slot = Some((item, ingress));
             self.poll_complete()?;
         }
 
@@ -140,9 +147,9 @@ where
     }
 
     fn poll_complete(&mut self) -> Poll01<(), Self::SinkError> {
-        if let Some(item) = self.slot.take() {
-            if let AsyncSink::NotReady(item) = self.inner.start_send(item)? {
-                self.slot = Some(item);
+        if let Some((item, ingress)) = self.slot.take() {
+            if let AsyncSink::NotReady(item) = self.inner.start_send_from(item, ingress)? {
+                self.slot = Some((item, ingress));
                 return Ok(Async::NotReady);
             }
         }
@@ -180,7 +187,7 @@ where
     // An empty slot is needed to buffer an item where we encoded it but
     // the inner sink is applying back pressure. This trick is used in the `WithFlatMap`
     // sink combinator. https://docs.rs/futures/0.1.29/src/futures/sink/with_flat_map.rs.html#20
-    slot: Option<B::Input>,
+    slot: Option<(B::Input, Option<Instant>)>,
 }
 
 impl<T, B> BatchedHttpSink<T, B, HttpRetryLogic>
@@ -196,6 +203,7 @@ where
         batch_timeout: Duration,
         client: HttpClient,
         acker: Acker,
+        flush: FlushSignal,
     ) -> Self {
         Self::with_retry_logic(
             sink,
@@ -205,6 +213,7 @@ where
             batch_timeout,
             client,
             acker,
+            flush,
         )
     }
 }
@@ -224,6 +233,7 @@ where
         batch_timeout: Duration,
         client: HttpClient,
         acker: Acker,
+        flush: FlushSignal,
     ) -> Self {
         let sink = Arc::new(sink);
 
@@ -235,7 +245,7 @@ where
             };
 
         let svc = HttpBatchService::new(client, request_builder);
-        let inner = request_settings.batch_sink(logic, svc, batch, batch_timeout, acker);
+        let inner = request_settings.batch_sink(logic, svc, batch, batch_timeout, acker, flush);
 
         Self {
             sink,
@@ -261,8 +271,9 @@ where
         }
         assert!(self.slot.is_none(), "poll_complete did not clear slot");
 
+        let ingress = item.ingress();
         if let Some(item) = self.sink.encode_event(item) {
-            self.slot = Some(item);
+            self.slot = Some((item, ingress));
             self.poll_complete()?;
         }
 
@@ -270,9 +281,9 @@ where
     }
 
     fn poll_complete(&mut self) -> Poll01<(), Self::SinkError> {
-        if let Some(item) = self.slot.take() {
-            if let AsyncSink::NotReady(item) = self.inner.start_send(item)? {
-                self.slot = Some(item);
+        if let Some((item, ingress)) = self.slot.take() {
+            if let AsyncSink::NotReady(item) = self.inner.start_send_from(item, ingress)? {
+                self.slot = Some((item, ingress));
                 return Ok(Async::NotReady);
             }
         }
@@ -355,10 +366,18 @@ impl RetryLogic for HttpRetryLogic {
         let status = response.status();
 
         match status {
-            StatusCode::TOO_MANY_REQUESTS => RetryAction::Retry("too many requests".into()),
+            StatusCode::TOO_MANY_REQUESTS => {
+                RetryAction::Throttled("too many requests".into(), retry_after(response))
+            }
             StatusCode::NOT_IMPLEMENTED => {
                 RetryAction::DontRetry("endpoint not implemented".into())
             }
+            StatusCode::SERVICE_UNAVAILABLE if response.headers().contains_key(RETRY_AFTER) => {
+                RetryAction::Throttled(
+                    format!("{}: {}", status, String::from_utf8_lossy(response.body())),
+                    retry_after(response),
+                )
+            }
             _ if status.is_server_error() => RetryAction::Retry(format!(
                 "{}: {}",
                 status,
@@ -370,10 +389,29 @@ impl RetryLogic for HttpRetryLogic {
     }
 }
 
+/// Parses the delay requested by the `Retry-After` header of a response,
+/// given either in seconds or as an HTTP date.
+pub fn retry_after<B>(response: &http::Response<B>) -> Option<Duration> {
+    let value = response.headers().get(RETRY_AFTER)?.to_str().ok()?.trim();
+
+    match value.parse::<u64>() {
+        Ok(seconds) => Some(Duration::from_secs(seconds)),
+        Err(_) => {
+            let date = DateTime::parse_from_rfc2822(value).ok()?;
+            // Dates in the past ask for an immediate retry.
+            Some(
+                (date.with_timezone(&Utc) - Utc::now())
+                    .to_std()
+                    .unwrap_or_default(),
+            )
+        }
+    }
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
-    use crate::test_util::next_addr;
+    use crate::{config::ProxyConfig, test_util::next_addr};
     use futures::{compat::Future01CompatExt, future::ready};
     use futures01::Stream;
     use hyper::{
@@ -391,7 +429,7 @@ mod test {
         let response_400 = Response::builder().status(400).body(Bytes::new()).unwrap();
         let response_501 = Response::builder().status(501).body(Bytes::new()).unwrap();
 
-        assert!(logic.should_retry_response(&response_429).is_retryable());
+        assert!(logic.should_retry_response(&response_429).is_throttled());
         assert!(logic.should_retry_response(&response_500).is_retryable());
         assert!(logic
             .should_retry_response(&response_400)
@@ -401,6 +439,38 @@ mod test {
             .is_not_retryable());
     }
 
+    #[test]
+    fn util_http_retry_after() {
+        let logic = HttpRetryLogic;
+        let response = |status: u16, retry_after: &str| {
+            Response::builder()
+                .status(status)
+                .header("Retry-After", retry_after)
+                .body(Bytes::new())
+                .unwrap()
+        };
+
+        match logic.should_retry_response(&response(429, "120")) {
+            RetryAction::Throttled(_, retry_after) => {
+                assert_eq!(retry_after, Some(Duration::from_secs(120)))
+            }
+            _ => panic!("expected a throttled response"),
+        }
+
+        let unavailable = response(503, "Wed, 21 Oct 2015 07:28:00 GMT");
+        assert!(logic.should_retry_response(&unavailable).is_throttled());
+        assert_eq!(retry_after(&unavailable), Some(Duration::from_secs(0)));
+
+        let date = (Utc::now() + chrono::Duration::seconds(30)).to_rfc2822();
+        let delay = retry_after(&response(429, &date)).unwrap();
+        assert!(delay > Duration::from_secs(25) && delay <= Duration::from_secs(30));
+
+        assert_eq!(retry_after(&response(429, "soon")), None);
+        assert!(!logic
+            .should_retry_response(&Response::builder().status(503).body(Bytes::new()).unwrap())
+            .is_throttled());
+    }
+
     #[tokio::test]
     async fn util_http_it_makes_http_requests() {
         let addr = next_addr();
@@ -410,7 +480,7 @@ mod test {
             .unwrap();
 
         let request = b"hello".to_vec();
-        let client = HttpClient::new(None).unwrap();
+        let client = HttpClient::new(None, &ProxyConfig::default()).unwrap();
         let mut service = HttpBatchService::new(client, move |body: Vec<u8>| {
             Box::pin(ready(
                 http::Request::post(&uri).body(body).map_err(Into::into),
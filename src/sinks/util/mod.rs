@@ -2,6 +2,7 @@ pub mod auto_concurrency;
 pub mod batch;
 pub mod buffer;
 pub mod encoding;
+pub mod flush;
 pub mod http;
 pub mod retries;
 pub mod service;
@@ -18,7 +19,7 @@ pub mod uri;
 
 use crate::event::Event;
 use bytes::Bytes;
-use encoding::{EncodingConfig, EncodingConfiguration};
+use encoding::{Encoder, EncodingConfig, EncodingConfiguration, StandardEncodings};
 use serde::{Deserialize, Serialize};
 use snafu::Snafu;
 use std::borrow::Cow;
@@ -33,7 +34,7 @@ pub use service::{
     InFlightLimit, ServiceBuilderExt, TowerBatchedSink, TowerPartitionSink, TowerRequestConfig,
     TowerRequestLayer, TowerRequestSettings,
 };
-pub use sink::{BatchSink, PartitionBatchSink, StreamSink};
+pub use sink::{BatchSink, EncodedSink, PartitionBatchSink, StreamSink};
 pub use uri::UriSerde;
 
 #[derive(Debug, Snafu)]
@@ -54,32 +55,26 @@ pub enum Encoding {
     Json,
 }
 
+impl Encoder for Encoding {
+    fn encode_event(&self, event: Event) -> crate::Result<Vec<u8>> {
+        match self {
+            Encoding::Text => StandardEncodings::Text.encode_event(event),
+            Encoding::Json => StandardEncodings::Json.encode_event(event),
+        }
+    }
+}
+
 /**
 * Encodes the given event into raw bytes that can be sent into a Sink, according to
-* the given encoding. If there are any errors encoding the event, logs a warning
-* and returns None.
+* the given encoding, followed by a newline or, for binary encodings, preceded by its
+* length. If there are any errors encoding the event, logs a warning and returns None.
 **/
-pub fn encode_event(mut event: Event, encoding: &EncodingConfig<Encoding>) -> Option<Bytes> {
-    encoding.apply_rules(&mut event);
-    let log = event.into_log();
-
-    let b = match encoding.codec() {
-        Encoding::Json => serde_json::to_vec(&log),
-        Encoding::Text => {
-            let bytes = log
-                .get(crate::config::log_schema().message_key())
-                .map(|v| v.as_bytes().to_vec())
-                .unwrap_or_default();
-            Ok(bytes)
-        }
-    };
-
-    b.map(|mut b| {
-        b.push(b'\n');
-        Bytes::from(b)
-    })
-    .map_err(|error| error!(message = "Unable to encode.", %error))
-    .ok()
+pub fn encode_event<E: Encoder>(event: Event, encoding: &EncodingConfig<E>) -> Option<Bytes> {
+    encoding
+        .encode(event)
+        .map(|b| Bytes::from(encoding.codec().frame(b)))
+        .map_err(|error| error!(message = "Unable to encode.", %error))
+        .ok()
 }
 
 /// Joins namespace with name via delimiter if namespace is present.
@@ -1,3 +1,4 @@
+use super::service::CircuitOpen;
 use crate::Error;
 use futures::FutureExt;
 use std::{
@@ -13,6 +14,9 @@ use tower::{retry::Policy, timeout::error::Elapsed};
 pub enum RetryAction {
     /// Indicate that this request should be retried with a reason
     Retry(String),
+    /// Indicate that the server is throttling requests, and that this request
+    /// should be retried after the delay it asked for, if any
+    Throttled(String, Option<Duration>),
     /// Indicate that this request should not be retried with a reason
     DontRetry(String),
     /// Indicate that this request should not be retried but the request was successful
@@ -77,11 +81,21 @@ impl<L: RetryLogic> FixedRetryPolicy<L> {
         self.current_duration
     }
 
-    fn build_retry(&self) -> RetryPolicyFuture<L> {
+    /// Returns the delay to wait before the next attempt. A delay requested
+    /// by the server takes the place of the backoff, up to the maximum.
+    fn delay(&self, retry_after: Option<Duration>) -> Duration {
+        match retry_after {
+            Some(retry_after) => cmp::min(retry_after, self.max_duration),
+            None => self.backoff(),
+        }
+    }
+
+    fn build_retry(&self, retry_after: Option<Duration>) -> RetryPolicyFuture<L> {
         let policy = self.advance();
-        let delay = delay_for(self.backoff());
+        let duration = self.delay(retry_after);
+        let delay = delay_for(duration);
 
-        debug!(message = "Retrying request.", delay_ms = %self.backoff().as_millis());
+        debug!(message = "Retrying request.", delay_ms = %duration.as_millis());
         RetryPolicyFuture { delay, policy }
     }
 }
@@ -104,7 +118,16 @@ where
                 match self.logic.should_retry_response(response) {
                     RetryAction::Retry(reason) => {
                         warn!(message = "Retrying after response.", reason = %reason);
-                        Some(self.build_retry())
+                        Some(self.build_retry(None))
+                    }
+
+                    RetryAction::Throttled(reason, retry_after) => {
+                        warn!(
+                            message = "Retrying after throttled response.",
+                            reason = %reason,
+                            retry_after = ?retry_after,
+                        );
+                        Some(self.build_retry(retry_after))
                     }
 
                     RetryAction::DontRetry(reason) => {
@@ -124,7 +147,7 @@ where
                 if let Some(expected) = error.downcast_ref::<L::Error>() {
                     if self.logic.is_retriable_error(expected) {
                         warn!(message = "Retrying after error.", error = ?expected);
-                        Some(self.build_retry())
+                        Some(self.build_retry(None))
                     } else {
                         error!(
                             message = "Non-retriable error; dropping the request.",
@@ -134,7 +157,10 @@ where
                     }
                 } else if error.downcast_ref::<Elapsed>().is_some() {
                     warn!("Request timed out.");
-                    Some(self.build_retry())
+                    Some(self.build_retry(None))
+                } else if error.downcast_ref::<CircuitOpen>().is_some() {
+                    // Rejected requests are already reported by the breaker.
+                    None
                 } else {
                     error!(
                         message = "Unexpected error type; dropping the request.",
@@ -166,7 +192,11 @@ impl<L: RetryLogic> Future for RetryPolicyFuture<L> {
 
 impl RetryAction {
     pub fn is_retryable(&self) -> bool {
-        matches!(self, RetryAction::Retry(_))
+        matches!(self, RetryAction::Retry(_) | RetryAction::Throttled(..))
+    }
+
+    pub fn is_throttled(&self) -> bool {
+        matches!(self, RetryAction::Throttled(..))
     }
 
     pub fn is_not_retryable(&self) -> bool {
@@ -267,6 +297,59 @@ mod tests {
         assert_eq!(fut.await.unwrap(), "world");
     }
 
+    #[tokio::test]
+    async fn throttled_response_retry_after() {
+        trace_init();
+
+        time::pause();
+
+        let policy = FixedRetryPolicy::new(
+            5,
+            Duration::from_secs(1),
+            Duration::from_secs(10),
+            SvcRetryLogic,
+        );
+
+        let (mut svc, mut handle) = mock::spawn_layer(RetryLayer::new(policy));
+
+        assert_ready_ok!(svc.poll_ready());
+
+        let mut fut = task::spawn(svc.call("hello"));
+        assert_request_eq!(handle, "hello").send_response("throttled");
+        assert_pending!(fut.poll());
+
+        // The requested delay is longer than the initial backoff.
+        time::advance(Duration::from_secs(2)).await;
+        assert_pending!(fut.poll());
+        assert_pending!(task::spawn(handle.next_request()).poll());
+
+        time::advance(Duration::from_secs(2)).await;
+        assert_pending!(fut.poll());
+
+        assert_request_eq!(handle, "hello").send_response("world");
+        assert_eq!(fut.await.unwrap(), "world");
+    }
+
+    #[test]
+    fn retry_after_is_capped() {
+        let policy = FixedRetryPolicy::new(
+            10,
+            Duration::from_secs(1),
+            Duration::from_secs(10),
+            SvcRetryLogic,
+        );
+
+        assert_eq!(policy.delay(None), Duration::from_secs(1));
+        assert_eq!(
+            policy.delay(Some(Duration::from_secs(5))),
+            Duration::from_secs(5)
+        );
+        assert_eq!(
+            policy.delay(Some(Duration::from_secs(60))),
+            Duration::from_secs(10)
+        );
+    }
+
     #[test]
     fn backoff_grows_to_max() {
         let mut policy = FixedRetryPolicy::new(
@@ -309,6 +392,15 @@ mod tests {
         fn is_retriable_error(&self, error: &Self::Error) -> bool {
             error.0
         }
+
+        fn should_retry_response(&self, response: &Self::Response) -> RetryAction {
+            match *response {
+                "throttled" => {
+                    RetryAction::Throttled("slow down".into(), Some(Duration::from_secs(3)))
+                }
+                _ => RetryAction::Successful,
+            }
+        }
     }
 
     #[derive(Debug)]
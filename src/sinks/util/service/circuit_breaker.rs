@@ -0,0 +1,452 @@
+//! Stop sending requests to a service that keeps failing.
+//!
+//! After `failure_threshold` consecutive failed requests the breaker opens,
+//! and requests are held back (or dropped, if the sink's buffer is set to
+//! drop when full) until `probe_interval_secs` have passed. A single request
+//! is then let through as a probe: if it succeeds the breaker closes again,
+//! otherwise it stays open for another interval.
+
+use crate::{
+    buffers::WhenFull,
+    emit,
+    internal_events::{CircuitBreakerRejectedRequest, SinkHealthChanged},
+    sinks::util::retries::RetryLogic,
+};
+use futures::{future::BoxFuture, FutureExt};
+use serde::{Deserialize, Serialize};
+use snafu::Snafu;
+use std::{
+    cmp,
+    sync::{Arc, Mutex},
+    task::{Context, Poll},
+    time::Duration,
+};
+use tokio::time::{delay_until, Delay, Instant};
+use tower::{Layer, Service};
+
+/// How long a request waiting for the breaker to close sleeps before
+/// checking it again, so that it notices a successful probe.
+const RECHECK_INTERVAL: Duration = Duration::from_secs(1);
+
+#[derive(Clone, Copy, Debug, Derivative, Deserialize, Serialize, PartialEq)]
+#[derivative(Default)]
+pub struct CircuitBreakerSettings {
+    /// Consecutive failed requests after which the breaker opens. The
+    /// breaker is disabled when this is not set.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub failure_threshold: Option<usize>,
+
+    #[serde(default = "default_probe_interval_secs")]
+    #[derivative(Default(value = "default_probe_interval_secs()"))]
+    pub probe_interval_secs: u64,
+}
+
+const fn default_probe_interval_secs() -> u64 {
+    30
+}
+
+/// The health of a sink, as seen by its circuit breaker.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum SinkHealth {
+    /// The last request succeeded.
+    Healthy,
+    /// Requests are failing, or the breaker is probing the service.
+    Degraded,
+    /// The breaker is open.
+    Unhealthy,
+}
+
+impl SinkHealth {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Healthy => "healthy",
+            Self::Degraded => "degraded",
+            Self::Unhealthy => "unhealthy",
+        }
+    }
+
+    /// The value of the `sink_health` gauge.
+    pub fn as_gauge(self) -> f64 {
+        match self {
+            Self::Healthy => 0.0,
+            Self::Degraded => 1.0,
+            Self::Unhealthy => 2.0,
+        }
+    }
+
+    pub fn from_gauge(value: f64) -> Self {
+        if value >= 2.0 {
+            Self::Unhealthy
+        } else if value >= 1.0 {
+            Self::Degraded
+        } else {
+            Self::Healthy
+        }
+    }
+}
+
+#[derive(Debug, Snafu)]
+#[snafu(display("Circuit breaker is open; dropping the request."))]
+pub struct CircuitOpen;
+
+#[derive(Debug, Clone)]
+pub struct CircuitBreakerLayer<L> {
+    settings: CircuitBreakerSettings,
+    when_full: WhenFull,
+    logic: L,
+}
+
+impl<L> CircuitBreakerLayer<L> {
+    pub fn new(settings: CircuitBreakerSettings, when_full: WhenFull, logic: L) -> Self {
+        Self {
+            settings,
+            when_full,
+            logic,
+        }
+    }
+}
+
+impl<S, L: RetryLogic> Layer<S> for CircuitBreakerLayer<L> {
+    type Service = CircuitBreaker<S, L>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        CircuitBreaker {
+            inner,
+            logic: self.logic.clone(),
+            settings: self.settings,
+            when_full: self.when_full,
+            state: Arc::new(Mutex::new(State {
+                consecutive_failures: 0,
+                open_until: None,
+                health: SinkHealth::Healthy,
+            })),
+            delay: None,
+            reject: false,
+        }
+    }
+}
+
+#[derive(Debug)]
+struct State {
+    consecutive_failures: usize,
+    /// Set while the breaker is open. Once this time has passed the next
+    /// request is let through as a probe.
+    open_until: Option<Instant>,
+    health: SinkHealth,
+}
+
+impl State {
+    fn set_health(&mut self, health: SinkHealth) {
+        if self.health != health {
+            emit!(SinkHealthChanged {
+                from: self.health,
+                to: health,
+            });
+            self.health = health;
+        }
+    }
+}
+
+pub struct CircuitBreaker<S, L> {
+    inner: S,
+    logic: L,
+    settings: CircuitBreakerSettings,
+    when_full: WhenFull,
+    state: Arc<Mutex<State>>,
+    delay: Option<Delay>,
+    /// The next request is rejected without calling the inner service.
+    reject: bool,
+}
+
+impl<S: Clone, L: Clone> Clone for CircuitBreaker<S, L> {
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+            logic: self.logic.clone(),
+            settings: self.settings,
+            when_full: self.when_full,
+            state: Arc::clone(&self.state),
+            delay: None,
+            reject: false,
+        }
+    }
+}
+
+impl<S, L> CircuitBreaker<S, L> {
+    /// Get a reference to the inner service
+    pub fn get_ref(&self) -> &S {
+        &self.inner
+    }
+
+    /// Whether a request may be sent now. When the breaker is open this
+    /// returns the time to check it again.
+    ///
+    /// Once the breaker is due to be probed, `take_probe` lets this request
+    /// through as the probe and holds back all others until the next
+    /// interval.
+    fn check(&self, take_probe: bool) -> Result<(), Instant> {
+        let now = Instant::now();
+        let mut state = self
+            .state
+            .lock()
+            .expect("Circuit breaker mutex is poisoned");
+
+        match state.open_until {
+            None => Ok(()),
+            Some(until) if now < until => Err(cmp::min(until, now + RECHECK_INTERVAL)),
+            Some(_) if take_probe => {
+                state.open_until = Some(now + self.probe_interval());
+                state.set_health(SinkHealth::Degraded);
+                Ok(())
+            }
+            Some(_) => Ok(()),
+        }
+    }
+
+    fn probe_interval(&self) -> Duration {
+        Duration::from_secs(self.settings.probe_interval_secs)
+    }
+}
+
+impl<S, L, Request> Service<Request> for CircuitBreaker<S, L>
+where
+    S: Service<Request>,
+    S::Response: Send + 'static,
+    S::Error: Into<crate::Error>,
+    S::Future: Send + 'static,
+    L: RetryLogic<Response = S::Response>,
+{
+    type Response = S::Response;
+    type Error = crate::Error;
+    type Future = BoxFuture<'static, Result<S::Response, crate::Error>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        if self.reject {
+            return Poll::Ready(Ok(()));
+        }
+        if self.settings.failure_threshold.is_none() {
+            return self.inner.poll_ready(cx).map_err(Into::into);
+        }
+
+        loop {
+            if let Some(delay) = &mut self.delay {
+                futures::ready!(delay.poll_unpin(cx));
+                self.delay = None;
+            }
+
+            // The probe is only taken once the inner service is ready, so
+            // that it isn't held back while waiting for it.
+            let checked = match self.check(false) {
+                Ok(()) => {
+                    futures::ready!(self.inner.poll_ready(cx)).map_err(Into::into)?;
+                    self.check(true)
+                }
+                Err(recheck) => Err(recheck),
+            };
+
+            match checked {
+                Ok(()) => return Poll::Ready(Ok(())),
+                Err(_) if self.when_full == WhenFull::DropNewest => {
+                    self.reject = true;
+                    return Poll::Ready(Ok(()));
+                }
+                Err(recheck) => self.delay = Some(delay_until(recheck)),
+            }
+        }
+    }
+
+    fn call(&mut self, request: Request) -> Self::Future {
+        if std::mem::replace(&mut self.reject, false) {
+            emit!(CircuitBreakerRejectedRequest);
+            return futures::future::err(CircuitOpen.into()).boxed();
+        }
+
+        let threshold = match self.settings.failure_threshold {
+            Some(threshold) => threshold,
+            None => {
+                return self
+                    .inner
+                    .call(request)
+                    .map(|r| r.map_err(Into::into))
+                    .boxed()
+            }
+        };
+
+        let logic = self.logic.clone();
+        let state = Arc::clone(&self.state);
+        let probe_interval = self.probe_interval();
+        let future = self.inner.call(request);
+
+        async move {
+            let response = future.await.map_err(Into::into);
+
+            let failed = match &response {
+                Ok(response) => logic.should_retry_response(response).is_retryable(),
+                Err(_) => true,
+            };
+
+            let mut state = state.lock().expect("Circuit breaker mutex is poisoned");
+            if failed {
+                state.consecutive_failures += 1;
+                if state.consecutive_failures >= threshold {
+                    state.open_until = Some(Instant::now() + probe_interval);
+                    state.set_health(SinkHealth::Unhealthy);
+                } else {
+                    state.set_health(SinkHealth::Degraded);
+                }
+            } else {
+                state.consecutive_failures = 0;
+                state.open_until = None;
+                state.set_health(SinkHealth::Healthy);
+            }
+            drop(state);
+
+            response
+        }
+        .boxed()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{sinks::util::retries::RetryAction, test_util::trace_init};
+    use std::fmt;
+    use tokio::time;
+    use tokio_test::{assert_pending, assert_ready_ok, task};
+    use tower_test::{assert_request_eq, mock};
+
+    fn settings() -> CircuitBreakerSettings {
+        CircuitBreakerSettings {
+            failure_threshold: Some(2),
+            probe_interval_secs: 5,
+        }
+    }
+
+    type Svc = mock::Spawn<CircuitBreaker<mock::Mock<&'static str, &'static str>, SvcRetryLogic>>;
+    type Handle = mock::Handle<&'static str, &'static str>;
+
+    /// Fails enough requests to open the breaker.
+    async fn open(svc: &mut Svc, handle: &mut Handle) {
+        for _ in 0..2 {
+            assert_ready_ok!(svc.poll_ready());
+            let fut = svc.call("hello");
+            assert_request_eq!(handle, "hello").send_response("failed");
+            assert_eq!(fut.await.unwrap(), "failed");
+        }
+    }
+
+    fn health(svc: &Svc) -> SinkHealth {
+        svc.get_ref().state.lock().unwrap().health
+    }
+
+    #[tokio::test]
+    async fn blocks_while_open() {
+        trace_init();
+        time::pause();
+
+        let layer = CircuitBreakerLayer::new(settings(), WhenFull::Block, SvcRetryLogic);
+        let (mut svc, mut handle) = mock::spawn_layer(layer);
+
+        open(&mut svc, &mut handle).await;
+        assert_eq!(health(&svc), SinkHealth::Unhealthy);
+        assert_pending!(svc.poll_ready());
+
+        time::advance(Duration::from_secs(3)).await;
+        assert_pending!(svc.poll_ready());
+
+        // The probe succeeds and closes the breaker.
+        time::advance(Duration::from_secs(3)).await;
+        assert_ready_ok!(svc.poll_ready());
+        assert_eq!(health(&svc), SinkHealth::Degraded);
+
+        let fut = svc.call("hello");
+        assert_request_eq!(handle, "hello").send_response("world");
+        assert_eq!(fut.await.unwrap(), "world");
+        assert_eq!(health(&svc), SinkHealth::Healthy);
+        assert_ready_ok!(svc.poll_ready());
+    }
+
+    #[tokio::test]
+    async fn drops_while_open() {
+        trace_init();
+        time::pause();
+
+        let layer = CircuitBreakerLayer::new(settings(), WhenFull::DropNewest, SvcRetryLogic);
+        let (mut svc, mut handle) = mock::spawn_layer(layer);
+
+        open(&mut svc, &mut handle).await;
+
+        assert_ready_ok!(svc.poll_ready());
+        let error = svc.call("hello").await.unwrap_err();
+        assert!(error.downcast_ref::<CircuitOpen>().is_some());
+        assert_pending!(task::spawn(handle.next_request()).poll());
+
+        // A failed probe keeps the breaker open.
+        time::advance(Duration::from_secs(6)).await;
+        assert_ready_ok!(svc.poll_ready());
+        let fut = svc.call("hello");
+        assert_request_eq!(handle, "hello").send_response("failed");
+        fut.await.unwrap();
+        assert_eq!(health(&svc), SinkHealth::Unhealthy);
+
+        assert_ready_ok!(svc.poll_ready());
+        assert!(svc.call("hello").await.is_err());
+    }
+
+    #[tokio::test]
+    async fn probes_once_inner_is_ready() {
+        trace_init();
+        time::pause();
+
+        let layer = CircuitBreakerLayer::new(settings(), WhenFull::Block, SvcRetryLogic);
+        let (mut svc, mut handle) = mock::spawn_layer(layer);
+
+        open(&mut svc, &mut handle).await;
+        time::advance(Duration::from_secs(6)).await;
+
+        handle.allow(0);
+        assert_pending!(svc.poll_ready());
+        assert_eq!(health(&svc), SinkHealth::Unhealthy);
+
+        handle.allow(1);
+        assert_ready_ok!(svc.poll_ready());
+        assert_eq!(health(&svc), SinkHealth::Degraded);
+
+        let fut = svc.call("hello");
+        assert_request_eq!(handle, "hello").send_response("world");
+        assert_eq!(fut.await.unwrap(), "world");
+        assert_eq!(health(&svc), SinkHealth::Healthy);
+    }
+
+    #[derive(Debug, Clone)]
+    struct SvcRetryLogic;
+
+    impl RetryLogic for SvcRetryLogic {
+        type Error = Error;
+        type Response = &'static str;
+
+        fn is_retriable_error(&self, _: &Self::Error) -> bool {
+            true
+        }
+
+        fn should_retry_response(&self, response: &Self::Response) -> RetryAction {
+            match *response {
+                "failed" => RetryAction::Retry("failed".into()),
+                _ => RetryAction::Successful,
+            }
+        }
+    }
+
+    #[derive(Debug)]
+    struct Error;
+
+    impl fmt::Display for Error {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            write!(f, "error")
+        }
+    }
+
+    impl std::error::Error for Error {}
+}
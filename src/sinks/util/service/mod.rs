@@ -3,8 +3,8 @@ use super::auto_concurrency::{
 };
 use super::retries::{FixedRetryPolicy, RetryLogic};
 use super::sink::Response;
-use super::{Batch, BatchSink, Partition, PartitionBatchSink};
-use crate::buffers::Acker;
+use super::{flush::FlushSignal, Batch, BatchSink, Partition, PartitionBatchSink};
+use crate::buffers::{Acker, WhenFull};
 use futures::TryFutureExt;
 use serde::{
     de::{self, Unexpected, Visitor},
@@ -24,7 +24,14 @@ use tower::{
     Service, ServiceBuilder,
 };
 
-pub type Svc<S, L> = RateLimit<Retry<FixedRetryPolicy<L>, AutoConcurrencyLimit<Timeout<S>, L>>>;
+mod circuit_breaker;
+
+pub use circuit_breaker::{
+    CircuitBreaker, CircuitBreakerLayer, CircuitBreakerSettings, CircuitOpen, SinkHealth,
+};
+
+pub type Svc<S, L> =
+    RateLimit<Retry<FixedRetryPolicy<L>, CircuitBreaker<AutoConcurrencyLimit<Timeout<S>, L>, L>>>;
 pub type TowerBatchedSink<S, B, L, Request> = BatchSink<Svc<S, L>, B, Request>;
 pub type TowerPartitionSink<S, B, L, K, Request> = PartitionBatchSink<B, Svc<S, L>, K, Request>;
 
@@ -176,6 +183,8 @@ pub struct TowerRequestConfig<T: InFlightLimitOption = InFlightLimit> {
     pub retry_initial_backoff_secs: Option<u64>, // 1
     #[serde(default)]
     pub auto_concurrency: AutoConcurrencySettings,
+    #[serde(default)]
+    pub circuit_breaker: CircuitBreakerSettings,
 }
 
 impl<T: InFlightLimitOption> TowerRequestConfig<T> {
@@ -206,6 +215,8 @@ impl<T: InFlightLimitOption> TowerRequestConfig<T> {
                     .unwrap_or(1),
             ),
             auto_concurrency: self.auto_concurrency,
+            circuit_breaker: self.circuit_breaker,
+            when_full: WhenFull::Block,
         }
     }
 }
@@ -220,9 +231,19 @@ pub struct TowerRequestSettings {
     pub retry_max_duration_secs: Duration,
     pub retry_initial_backoff_secs: Duration,
     pub auto_concurrency: AutoConcurrencySettings,
+    pub circuit_breaker: CircuitBreakerSettings,
+    /// What the circuit breaker does with requests while it is open: wait,
+    /// or drop them, as the sink's buffer does when it is full.
+    pub when_full: WhenFull,
 }
 
 impl TowerRequestSettings {
+    /// Applies the `when_full` setting of the sink's buffer.
+    pub fn when_full(mut self, when_full: WhenFull) -> Self {
+        self.when_full = when_full;
+        self
+    }
+
     pub fn retry_policy<L: RetryLogic>(&self, logic: L) -> FixedRetryPolicy<L> {
         FixedRetryPolicy::new(
             self.retry_attempts,
@@ -239,12 +260,13 @@ impl TowerRequestSettings {
         batch: B,
         batch_timeout: Duration,
         acker: Acker,
+        flush: FlushSignal,
     ) -> TowerPartitionSink<S, B, L, K, Request>
     where
         L: RetryLogic<Response = S::Response>,
         S: Service<Request> + Clone + Send + 'static,
         S::Error: Into<crate::Error> + Send + Sync + 'static,
-        S::Response: Send + Response,
+        S::Response: Send + Response + 'static,
         S::Future: Send + 'static,
         B: Batch<Output = Request>,
         B::Input: Partition<K>,
@@ -256,6 +278,7 @@ impl TowerRequestSettings {
             batch,
             batch_timeout,
             acker,
+            flush,
         )
     }
 
@@ -266,6 +289,7 @@ impl TowerRequestSettings {
         batch: B,
         batch_timeout: Duration,
         acker: Acker,
+        flush: FlushSignal,
     ) -> TowerBatchedSink<S, B, L, Request>
     // Would like to return `impl Sink + SinkExt<T>` here, but that
     // doesn't work with later calls to `batched_with_min` etc (via
@@ -275,7 +299,7 @@ impl TowerRequestSettings {
         L: RetryLogic<Response = S::Response>,
         S: Service<Request> + Clone + Send + 'static,
         S::Error: Into<crate::Error> + Send + Sync + 'static,
-        S::Response: Send + Response,
+        S::Response: Send + Response + 'static,
         S::Future: Send + 'static,
         B: Batch<Output = Request>,
         Request: Send + Clone + 'static,
@@ -285,6 +309,7 @@ impl TowerRequestSettings {
             batch,
             batch_timeout,
             acker,
+            flush,
         )
     }
 
@@ -293,7 +318,7 @@ impl TowerRequestSettings {
         L: RetryLogic<Response = S::Response>,
         S: Service<Request> + Clone + Send + 'static,
         S::Error: Into<crate::Error> + Send + Sync + 'static,
-        S::Response: Send + Response,
+        S::Response: Send + Response + 'static,
         S::Future: Send + 'static,
         Request: Send + Clone + 'static,
     {
@@ -301,6 +326,11 @@ impl TowerRequestSettings {
         ServiceBuilder::new()
             .rate_limit(self.rate_limit_num, self.rate_limit_duration)
             .retry(policy)
+            .layer(CircuitBreakerLayer::new(
+                self.circuit_breaker,
+                self.when_full,
+                retry_logic.clone(),
+            ))
             .layer(AutoConcurrencyLimitLayer::new(
                 self.in_flight_limit,
                 self.auto_concurrency,
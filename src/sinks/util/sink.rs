@@ -35,6 +35,7 @@
 use super::{
     batch::{Batch, PushResult, StatefulBatch},
     buffer::partition::Partition,
+    flush::FlushSignal,
 };
 use crate::{buffers::Acker, Event};
 use async_trait::async_trait;
@@ -53,9 +54,11 @@ use std::{
     fmt,
     hash::Hash,
     marker::PhantomData,
+    time::Instant,
 };
 use tokio::time::{delay_for, Duration};
 use tower::Service;
+use tracing::Span;
 use tracing_futures::Instrument;
 
 // === StreamSink ===
@@ -73,7 +76,8 @@ pub trait StreamSink {
 /// Provided a batching scheme, a service and batch settings
 /// this type will handle buffering events via the batching scheme
 /// and dispatching requests via the service based on either the size
-/// of the batch or a batch linger timeout.
+/// of the batch or a batch linger timeout. The batch is also sent when a
+/// flush is requested through the sink's `FlushHandle`.
 ///
 /// # Acking
 ///
@@ -86,7 +90,11 @@ pub struct BatchSink<S, B, Request> {
     batch: StatefulBatch<B>,
     timeout: Duration,
     linger: Option<SafeLinger>,
+    /// Covers the current batch, from its first item until it's sent.
+    batch_span: Option<Span>,
     closing: bool,
+    flush: FlushSignal,
+    flushing: bool,
     service_was_not_ready: bool,
     _pd: PhantomData<Request>,
 }
@@ -99,7 +107,7 @@ where
     S::Response: Response,
     B: Batch<Output = Request>,
 {
-    pub fn new(service: S, batch: B, timeout: Duration, acker: Acker) -> Self {
+    pub fn new(service: S, batch: B, timeout: Duration, acker: Acker, flush: FlushSignal) -> Self {
         let service = ServiceSink::new(service, acker);
 
         Self {
@@ -107,14 +115,22 @@ where
             batch: batch.into(),
             timeout,
             linger: None,
+            batch_span: None,
             closing: false,
+            flush,
+            flushing: false,
             service_was_not_ready: false,
             _pd: PhantomData,
         }
     }
 
     fn should_send(&mut self) -> bool {
-        self.closing || self.batch.was_full() || self.linger_elapsed()
+        // A requested flush holds until the batch is sent, as the service
+        // may not be ready for it yet.
+        if self.flush.poll() {
+            self.flushing = true;
+        }
+        self.closing || self.flushing || self.batch.was_full() || self.linger_elapsed()
     }
 
     fn linger_elapsed(&mut self) -> bool {
@@ -147,40 +163,14 @@ where
     type SinkError = crate::Error;
 
     fn start_send(&mut self, item: Self::SinkItem) -> StartSend<Self::SinkItem, Self::SinkError> {
-        if self.batch.was_full() {
-            trace!("Batch full.");
-            self.poll_complete()?;
-
-            if !self.batch.is_empty() {
-                debug!(
-                    message = "Batch buffer full; applying back pressure.",
-                    rate_limit_secs = 10
-                );
-                return Ok(AsyncSink::NotReady(item));
-            }
-        }
-
-        if self.linger.is_none() {
-            trace!("Starting new batch timer.");
-            // We just inserted the first item of a new batch, so set our delay to the longest time
-            // we want to allow that item to linger in the batch before being flushed.
-            let delay = SafeLinger::new(self.timeout);
-            self.linger = Some(delay);
-        }
-
-        match self.batch.push(item) {
-            PushResult::Ok(false) => Ok(AsyncSink::Ready),
-            PushResult::Ok(true) => {
-                self.poll_complete()?;
-                Ok(AsyncSink::Ready)
-            }
-            PushResult::Overflow(item) => self.start_send(item),
-        }
+        self.start_send_from(item, None)
     }
 
     fn poll_complete(&mut self) -> Poll<(), Self::SinkError> {
         loop {
             if self.batch.is_empty() {
+                // There is nothing to flush, but keep listening for requests.
+                self.flush.poll();
                 trace!("No batches; driving service to completion.");
                 return self.service.poll_complete();
             } else {
@@ -197,16 +187,23 @@ where
                     self.service_was_not_ready = false;
 
                     trace!("Service ready; Sending batch.");
-                    let batch = self.batch.fresh_replace();
+                    let mut batch = self.batch.fresh_replace();
 
                     let batch_size = batch.num_items();
+                    let ingress = batch.take_ingress();
                     let request = batch.finish();
 
-                    let fut = self.service.call(request, batch_size).compat();
+                    // The request is made within the batch's span, which ends
+                    // once it's sent
+                    let batch_span = self.batch_span.take().unwrap_or_else(Span::none);
+                    let fut = batch_span
+                        .in_scope(|| self.service.call(request, batch_size, ingress))
+                        .compat();
                     tokio::spawn(fut);
 
                     // Remove the now-sent batch's linger timeout
                     self.linger = None;
+                    self.flushing = false;
                 } else {
                     // We have data but not a full batch and the linger time has not elapsed, so do
                     // not sent a request yet. Instead, poll the inner service to drive progress
@@ -236,6 +233,52 @@ where
     }
 }
 
+impl<S, B, Request> EncodedSink for BatchSink<S, B, Request>
+where
+    S: Service<Request>,
+    S::Future: Send + 'static,
+    S::Error: Into<crate::Error> + Send + 'static,
+    S::Response: Response,
+    B: Batch<Output = Request>,
+{
+    fn start_send_from(
+        &mut self,
+        item: Self::SinkItem,
+        ingress: Option<Instant>,
+    ) -> StartSend<Self::SinkItem, Self::SinkError> {
+        if self.batch.was_full() {
+            trace!("Batch full.");
+            self.poll_complete()?;
+
+            if !self.batch.is_empty() {
+                debug!(
+                    message = "Batch buffer full; applying back pressure.",
+                    rate_limit_secs = 10
+                );
+                return Ok(AsyncSink::NotReady(item));
+            }
+        }
+
+        if self.linger.is_none() {
+            trace!("Starting new batch timer.");
+            // We just inserted the first item of a new batch, so set our delay to the longest time
+            // we want to allow that item to linger in the batch before being flushed.
+            let delay = SafeLinger::new(self.timeout);
+            self.linger = Some(delay);
+            self.batch_span = Some(info_span!("batch"));
+        }
+
+        match self.batch.push_from(item, ingress) {
+            PushResult::Ok(false) => Ok(AsyncSink::Ready),
+            PushResult::Ok(true) => {
+                self.poll_complete()?;
+                Ok(AsyncSink::Ready)
+            }
+            PushResult::Overflow(item) => self.start_send_from(item, ingress),
+        }
+    }
+}
+
 impl<S, B, Request> fmt::Debug for BatchSink<S, B, Request>
 where
     S: fmt::Debug,
@@ -288,7 +331,8 @@ type LingerDelay<K> = Box<dyn Future<Item = LingerState<K>, Error = ()> + Send +
 ///
 /// This type is similar to `BatchSink` with the added benefit that it has
 /// more fine grained partitioning ability. It will hold many different batches
-/// of events and contain linger timeouts for each.
+/// of events and contain linger timeouts for each. All of the batches are
+/// sent when a flush is requested through the sink's `FlushHandle`.
 ///
 /// # Acking
 ///
@@ -302,7 +346,11 @@ pub struct PartitionBatchSink<B, S, K, Request> {
     partitions: HashMap<K, StatefulBatch<B>>,
     timeout: Duration,
     closing: bool,
-    sending: VecDeque<B>,
+    flush: FlushSignal,
+    sending: VecDeque<(StatefulBatch<B>, Span)>,
+    /// Cover the batches of the partitions, from their first item until
+    /// they're sent.
+    batch_spans: HashMap<K, Span>,
     lingers: FuturesUnordered<LingerDelay<K>>,
     linger_handles: HashMap<K, oneshot::Sender<K>>,
 }
@@ -322,7 +370,7 @@ where
     S::Error: Into<crate::Error> + Send + 'static,
     S::Response: Response,
 {
-    pub fn new(service: S, batch: B, timeout: Duration, acker: Acker) -> Self {
+    pub fn new(service: S, batch: B, timeout: Duration, acker: Acker, flush: FlushSignal) -> Self {
         let service = ServiceSink::new(service, acker);
 
         Self {
@@ -331,7 +379,9 @@ where
             partitions: HashMap::new(),
             timeout,
             closing: false,
+            flush,
             sending: VecDeque::new(),
+            batch_spans: HashMap::new(),
             lingers: FuturesUnordered::new(),
             linger_handles: HashMap::new(),
         }
@@ -362,21 +412,33 @@ where
         self.lingers.push(Box::new(fut));
     }
 
-    fn poll_send(&mut self, batch: B) -> Poll<(), crate::Error> {
+    fn poll_send(
+        &mut self,
+        mut batch: StatefulBatch<B>,
+        batch_span: Span,
+    ) -> Poll<(), crate::Error> {
         if let Async::NotReady = self.service.poll_ready()? {
-            self.sending.push_front(batch);
+            self.sending.push_front((batch, batch_span));
         } else {
             let batch_size = batch.num_items();
+            let ingress = batch.take_ingress();
             let batch = batch.finish();
 
-            let fut = self.service.call(batch, batch_size).compat();
+            let fut = batch_span
+                .in_scope(|| self.service.call(batch, batch_size, ingress))
+                .compat();
             tokio::spawn(fut);
         }
 
         self.service.poll_complete()
     }
 
-    fn handle_full_batch(&mut self, item: B::Input, partition: &K) -> FullBatchResult<B::Input> {
+    fn handle_full_batch(
+        &mut self,
+        item: B::Input,
+        ingress: Option<Instant>,
+        partition: &K,
+    ) -> FullBatchResult<B::Input> {
         trace!("Batch full; driving service to completion.");
         if let Err(error) = self.poll_complete() {
             return FullBatchResult::Result(Err(error));
@@ -391,7 +453,7 @@ where
                     );
                     FullBatchResult::Result(Ok(AsyncSink::NotReady(item)))
                 } else {
-                    match batch.push(item) {
+                    match batch.push_from(item, ingress) {
                         PushResult::Ok(full) => {
                             if full {
                                 if let Err(error) = self.poll_complete() {
@@ -428,6 +490,101 @@ where
     type SinkError = crate::Error;
 
     fn start_send(&mut self, item: Self::SinkItem) -> StartSend<Self::SinkItem, Self::SinkError> {
+        self.start_send_from(item, None)
+    }
+
+    fn poll_complete(&mut self) -> Poll<(), Self::SinkError> {
+        self.service.poll_complete()?;
+
+        while let Some((batch, batch_span)) = self.sending.pop_front() {
+            if self.poll_send(batch, batch_span)? == Async::Ready(()) {
+                break;
+            }
+        }
+
+        // Batches taken for a flush are queued in `sending` if the service
+        // isn't ready for them.
+        let closing = self.flush.poll() || self.closing;
+
+        let mut partitions = Vec::new();
+
+        while let Ok(Async::Ready(Some(linger))) = self.lingers.poll() {
+            // Only if the linger has elapsed trigger the removal
+            if let LingerState::Elapsed(partition) = linger {
+                trace!("Batch linger expired.");
+                self.linger_handles.remove(&partition);
+
+                if let Some(batch) = self.partitions.remove(&partition) {
+                    let batch_span = self.batch_spans.remove(&partition);
+                    partitions.push((batch, batch_span));
+                }
+            }
+        }
+
+        let ready = self
+            .partitions
+            .iter()
+            .filter(|(_, b)| closing || b.was_full())
+            .map(|(p, _)| p.clone())
+            .collect::<Vec<_>>();
+
+        let mut ready_batches = Vec::new();
+        for partition in ready {
+            if let Some(batch) = self.partitions.remove(&partition) {
+                let batch_span = self.batch_spans.remove(&partition);
+                if let Some(linger_cancel) = self.linger_handles.remove(&partition) {
+                    // XXX: had to remove the expect here, a cancellation should
+                    // always be a best effort.
+                    let _ = linger_cancel.send(partition.clone());
+                }
+
+                ready_batches.push((batch, batch_span));
+            }
+        }
+        if !self.partitions.is_empty() {
+            assert!(
+                !self.lingers.is_empty(),
+                "If partitions are not empty, then there must be a linger"
+            );
+        }
+
+        for (batch, batch_span) in ready_batches.into_iter().chain(partitions) {
+            self.poll_send(batch, batch_span.unwrap_or_else(Span::none))?;
+        }
+
+        // If we still have an inflight partition then
+        // we should have a linger associated with it that
+        // will wake up this task when it is ready to be flushed.
+        if !self.partitions.is_empty() || !self.sending.is_empty() {
+            self.service.poll_complete()?;
+            Ok(Async::NotReady)
+        } else {
+            self.service.poll_complete()
+        }
+    }
+
+    fn close(&mut self) -> Poll<(), Self::SinkError> {
+        trace!("Closing partition batch sink.");
+        self.closing = true;
+        self.poll_complete()
+    }
+}
+
+impl<B, S, K, Request> EncodedSink for PartitionBatchSink<B, S, K, Request>
+where
+    B: Batch<Output = Request>,
+    B::Input: Partition<K>,
+    K: Hash + Eq + Clone + Send + 'static,
+    S: Service<Request>,
+    S::Future: Send + 'static,
+    S::Error: Into<crate::Error> + Send + 'static,
+    S::Response: Response,
+{
+    fn start_send_from(
+        &mut self,
+        item: Self::SinkItem,
+        ingress: Option<Instant>,
+    ) -> StartSend<Self::SinkItem, Self::SinkError> {
         // Apply back pressure if we are buffering more than
         // 5 batches, this should only happen if the inner sink
         // is apply back pressure.
@@ -453,13 +610,13 @@ where
         let item = match self.partitions.get_mut(&partition) {
             Some(batch) => {
                 if batch.was_full() {
-                    match self.handle_full_batch(item, &partition) {
+                    match self.handle_full_batch(item, ingress, &partition) {
                         FullBatchResult::Result(result) => return result,
                         FullBatchResult::Continue(item) => item,
                     }
                 } else {
                     trace!("Adding event to batch.");
-                    match batch.push(item) {
+                    match batch.push_from(item, ingress) {
                         PushResult::Ok(full) => {
                             if full {
                                 self.poll_complete()?;
@@ -467,7 +624,7 @@ where
                             return Ok(AsyncSink::Ready);
                         }
                         PushResult::Overflow(item) => {
-                            match self.handle_full_batch(item, &partition) {
+                            match self.handle_full_batch(item, ingress, &partition) {
                                 FullBatchResult::Result(result) => return result,
                                 FullBatchResult::Continue(item) => item,
                             }
@@ -483,11 +640,13 @@ where
         // or the batch got submitted by polling_complete above.
         let mut batch = self.batch.fresh();
 
-        match batch.push(item) {
+        match batch.push_from(item, ingress) {
             PushResult::Overflow(_) => unreachable!("Empty buffer overflowed"),
             PushResult::Ok(full) => {
                 self.set_linger(partition.clone());
 
+                self.batch_spans
+                    .insert(partition.clone(), info_span!("batch"));
                 self.partitions.insert(partition, batch);
 
                 if full {
@@ -498,91 +657,109 @@ where
             }
         }
     }
+}
 
-    fn poll_complete(&mut self) -> Poll<(), Self::SinkError> {
-        self.service.poll_complete()?;
-
-        while let Some(batch) = self.sending.pop_front() {
-            if self.poll_send(batch)? == Async::Ready(()) {
-                break;
-            }
-        }
-
-        let closing = self.closing;
-
-        let mut partitions = Vec::new();
+impl<B, S, K, Request> fmt::Debug for PartitionBatchSink<B, S, K, Request>
+where
+    B: fmt::Debug,
+    S: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("PartitionedBatchSink")
+            .field("batch", &self.batch)
+            .field("service", &self.service)
+            .field("timeout", &self.timeout)
+            .finish()
+    }
+}
 
-        while let Ok(Async::Ready(Some(linger))) = self.lingers.poll() {
-            // Only if the linger has elapsed trigger the removal
-            if let LingerState::Elapsed(partition) = linger {
-                trace!("Batch linger expired.");
-                self.linger_handles.remove(&partition);
+// === EncodeEvents ===
 
-                if let Some(batch) = self.partitions.remove(&partition) {
-                    partitions.push(batch);
-                }
-            }
+/// A sink of items encoded from events, which records the time the events
+/// spent in Vector once it acks their items.
+pub trait EncodedSink: Sink {
+    /// Sends an item encoded from an event that entered Vector at `ingress`.
+    fn start_send_from(
+        &mut self,
+        item: Self::SinkItem,
+        ingress: Option<Instant>,
+    ) -> StartSend<Self::SinkItem, Self::SinkError>;
+
+    /// Encodes the events sent to this sink into its items like
+    /// `with_flat_map`, passing along when they entered Vector.
+    fn encode_events<F, I>(self, encode: F) -> EncodeEvents<Self, F, I>
+    where
+        Self: Sized,
+        F: FnMut(Event) -> I,
+        I: IntoIterator<Item = Self::SinkItem>,
+    {
+        EncodeEvents {
+            inner: self,
+            encode,
+            items: None,
+            slot: None,
         }
+    }
+}
 
-        let ready = self
-            .partitions
-            .iter()
-            .filter(|(_, b)| closing || b.was_full())
-            .map(|(p, _)| p.clone())
-            .collect::<Vec<_>>();
+/// A sink that encodes events into the items of an `EncodedSink`, built by
+/// `EncodedSink::encode_events`.
+pub struct EncodeEvents<S: Sink, F, I: IntoIterator> {
+    inner: S,
+    encode: F,
+    /// The items of the last event not yet sent, and when it entered Vector.
+    items: Option<(I::IntoIter, Option<Instant>)>,
+    /// An item the inner sink applied back pressure to.
+    slot: Option<S::SinkItem>,
+}
 
-        let mut ready_batches = Vec::new();
-        for partition in ready {
-            if let Some(batch) = self.partitions.remove(&partition) {
-                if let Some(linger_cancel) = self.linger_handles.remove(&partition) {
-                    // XXX: had to remove the expect here, a cancellation should
-                    // always be a best effort.
-                    let _ = linger_cancel.send(partition.clone());
+impl<S, F, I> EncodeEvents<S, F, I>
+where
+    S: EncodedSink,
+    I: IntoIterator<Item = S::SinkItem>,
+{
+    fn try_empty(&mut self) -> Poll<(), S::SinkError> {
+        if let Some((items, ingress)) = &mut self.items {
+            while let Some(item) = self.slot.take().or_else(|| items.next()) {
+                if let AsyncSink::NotReady(item) = self.inner.start_send_from(item, *ingress)? {
+                    self.slot = Some(item);
+                    return Ok(Async::NotReady);
                 }
-
-                ready_batches.push(batch);
             }
         }
-        if !self.partitions.is_empty() {
-            assert!(
-                !self.lingers.is_empty(),
-                "If partitions are not empty, then there must be a linger"
-            );
-        }
+        self.items = None;
+        Ok(Async::Ready(()))
+    }
+}
 
-        for batch in ready_batches.into_iter().chain(partitions) {
-            self.poll_send(batch.into_inner())?;
-        }
+impl<S, F, I> Sink for EncodeEvents<S, F, I>
+where
+    S: EncodedSink,
+    F: FnMut(Event) -> I,
+    I: IntoIterator<Item = S::SinkItem>,
+{
+    type SinkItem = Event;
+    type SinkError = S::SinkError;
 
-        // If we still have an inflight partition then
-        // we should have a linger associated with it that
-        // will wake up this task when it is ready to be flushed.
-        if !self.partitions.is_empty() || !self.sending.is_empty() {
-            self.service.poll_complete()?;
-            Ok(Async::NotReady)
-        } else {
-            self.service.poll_complete()
+    fn start_send(&mut self, event: Self::SinkItem) -> StartSend<Self::SinkItem, Self::SinkError> {
+        if self.try_empty()?.is_not_ready() {
+            return Ok(AsyncSink::NotReady(event));
         }
+
+        let ingress = event.ingress();
+        self.items = Some(((self.encode)(event).into_iter(), ingress));
+        self.try_empty()?;
+        Ok(AsyncSink::Ready)
     }
 
-    fn close(&mut self) -> Poll<(), Self::SinkError> {
-        trace!("Closing partition batch sink.");
-        self.closing = true;
-        self.poll_complete()
+    fn poll_complete(&mut self) -> Poll<(), Self::SinkError> {
+        try_ready!(self.try_empty());
+        self.inner.poll_complete()
     }
-}
 
-impl<B, S, K, Request> fmt::Debug for PartitionBatchSink<B, S, K, Request>
-where
-    B: fmt::Debug,
-    S: fmt::Debug,
-{
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        f.debug_struct("PartitionedBatchSink")
-            .field("batch", &self.batch)
-            .field("service", &self.service)
-            .field("timeout", &self.timeout)
-            .finish()
+    fn close(&mut self) -> Poll<(), Self::SinkError> {
+        try_ready!(self.try_empty());
+        self.inner.close()
     }
 }
 
@@ -590,11 +767,11 @@ where
 
 struct ServiceSink<S, Request> {
     service: S,
-    in_flight: FuturesUnordered<oneshot::Receiver<(usize, usize)>>,
+    in_flight: FuturesUnordered<oneshot::Receiver<(usize, usize, Vec<Instant>)>>,
     acker: Acker,
     seq_head: usize,
     seq_tail: usize,
-    pending_acks: HashMap<usize, usize>,
+    pending_acks: HashMap<usize, (usize, Vec<Instant>)>,
     next_request_id: usize,
     _pd: PhantomData<Request>,
 }
@@ -628,6 +805,7 @@ where
         &mut self,
         req: Request,
         batch_size: usize,
+        ingress: Vec<Instant>,
     ) -> Box<dyn Future<Item = (), Error = ()> + Send + 'static> {
         let seqno = self.seq_head;
         self.seq_head += 1;
@@ -661,7 +839,7 @@ where
                 // If the rx end is dropped we still completed
                 // the request so this is a weird case that we can
                 // ignore for now.
-                let _ = tx.send((seqno, batch_size));
+                let _ = tx.send((seqno, batch_size, ingress));
 
                 Ok::<_, ()>(())
             })
@@ -675,16 +853,18 @@ where
             match self.in_flight.poll() {
                 Ok(Async::NotReady) => return Ok(Async::NotReady),
                 Ok(Async::Ready(None)) => return Ok(Async::Ready(())),
-                Ok(Async::Ready(Some((seqno, batch_size)))) => {
-                    self.pending_acks.insert(seqno, batch_size);
+                Ok(Async::Ready(Some((seqno, batch_size, ingress)))) => {
+                    self.pending_acks.insert(seqno, (batch_size, ingress));
 
                     let mut num_to_ack = 0;
-                    while let Some(ack_size) = self.pending_acks.remove(&self.seq_tail) {
+                    let mut ingress_to_ack = Vec::new();
+                    while let Some((ack_size, ingress)) = self.pending_acks.remove(&self.seq_tail) {
                         num_to_ack += ack_size;
+                        ingress_to_ack.extend(ingress);
                         self.seq_tail += 1
                     }
                     trace!(message = "Acking events.", acking_num = num_to_ack);
-                    self.acker.ack(num_to_ack);
+                    self.acker.ack_with_ingress(num_to_ack, ingress_to_ack);
                 }
                 Err(_) => panic!("ServiceSink service sender dropped."),
             }
@@ -723,12 +903,19 @@ mod tests {
     use super::*;
     use crate::{
         buffers::Acker,
-        sinks::util::{buffer::partition::Partition, BatchSettings, EncodedLength, VecBuffer},
+        config::log_schema,
+        sinks::util::{
+            buffer::partition::Partition, flush::FlushHandle, BatchSettings, EncodedLength,
+            VecBuffer,
+        },
     };
     use bytes::Bytes;
     use futures::{compat::Future01CompatExt, future};
     use futures01::{future as future01, Sink};
-    use std::sync::{atomic::Ordering::Relaxed, Arc, Mutex};
+    use std::sync::{
+        atomic::{AtomicUsize, Ordering::Relaxed},
+        Arc, Mutex,
+    };
     use tokio::task::yield_now;
 
     const TIMEOUT: Duration = Duration::from_secs(10);
@@ -761,7 +948,13 @@ mod tests {
 
         let svc = tower::service_fn(|_| future::ok::<_, std::io::Error>(()));
         let batch = BatchSettings::default().events(10).bytes(9999);
-        let buffered = BatchSink::new(svc, VecBuffer::new(batch.size), TIMEOUT, acker);
+        let buffered = BatchSink::new(
+            svc,
+            VecBuffer::new(batch.size),
+            TIMEOUT,
+            acker,
+            FlushSignal::default(),
+        );
 
         let _ = buffered
             .sink_map_err(drop)
@@ -800,7 +993,13 @@ mod tests {
 
             let batch = BatchSettings::default().bytes(9999).events(1);
 
-            let mut sink = BatchSink::new(svc, VecBuffer::new(batch.size), TIMEOUT, acker);
+            let mut sink = BatchSink::new(
+                svc,
+                VecBuffer::new(batch.size),
+                TIMEOUT,
+                acker,
+                FlushSignal::default(),
+            );
 
             assert!(sink.start_send(0).unwrap().is_ready());
             assert!(sink.start_send(1).unwrap().is_ready());
@@ -860,7 +1059,13 @@ mod tests {
             future::ok::<_, std::io::Error>(())
         });
         let batch = BatchSettings::default().bytes(9999).events(10);
-        let buffered = BatchSink::new(svc, VecBuffer::new(batch.size), TIMEOUT, acker);
+        let buffered = BatchSink::new(
+            svc,
+            VecBuffer::new(batch.size),
+            TIMEOUT,
+            acker,
+            FlushSignal::default(),
+        );
 
         let _ = buffered
             .sink_map_err(drop)
@@ -892,7 +1097,13 @@ mod tests {
         });
 
         let batch = BatchSettings::default().bytes(9999).events(10);
-        let mut buffered = BatchSink::new(svc, VecBuffer::new(batch.size), TIMEOUT, acker);
+        let mut buffered = BatchSink::new(
+            svc,
+            VecBuffer::new(batch.size),
+            TIMEOUT,
+            acker,
+            FlushSignal::default(),
+        );
 
         assert!(buffered.start_send(0).unwrap().is_ready());
         assert!(buffered.start_send(1).unwrap().is_ready());
@@ -919,7 +1130,13 @@ mod tests {
             });
 
             let batch = BatchSettings::default().bytes(9999).events(10);
-            let mut buffered = BatchSink::new(svc, VecBuffer::new(batch.size), TIMEOUT, acker);
+            let mut buffered = BatchSink::new(
+                svc,
+                VecBuffer::new(batch.size),
+                TIMEOUT,
+                acker,
+                FlushSignal::default(),
+            );
 
             assert!(buffered.start_send(0).unwrap().is_ready());
             assert!(buffered.start_send(1).unwrap().is_ready());
@@ -937,6 +1154,46 @@ mod tests {
         .await;
     }
 
+    #[tokio::test]
+    async fn batch_sink_flushes_on_request() {
+        run_as_future01(async {
+            let (acker, _) = Acker::new_for_testing();
+            let sent_requests = Arc::new(Mutex::new(Vec::new()));
+
+            let svc = tower::service_fn(|req| {
+                let sent_requests = Arc::clone(&sent_requests);
+                sent_requests.lock().unwrap().push(req);
+                future::ok::<_, std::io::Error>(())
+            });
+
+            let flush = FlushHandle::default();
+            assert!(!flush.request());
+
+            let batch = BatchSettings::default().bytes(9999).events(10);
+            let mut buffered = BatchSink::new(
+                svc,
+                VecBuffer::new(batch.size),
+                TIMEOUT,
+                acker,
+                flush.signal(),
+            );
+
+            assert!(buffered.start_send(0).unwrap().is_ready());
+            assert!(buffered.start_send(1).unwrap().is_ready());
+            assert_eq!(buffered.poll_complete().unwrap(), Async::NotReady);
+
+            assert!(flush.request());
+
+            while buffered.poll_complete().unwrap() == Async::NotReady {
+                yield_now().await;
+            }
+
+            let output = sent_requests.lock().unwrap();
+            assert_eq!(&*output, &vec![vec![0, 1]]);
+        })
+        .await;
+    }
+
     #[tokio::test]
     async fn partition_batch_sink_buffers_messages_until_limit() {
         let (acker, _) = Acker::new_for_testing();
@@ -949,7 +1206,13 @@ mod tests {
         });
 
         let batch = BatchSettings::default().bytes(9999).events(10);
-        let buffered = PartitionBatchSink::new(svc, VecBuffer::new(batch.size), TIMEOUT, acker);
+        let buffered = PartitionBatchSink::new(
+            svc,
+            VecBuffer::new(batch.size),
+            TIMEOUT,
+            acker,
+            FlushSignal::default(),
+        );
 
         let (_buffered, _) = buffered
             .sink_map_err(drop)
@@ -981,7 +1244,13 @@ mod tests {
         });
 
         let batch = BatchSettings::default().bytes(9999).events(1);
-        let buffered = PartitionBatchSink::new(svc, VecBuffer::new(batch.size), TIMEOUT, acker);
+        let buffered = PartitionBatchSink::new(
+            svc,
+            VecBuffer::new(batch.size),
+            TIMEOUT,
+            acker,
+            FlushSignal::default(),
+        );
 
         let input = vec![Partitions::A, Partitions::B];
         let (_buffered, _) = buffered
@@ -1008,7 +1277,13 @@ mod tests {
         });
 
         let batch = BatchSettings::default().bytes(9999).events(2);
-        let buffered = PartitionBatchSink::new(svc, VecBuffer::new(batch.size), TIMEOUT, acker);
+        let buffered = PartitionBatchSink::new(
+            svc,
+            VecBuffer::new(batch.size),
+            TIMEOUT,
+            acker,
+            FlushSignal::default(),
+        );
 
         let input = vec![Partitions::A, Partitions::B, Partitions::A, Partitions::B];
         let (_buffered, _) = buffered
@@ -1042,8 +1317,13 @@ mod tests {
             });
 
             let batch = BatchSettings::default().bytes(9999).events(10);
-            let mut buffered =
-                PartitionBatchSink::new(svc, VecBuffer::new(batch.size), TIMEOUT, acker);
+            let mut buffered = PartitionBatchSink::new(
+                svc,
+                VecBuffer::new(batch.size),
+                TIMEOUT,
+                acker,
+                FlushSignal::default(),
+            );
 
             buffered.start_send(1 as usize).unwrap();
             buffered.poll_complete().unwrap();
@@ -1079,8 +1359,8 @@ mod tests {
             let mut sink = ServiceSink::new(svc, acker);
 
             // send some initial requests
-            let mut fut1 = sink.call(1, 1);
-            let mut fut2 = sink.call(2, 2);
+            let mut fut1 = sink.call(1, 1, Vec::new());
+            let mut fut2 = sink.call(2, 2, Vec::new());
 
             assert_eq!(ack_counter.load(Relaxed), 0);
 
@@ -1091,8 +1371,8 @@ mod tests {
             assert_eq!(ack_counter.load(Relaxed), 3);
 
             // send one request that will error and one normal
-            let mut fut3 = sink.call(3, 3); // i will error
-            let mut fut4 = sink.call(4, 4);
+            let mut fut3 = sink.call(3, 3, Vec::new()); // i will error
+            let mut fut4 = sink.call(4, 4, Vec::new());
 
             // make sure they all "worked"
             assert!(fut3.poll().unwrap().is_ready());
@@ -1103,6 +1383,94 @@ mod tests {
         .await;
     }
 
+    /// Takes up to `limit` items, recording when their events entered Vector.
+    struct IngressSink {
+        limit: Arc<AtomicUsize>,
+        sent: Arc<Mutex<Vec<(u8, Option<Instant>)>>>,
+    }
+
+    impl Sink for IngressSink {
+        type SinkItem = u8;
+        type SinkError = ();
+
+        fn start_send(&mut self, item: u8) -> StartSend<u8, ()> {
+            self.start_send_from(item, None)
+        }
+
+        fn poll_complete(&mut self) -> Poll<(), ()> {
+            Ok(Async::Ready(()))
+        }
+    }
+
+    impl EncodedSink for IngressSink {
+        fn start_send_from(&mut self, item: u8, ingress: Option<Instant>) -> StartSend<u8, ()> {
+            let mut sent = self.sent.lock().unwrap();
+            if sent.len() < self.limit.load(Relaxed) {
+                sent.push((item, ingress));
+                Ok(AsyncSink::Ready)
+            } else {
+                Ok(AsyncSink::NotReady(item))
+            }
+        }
+    }
+
+    #[test]
+    fn encode_events_carries_ingress_to_items() {
+        let limit = Arc::new(AtomicUsize::new(3));
+        let sent = Arc::new(Mutex::new(Vec::new()));
+        let mut sink = IngressSink {
+            limit: Arc::clone(&limit),
+            sent: Arc::clone(&sent),
+        }
+        // Each event is encoded into as many items as its message says, so
+        // the second one is dropped
+        .encode_events(|event: Event| {
+            let count = event.as_log()[log_schema().message_key()]
+                .to_string_lossy()
+                .parse::<u8>()
+                .unwrap();
+            (1..=count).collect::<Vec<_>>()
+        });
+
+        let start = Instant::now();
+        let ingress = (0..4)
+            .map(|i| start + Duration::from_secs(i))
+            .collect::<Vec<_>>();
+        let events = ["2", "0", "3", "1"]
+            .iter()
+            .zip(&ingress)
+            .map(|(count, ingress)| {
+                let mut event = Event::from(*count);
+                event.as_mut_log().metadata_mut().ingress = Some(*ingress);
+                event
+            });
+
+        for event in events {
+            let mut event = Some(event);
+            while let Some(pending) = event.take() {
+                if let AsyncSink::NotReady(pending) = sink.start_send(pending).unwrap() {
+                    // Items of the previous event are still waiting
+                    limit.fetch_add(1, Relaxed);
+                    event = Some(pending);
+                }
+            }
+        }
+        limit.store(usize::MAX, Relaxed);
+        assert!(sink.poll_complete().unwrap().is_ready());
+
+        assert_eq!(
+            *sent.lock().unwrap(),
+            vec![
+                (1, Some(ingress[0])),
+                (2, Some(ingress[0])),
+                (1, Some(ingress[2])),
+                (2, Some(ingress[2])),
+                (3, Some(ingress[2])),
+                (1, Some(ingress[3])),
+            ]
+        );
+    }
+
     #[derive(Debug, PartialEq, Eq, Ord, PartialOrd)]
     enum Partitions {
         A,
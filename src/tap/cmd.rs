@@ -0,0 +1,85 @@
+use crate::config;
+use tokio::stream::StreamExt;
+use url::Url;
+use vector_api_client::{
+    connect_subscription_client_with_auth,
+    gql::{output_events_subscription::EventEncodingType, EventsSubscriptionExt},
+};
+
+/// CLI command func for printing a sample of the events leaving components, tapped through
+/// a local/remote Vector API server via WebSockets
+pub async fn cmd(opts: &super::Opts) -> exitcode::ExitCode {
+    // Use the provided URL as the Vector GraphQL API server, or default to the local port
+    // provided by the API config
+    let mut url = opts.url.clone().unwrap_or_else(|| {
+        let addr = config::api::default_bind().unwrap();
+        Url::parse(&*format!("ws://{}/graphql", addr))
+            .expect("Couldn't parse default API URL. Please report this.")
+    });
+
+    // Subscriptions are served over WebSockets
+    let scheme = match url.scheme() {
+        "https" => Some("wss"),
+        "http" => Some("ws"),
+        _ => None,
+    };
+    if let Some(scheme) = scheme {
+        url.set_scheme(scheme)
+            .expect("Couldn't set the API URL scheme. Please report this.");
+    }
+
+    let auth = opts.auth.auth();
+    let client = match connect_subscription_client_with_auth(&url, auth.as_ref()).await {
+        Ok(client) => client,
+        _ => {
+            eprintln!("Vector API server not reachable");
+            return exitcode::UNAVAILABLE;
+        }
+    };
+
+    let encoding = match opts.format.as_str() {
+        "logfmt" => EventEncodingType::LOGFMT,
+        _ => EventEncodingType::JSON,
+    };
+    let subscription = match client
+        .output_events_subscription(
+            opts.component_names.clone(),
+            opts.interval as i64,
+            opts.limit as i64,
+            encoding,
+        )
+        .await
+    {
+        Ok(subscription) => subscription,
+        _ => {
+            eprintln!("Couldn't start tapping events");
+            return exitcode::UNAVAILABLE;
+        }
+    };
+
+    let mut stream = subscription.stream();
+    while let Some(response) = stream.next().await {
+        let response = match response {
+            Some(response) => response,
+            None => continue,
+        };
+
+        if let Some(errors) = response.errors {
+            for error in errors {
+                eprintln!("{}", error.message);
+            }
+            return exitcode::USAGE;
+        }
+
+        if let Some(data) = response.data {
+            let event = data.output_events;
+            if opts.meta {
+                println!("{}: {}", event.component_name, event.encoded);
+            } else {
+                println!("{}", event.encoded);
+            }
+        }
+    }
+
+    exitcode::OK
+}
@@ -0,0 +1,38 @@
+mod cmd;
+
+use crate::cli::ApiAuthOpts;
+use structopt::StructOpt;
+use url::Url;
+
+pub use cmd::cmd;
+
+#[derive(StructOpt, Debug)]
+#[structopt(rename_all = "kebab-case")]
+pub struct Opts {
+    /// Names of the sources and transforms to tap, which can be globs
+    #[structopt(required = true)]
+    component_names: Vec<String>,
+
+    /// How often events are sampled (in milliseconds)
+    #[structopt(default_value = "500", short = "i", long)]
+    interval: u32,
+
+    /// Most events sampled per interval
+    #[structopt(default_value = "100", short = "l", long)]
+    limit: u32,
+
+    /// Encoding of the events
+    #[structopt(default_value = "json", short, long, possible_values = &["json", "logfmt"])]
+    format: String,
+
+    /// Vector GraphQL API server endpoint
+    #[structopt(short, long)]
+    url: Option<Url>,
+
+    /// Prefix each event with the name of the component it left
+    #[structopt(short = "m", long)]
+    meta: bool,
+
+    #[structopt(flatten)]
+    auth: ApiAuthOpts,
+}
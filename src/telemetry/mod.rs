@@ -0,0 +1,8 @@
+//! Vector's own telemetry, served to external systems without going through
+//! the topology.
+
+pub mod otlp;
+#[cfg(feature = "sinks-prometheus")]
+mod process;
+#[cfg(feature = "sinks-prometheus")]
+pub mod prometheus;
@@ -0,0 +1,422 @@
+//! Exports sampled `tracing` spans as OpenTelemetry traces, over OTLP/HTTP
+//! with the JSON encoding.
+//!
+//! The `SpanExportLayer` is part of the subscriber set up by `crate::trace`,
+//! before the configuration is loaded, so it does nothing until an `Exporter`
+//! is started. Spans named in the configuration are exported, along with the
+//! fields of the component they run in. A span nested in an exported span
+//! joins its trace; otherwise it starts a trace of its own, which is sampled.
+
+use crate::{
+    config::telemetry::OtlpOptions,
+    http::HttpClient,
+    internal_events::{OtlpSpansDropped, OtlpSpansExportFailed, OtlpSpansExported},
+    tls::TlsSettings,
+};
+use futures::{future, FutureExt};
+use http::{header::HeaderName, HeaderValue, Request, Uri};
+use hyper::Body;
+use lazy_static::lazy_static;
+use serde_json::{json, Value};
+use std::{
+    collections::HashSet,
+    convert::TryFrom,
+    mem,
+    sync::{
+        atomic::{AtomicBool, AtomicUsize, Ordering},
+        Mutex, RwLock,
+    },
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+use stream_cancel::{Trigger, Tripwire};
+use tracing::{
+    field::{Field, Visit},
+    span, Subscriber,
+};
+use tracing_subscriber::{
+    layer::{Context, Layer},
+    registry::LookupSpan,
+};
+
+/// How often the finished spans are sent.
+const EXPORT_INTERVAL: Duration = Duration::from_secs(5);
+/// Finished spans are dropped beyond this many waiting to be sent.
+const MAX_QUEUED_SPANS: usize = 4096;
+
+const COMPONENT_FIELDS: [&str; 3] = ["component_kind", "component_name", "component_type"];
+
+lazy_static! {
+    static ref SETTINGS: RwLock<Option<Settings>> = RwLock::new(None);
+    static ref QUEUE: Mutex<Vec<FinishedSpan>> = Mutex::new(Vec::new());
+}
+
+/// Whether an exporter is running, checked before anything else is done
+/// with a span.
+static ENABLED: AtomicBool = AtomicBool::new(false);
+/// Spans dropped since the last export, reported by the exporter rather than
+/// from within the subscriber.
+static DROPPED: AtomicUsize = AtomicUsize::new(0);
+
+struct Settings {
+    sample_rate: f64,
+    spans: HashSet<String>,
+}
+
+type Attributes = Vec<(&'static str, String)>;
+
+/// The fields of a component's span, attached to the spans exported within it.
+struct ComponentFields(Attributes);
+
+/// A span being exported, until it closes.
+struct ExportedSpan {
+    trace_id: u128,
+    span_id: u64,
+    parent_span_id: Option<u64>,
+    start: SystemTime,
+    attributes: Attributes,
+}
+
+/// Marks a span, and those nested in it, left out of the sample.
+struct Unsampled;
+
+struct FinishedSpan {
+    name: &'static str,
+    span: ExportedSpan,
+    end: SystemTime,
+}
+
+struct FieldVisitor<'a>(&'a mut Attributes);
+
+impl Visit for FieldVisitor<'_> {
+    fn record_str(&mut self, field: &Field, value: &str) {
+        self.0.push((field.name(), value.to_owned()));
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn std::fmt::Debug) {
+        self.0.push((field.name(), format!("{:?}", value)));
+    }
+}
+
+/// Records the spans to export, once an `Exporter` is started.
+#[derive(Debug, Default)]
+pub struct SpanExportLayer;
+
+impl<S> Layer<S> for SpanExportLayer
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+{
+    fn new_span(&self, attrs: &span::Attributes<'_>, id: &span::Id, ctx: Context<'_, S>) {
+        if !ENABLED.load(Ordering::Relaxed) {
+            return;
+        }
+        let span = match ctx.span(id) {
+            Some(span) => span,
+            None => return,
+        };
+
+        if attrs
+            .metadata()
+            .fields()
+            .iter()
+            .any(|field| field.name() == "component_name")
+        {
+            let mut fields = Vec::new();
+            attrs.record(&mut FieldVisitor(&mut fields));
+            fields.retain(|(name, _)| COMPONENT_FIELDS.contains(name));
+            span.extensions_mut().insert(ComponentFields(fields));
+            return;
+        }
+
+        let settings = SETTINGS.read().unwrap();
+        let settings = match settings.as_ref() {
+            Some(settings) if settings.spans.contains(span.name()) => settings,
+            _ => return,
+        };
+
+        // The closest exported or unsampled span decides the trace
+        let mut parent = None;
+        let mut component = None;
+        for ancestor in span.parents() {
+            let extensions = ancestor.extensions();
+            if parent.is_none() {
+                if extensions.get::<Unsampled>().is_some() {
+                    drop(extensions);
+                    span.extensions_mut().insert(Unsampled);
+                    return;
+                }
+                if let Some(exported) = extensions.get::<ExportedSpan>() {
+                    parent = Some((exported.trace_id, exported.span_id));
+                }
+            }
+            if component.is_none() {
+                component = extensions
+                    .get::<ComponentFields>()
+                    .map(|fields| fields.0.clone());
+            }
+        }
+
+        let (trace_id, parent_span_id) = match parent {
+            Some((trace_id, span_id)) => (trace_id, Some(span_id)),
+            None if rand::random::<f64>() < settings.sample_rate => {
+                (non_zero(rand::random::<u128>), None)
+            }
+            None => {
+                span.extensions_mut().insert(Unsampled);
+                return;
+            }
+        };
+
+        let mut attributes = component.unwrap_or_default();
+        attrs.record(&mut FieldVisitor(&mut attributes));
+        span.extensions_mut().insert(ExportedSpan {
+            trace_id,
+            span_id: non_zero(rand::random::<u64>),
+            parent_span_id,
+            start: SystemTime::now(),
+            attributes,
+        });
+    }
+
+    fn on_record(&self, id: &span::Id, values: &span::Record<'_>, ctx: Context<'_, S>) {
+        if !ENABLED.load(Ordering::Relaxed) {
+            return;
+        }
+        if let Some(span) = ctx.span(id) {
+            if let Some(exported) = span.extensions_mut().get_mut::<ExportedSpan>() {
+                values.record(&mut FieldVisitor(&mut exported.attributes));
+            }
+        }
+    }
+
+    fn on_close(&self, id: span::Id, ctx: Context<'_, S>) {
+        let span = match ctx.span(&id) {
+            Some(span) => span,
+            None => return,
+        };
+        let exported = match span.extensions_mut().remove::<ExportedSpan>() {
+            Some(exported) => exported,
+            None => return,
+        };
+
+        let mut queue = QUEUE.lock().unwrap();
+        if queue.len() < MAX_QUEUED_SPANS {
+            queue.push(FinishedSpan {
+                name: span.name(),
+                span: exported,
+                end: SystemTime::now(),
+            });
+        } else {
+            DROPPED.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+}
+
+fn non_zero<T: Default + PartialEq>(random: impl Fn() -> T) -> T {
+    loop {
+        let id = random();
+        if id != T::default() {
+            return id;
+        }
+    }
+}
+
+/// Sends the finished spans to the configured endpoint, until dropped.
+pub struct Exporter {
+    _shutdown: Trigger,
+}
+
+impl Exporter {
+    pub fn start(options: &OtlpOptions) -> crate::Result<Self> {
+        if !(0.0..=1.0).contains(&options.sample_rate) {
+            return Err("`telemetry.otlp.sample_rate` must be between 0 and 1".into());
+        }
+        let uri = Uri::try_from(options.endpoint.as_str())?;
+        let mut headers = Vec::new();
+        for (name, value) in &options.headers {
+            headers.push((
+                HeaderName::try_from(name.as_str())?,
+                HeaderValue::try_from(value.as_str())?,
+            ));
+        }
+        let tls = TlsSettings::from_options(&options.tls)?;
+        let mut client = HttpClient::new(tls, &Default::default())?;
+
+        *SETTINGS.write().unwrap() = Some(Settings {
+            sample_rate: options.sample_rate,
+            spans: options.spans.iter().cloned().collect(),
+        });
+        ENABLED.store(true, Ordering::Relaxed);
+
+        let (_shutdown, tripwire) = Tripwire::new();
+        let export = async move {
+            let mut interval = tokio::time::interval(EXPORT_INTERVAL);
+            loop {
+                interval.tick().await;
+                let dropped = DROPPED.swap(0, Ordering::Relaxed);
+                if dropped > 0 {
+                    emit!(OtlpSpansDropped { count: dropped });
+                }
+                let spans = mem::take(&mut *QUEUE.lock().unwrap());
+                if spans.is_empty() {
+                    continue;
+                }
+
+                let count = spans.len();
+                let mut request = Request::post(uri.clone())
+                    .header("Content-Type", "application/json")
+                    .body(Body::from(encode(&spans).to_string()))
+                    .expect("Export request is valid");
+                request.headers_mut().extend(headers.clone());
+
+                match client.send(request).await {
+                    Ok(response) if response.status().is_success() => {
+                        emit!(OtlpSpansExported { count })
+                    }
+                    Ok(response) => emit!(OtlpSpansExportFailed {
+                        count,
+                        error: format!("Unexpected status {}", response.status()).into(),
+                    }),
+                    Err(error) => emit!(OtlpSpansExportFailed { count, error }),
+                }
+            }
+        };
+        tokio::spawn(future::select(Box::pin(export), tripwire).map(|_| ()));
+
+        Ok(Self { _shutdown })
+    }
+}
+
+impl Drop for Exporter {
+    fn drop(&mut self) {
+        ENABLED.store(false, Ordering::Relaxed);
+        *SETTINGS.write().unwrap() = None;
+        QUEUE.lock().unwrap().clear();
+    }
+}
+
+fn unix_nanos(time: SystemTime) -> String {
+    time.duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_nanos()
+        .to_string()
+}
+
+fn attributes(attributes: &[(&str, String)]) -> Vec<Value> {
+    attributes
+        .iter()
+        .map(|(key, value)| json!({ "key": key, "value": { "stringValue": value } }))
+        .collect()
+}
+
+/// Encodes spans as an `ExportTraceServiceRequest`.
+fn encode(spans: &[FinishedSpan]) -> Value {
+    let spans = spans
+        .iter()
+        .map(|finished| {
+            let span = &finished.span;
+            let mut value = json!({
+                "traceId": format!("{:032x}", span.trace_id),
+                "spanId": format!("{:016x}", span.span_id),
+                "name": finished.name,
+                // SPAN_KIND_INTERNAL
+                "kind": 1,
+                "startTimeUnixNano": unix_nanos(span.start),
+                "endTimeUnixNano": unix_nanos(finished.end),
+                "attributes": attributes(&span.attributes),
+            });
+            if let Some(parent_span_id) = span.parent_span_id {
+                value["parentSpanId"] = format!("{:016x}", parent_span_id).into();
+            }
+            value
+        })
+        .collect::<Vec<_>>();
+
+    let resource = [
+        ("service.name", "vector".to_owned()),
+        ("service.version", crate::get_version()),
+        ("host.name", crate::get_hostname().unwrap_or_default()),
+    ];
+
+    json!({
+        "resourceSpans": [{
+            "resource": { "attributes": attributes(&resource) },
+            "scopeSpans": [{
+                "scope": { "name": "vector" },
+                "spans": spans,
+            }],
+        }],
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tracing::{error_span, info_span};
+    use tracing_subscriber::layer::SubscriberExt;
+
+    fn settings(sample_rate: f64) {
+        *SETTINGS.write().unwrap() = Some(Settings {
+            sample_rate,
+            spans: vec!["test_batch".to_owned(), "test_request".to_owned()]
+                .into_iter()
+                .collect(),
+        });
+        ENABLED.store(true, Ordering::Relaxed);
+    }
+
+    // The layer's state is global, so the scenarios run in one test, with
+    // span names no other test uses
+    #[test]
+    fn exports_sampled_spans() {
+        let subscriber = tracing_subscriber::registry().with(SpanExportLayer);
+        tracing::subscriber::with_default(subscriber, || {
+            settings(1.0);
+            let sink = error_span!(
+                "sink",
+                component_kind = "sink",
+                component_name = "out",
+                component_type = "http",
+            );
+            sink.in_scope(|| {
+                let batch = info_span!("test_batch");
+                batch.in_scope(|| drop(info_span!("test_request", request_id = 1)));
+                drop(batch);
+            });
+
+            let spans = mem::take(&mut *QUEUE.lock().unwrap());
+            assert_eq!(spans.len(), 2);
+            let (request, batch) = (&spans[0], &spans[1]);
+            assert_eq!(request.name, "test_request");
+            assert_eq!(batch.name, "test_batch");
+            assert_eq!(request.span.trace_id, batch.span.trace_id);
+            assert_eq!(request.span.parent_span_id, Some(batch.span.span_id));
+            assert_eq!(batch.span.parent_span_id, None);
+            assert!(request
+                .span
+                .attributes
+                .contains(&("component_name", "out".to_owned())));
+            assert!(request
+                .span
+                .attributes
+                .contains(&("request_id", "1".to_owned())));
+
+            let encoded = encode(&spans);
+            let encoded = &encoded["resourceSpans"][0]["scopeSpans"][0]["spans"];
+            assert_eq!(
+                encoded[0]["parentSpanId"],
+                format!("{:016x}", batch.span.span_id)
+            );
+            assert_eq!(encoded[1]["traceId"].as_str().unwrap().len(), 32);
+
+            // Unsampled traces are left out as a whole
+            settings(0.0);
+            sink.in_scope(|| {
+                let batch = info_span!("test_batch");
+                batch.in_scope(|| drop(info_span!("test_request", request_id = 2)));
+            });
+            assert!(QUEUE.lock().unwrap().is_empty());
+
+            ENABLED.store(false, Ordering::Relaxed);
+        });
+    }
+}
@@ -0,0 +1,121 @@
+//! Metrics of the Vector process, named as the Prometheus client libraries
+//! name them.
+
+use crate::event::metric::{Metric, MetricKind, MetricValue};
+use chrono::Utc;
+
+/// Returns the metrics of the process, on platforms where they're known.
+pub fn metrics() -> Vec<Metric> {
+    #[cfg(target_os = "linux")]
+    {
+        linux::metrics().unwrap_or_default()
+    }
+    #[cfg(not(target_os = "linux"))]
+    {
+        Vec::new()
+    }
+}
+
+fn metric(name: &str, value: MetricValue) -> Metric {
+    Metric {
+        name: name.to_owned(),
+        namespace: None,
+        timestamp: Some(Utc::now()),
+        tags: None,
+        kind: MetricKind::Absolute,
+        value,
+    }
+}
+
+#[cfg(target_os = "linux")]
+mod linux {
+    use super::metric;
+    use crate::event::metric::{Metric, MetricValue};
+    use std::fs;
+
+    pub fn metrics() -> Option<Vec<Metric>> {
+        let stat = fs::read_to_string("/proc/self/stat").ok()?;
+        // The command may contain spaces and parentheses, so the fields are
+        // read from its end. They're numbered as in proc(5), from 3
+        let fields = stat[stat.rfind(')')? + 1..]
+            .split_whitespace()
+            .collect::<Vec<_>>();
+        let field = |n: usize| -> Option<f64> { fields.get(n - 3)?.parse().ok() };
+
+        let ticks = unsafe { libc::sysconf(libc::_SC_CLK_TCK) } as f64;
+        let page_size = unsafe { libc::sysconf(libc::_SC_PAGESIZE) } as f64;
+
+        let mut metrics = vec![
+            metric(
+                "process_cpu_seconds_total",
+                MetricValue::Counter {
+                    value: (field(14)? + field(15)?) / ticks,
+                },
+            ),
+            metric(
+                "process_virtual_memory_bytes",
+                MetricValue::Gauge { value: field(23)? },
+            ),
+            metric(
+                "process_resident_memory_bytes",
+                MetricValue::Gauge {
+                    value: field(24)? * page_size,
+                },
+            ),
+        ];
+        if let Some(boot_time) = boot_time() {
+            metrics.push(metric(
+                "process_start_time_seconds",
+                MetricValue::Gauge {
+                    value: boot_time + field(22)? / ticks,
+                },
+            ));
+        }
+        if let Ok(fds) = fs::read_dir("/proc/self/fd") {
+            metrics.push(metric(
+                "process_open_fds",
+                MetricValue::Gauge {
+                    value: fds.count() as f64,
+                },
+            ));
+        }
+
+        Some(metrics)
+    }
+
+    /// Seconds since the epoch at which the system booted.
+    fn boot_time() -> Option<f64> {
+        fs::read_to_string("/proc/stat")
+            .ok()?
+            .lines()
+            .find_map(|line| line.strip_prefix("btime "))?
+            .trim()
+            .parse()
+            .ok()
+    }
+}
+
+#[cfg(all(test, target_os = "linux"))]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    #[test]
+    fn reads_process_metrics() {
+        let metrics = metrics()
+            .into_iter()
+            .map(|metric| (metric.name, metric.value))
+            .collect::<HashMap<_, _>>();
+
+        match metrics["process_resident_memory_bytes"] {
+            MetricValue::Gauge { value } => assert!(value > 0.0),
+            ref value => panic!("unexpected value {:?}", value),
+        }
+        match metrics["process_start_time_seconds"] {
+            MetricValue::Gauge { value } => assert!(value <= Utc::now().timestamp() as f64 + 1.0),
+            ref value => panic!("unexpected value {:?}", value),
+        }
+        assert!(metrics.contains_key("process_cpu_seconds_total"));
+        assert!(metrics.contains_key("process_open_fds"));
+    }
+}
@@ -0,0 +1,117 @@
+//! Serves Vector's internal metrics in the Prometheus exposition format, as
+//! the `prometheus` sink would serve them from the `internal_metrics` source.
+
+use super::process;
+use crate::{
+    config::telemetry::PrometheusOptions,
+    internal_events::TelemetryServerFailed,
+    metrics::{capture_metrics, get_controller},
+    sinks::prometheus::encode_metrics,
+};
+use futures::{future, FutureExt, TryFutureExt};
+use hyper::{
+    service::{make_service_fn, service_fn},
+    Body, Method, Request, Response, Server as HyperServer, StatusCode,
+};
+use std::{convert::Infallible, net::SocketAddr, sync::Arc};
+use stream_cancel::{Trigger, Tripwire};
+
+pub const CONTENT_TYPE: &str = "text/plain; version=0.0.4";
+
+/// Renders the internal metrics, labelled with the components they're about,
+/// along with the metrics of the process.
+pub fn render(options: &PrometheusOptions) -> String {
+    let mut metrics = get_controller()
+        .map(|controller| {
+            capture_metrics(controller)
+                .map(|event| event.into_metric())
+                .collect::<Vec<_>>()
+        })
+        .unwrap_or_default();
+    metrics.extend(process::metrics());
+
+    // The data of a metric must follow its headers
+    metrics.sort_by(|a, b| (&a.namespace, &a.name).cmp(&(&b.namespace, &b.name)));
+
+    encode_metrics(None, &options.buckets, &options.quantiles, false, &metrics)
+}
+
+/// The standalone listener serving `/metrics`. The server shuts down when
+/// this is dropped.
+pub struct Server {
+    _shutdown: Trigger,
+    addr: SocketAddr,
+}
+
+impl Server {
+    pub fn start(options: &PrometheusOptions) -> crate::Result<Self> {
+        let options = Arc::new(options.clone());
+        let server = HyperServer::try_bind(&options.address)?.serve(make_service_fn(move |_| {
+            let options = Arc::clone(&options);
+            async move {
+                Ok::<_, Infallible>(service_fn(move |req| {
+                    future::ok::<_, Infallible>(handle(&req, &options))
+                }))
+            }
+        }));
+        let addr = server.local_addr();
+
+        let (_shutdown, tripwire) = Tripwire::new();
+        let server = server
+            .with_graceful_shutdown(tripwire.then(crate::stream::tripwire_handler))
+            .map_err(|error| emit!(TelemetryServerFailed { error }));
+        tokio::spawn(server);
+
+        Ok(Self { _shutdown, addr })
+    }
+
+    /// Returns the address the server is listening on.
+    pub fn addr(&self) -> SocketAddr {
+        self.addr
+    }
+}
+
+fn handle(req: &Request<Body>, options: &PrometheusOptions) -> Response<Body> {
+    match (req.method(), req.uri().path()) {
+        (&Method::GET, "/metrics") => Response::builder()
+            .header("Content-Type", CONTENT_TYPE)
+            .body(render(options).into())
+            .expect("Metrics response is valid"),
+        _ => Response::builder()
+            .status(StatusCode::NOT_FOUND)
+            .body(Body::empty())
+            .expect("Not found response is valid"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::next_addr;
+    use metrics::counter;
+
+    #[tokio::test]
+    async fn serves_metrics() {
+        let _ = crate::metrics::init();
+        counter!("telemetry_test_total", 3, "component_name" => "in");
+
+        let options = PrometheusOptions {
+            address: next_addr(),
+            ..PrometheusOptions::default()
+        };
+        let server = Server::start(&options).unwrap();
+
+        let url = format!("http://{}/metrics", server.addr());
+        let response = reqwest::get(&url).await.unwrap();
+        assert_eq!(response.headers()["content-type"], CONTENT_TYPE);
+
+        let body = response.text().await.unwrap();
+        assert!(body.contains("# TYPE vector_telemetry_test_total counter\n"));
+        assert!(body.contains("vector_telemetry_test_total{component_name=\"in\"} 3\n"));
+        #[cfg(target_os = "linux")]
+        assert!(body.contains("# TYPE process_cpu_seconds_total counter\n"));
+
+        let url = format!("http://{}/other", server.addr());
+        assert_eq!(reqwest::get(&url).await.unwrap().status(), 404);
+    }
+}
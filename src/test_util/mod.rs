@@ -272,6 +272,7 @@ pub fn runtime() -> runtime::Runtime {
     runtime::Builder::new()
         .threaded_scheduler()
         .enable_all()
+        .on_thread_start(crate::dns::mark_runtime_worker)
         .build()
         .unwrap()
 }
@@ -508,7 +509,9 @@ pub async fn start_topology(
     require_healthy: bool,
 ) -> (RunningTopology, mpsc::UnboundedReceiver<()>) {
     let diff = ConfigDiff::initial(&config);
-    let pieces = topology::build_or_log_errors(&config, &diff).await.unwrap();
+    let pieces = topology::build_or_log_errors(&config, &diff, &Default::default())
+        .await
+        .unwrap();
     topology::start_validated(config, diff, pieces, require_healthy)
         .await
         .unwrap()
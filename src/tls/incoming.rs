@@ -64,6 +64,10 @@ impl MaybeTlsListener {
             .context(IncomingListener)
     }
 
+    pub(crate) fn local_addr(&self) -> Result<SocketAddr, std::io::Error> {
+        self.listener.local_addr()
+    }
+
     async fn into_accept(
         mut self,
     ) -> (crate::tls::Result<MaybeTlsIncomingStream<TcpStream>>, Self) {
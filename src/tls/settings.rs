@@ -21,7 +21,7 @@ use std::{
 
 const PEM_START_MARKER: &str = "-----BEGIN ";
 
-#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+#[derive(Clone, Debug, Default, Deserialize, Serialize, PartialEq)]
 pub struct TlsConfig {
     pub enabled: Option<bool>,
     #[serde(flatten)]
@@ -38,7 +38,7 @@ impl TlsConfig {
 }
 
 /// Standard TLS options
-#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+#[derive(Clone, Debug, Default, Deserialize, Serialize, PartialEq)]
 pub struct TlsOptions {
     pub verify_certificate: Option<bool>,
     pub verify_hostname: Option<bool>,
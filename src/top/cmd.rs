@@ -1,13 +1,16 @@
 use super::{
     dashboard::{init_dashboard, is_tty, Widgets},
-    state::{ComponentRow, ComponentsState, WidgetsState},
+    state::{ComponentRow, ComponentsState, Direction, WidgetsState},
 };
 use crate::config;
-use std::sync::Arc;
+use futures::future::{self, Either};
+use std::{collections::HashMap, sync::Arc};
+use tokio::stream::StreamExt;
 use url::Url;
 use vector_api_client::{
-    gql::{ComponentsQueryExt, HealthQueryExt},
-    Client,
+    connect_subscription_client_with_auth,
+    gql::{ComponentsQueryExt, HealthQueryExt, MetricsSubscriptionExt},
+    Client, SubscriptionClient,
 };
 
 /// Executes a toplogy query to the GraphQL server, and creates an initial ComponentsState
@@ -25,24 +28,38 @@ async fn update_components(
         interval.tick().await;
 
         // Execute a query to get the latest components, and aggregate metrics for each resource
-        let rows = client
+        let data = client
             .components_query()
             .await
             .map_err(|_| ())?
             .data
-            .ok_or_else(|| ())?
+            .ok_or_else(|| ())?;
+
+        let mut inputs = data
+            .transforms
+            .into_iter()
+            .map(|t| (t.name, t.inputs))
+            .chain(data.sinks.into_iter().map(|s| (s.name, s.inputs)))
+            .collect::<HashMap<_, _>>();
+
+        let rows = data
             .components
             .into_iter()
-            .map(|d| ComponentRow {
-                name: d.name,
-                component_type: d.on.to_string(),
-                events_processed_total: d
-                    .events_processed_total
-                    .as_ref()
-                    .map(|ep| ep.events_processed_total as i64)
-                    .unwrap_or(0),
-                errors: 0,
-                throughput: 0.00,
+            .map(|d| {
+                let inputs = inputs.remove(&d.name).unwrap_or_default();
+                ComponentRow::new(
+                    d.name,
+                    d.on.to_string(),
+                    inputs,
+                    d.events_processed_total
+                        .as_ref()
+                        .map(|ep| ep.events_processed_total as i64)
+                        .unwrap_or(0),
+                    d.errors_total
+                        .as_ref()
+                        .map(|e| e.errors_total as i64)
+                        .unwrap_or(0),
+                )
             })
             .collect::<Vec<_>>();
 
@@ -50,6 +67,60 @@ async fn update_components(
     }
 }
 
+/// Subscribes to the throughputs and errors of the components, recording them as samples
+/// for the dashboard. Samples are taken every `interval` ms
+async fn subscribe_metrics(
+    interval: u64,
+    client: SubscriptionClient,
+    state: Arc<WidgetsState>,
+) -> Result<(), ()> {
+    let interval = interval as i64;
+
+    // Subscriptions stop once dropped, so they're held until the streams end
+    let events_in_subscription = client
+        .component_events_in_throughputs_subscription(interval)
+        .await
+        .map_err(|_| ())?;
+    let events_out_subscription = client
+        .component_events_out_throughputs_subscription(interval)
+        .await
+        .map_err(|_| ())?;
+    let errors_subscription = client
+        .component_errors_totals_subscription(interval)
+        .await
+        .map_err(|_| ())?;
+
+    let events_in = events_in_subscription
+        .stream()
+        .filter_map(|res| res?.data)
+        .map(|d| {
+            let t = d.component_events_in_throughputs;
+            (t.name, Direction::In, t.throughput)
+        });
+    let events_out = events_out_subscription
+        .stream()
+        .filter_map(|res| res?.data)
+        .map(|d| {
+            let t = d.component_events_out_throughputs;
+            (t.name, Direction::Out, t.throughput)
+        });
+    let mut errors = errors_subscription.stream().filter_map(|res| res?.data);
+    let mut throughputs = events_in.merge(events_out);
+
+    loop {
+        tokio::select! {
+            Some((name, direction, throughput)) = throughputs.next() => {
+                state.record_throughput(&name, direction, throughput);
+            }
+            Some(d) = errors.next() => {
+                let e = d.component_errors_totals;
+                state.record_errors_total(&e.name, e.errors_total as i64);
+            }
+            else => return Ok(()),
+        }
+    }
+}
+
 /// CLI command func for displaying Vector components, and communicating with a local/remote
 /// Vector API server via HTTP/WebSockets
 pub async fn cmd(opts: &super::Opts) -> exitcode::ExitCode {
@@ -69,7 +140,7 @@ pub async fn cmd(opts: &super::Opts) -> exitcode::ExitCode {
     });
 
     // Create a new API client for connecting to the local/remote Vector instance
-    let client = Client::new(url.clone());
+    let client = Client::new_with_auth(url.clone(), opts.auth.auth());
 
     // Check that the GraphQL server is reachable
     match client.health_query().await {
@@ -90,10 +161,31 @@ pub async fn cmd(opts: &super::Opts) -> exitcode::ExitCode {
         Arc::clone(&state),
     ));
 
+    // Sample the throughputs and errors of the components over WebSockets. The dashboard
+    // still shows the components and their totals if the subscriptions can't be made
+    let mut ws_url = url.clone();
+    let scheme = if url.scheme() == "https" { "wss" } else { "ws" };
+    ws_url
+        .set_scheme(scheme)
+        .expect("Couldn't set the API URL scheme. Please report this.");
+    let auth = opts.auth.auth();
+    let metrics = async {
+        if let Ok(client) = connect_subscription_client_with_auth(&ws_url, auth.as_ref()).await {
+            let _ = subscribe_metrics(opts.refresh_interval, client, Arc::clone(&state)).await;
+        }
+    };
+
     // Render a dashboard with the configured widgets
     let widgets = Widgets::new(Arc::clone(&state));
 
-    match init_dashboard(&widgets).await {
+    // Subscriptions aren't `Send`, so they're polled alongside the dashboard
+    let dashboard =
+        match future::select(Box::pin(init_dashboard(&widgets)), Box::pin(metrics)).await {
+            Either::Left((result, _)) => result,
+            Either::Right((_, dashboard)) => dashboard.await,
+        };
+
+    match dashboard {
         Ok(_) => exitcode::OK,
         _ => {
             eprintln!("Your terminal doesn't support building a dashboard. Exiting.");
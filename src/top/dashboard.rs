@@ -1,9 +1,10 @@
 use super::{
     events::capture_key_press,
-    state::{WidgetsState, COMPONENT_HEADERS},
+    state::{ComponentRow, WidgetsState, COMPONENT_HEADERS},
+    view::{topology_tree, KeyAction, ViewState},
 };
 use crossterm::{
-    event::{DisableMouseCapture, EnableMouseCapture, KeyCode},
+    event::{DisableMouseCapture, EnableMouseCapture},
     execute,
     terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
     tty::IsTty,
@@ -11,21 +12,24 @@ use crossterm::{
 };
 use std::{
     io::{stdout, Write},
-    sync::Arc,
+    sync::{Arc, Mutex},
 };
 use tokio::stream::StreamExt;
 use tui::{
     backend::{Backend, CrosstermBackend},
-    layout::{Alignment, Constraint, Layout, Rect},
+    layout::{Alignment, Constraint, Direction, Layout, Rect},
     style::{Color, Modifier, Style},
     text::{Span, Spans},
-    widgets::{Block, Borders, Paragraph, Row, Table, Wrap},
+    widgets::{Block, Borders, Paragraph, Row, Sparkline, Table, TableState, Wrap},
     Frame, Terminal,
 };
 
+static VIEW_LOCK_INVARIANT: &str = "Unable to acquire view lock. Please report this.";
+
 pub struct Widgets {
     constraints: Vec<Constraint>,
     state: Arc<WidgetsState>,
+    view: Mutex<ViewState>,
 }
 
 impl Widgets {
@@ -33,11 +37,22 @@ impl Widgets {
     pub fn new(state: Arc<WidgetsState>) -> Self {
         let constraints = vec![
             Constraint::Length(3),
-            Constraint::Max(90),
+            Constraint::Min(5),
             Constraint::Length(3),
         ];
 
-        Self { constraints, state }
+        Self {
+            constraints,
+            state,
+            view: Mutex::new(ViewState::default()),
+        }
+    }
+
+    /// Handles a key press, returning whether the dashboard should quit.
+    pub fn handle_key(&self, key: crossterm::event::KeyCode) -> KeyAction {
+        let mut view = self.view.lock().expect(VIEW_LOCK_INVARIANT);
+        let rows = view.visible_rows(self.state.components().rows());
+        view.handle_key(key, &rows)
     }
 
     /// Renders a title showing 'Vector', and the URL the dashboard is currently connected to.
@@ -56,10 +71,24 @@ impl Widgets {
     }
 
     /// Renders a components table, showing sources, transforms and sinks in tabular form, with
-    /// statistics pulled from `ComponentsState`,
-    fn components_table<B: Backend>(&self, f: &mut Frame<B>, area: Rect) {
-        let components = self.state.components();
-        let items = components.rows().into_iter().map(|r| {
+    /// statistics pulled from `ComponentsState`. The sorted column is marked in its header.
+    fn components_table<B: Backend>(
+        &self,
+        f: &mut Frame<B>,
+        area: Rect,
+        view: &ViewState,
+        rows: &[ComponentRow],
+    ) {
+        let headers = COMPONENT_HEADERS
+            .iter()
+            .enumerate()
+            .map(|(i, header)| match i {
+                i if i == view.sort_column() && view.descending() => format!("{} ▼", header),
+                i if i == view.sort_column() => format!("{} ▲", header),
+                _ => (*header).to_owned(),
+            })
+            .collect::<Vec<_>>();
+        let items = rows.iter().map(|r| {
             Row::StyledData(
                 vec![
                     r.name.clone(),
@@ -73,10 +102,11 @@ impl Widgets {
             )
         });
 
-        let w = Table::new(COMPONENT_HEADERS.iter(), items)
+        let w = Table::new(headers.iter(), items)
             .block(Block::default().borders(Borders::ALL).title("Components"))
             .header_gap(1)
             .column_spacing(2)
+            .highlight_style(Style::default().add_modifier(Modifier::REVERSED))
             .widths(&[
                 Constraint::Percentage(20),
                 Constraint::Percentage(20),
@@ -85,17 +115,113 @@ impl Widgets {
                 Constraint::Percentage(20),
             ]);
 
+        let mut state = TableState::default();
+        state.select(view.selected_index(rows));
+        f.render_stateful_widget(w, area, &mut state);
+    }
+
+    /// Renders the topology as a tree, from each source down to its sinks, following the
+    /// inputs of each component. The selected component is highlighted wherever it appears.
+    fn topology_tree<B: Backend>(
+        &self,
+        f: &mut Frame<B>,
+        area: Rect,
+        view: &ViewState,
+        rows: &[ComponentRow],
+    ) {
+        let selected = view.selected_index(rows).map(|i| rows[i].name.as_str());
+        let all_rows = self.state.components().rows();
+        let text = topology_tree(&all_rows)
+            .into_iter()
+            .map(|line| {
+                let style = if Some(line.name.as_str()) == selected {
+                    Style::default().add_modifier(Modifier::REVERSED)
+                } else {
+                    Style::default().fg(Color::White)
+                };
+                let row = all_rows.iter().find(|row| row.name == line.name);
+                let detail = row
+                    .map(|row| format!("  {}  {}/s", row.component_type, row.format_throughput()))
+                    .unwrap_or_default();
+                Spans::from(vec![
+                    Span::styled(line.prefix, Style::default().fg(Color::Gray)),
+                    Span::styled(line.name, style),
+                    Span::styled(detail, Style::default().fg(Color::Gray)),
+                ])
+            })
+            .collect::<Vec<_>>();
+
+        let w =
+            Paragraph::new(text).block(Block::default().borders(Borders::ALL).title("Topology"));
+
         f.render_widget(w, area);
     }
 
-    /// Renders a box showing instructions on how to exit from `vector top`.
-    fn quit_box<B: Backend>(&self, f: &mut Frame<B>, area: Rect) {
-        let text = vec![Spans::from("To quit, press ESC or 'q'")];
+    /// Renders the details of the selected component: its inputs, and sparklines of its
+    /// recent throughput and errors.
+    fn detail_pane<B: Backend>(&self, f: &mut Frame<B>, area: Rect, row: &ComponentRow) {
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .title(format!("{} ({})", row.name, row.component_type));
+        let inner = block.inner(area);
+        f.render_widget(block, area);
+
+        let rects = Layout::default()
+            .constraints(vec![Constraint::Length(1), Constraint::Min(3)])
+            .split(inner);
+        let inputs = if row.inputs.is_empty() {
+            "--".to_owned()
+        } else {
+            row.inputs.join(", ")
+        };
+        f.render_widget(Paragraph::new(format!("Inputs: {}", inputs)), rects[0]);
+
+        let charts = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints(vec![Constraint::Percentage(50), Constraint::Percentage(50)])
+            .split(rects[1]);
+        let throughput = row.throughput_history.iter().copied().collect::<Vec<_>>();
+        let w = Sparkline::default()
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .title(format!("Throughput: {}/s", row.format_throughput())),
+            )
+            .data(&throughput)
+            .style(Style::default().fg(Color::Green));
+        f.render_widget(w, charts[0]);
+
+        let errors = row.errors_history.iter().copied().collect::<Vec<_>>();
+        let w = Sparkline::default()
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .title(format!("Errors: {}", row.format_errors())),
+            )
+            .data(&errors)
+            .style(Style::default().fg(Color::Red));
+        f.render_widget(w, charts[1]);
+    }
+
+    /// Renders a box showing the filter being edited, or the keys of the dashboard.
+    fn help_box<B: Backend>(&self, f: &mut Frame<B>, area: Rect, view: &ViewState) {
+        let text = if view.filtering() {
+            format!("Filter: {}_  (Enter to apply, ESC to clear)", view.filter())
+        } else if !view.filter().is_empty() {
+            format!(
+                "Filtered by \"{}\". To quit, press ESC or 'q'; '/' to filter",
+                view.filter()
+            )
+        } else {
+            "To quit, press ESC or 'q'. Select with ↑/↓, sort with 1-5, filter with '/', \
+             toggle the topology tree with 't'"
+                .to_owned()
+        };
 
         let block = Block::default()
             .borders(Borders::ALL)
             .border_style(Style::default().fg(Color::Gray));
-        let w = Paragraph::new(text)
+        let w = Paragraph::new(vec![Spans::from(text)])
             .block(block)
             .style(Style::default().fg(Color::Gray))
             .alignment(Alignment::Left);
@@ -104,14 +230,33 @@ impl Widgets {
     }
 
     /// Draw a single frame. Creates a layout and renders widgets into it.
-    fn draw<B: Backend>(&self, f: &mut Frame<B>) {
+    pub fn draw<B: Backend>(&self, f: &mut Frame<B>) {
+        let view = self.view.lock().expect(VIEW_LOCK_INVARIANT);
+        let rows = view.visible_rows(self.state.components().rows());
+        let selected = view.selected_index(&rows).map(|i| &rows[i]);
+
         let rects = Layout::default()
             .constraints(self.constraints.as_ref())
             .split(f.size());
 
         self.title(f, rects[0]);
-        self.components_table(f, rects[1]);
-        self.quit_box(f, rects[2]);
+
+        let body = match selected {
+            Some(_) => Layout::default()
+                .constraints(vec![Constraint::Min(5), Constraint::Length(10)])
+                .split(rects[1]),
+            None => vec![rects[1]],
+        };
+        if view.tree() {
+            self.topology_tree(f, body[0], &view, &rows);
+        } else {
+            self.components_table(f, body[0], &view, &rows);
+        }
+        if let Some(row) = selected {
+            self.detail_pane(f, body[1], row);
+        }
+
+        self.help_box(f, rects[2], &view);
     }
 
     /// Listen for state updates. Used to determine when to redraw.
@@ -161,10 +306,11 @@ pub async fn init_dashboard(widgets: &Widgets) -> Result<(), Box<dyn std::error:
                 terminal.draw(|f| widgets.draw(f))?;
             },
             k = key_press_rx.recv() => {
-                if let KeyCode::Esc | KeyCode::Char('q') = k.unwrap() {
+                if let KeyAction::Quit = widgets.handle_key(k.unwrap()) {
                     let _ = key_press_kill_tx.send(());
                     break
                 }
+                terminal.draw(|f| widgets.draw(f))?;
             }
         }
     }
@@ -177,3 +323,113 @@ pub async fn init_dashboard(widgets: &Widgets) -> Result<(), Box<dyn std::error:
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::top::state::ComponentsState;
+    use crossterm::event::KeyCode;
+    use tui::backend::TestBackend;
+
+    fn widgets() -> Widgets {
+        let state = Arc::new(WidgetsState::new(
+            "http://127.0.0.1:8686/graphql".parse().unwrap(),
+            ComponentsState::new(),
+        ));
+        state.update_component_rows(vec![
+            ComponentRow::new("in1".into(), "source".into(), vec![], 300, 0),
+            ComponentRow::new(
+                "parse".into(),
+                "transform".into(),
+                vec!["in1".into()],
+                200,
+                5,
+            ),
+            ComponentRow::new("out1".into(), "sink".into(), vec!["parse".into()], 100, 0),
+        ]);
+        Widgets::new(state)
+    }
+
+    /// Draws a frame, returning the lines of the screen
+    fn render(widgets: &Widgets) -> Vec<String> {
+        let mut terminal = Terminal::new(TestBackend::new(100, 30)).unwrap();
+        terminal.draw(|f| widgets.draw(f)).unwrap();
+
+        let buffer = terminal.backend().buffer();
+        buffer
+            .content
+            .chunks(buffer.area.width as usize)
+            .map(|line| line.iter().map(|cell| cell.symbol.as_str()).collect())
+            .collect()
+    }
+
+    /// Returns the index of the first line containing `text`
+    fn line_of(lines: &[String], text: &str) -> Option<usize> {
+        lines.iter().position(|line| line.contains(text))
+    }
+
+    #[test]
+    fn sorts_by_column() {
+        let widgets = widgets();
+        let lines = render(&widgets);
+        assert!(line_of(&lines, "in1") < line_of(&lines, "out1"));
+
+        // Sort by events, then reverse
+        widgets.handle_key(KeyCode::Char('3'));
+        let lines = render(&widgets);
+        assert!(line_of(&lines, "out1") < line_of(&lines, "parse"));
+        assert!(line_of(&lines, "parse") < line_of(&lines, "in1"));
+
+        widgets.handle_key(KeyCode::Char('3'));
+        let lines = render(&widgets);
+        assert!(line_of(&lines, "in1") < line_of(&lines, "parse"));
+        assert!(line_of(&lines, "parse") < line_of(&lines, "out1"));
+    }
+
+    #[test]
+    fn filters_by_name_and_kind() {
+        let widgets = widgets();
+        for key in "/sink".chars() {
+            widgets.handle_key(KeyCode::Char(key));
+        }
+        widgets.handle_key(KeyCode::Enter);
+
+        let lines = render(&widgets);
+        assert!(line_of(&lines, "out1").is_some());
+        assert!(line_of(&lines, "in1").is_none());
+        assert!(line_of(&lines, "parse").is_none());
+
+        widgets.handle_key(KeyCode::Char('/'));
+        widgets.handle_key(KeyCode::Esc);
+        assert!(line_of(&render(&widgets), "in1").is_some());
+    }
+
+    #[test]
+    fn shows_topology_tree() {
+        let widgets = widgets();
+        widgets.handle_key(KeyCode::Char('t'));
+
+        let lines = render(&widgets);
+        assert!(line_of(&lines, "└─ parse").is_some());
+        assert!(line_of(&lines, "   └─ out1").is_some());
+    }
+
+    #[test]
+    fn details_selected_component() {
+        let widgets = widgets();
+        assert!(line_of(&render(&widgets), "Inputs:").is_none());
+
+        widgets.handle_key(KeyCode::Down);
+        widgets.handle_key(KeyCode::Down);
+        let lines = render(&widgets);
+        assert!(line_of(&lines, "parse (transform)").is_some());
+        assert!(line_of(&lines, "Inputs: in1").is_some());
+    }
+
+    #[test]
+    fn quits() {
+        let widgets = widgets();
+        assert_eq!(widgets.handle_key(KeyCode::Char('j')), KeyAction::Redraw);
+        assert_eq!(widgets.handle_key(KeyCode::Char('q')), KeyAction::Quit);
+    }
+}
@@ -2,7 +2,9 @@ mod cmd;
 mod dashboard;
 mod events;
 mod state;
+mod view;
 
+use crate::cli::ApiAuthOpts;
 use structopt::StructOpt;
 use url::Url;
 
@@ -20,4 +22,7 @@ pub struct Opts {
 
     #[structopt(short, long)]
     human: bool,
+
+    #[structopt(flatten)]
+    auth: ApiAuthOpts,
 }
@@ -1,27 +1,62 @@
 use num_format::{Locale, ToFormattedString};
 use std::{
-    collections::btree_map::BTreeMap,
+    collections::{btree_map::BTreeMap, VecDeque},
     sync::{Arc, Mutex},
 };
 use tokio::sync::watch;
 
-pub static COMPONENT_HEADERS: [&str; 5] = ["Name", "Type", "Events", "Errors", "Throughput"];
+pub static COMPONENT_HEADERS: [&str; 5] = ["Name", "Kind", "Events", "Errors", "Throughput"];
 pub static ACQUIRE_LOCK_INVARIANT: &str = "Unable to acquire components lock. Please report this.";
 
+/// Number of throughput and error samples kept for each component.
+const HISTORY_LEN: usize = 120;
+
+/// Direction of the events counted by a throughput sample.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Direction {
+    In,
+    Out,
+}
+
 #[derive(Debug, Clone)]
 pub struct ComponentRow {
     pub name: String,
     pub component_type: String,
+    pub inputs: Vec<String>,
     pub events_processed_total: i64,
     pub errors: i64,
     pub throughput: f64,
+    /// Recent events per second, oldest first
+    pub throughput_history: VecDeque<u64>,
+    /// Recent errors per sample, oldest first
+    pub errors_history: VecDeque<u64>,
 }
 
 impl ComponentRow {
+    /// Returns a row without any samples yet
+    pub fn new(
+        name: String,
+        component_type: String,
+        inputs: Vec<String>,
+        events_processed_total: i64,
+        errors: i64,
+    ) -> Self {
+        Self {
+            name,
+            component_type,
+            inputs,
+            events_processed_total,
+            errors,
+            throughput: 0.00,
+            throughput_history: VecDeque::new(),
+            errors_history: VecDeque::new(),
+        }
+    }
+
     /// Helper method for formatting an f64 value -> String
     fn format_f64(val: f64) -> String {
         if val.is_normal() {
-            val.to_string()
+            format!("{:.2}", val)
         } else {
             "--".into()
         }
@@ -49,6 +84,18 @@ impl ComponentRow {
     pub fn format_throughput(&self) -> String {
         Self::format_f64(self.throughput)
     }
+
+    /// Sinks report the events they receive; other components the events they send
+    fn reports(&self, direction: Direction) -> bool {
+        (self.component_type == "sink") == (direction == Direction::In)
+    }
+
+    fn push_sample(history: &mut VecDeque<u64>, sample: u64) {
+        if history.len() == HISTORY_LEN {
+            history.pop_front();
+        }
+        history.push_back(sample);
+    }
 }
 
 pub struct ComponentsState {
@@ -64,26 +111,55 @@ impl ComponentsState {
     }
 
     /// Updates the existing component rows. Rows that don't exist in `rows` will be deleted;
-    /// new rows will be added, and existing rows will be updated
+    /// new rows will be added, and existing rows will be updated. The errors, throughput and
+    /// samples of existing rows are kept, as they are updated by subscriptions
     pub fn update_rows(&self, rows: Vec<ComponentRow>) {
+        let mut existing = self.rows.lock().expect(ACQUIRE_LOCK_INVARIANT);
         let rows = rows
             .into_iter()
-            .map(|r| {
-                (
-                    r.name.clone(),
-                    match self.rows.lock().expect(ACQUIRE_LOCK_INVARIANT).get(&r.name) {
-                        Some(existing) if existing.component_type == r.component_type => {
-                            // TODO - update values > 0 when throughput and other metrics gleaned
-                            // are independently updated. For now, just return the new row.
-                            r
-                        }
-                        _ => r,
-                    },
-                )
+            .map(|mut r| {
+                if let Some(previous) = existing.remove(&r.name) {
+                    if previous.component_type == r.component_type {
+                        r.errors = previous.errors;
+                        r.throughput = previous.throughput;
+                        r.throughput_history = previous.throughput_history;
+                        r.errors_history = previous.errors_history;
+                    }
+                }
+                (r.name.clone(), r)
             })
             .collect();
 
-        *self.rows.lock().expect(ACQUIRE_LOCK_INVARIANT) = rows;
+        *existing = rows;
+    }
+
+    /// Records the throughput of a component, in events per second, if it reports events in
+    /// that direction
+    pub fn record_throughput(&self, name: &str, direction: Direction, throughput: f64) {
+        if let Some(row) = self
+            .rows
+            .lock()
+            .expect(ACQUIRE_LOCK_INVARIANT)
+            .get_mut(name)
+            .filter(|row| row.reports(direction))
+        {
+            row.throughput = throughput;
+            ComponentRow::push_sample(&mut row.throughput_history, throughput.round() as u64);
+        }
+    }
+
+    /// Records the errors total of a component, sampling the errors since the last record
+    pub fn record_errors_total(&self, name: &str, errors_total: i64) {
+        if let Some(row) = self
+            .rows
+            .lock()
+            .expect(ACQUIRE_LOCK_INVARIANT)
+            .get_mut(name)
+        {
+            let errors = (errors_total - row.errors).max(0) as u64;
+            row.errors = errors_total;
+            ComponentRow::push_sample(&mut row.errors_history, errors);
+        }
     }
 
     /// Returns a cloned copy of component rows, typically used inside of frame re-renders
@@ -145,4 +221,17 @@ impl WidgetsState {
         self.components.update_rows(rows);
         self.notify();
     }
+
+    /// Record a throughput sample of a component.
+    pub fn record_throughput(&self, name: &str, direction: Direction, throughput: f64) {
+        self.components
+            .record_throughput(name, direction, throughput);
+        self.notify();
+    }
+
+    /// Record the errors total of a component.
+    pub fn record_errors_total(&self, name: &str, errors_total: i64) {
+        self.components.record_errors_total(name, errors_total);
+        self.notify();
+    }
 }
@@ -0,0 +1,220 @@
+use super::state::{ComponentRow, COMPONENT_HEADERS};
+use crossterm::event::KeyCode;
+use std::{
+    cmp::Ordering,
+    collections::{BTreeMap, HashSet},
+};
+
+/// What the dashboard should do after a key press.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KeyAction {
+    Redraw,
+    Quit,
+}
+
+/// How the components are presented: the sorting and filtering of the table, the selected
+/// component, and whether the topology tree is shown instead of the table.
+#[derive(Debug, Default)]
+pub struct ViewState {
+    /// Index of the column in `COMPONENT_HEADERS` the table is sorted by
+    sort_column: usize,
+    descending: bool,
+    filter: String,
+    /// Whether key presses are edited into the filter
+    filtering: bool,
+    selected: Option<String>,
+    tree: bool,
+}
+
+impl ViewState {
+    /// Handles a key press, given the rows currently shown.
+    ///
+    /// * Up/Down or k/j select a component, which is detailed below the table
+    /// * 1 to 5 sort the table by that column; again to reverse the order
+    /// * `/` edits the filter, until Enter keeps it or Esc clears it
+    /// * `t` toggles the topology tree
+    /// * Esc or q quit
+    pub fn handle_key(&mut self, key: KeyCode, rows: &[ComponentRow]) -> KeyAction {
+        if self.filtering {
+            match key {
+                KeyCode::Char(c) => self.filter.push(c),
+                KeyCode::Backspace => {
+                    self.filter.pop();
+                }
+                KeyCode::Enter => self.filtering = false,
+                KeyCode::Esc => {
+                    self.filter.clear();
+                    self.filtering = false;
+                }
+                _ => (),
+            }
+            return KeyAction::Redraw;
+        }
+
+        match key {
+            KeyCode::Esc | KeyCode::Char('q') => return KeyAction::Quit,
+            KeyCode::Up | KeyCode::Char('k') => self.move_selection(rows, -1),
+            KeyCode::Down | KeyCode::Char('j') => self.move_selection(rows, 1),
+            KeyCode::Char('/') => self.filtering = true,
+            KeyCode::Char('t') => self.tree = !self.tree,
+            KeyCode::Char(c) => {
+                if let Some(column) = c
+                    .to_digit(10)
+                    .map(|n| n as usize)
+                    .filter(|n| (1..=COMPONENT_HEADERS.len()).contains(n))
+                {
+                    self.sort_by(column - 1);
+                }
+            }
+            _ => (),
+        }
+        KeyAction::Redraw
+    }
+
+    fn sort_by(&mut self, column: usize) {
+        if self.sort_column == column {
+            self.descending = !self.descending;
+        } else {
+            self.sort_column = column;
+            self.descending = false;
+        }
+    }
+
+    fn move_selection(&mut self, rows: &[ComponentRow], offset: isize) {
+        if rows.is_empty() {
+            self.selected = None;
+            return;
+        }
+
+        let index = match self.selected_index(rows) {
+            Some(index) => (index as isize + offset)
+                .max(0)
+                .min(rows.len() as isize - 1),
+            None if offset < 0 => rows.len() as isize - 1,
+            None => 0,
+        };
+        self.selected = Some(rows[index as usize].name.clone());
+    }
+
+    /// Returns the index of the selected component in `rows`, if shown.
+    pub fn selected_index(&self, rows: &[ComponentRow]) -> Option<usize> {
+        let selected = self.selected.as_ref()?;
+        rows.iter().position(|row| &row.name == selected)
+    }
+
+    pub fn sort_column(&self) -> usize {
+        self.sort_column
+    }
+
+    pub fn descending(&self) -> bool {
+        self.descending
+    }
+
+    pub fn filter(&self) -> &str {
+        &self.filter
+    }
+
+    pub fn filtering(&self) -> bool {
+        self.filtering
+    }
+
+    pub fn tree(&self) -> bool {
+        self.tree
+    }
+
+    /// Returns the rows matching the filter by name or kind, in the selected order.
+    pub fn visible_rows(&self, mut rows: Vec<ComponentRow>) -> Vec<ComponentRow> {
+        let filter = self.filter.to_lowercase();
+        rows.retain(|row| {
+            row.name.to_lowercase().contains(&filter)
+                || row.component_type.to_lowercase().contains(&filter)
+        });
+
+        let column = self.sort_column;
+        rows.sort_by(|a, b| {
+            let ordering = match column {
+                1 => a.component_type.cmp(&b.component_type),
+                2 => a.events_processed_total.cmp(&b.events_processed_total),
+                3 => a.errors.cmp(&b.errors),
+                4 => a
+                    .throughput
+                    .partial_cmp(&b.throughput)
+                    .unwrap_or(Ordering::Equal),
+                _ => Ordering::Equal,
+            }
+            .then_with(|| a.name.cmp(&b.name));
+            if self.descending {
+                ordering.reverse()
+            } else {
+                ordering
+            }
+        });
+        rows
+    }
+}
+
+/// A line of the topology tree: the component, and the prefix drawing its branch.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TreeLine {
+    pub prefix: String,
+    pub name: String,
+}
+
+/// Lays out the topology as a tree following the `inputs` of each component, from each
+/// source down to its sinks. Components with several inputs appear under each of them.
+pub fn topology_tree(rows: &[ComponentRow]) -> Vec<TreeLine> {
+    let mut outputs = BTreeMap::<&str, Vec<&str>>::new();
+    for row in rows {
+        for input in &row.inputs {
+            outputs.entry(input.as_str()).or_default().push(&row.name);
+        }
+    }
+    for children in outputs.values_mut() {
+        children.sort();
+    }
+
+    let mut lines = Vec::new();
+    let mut roots = rows
+        .iter()
+        .filter(|row| row.component_type == "source")
+        .map(|row| row.name.as_str())
+        .collect::<Vec<_>>();
+    roots.sort();
+    for root in roots {
+        lines.push(TreeLine {
+            prefix: String::new(),
+            name: root.to_owned(),
+        });
+        let mut path = HashSet::new();
+        path.insert(root);
+        add_branches(&outputs, root, "", &mut path, &mut lines);
+    }
+    lines
+}
+
+fn add_branches<'a>(
+    outputs: &BTreeMap<&'a str, Vec<&'a str>>,
+    name: &'a str,
+    indent: &str,
+    path: &mut HashSet<&'a str>,
+    lines: &mut Vec<TreeLine>,
+) {
+    let children = match outputs.get(name) {
+        Some(children) => children,
+        None => return,
+    };
+
+    for (i, &child) in children.iter().enumerate() {
+        let last = i + 1 == children.len();
+        lines.push(TreeLine {
+            prefix: format!("{}{}", indent, if last { "└─ " } else { "├─ " }),
+            name: child.to_owned(),
+        });
+        // The configuration is checked to be acyclic, but a cycle mustn't hang the dashboard
+        if path.insert(child) {
+            let indent = format!("{}{}", indent, if last { "   " } else { "│  " });
+            add_branches(outputs, child, &indent, path, lines);
+            path.remove(child);
+        }
+    }
+}
@@ -5,9 +5,12 @@ use super::{
 };
 use crate::{
     buffers,
-    config::{DataType, SinkContext},
+    config::{DataType, SinkContext, TransformContext},
+    enrichment_tables::{EnrichmentTables, LoadedTables},
     event::Event,
+    internal_events::{EventIn, EventLag, EventOut},
     shutdown::SourceShutdownCoordinator,
+    sinks::util::flush::FlushHandle,
     transforms::Transform,
     Pipeline,
 };
@@ -25,23 +28,36 @@ pub struct Pieces {
     pub tasks: HashMap<String, Task>,
     pub source_tasks: HashMap<String, Task>,
     pub healthchecks: HashMap<String, Task>,
+    pub flush_handles: HashMap<String, FlushHandle>,
     pub shutdown_coordinator: SourceShutdownCoordinator,
+    pub enrichment_tables: LoadedTables,
 }
 
 /// Builds only the new pieces, and doesn't check their topology.
+///
+/// Enrichment tables are loaded next to the `current` ones of the running
+/// topology, and only replace them once the pieces' tables are committed.
 pub async fn build_pieces(
     config: &super::Config,
     diff: &ConfigDiff,
+    current: &EnrichmentTables,
 ) -> Result<Pieces, Vec<String>> {
     let mut inputs = HashMap::new();
     let mut outputs = HashMap::new();
     let mut tasks = HashMap::new();
     let mut source_tasks = HashMap::new();
     let mut healthchecks = HashMap::new();
+    let mut flush_handles = HashMap::new();
     let mut shutdown_coordinator = SourceShutdownCoordinator::default();
 
     let mut errors = vec![];
 
+    // Load enrichment tables first, since transforms look them up when built.
+    let enrichment_tables = EnrichmentTables::load(&config.enrichment_tables, current)?;
+    let transform_cx = TransformContext {
+        enrichment_tables: enrichment_tables.tables().clone(),
+    };
+
     // Build sources
     for (name, source) in config
         .sources
@@ -67,7 +83,11 @@ pub async fn build_pieces(
         };
 
         let (output, control) = Fanout::new();
-        let pump = rx.forward(output).map(|_| ()).compat();
+        let pump = rx
+            .inspect(|_| emit!(EventOut))
+            .forward(output)
+            .map(|_| ())
+            .compat();
         let pump = Task::new(name, typetag, pump);
 
         // The force_shutdown_tripwire is a Future that when it resolves means that this source
@@ -100,7 +120,7 @@ pub async fn build_pieces(
         let typetag = transform.inner.transform_type();
 
         let input_type = transform.inner.input_type();
-        let transform = match transform.inner.build().await {
+        let transform = match transform.inner.build_with_context(&transform_cx).await {
             Err(error) => {
                 errors.push(format!("Transform \"{}\": {}", name, error));
                 continue;
@@ -109,13 +129,13 @@ pub async fn build_pieces(
         };
 
         let (input_tx, input_rx) = futures01::sync::mpsc::channel(100);
-        let input_tx = buffers::BufferInputCloner::Memory(input_tx, buffers::WhenFull::Block);
+        let input_tx = buffers::BufferInputCloner::Memory(input_tx, buffers::WhenFull::Block, None);
 
         let (output, control) = Fanout::new();
 
+        let filtered = filter_event_type(input_rx, input_type).inspect(|_| emit!(EventIn));
         let transform = match transform {
             Transform::Function(mut t) => {
-                let filtered = filter_event_type(input_rx, input_type);
                 #[allow(deprecated)]
                 // `boxed()` here is deprecated, but the replacement won't work until we adopt futures 0.3 here.
                 let transformed = filtered
@@ -126,13 +146,12 @@ pub async fn build_pieces(
                     })
                     .flatten()
                     .boxed();
-                transformed.forward(output)
+                transformed.inspect(|_| emit!(EventOut)).forward(output)
             }
             Transform::Task(t) => {
-                let filtered = filter_event_type(input_rx, input_type);
                 let transformed: Box<dyn futures01::Stream<Item = _, Error = _> + Send> =
-                    t.transform(filtered);
-                transformed.forward(output)
+                    t.transform(Box::new(filtered));
+                transformed.inspect(|_| emit!(EventOut)).forward(output)
             }
         }
         .map(|_| debug!("Finished."))
@@ -165,7 +184,17 @@ pub async fn build_pieces(
             Ok(buffer) => buffer,
         };
 
-        let cx = SinkContext { acker };
+        // Sinks record the time the events they ack spent in Vector from
+        // their ingress, so it's only kept if that's wanted
+        let end_to_end_latency = config.telemetry.end_to_end_latency;
+
+        let flush = FlushHandle::default();
+        let cx = SinkContext {
+            acker,
+            proxy: sink.proxy.merge(&config.global.proxy),
+            when_full: sink.buffer.when_full(),
+            flush: flush.clone(),
+        };
 
         let (sink, healthcheck) = match sink.inner.build(cx).await {
             Err(error) => {
@@ -178,6 +207,16 @@ pub async fn build_pieces(
         let sink = sink
             .run(
                 filter_event_type(rx, input_type)
+                    .map(move |mut event| {
+                        emit!(EventIn);
+                        if let Some(lag) = event_lag(&event) {
+                            emit!(EventLag { lag });
+                        }
+                        if !end_to_end_latency {
+                            event.clear_ingress();
+                        }
+                        event
+                    })
                     .compat()
                     .take_while(|e| future::ready(e.is_ok()))
                     .map(|x| x.unwrap()),
@@ -213,6 +252,7 @@ pub async fn build_pieces(
 
         inputs.insert(name.clone(), (tx, sink_inputs.clone()));
         healthchecks.insert(name.clone(), healthcheck_task);
+        flush_handles.insert(name.clone(), flush);
         tasks.insert(name.clone(), task);
     }
 
@@ -223,7 +263,9 @@ pub async fn build_pieces(
             tasks,
             source_tasks,
             healthchecks,
+            flush_handles,
             shutdown_coordinator,
+            enrichment_tables,
         };
 
         Ok(pieces)
@@ -245,3 +287,10 @@ where
         DataType::Metric => Box::new(stream.filter(|event| matches!(event, Event::Metric(_)))),
     }
 }
+
+/// The time since an event happened, unless it has no timestamp or its
+/// timestamp is in the future.
+fn event_lag(event: &Event) -> Option<std::time::Duration> {
+    let timestamp = event.timestamp()?;
+    (chrono::Utc::now() - timestamp).to_std().ok()
+}
@@ -2,11 +2,12 @@ use crate::Event;
 use futures::compat::Future01CompatExt;
 use futures01::{future, sync::mpsc, Async, AsyncSink, Poll, Sink, StartSend, Stream};
 
-type RouterSink = Box<dyn Sink<SinkItem = Event, SinkError = ()> + 'static + Send>;
+pub type RouterSink = Box<dyn Sink<SinkItem = Event, SinkError = ()> + 'static + Send>;
 
 pub struct Fanout {
     sinks: Vec<(String, RouterSink)>,
     i: usize,
+    paused: bool,
     control_channel: mpsc::UnboundedReceiver<ControlMessage>,
 }
 
@@ -14,6 +15,9 @@ pub enum ControlMessage {
     Add(String, RouterSink),
     Remove(String),
     Replace(String, RouterSink),
+    /// Stops accepting events, applying back pressure to the component.
+    Pause,
+    Resume,
 }
 
 pub type ControlChannel = mpsc::UnboundedSender<ControlMessage>;
@@ -25,6 +29,7 @@ impl Fanout {
         let fanout = Self {
             sinks: vec![],
             i: 0,
+            paused: false,
             control_channel: control_rx,
         };
 
@@ -67,6 +72,8 @@ impl Fanout {
                 ControlMessage::Add(name, sink) => self.add(name, sink),
                 ControlMessage::Remove(name) => self.remove(&name),
                 ControlMessage::Replace(name, sink) => self.replace(name, sink),
+                ControlMessage::Pause => self.paused = true,
+                ControlMessage::Resume => self.paused = false,
             }
         }
     }
@@ -116,6 +123,13 @@ impl Sink for Fanout {
     fn start_send(&mut self, item: Self::SinkItem) -> StartSend<Self::SinkItem, Self::SinkError> {
         self.process_control_messages();
 
+        // Processing the control messages registered this task to be woken
+        // by the next one, which is how a paused fanout learns to resume.
+        // Pausing only takes effect between events.
+        if self.paused && self.i == 0 {
+            return Ok(AsyncSink::NotReady(item));
+        }
+
         if self.sinks.is_empty() {
             return Ok(AsyncSink::Ready);
         }
@@ -156,7 +170,7 @@ mod tests {
     use crate::{test_util::collect_ready, Event};
     use futures::compat::Future01CompatExt;
     use futures01::{stream, sync::mpsc, Future, Sink, Stream};
-    use tokio::time::{delay_for, Duration};
+    use tokio::time::{delay_for, timeout, Duration};
 
     #[tokio::test]
     async fn fanout_writes_to_all() {
@@ -448,4 +462,32 @@ mod tests {
         );
         assert_eq!(collect_ready(rx_a2).await.unwrap(), vec![rec3]);
     }
+
+    #[tokio::test]
+    async fn fanout_pause_resume() {
+        let (tx_a, rx_a) = mpsc::unbounded();
+        let tx_a = Box::new(tx_a.sink_map_err(|_| unreachable!()));
+
+        let (mut fanout, fanout_control) = Fanout::new();
+
+        fanout.add("a".to_string(), tx_a);
+
+        let rec1 = Event::from("line 1".to_string());
+        let rec2 = Event::from("line 2".to_string());
+
+        let fanout = fanout.send(rec1.clone()).compat().await.unwrap();
+
+        fanout_control
+            .unbounded_send(ControlMessage::Pause)
+            .unwrap();
+        let mut send = tokio::spawn(fanout.send(rec2.clone()).compat());
+        assert!(timeout(Duration::from_millis(50), &mut send).await.is_err());
+
+        fanout_control
+            .unbounded_send(ControlMessage::Resume)
+            .unwrap();
+        let _fanout = send.await.unwrap().unwrap();
+
+        assert_eq!(collect_ready(rx_a).await.unwrap(), vec![rec1, rec2]);
+    }
 }
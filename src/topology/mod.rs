@@ -7,13 +7,15 @@
 //! each type of component.
 
 pub mod builder;
-mod fanout;
+pub mod fanout;
 mod task;
 
 use crate::{
     buffers,
     config::{Config, ConfigDiff, Resource},
+    enrichment_tables::EnrichmentTables,
     shutdown::SourceShutdownCoordinator,
+    sinks::util::flush::FlushHandle,
     topology::{builder::Pieces, task::Task},
 };
 use futures::{compat::Future01CompatExt, future, FutureExt, StreamExt, TryFutureExt};
@@ -34,8 +36,10 @@ pub struct RunningTopology {
     outputs: HashMap<String, fanout::ControlChannel>,
     source_tasks: HashMap<String, TaskHandle>,
     tasks: HashMap<String, TaskHandle>,
+    flush_handles: HashMap<String, FlushHandle>,
     shutdown_coordinator: SourceShutdownCoordinator,
     config: Config,
+    enrichment_tables: EnrichmentTables,
     abort_tx: mpsc::UnboundedSender<()>,
 }
 
@@ -51,9 +55,11 @@ pub async fn start_validated(
         inputs: HashMap::new(),
         outputs: HashMap::new(),
         config,
+        enrichment_tables: EnrichmentTables::default(),
         shutdown_coordinator: SourceShutdownCoordinator::default(),
         source_tasks: HashMap::new(),
         tasks: HashMap::new(),
+        flush_handles: HashMap::new(),
         abort_tx,
     };
 
@@ -71,8 +77,12 @@ pub async fn start_validated(
     Some((running_topology, abort_rx))
 }
 
-pub async fn build_or_log_errors(config: &Config, diff: &ConfigDiff) -> Option<Pieces> {
-    match builder::build_pieces(config, diff).await {
+pub async fn build_or_log_errors(
+    config: &Config,
+    diff: &ConfigDiff,
+    enrichment_tables: &EnrichmentTables,
+) -> Option<Pieces> {
+    match builder::build_pieces(config, diff, enrichment_tables).await {
         Err(errors) => {
             for error in errors {
                 error!(message = "Configuration error.", %error);
@@ -247,7 +257,9 @@ impl RunningTopology {
         }
 
         // Now let's actually build the new pieces.
-        if let Some(mut new_pieces) = build_or_log_errors(&new_config, &diff).await {
+        if let Some(mut new_pieces) =
+            build_or_log_errors(&new_config, &diff, &self.enrichment_tables).await
+        {
             if self
                 .run_healthchecks(&diff, &mut new_pieces, require_healthy)
                 .await
@@ -264,7 +276,9 @@ impl RunningTopology {
         // We need to rebuild the removed.
         info!("Rebuilding old configuration.");
         let diff = diff.flip();
-        if let Some(mut new_pieces) = build_or_log_errors(&self.config, &diff).await {
+        if let Some(mut new_pieces) =
+            build_or_log_errors(&self.config, &diff, &self.enrichment_tables).await
+        {
             if self
                 .run_healthchecks(&diff, &mut new_pieces, require_healthy)
                 .await
@@ -386,6 +400,7 @@ impl RunningTopology {
 
         // Second pass for final cleanup
         for name in &diff.sinks.to_remove {
+            self.flush_handles.remove(name);
             let previous = self.tasks.remove(name).unwrap();
             if wait_for_sinks.contains(name) {
                 previous.await.unwrap().unwrap();
@@ -443,8 +458,11 @@ impl RunningTopology {
         }
     }
 
-    /// Starts new and changed pieces of topology.
+    /// Starts new and changed pieces of topology, and swaps in the enrichment
+    /// tables they were built with.
     fn spawn_diff(&mut self, diff: &ConfigDiff, mut new_pieces: Pieces) {
+        let enrichment_tables = std::mem::take(&mut new_pieces.enrichment_tables);
+
         // Sources
         for name in &diff.sources.to_change {
             info!(message = "Rebuilding source.", name = ?name);
@@ -477,6 +495,8 @@ impl RunningTopology {
             info!(message = "Starting sink.", name = ?name);
             self.spawn_sink(&name, &mut new_pieces);
         }
+
+        self.enrichment_tables = enrichment_tables.commit();
     }
 
     fn spawn_sink(&mut self, name: &str, new_pieces: &mut builder::Pieces) {
@@ -492,6 +512,9 @@ impl RunningTopology {
         if let Some(previous) = self.tasks.insert(name.to_string(), spawned) {
             drop(previous); // detach and forget
         }
+
+        let flush = new_pieces.flush_handles.remove(name).unwrap();
+        self.flush_handles.insert(name.to_string(), flush);
     }
 
     fn spawn_transform(&mut self, name: &str, new_pieces: &mut builder::Pieces) {
@@ -640,6 +663,17 @@ impl RunningTopology {
     pub fn config(&self) -> &Config {
         &self.config
     }
+
+    /// The control channels of the running components' outputs, by component
+    /// name.
+    pub fn outputs(&self) -> &HashMap<String, fanout::ControlChannel> {
+        &self.outputs
+    }
+
+    /// The flush handles of the running sinks, by sink name.
+    pub fn flush_handles(&self) -> &HashMap<String, FlushHandle> {
+        &self.flush_handles
+    }
 }
 
 fn handle_errors(
@@ -1,3 +1,4 @@
+use crate::telemetry::otlp::SpanExportLayer;
 use metrics_tracing_context::MetricsLayer;
 use tracing::{
     dispatcher::{set_global_default, Dispatch},
@@ -18,7 +19,8 @@ pub fn init(color: bool, json: bool, levels: &str) {
             .flatten_event(true)
             .finish()
             .with(Limit::default())
-            .with(MetricsLayer::new());
+            .with(MetricsLayer::new())
+            .with(SpanExportLayer::default());
 
         Dispatch::new(subscriber)
     } else {
@@ -27,7 +29,8 @@ pub fn init(color: bool, json: bool, levels: &str) {
             .with_env_filter(levels)
             .finish()
             .with(Limit::default())
-            .with(MetricsLayer::new());
+            .with(MetricsLayer::new())
+            .with(SpanExportLayer::default());
 
         Dispatch::new(subscriber)
     };
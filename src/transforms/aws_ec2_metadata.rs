@@ -1,5 +1,5 @@
 use crate::{
-    config::{DataType, TransformConfig, TransformDescription},
+    config::{DataType, ProxyConfig, TransformConfig, TransformDescription},
     event::Event,
     http::HttpClient,
     internal_events::{
@@ -139,7 +139,12 @@ impl TransformConfig for Ec2Metadata {
             .clone()
             .unwrap_or_else(|| DEFAULT_FIELD_WHITELIST.clone());
 
-        let http_client = HttpClient::new(None)?;
+        // The metadata service is link-local, so it's never proxied.
+        let proxy = ProxyConfig {
+            enabled: false,
+            ..Default::default()
+        };
+        let http_client = HttpClient::new(None, &proxy)?;
 
         let mut client =
             MetadataClient::new(http_client, host, keys, write, refresh_interval, fields);
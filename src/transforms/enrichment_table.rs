@@ -0,0 +1,186 @@
+use crate::{
+    config::{DataType, GenerateConfig, TransformConfig, TransformContext, TransformDescription},
+    enrichment_tables::Table,
+    event::Event,
+    internal_events::{
+        EnrichmentTableEventProcessed, EnrichmentTableFieldMissing, EnrichmentTableLookupFailed,
+    },
+    transforms::{FunctionTransform, Transform},
+    Result,
+};
+use indexmap::IndexMap;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct EnrichmentTableConfig {
+    pub table: String,
+    /// Table fields, mapped to the event fields holding the values they
+    /// must match.
+    pub keys: IndexMap<String, String>,
+    /// Field to insert the matching record under. Defaults to the table name.
+    pub target: Option<String>,
+}
+
+inventory::submit! {
+    TransformDescription::new::<EnrichmentTableConfig>("enrichment_table")
+}
+
+impl GenerateConfig for EnrichmentTableConfig {
+    fn generate_config() -> toml::Value {
+        let mut keys = IndexMap::new();
+        keys.insert("host".to_owned(), "host".to_owned());
+
+        toml::Value::try_from(Self {
+            table: "owners".to_owned(),
+            keys,
+            target: None,
+        })
+        .unwrap()
+    }
+}
+
+#[async_trait::async_trait]
+#[typetag::serde(name = "enrichment_table")]
+impl TransformConfig for EnrichmentTableConfig {
+    async fn build(&self) -> Result<Transform> {
+        self.build_with_context(&TransformContext::default()).await
+    }
+
+    async fn build_with_context(&self, cx: &TransformContext) -> Result<Transform> {
+        let table = cx.enrichment_tables().get(&self.table)?;
+        let target = self.target.clone().unwrap_or_else(|| self.table.clone());
+
+        Ok(Transform::function(EnrichmentTable::new(
+            table,
+            self.keys.clone(),
+            target,
+        )))
+    }
+
+    fn input_type(&self) -> DataType {
+        DataType::Log
+    }
+
+    fn output_type(&self) -> DataType {
+        DataType::Log
+    }
+
+    fn transform_type(&self) -> &'static str {
+        "enrichment_table"
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct EnrichmentTable {
+    table: Arc<Table>,
+    keys: IndexMap<String, String>,
+    target: String,
+}
+
+impl EnrichmentTable {
+    pub fn new(table: Arc<Table>, keys: IndexMap<String, String>, target: String) -> Self {
+        Self {
+            table,
+            keys,
+            target,
+        }
+    }
+}
+
+impl FunctionTransform for EnrichmentTable {
+    fn transform(&mut self, output: &mut Vec<Event>, mut event: Event) {
+        emit!(EnrichmentTableEventProcessed);
+
+        let mut condition = BTreeMap::new();
+        for (table_field, event_field) in &self.keys {
+            match event.as_log().get(event_field) {
+                Some(value) => {
+                    condition.insert(table_field.clone(), value.to_string_lossy());
+                }
+                None => {
+                    emit!(EnrichmentTableFieldMissing { field: event_field });
+                    output.push(event);
+                    return;
+                }
+            }
+        }
+
+        match self.table.find_record(&condition) {
+            Ok(record) => {
+                let log = event.as_mut_log();
+                for (field, value) in record {
+                    log.insert(format!("{}.{}", self.target, field), value);
+                }
+            }
+            Err(error) => emit!(EnrichmentTableLookupFailed { error }),
+        }
+
+        output.push(event);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{enrichment_tables::TableConfig, test_util::temp_file};
+
+    #[test]
+    fn generate_config() {
+        crate::test_util::test_generate_config::<EnrichmentTableConfig>();
+    }
+
+    fn transform(keys: &[(&str, &str)]) -> EnrichmentTable {
+        let path = temp_file();
+        std::fs::write(
+            &path,
+            "host,region,team\nweb-1,eu,frontend\nweb-1,us,platform\ndb-1,eu,storage\n",
+        )
+        .unwrap();
+
+        let config: TableConfig = toml::from_str(&format!(
+            "path = {:?}\nformat = \"csv\"",
+            path.to_str().unwrap()
+        ))
+        .unwrap();
+        let table = Arc::new(Table::load("owners", config).unwrap());
+
+        let keys = keys
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect();
+
+        EnrichmentTable::new(table, keys, "owner".to_owned())
+    }
+
+    #[test]
+    fn enriches_matching_events() {
+        let mut transform = transform(&[("host", "hostname"), ("region", "dc")]);
+
+        let mut event = Event::from("message");
+        event.as_mut_log().insert("hostname", "web-1");
+        event.as_mut_log().insert("dc", "us");
+
+        let event = transform.transform_one(event).unwrap();
+        assert_eq!(event.as_log()["owner.team"], "platform".into());
+        assert_eq!(event.as_log()["owner.region"], "us".into());
+    }
+
+    #[test]
+    fn passes_through_unmatched_events() {
+        let mut transform = transform(&[("host", "hostname")]);
+
+        // More than one record matches.
+        let mut event = Event::from("message");
+        event.as_mut_log().insert("hostname", "web-1");
+        let event = transform.transform_one(event).unwrap();
+        assert!(event.as_log().get("owner").is_none());
+
+        // The key field is missing.
+        let event = transform.transform_one(Event::from("message")).unwrap();
+        assert!(event.as_log().get("owner").is_none());
+        assert_eq!(event.as_log()["message"], "message".into());
+    }
+}
@@ -1,5 +1,6 @@
 use crate::{
-    config::{DataType, TransformConfig, TransformDescription},
+    config::{DataType, TransformConfig, TransformContext, TransformDescription},
+    enrichment_tables::EnrichmentTables,
     event::Event,
     internal_events::{RemapEventProcessed, RemapFailedMapping},
     transforms::{FunctionTransform, Transform},
@@ -29,6 +30,10 @@ impl TransformConfig for RemapConfig {
         Remap::new(self.clone()).map(Transform::function)
     }
 
+    async fn build_with_context(&self, cx: &TransformContext) -> Result<Transform> {
+        Remap::with_enrichment_tables(self.clone(), cx.enrichment_tables()).map(Transform::function)
+    }
+
     fn input_type(&self) -> DataType {
         DataType::Log
     }
@@ -50,8 +55,19 @@ pub struct Remap {
 
 impl Remap {
     pub fn new(config: RemapConfig) -> crate::Result<Remap> {
+        Self::with_enrichment_tables(config, &EnrichmentTables::default())
+    }
+
+    /// Builds the transform with `get_enrichment_table_record` looking up
+    /// records in the given tables.
+    pub fn with_enrichment_tables(
+        config: RemapConfig,
+        enrichment_tables: &EnrichmentTables,
+    ) -> crate::Result<Remap> {
+        let functions = crate::remap::functions_with_tables(enrichment_tables);
+
         Ok(Remap {
-            program: Program::new(&config.source, &crate::remap::FUNCTIONS_MUT)?,
+            program: Program::new(&config.source, &functions)?,
             drop_on_err: config.drop_on_err,
         })
     }
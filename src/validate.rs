@@ -53,6 +53,23 @@ pub async fn validate(opts: &Opts, color: bool) -> ExitCode {
     }
 }
 
+/// Runs the component and health checks of a loaded config, as `validate`
+/// does, returning whether they passed along with their uncolored report.
+pub async fn check_environment(mut config: Config) -> (bool, String) {
+    let mut fmt = Formatter::capture();
+
+    let validated = match create_tmp_directory(&mut config, &mut fmt) {
+        Some(tmp_directory) => {
+            let validated = validate_environment(&config, &mut fmt).await;
+            remove_tmp_directory(tmp_directory);
+            validated
+        }
+        None => false,
+    };
+
+    (validated, fmt.output.unwrap_or_default())
+}
+
 /// Ok if all configs were successfully validated.
 /// Err Some contains only successfully validated configs.
 fn validate_config(opts: &Opts, fmt: &mut Formatter) -> Option<Config> {
@@ -98,7 +115,7 @@ async fn validate_components(
         .set(config.global.log_schema.clone())
         .expect("Couldn't set schema");
 
-    match topology::builder::build_pieces(config, diff).await {
+    match topology::builder::build_pieces(config, diff, &Default::default()).await {
         Ok(pieces) => {
             fmt.success("Component configuration");
             Some(pieces)
@@ -187,6 +204,8 @@ struct Formatter {
     error_intro: String,
     warning_intro: String,
     success_intro: String,
+    /// Collects the output instead of printing it
+    output: Option<String>,
 }
 
 impl Formatter {
@@ -210,6 +229,14 @@ impl Formatter {
                 "√".to_owned()
             },
             color,
+            output: None,
+        }
+    }
+
+    fn capture() -> Self {
+        Self {
+            output: Some(String::new()),
+            ..Self::new(false)
         }
     }
 
@@ -279,7 +306,7 @@ impl Formatter {
     fn space(&mut self) {
         if self.print_space {
             self.print_space = false;
-            println!();
+            self.write("\n");
         }
     }
 
@@ -296,6 +323,13 @@ impl Formatter {
             .unwrap_or(0);
         self.max_line_width = width.max(self.max_line_width);
         self.print_space = true;
-        print!("{}", print.as_ref())
+        self.write(print.as_ref())
+    }
+
+    fn write(&mut self, s: &str) {
+        match &mut self.output {
+            Some(output) => output.push_str(s),
+            None => print!("{}", s),
+        }
     }
 }
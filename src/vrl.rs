@@ -0,0 +1,264 @@
+use crate::event::{self, Event};
+use colored::*;
+use remap::{Program, Runtime, State};
+use std::{
+    convert::{TryFrom, TryInto},
+    fs::File,
+    io::{self, BufRead, BufReader, Write},
+    path::PathBuf,
+};
+use structopt::StructOpt;
+
+#[derive(StructOpt, Debug)]
+#[structopt(rename_all = "kebab-case")]
+pub struct Opts {
+    /// Remap program to run against every input event. If omitted, an
+    /// interactive session is started instead.
+    #[structopt(short, long)]
+    program: Option<PathBuf>,
+
+    /// File containing newline delimited JSON events. Defaults to `stdin` when
+    /// running a program, and to a single empty event in an interactive
+    /// session.
+    #[structopt(short, long)]
+    input: Option<PathBuf>,
+}
+
+pub fn cmd(opts: &Opts) -> exitcode::ExitCode {
+    match &opts.program {
+        Some(path) => run_program(path, opts.input.as_ref()),
+        None => repl(opts.input.as_ref()),
+    }
+}
+
+/// Runs the program at `path` against every event read from `input`, printing
+/// each resulting event as a single line of JSON.
+fn run_program(path: &PathBuf, input: Option<&PathBuf>) -> exitcode::ExitCode {
+    let source = match std::fs::read_to_string(path) {
+        Ok(source) => source,
+        Err(error) => {
+            error!(message = "Unable to read program.", path = ?path, %error);
+            return exitcode::NOINPUT;
+        }
+    };
+
+    let program = match Program::new(&source, &crate::remap::FUNCTIONS_MUT) {
+        Ok(program) => program,
+        Err(error) => {
+            error!(message = "Unable to compile program.", %error);
+            return exitcode::DATAERR;
+        }
+    };
+
+    let reader: Box<dyn BufRead> = match input {
+        Some(path) => match File::open(path) {
+            Ok(file) => Box::new(BufReader::new(file)),
+            Err(error) => {
+                error!(message = "Unable to open input.", path = ?path, %error);
+                return exitcode::NOINPUT;
+            }
+        },
+        None => Box::new(BufReader::new(io::stdin())),
+    };
+
+    let stdout = io::stdout();
+    let mut stdout = stdout.lock();
+    let mut code = exitcode::OK;
+
+    for (i, line) in reader.lines().enumerate() {
+        let line = match line {
+            Ok(line) => line,
+            Err(error) => {
+                error!(message = "Unable to read input.", %error);
+                return exitcode::IOERR;
+            }
+        };
+
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        let mut event = match parse_event(&line) {
+            Ok(event) => event,
+            Err(error) => {
+                error!(message = "Invalid input event.", line = i + 1, %error);
+                code = exitcode::DATAERR;
+                continue;
+            }
+        };
+
+        let mut runtime = Runtime::default();
+        if let Err(error) = runtime.execute(&mut event, &program) {
+            error!(message = "Program failed.", line = i + 1, %error);
+            code = exitcode::DATAERR;
+            continue;
+        }
+
+        if writeln!(stdout, "{}", encode_event(event)).is_err() {
+            return exitcode::IOERR;
+        }
+    }
+
+    code
+}
+
+/// Starts an interactive session against the first event in `input`.
+///
+/// Each line is compiled and executed on its own, while variables assigned
+/// in earlier lines remain available to later ones. The returned value and
+/// the resulting event are printed after every line.
+fn repl(input: Option<&PathBuf>) -> exitcode::ExitCode {
+    let mut event = match input {
+        Some(path) => match read_first_event(path) {
+            Ok(event) => event,
+            Err(error) => {
+                error!(message = "Unable to read input event.", path = ?path, %error);
+                return exitcode::NOINPUT;
+            }
+        },
+        None => Event::from(event::LogEvent::default()),
+    };
+
+    println!("{}", "Vector remap language REPL.".bold());
+    println!("Type `help` for a list of commands, `exit` to quit.\n");
+    println!("{}", encode_event(event.clone()));
+
+    let mut runtime = Runtime::new(State::default());
+    let stdin = io::stdin();
+    let mut lines = stdin.lock().lines();
+
+    loop {
+        print!("{} ", "$".dimmed());
+        let _ = io::stdout().flush();
+
+        let line = match lines.next() {
+            Some(Ok(line)) => line,
+            Some(Err(error)) => {
+                error!(message = "Unable to read input.", %error);
+                return exitcode::IOERR;
+            }
+            None => break,
+        };
+
+        match line.trim() {
+            "" => continue,
+            "exit" | "quit" => break,
+            "help" => {
+                print_help();
+                continue;
+            }
+            "event" => {
+                println!("{}", encode_event(event.clone()));
+                continue;
+            }
+            _ => {}
+        }
+
+        match execute(&mut runtime, &mut event, &line) {
+            Ok(value) => {
+                println!("{}", value);
+                println!("{}", encode_event(event.clone()).dimmed());
+            }
+            Err(error) => println!("{} {}", "error:".red().bold(), error),
+        }
+    }
+
+    exitcode::OK
+}
+
+fn print_help() {
+    println!(
+        r#"Commands:
+  help   Show this message.
+  event  Print the current event.
+  exit   Leave the session.
+
+Any other input is executed as a remap program against the current event."#
+    );
+}
+
+/// Compiles and executes a single line, returning the encoded result value.
+fn execute(runtime: &mut Runtime, event: &mut Event, source: &str) -> Result<String, String> {
+    let program =
+        Program::new(source, &crate::remap::FUNCTIONS_MUT).map_err(|error| error.to_string())?;
+
+    let value = runtime
+        .execute(event, &program)
+        .map_err(|error| error.to_string())?;
+
+    Ok(match value {
+        Some(value) => encode_value(value),
+        None => "null".to_owned(),
+    })
+}
+
+fn parse_event(line: &str) -> crate::Result<Event> {
+    let json: serde_json::Value = serde_json::from_str(line)?;
+    Event::try_from(json)
+}
+
+fn read_first_event(path: &PathBuf) -> crate::Result<Event> {
+    let reader = BufReader::new(File::open(path)?);
+
+    for line in reader.lines() {
+        let line = line?;
+        if !line.trim().is_empty() {
+            return parse_event(&line);
+        }
+    }
+
+    Ok(Event::from(event::LogEvent::default()))
+}
+
+fn encode_event(event: Event) -> String {
+    event
+        .try_into()
+        .map(|json: serde_json::Value| json.to_string())
+        .unwrap_or_else(|error| format!("<unencodable event: {}>", error))
+}
+
+fn encode_value(value: remap::Value) -> String {
+    event::Value::from(value)
+        .try_into()
+        .map(|json: serde_json::Value| json.to_string())
+        .unwrap_or_else(|error| format!("<unencodable value: {}>", error))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn execute_keeps_variables_across_lines() {
+        let mut runtime = Runtime::default();
+        let mut event = parse_event(r#"{"message": "hello"}"#).unwrap();
+
+        assert_eq!(
+            execute(&mut runtime, &mut event, r#"$greeting = .message"#),
+            Ok(r#""hello""#.to_owned())
+        );
+        assert_eq!(
+            execute(&mut runtime, &mut event, r#".copy = $greeting + " world""#),
+            Ok(r#""hello world""#.to_owned())
+        );
+        assert_eq!(
+            encode_event(event),
+            r#"{"copy":"hello world","message":"hello"}"#
+        );
+    }
+
+    #[test]
+    fn execute_reports_errors() {
+        let mut runtime = Runtime::default();
+        let mut event = parse_event(r#"{}"#).unwrap();
+
+        assert!(execute(&mut runtime, &mut event, ".foo = ").is_err());
+        assert!(execute(&mut runtime, &mut event, "upcase(.missing)").is_err());
+    }
+
+    #[test]
+    fn parse_event_rejects_non_objects() {
+        assert!(parse_event("[1, 2, 3]").is_err());
+        assert!(parse_event("not json").is_err());
+    }
+}
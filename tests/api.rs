@@ -8,7 +8,7 @@ mod support;
 mod tests {
     use crate::support::{sink, source};
     use chrono::Utc;
-    use futures::StreamExt;
+    use futures::{channel::mpsc, StreamExt};
     use std::{
         net::SocketAddr,
         sync::Once,
@@ -20,13 +20,14 @@ mod tests {
         self,
         api::{self, Server},
         config::Config,
+        http::Auth,
         internal_events::{emit, GeneratorEventProcessed, Heartbeat},
         test_util::{next_addr, retry_until},
     };
     use vector_api_client::{
-        connect_subscription_client,
+        connect_subscription_client, connect_subscription_client_with_auth,
         gql::{HealthQueryExt, HealthSubscriptionExt, MetricsSubscriptionExt},
-        Client, SubscriptionClient,
+        Auth as ClientAuth, Client, SubscriptionClient,
     };
 
     static METRICS_INIT: Once = Once::new();
@@ -68,9 +69,11 @@ mod tests {
     }
 
     // Starts and returns the server
-    fn start_server() -> Server {
+    async fn start_server() -> Server {
         let config = api_enabled_config();
-        api::Server::start(&config)
+        api::Server::start(&config, mpsc::channel(1).0)
+            .await
+            .unwrap()
     }
 
     fn make_client(addr: SocketAddr) -> Client {
@@ -85,7 +88,9 @@ mod tests {
         let addr = config.api.bind.unwrap();
         let url = format!("http://{}:{}/{}", addr.ip(), addr.port(), url);
 
-        let _server = api::Server::start(&config);
+        let _server = api::Server::start(&config, mpsc::channel(1).0)
+            .await
+            .unwrap();
 
         // Build the request
         let client = reqwest::Client::new();
@@ -98,6 +103,30 @@ mod tests {
         .await
     }
 
+    // Posts a GraphQL request to the API, authenticated with `token` if given, and returns
+    // the response body
+    async fn graphql_post(addr: SocketAddr, query: &str, token: Option<&str>) -> serde_json::Value {
+        let url = format!("http://{}/graphql", addr);
+        let body = serde_json::json!({ "query": query });
+        let client = reqwest::Client::new();
+
+        let res = retry_until(
+            || {
+                let request = client.post(&url).json(&body);
+                match token {
+                    Some(token) => request.bearer_auth(token),
+                    None => request,
+                }
+                .send()
+            },
+            Duration::from_millis(100),
+            Duration::from_secs(10),
+        )
+        .await;
+
+        res.json().await.unwrap()
+    }
+
     // Creates and returns a new subscription client. Connection is re-attempted until
     // the specified timeout
     async fn new_subscription_client(addr: SocketAddr) -> SubscriptionClient {
@@ -259,7 +288,7 @@ mod tests {
     #[tokio::test]
     /// Tests the health query
     async fn api_graphql_health() {
-        let server = start_server();
+        let server = start_server().await;
         let client = make_client(server.addr());
 
         let res = client.health_query().await.unwrap();
@@ -271,7 +300,7 @@ mod tests {
     #[tokio::test]
     /// Tests that the heartbeat subscription returns a UTC payload every 1/2 second
     async fn api_graphql_heartbeat() {
-        let server = start_server();
+        let server = start_server().await;
         let client = new_subscription_client(server.addr()).await;
 
         new_heartbeat_subscription(&client, 3, 500).await;
@@ -280,7 +309,7 @@ mod tests {
     #[tokio::test]
     /// Tests for Vector instance uptime in seconds
     async fn api_graphql_uptime_metrics() {
-        let server = start_server();
+        let server = start_server().await;
         let client = new_subscription_client(server.addr()).await;
 
         let _metrics = init_metrics();
@@ -291,7 +320,7 @@ mod tests {
     #[tokio::test]
     /// Tests for events processed metrics, using fake generator events
     async fn api_graphql_event_processed_total_metrics() {
-        let server = start_server();
+        let server = start_server().await;
         let client = new_subscription_client(server.addr()).await;
 
         let _metrics = init_metrics();
@@ -302,7 +331,7 @@ mod tests {
     #[tokio::test]
     /// Tests whether 2 disparate subscriptions can run against a single client
     async fn api_graphql_combined_heartbeat_uptime() {
-        let server = start_server();
+        let server = start_server().await;
         let client = new_subscription_client(server.addr()).await;
 
         let _metrics = init_metrics();
@@ -312,4 +341,101 @@ mod tests {
             new_heartbeat_subscription(&client, 3, 500),
         };
     }
+
+    #[tokio::test]
+    /// Tests that mutations are only run when allowed, and from authenticated requests
+    async fn api_graphql_mutations_require_auth() {
+        let mutation = r#"mutation { pauseSource(name: "in1") }"#;
+
+        let config = api_enabled_config();
+        let server = api::Server::start(&config, mpsc::channel(1).0)
+            .await
+            .unwrap();
+        let res = graphql_post(server.addr(), mutation, None).await;
+        assert_eq!(
+            res["errors"][0]["message"],
+            "Mutations are disabled; see `api.allow_mutations`."
+        );
+
+        let mut config = api_enabled_config();
+        config.api.allow_mutations = true;
+        config.api.auth = Some(Auth::Bearer {
+            token: "secret".to_owned(),
+        });
+        let server = api::Server::start(&config, mpsc::channel(1).0)
+            .await
+            .unwrap();
+
+        let res = graphql_post(server.addr(), mutation, Some("wrong")).await;
+        assert_eq!(res["error"], "Unauthorized");
+
+        // Authorized, but the source isn't running as there's no topology.
+        let res = graphql_post(server.addr(), mutation, Some("secret")).await;
+        assert_eq!(
+            res["errors"][0]["message"],
+            "No source named \"in1\" is running."
+        );
+    }
+
+    #[tokio::test]
+    /// Tests that the topology graph lists the configured components and their connections
+    async fn api_graphql_graph() {
+        let server = start_server().await;
+        let query = r#"{
+            graph {
+                nodes { name componentType config }
+                edges { from to }
+            }
+        }"#;
+
+        let res = graphql_post(server.addr(), query, None).await;
+        let graph = &res["data"]["graph"];
+        assert_eq!(graph["nodes"][0]["name"], "in1");
+        assert_eq!(graph["nodes"][0]["componentType"], "mock");
+        assert_eq!(graph["nodes"][1]["name"], "out1");
+        assert_eq!(
+            graph["edges"],
+            serde_json::json!([{ "from": "in1", "to": "out1" }])
+        );
+
+        let config: serde_json::Value =
+            serde_json::from_str(graph["nodes"][1]["config"].as_str().unwrap()).unwrap();
+        assert_eq!(config["inputs"], serde_json::json!(["in1"]));
+        assert_eq!(config["type"], "mock");
+    }
+
+    #[tokio::test]
+    /// Tests that queries and subscriptions require the configured credentials, while the
+    /// health endpoint stays open for probes
+    async fn api_graphql_auth() {
+        let mut config = api_enabled_config();
+        config.api.auth = Some(Auth::Basic {
+            user: "vector".to_owned(),
+            password: "secret".to_owned(),
+        });
+        let server = api::Server::start(&config, mpsc::channel(1).0)
+            .await
+            .unwrap();
+        let auth = ClientAuth::Basic {
+            user: "vector".to_owned(),
+            password: "secret".to_owned(),
+        };
+
+        let url = Url::parse(&format!("http://{}/graphql", server.addr())).unwrap();
+        assert!(Client::new(url.clone()).health_query().await.is_err());
+        let res = Client::new_with_auth(url, Some(auth.clone()))
+            .health_query()
+            .await
+            .unwrap();
+        assert!(res.data.unwrap().health);
+
+        let url = Url::parse(&format!("ws://{}/graphql", server.addr())).unwrap();
+        assert!(connect_subscription_client(&url).await.is_err());
+        assert!(connect_subscription_client_with_auth(&url, Some(&auth))
+            .await
+            .is_ok());
+
+        let health = format!("http://{}/health", server.addr());
+        assert!(reqwest::get(&health).await.unwrap().status().is_success());
+    }
 }
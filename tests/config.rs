@@ -9,7 +9,7 @@ async fn load(config: &str) -> Result<Vec<String>, Vec<String>> {
             let diff = ConfigDiff::initial(&c);
             match (
                 config::warnings(&c),
-                topology::builder::build_pieces(&c, &diff).await,
+                topology::builder::build_pieces(&c, &diff, &Default::default()).await,
             ) {
                 (Some(warnings), Ok(_pieces)) => Ok(warnings),
                 (None, Ok(_pieces)) => Ok(vec![]),
@@ -440,6 +440,38 @@ async fn warnings() {
     )
 }
 
+#[cfg(all(feature = "api", feature = "sources-socket", feature = "sinks-socket"))]
+#[tokio::test]
+async fn warns_api_auth_without_tls() {
+    let warnings = load(
+        r#"
+        [api]
+        enabled = true
+        auth.strategy = "bearer"
+        auth.token = "secret"
+
+        [sources.in]
+        type = "socket"
+        mode = "tcp"
+        address = "127.0.0.1:1235"
+
+        [sinks.out]
+        type = "socket"
+        mode = "tcp"
+        inputs = ["in"]
+        encoding = "text"
+        address = "127.0.0.1:9999"
+      "#,
+    )
+    .await
+    .unwrap();
+
+    assert_eq!(
+        warnings,
+        vec!["API authentication is configured without TLS, so its credentials are sent in plaintext"]
+    )
+}
+
 #[cfg(all(
     feature = "sources-socket",
     feature = "transforms-sampler",
@@ -7,7 +7,7 @@ use serde::Deserialize;
 use serde_json::Value;
 use sinks::{
     socket::SocketSinkConfig,
-    util::{encoding::EncodingConfig, Encoding},
+    util::encoding::{EncodingConfig, StandardEncodings},
 };
 use std::{collections::HashMap, fmt, str::FromStr};
 use tokio_util::codec::BytesCodec;
@@ -338,5 +338,5 @@ fn encode_priority(severity: Severity, facility: Facility) -> u8 {
 }
 
 fn tcp_json_sink(address: String) -> SocketSinkConfig {
-    SocketSinkConfig::make_tcp_config(address, EncodingConfig::from(Encoding::Json), None)
+    SocketSinkConfig::make_tcp_config(address, EncodingConfig::from(StandardEncodings::Json), None)
 }
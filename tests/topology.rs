@@ -492,7 +492,9 @@ async fn topology_swap_transform_is_atomic() {
 async fn topology_required_healthcheck_fails_start() {
     let config = basic_config_with_sink_failing_healthcheck();
     let diff = vector::config::ConfigDiff::initial(&config);
-    let pieces = topology::build_or_log_errors(&config, &diff).await.unwrap();
+    let pieces = topology::build_or_log_errors(&config, &diff, &Default::default())
+        .await
+        .unwrap();
     assert!(topology::start_validated(config, diff, pieces, true)
         .await
         .is_none());
@@ -502,7 +504,9 @@ async fn topology_required_healthcheck_fails_start() {
 async fn topology_optional_healthcheck_does_not_fail_start() {
     let config = basic_config_with_sink_failing_healthcheck();
     let diff = vector::config::ConfigDiff::initial(&config);
-    let pieces = topology::build_or_log_errors(&config, &diff).await.unwrap();
+    let pieces = topology::build_or_log_errors(&config, &diff, &Default::default())
+        .await
+        .unwrap();
     assert!(topology::start_validated(config, diff, pieces, false)
         .await
         .is_some());